@@ -1,5 +1,10 @@
+use arc_swap::ArcSwap;
 use serde::{Deserialize, Serialize};
 use std::env;
+use std::sync::Arc;
+
+/// Configuration shared across components and swappable at runtime
+pub type SharedConfig = Arc<ArcSwap<BotConfig>>;
 
 /// Bot configuration structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -7,43 +12,576 @@ pub struct BotConfig {
     // Solana Configuration
     pub rpc_url: String,
     pub ws_url: Option<String>,
+    pub extra_ws_urls: Vec<String>,
+    pub source_priority: Vec<String>,
+    pub cluster: Option<Cluster>,
 
     // Wallet Configuration
     pub private_key: Option<String>,
     pub main_wallet_private_key: Option<String>,
+    pub external_signer_socket: Option<String>,
 
     // Trading Configuration
     pub buy_amount_sol: f64,
+    pub buy_mode: BuyMode,
+    pub buy_amount_tokens: u64,
+    pub balance_tiers: Vec<BalanceTier>,
+    pub buy_balance_pct: Option<f64>,
+    pub min_buy_amount_sol: f64,
+    pub max_buy_amount_sol: Option<f64>,
+    pub loss_streak_threshold: Option<u32>,
+    pub loss_streak_size_factor: f64,
     pub min_liquidity: f64,
     pub max_slippage: f64,
+    pub max_slippage_bps: Option<u64>,
+    pub slippage_retry_max_bps: Option<u64>,
+    pub emergency_slippage_bps: u64,
+    pub check_token_account_before_buy: bool,
+    pub amm_route_progress_pct: f64,
+    pub graduation_sol_target: f64,
+    pub scout_then_scale: bool,
+    pub scout_amount_sol: f64,
+    pub scout_sell_percentage: f64,
+    pub sell_price_guard_pct: Option<f64>,
+    pub buy_price_guard_pct: Option<f64>,
     pub take_profit_percentage: f64,
+    pub take_profit_bps: Option<u64>,
+    pub min_net_profit_sol: f64,
     pub stop_loss_percentage: f64,
+    pub stop_loss_bps: Option<u64>,
+    pub safety_stop_loss_range: Option<(f64, f64)>,
+    pub exit_presets: Vec<ExitPreset>,
     pub trailing_stop_loss_percentage: f64,
+    pub trailing_activation_pct: f64,
+    pub volume_drop_exit_fraction: Option<f64>,
+    pub volume_window_secs: u64,
+    pub exit_rules: Vec<crate::traders::exit_rules::ExitRule>,
+    pub recover_cost_at_pct: Option<f64>,
+    pub sell_on_migration: bool,
+    pub sell_on_creator_dump: bool,
+    pub insider_wallets: Vec<String>,
 
     // Safety Settings
     pub trading_cooldown_ms: u64,
+    pub startup_warmup_ms: u64,
     pub max_loss_per_trade_sol: f64,
+    pub max_daily_loss_sol: Option<f64>,
     pub max_trades_per_hour: u32,
+    pub max_exposure_per_token_sol: Option<f64>,
+    pub min_hold_seconds: u64,
+    pub min_hold_applies_to_take_profit: bool,
+    pub max_total_exposure_sol: Option<f64>,
+    pub safety_recheck_interval_ms: u64,
+    pub safety_recheck_min_score: u32,
+    pub max_inflight_analyses: usize,
+    pub max_event_age_secs: Option<u64>,
+    pub catch_up_minutes: Option<u64>,
+    pub adopt_untracked_holdings: bool,
+    pub max_notifications_per_sec: u32,
+    pub max_ws_message_bytes: usize,
+    pub health_failure_after_ms: Option<u64>,
+    pub health_failure_action: HealthFailureAction,
+    pub health_check_interval_ms: u64,
 
     // Token Filtering
     pub min_market_cap: f64,
     pub max_market_cap: f64,
+    pub min_buy_price: Option<f64>,
+    pub max_buy_price: Option<f64>,
+    pub max_price_impact_pct: Option<f64>,
+    pub max_exit_impact_pct: Option<f64>,
+    pub max_curve_idle_secs: Option<u64>,
+    pub min_unique_buyers: Option<u32>,
+    pub exclude_creator_from_buyers: bool,
+    pub buyer_observation_ms: u64,
+    pub max_creation_slot_buyers: Option<u32>,
+    pub reject_bundle_launches: bool,
+    pub allow_token_2022: bool,
+    pub min_opportunity_score: u32,
     pub min_holders: u32,
     pub max_holders: u32,
     pub require_social_links: bool,
     pub require_creator_verification: bool,
+    pub creator_buy_bonus_min_sol: f64,
+    pub creator_buy_penalty_max_sol: f64,
+    pub creator_buy_bonus: u32,
+    pub creator_buy_penalty: u32,
+    pub enrichment_provider: Option<EnrichmentProvider>,
+    pub enrichment_url: Option<String>,
+    pub enrichment_api_key: Option<String>,
+    pub enrichment_timeout_ms: u64,
+
+    // Filter Tuning
+    pub shadow_track_rejected: bool,
+    pub shadow_window_ms: u64,
+    pub shadow_refresh_ms: u64,
 
     // Gas Optimization
     pub priority_fee_lamports: u64,
     pub max_priority_fee_lamports: u64,
+    pub max_send_retries: u32,
+    pub balance_tolerance_pct: f64,
+    pub fee_reserve_sol: f64,
+    pub max_fee_ratio_pct: Option<f64>,
+    pub min_sell_proceeds_sol: f64,
+    pub batch_sells: bool,
+    pub max_sells_per_batch: usize,
+    pub require_buy_finalization: bool,
+    pub buy_count_commitment: solana_sdk::commitment_config::CommitmentLevel,
+    pub require_sell_confirmation: bool,
+    pub priority_fee_escalation_multiplier: f64,
+    pub adaptive_priority_fee: bool,
+    pub adaptive_fee_min_lamports: u64,
+    pub blockhash_refresh_ms: u64,
+    pub blockhash_max_age_ms: u64,
+    pub metadata_fetch_timeout_ms: u64,
+    pub curve_not_found_retries: u32,
+    pub curve_not_found_retry_delay_ms: u64,
+    pub jito_tip_min_lamports: u64,
+    pub jito_tip_max_lamports: Option<u64>,
+    pub jito_block_engine_url: Option<String>,
+
+    // Pump.fun Program
+    pub fee_recipients: Vec<String>,
+    pub fee_recipient_refresh_ms: u64,
+    pub account_layout: AccountLayout,
+    pub prepared_buys: Vec<String>,
 
     // Monitoring
     pub log_level: String,
     pub telegram_bot_token: Option<String>,
     pub telegram_chat_id: Option<String>,
+    pub event_log_path: Option<String>,
+    pub otlp_endpoint: Option<String>,
+    pub otel_service_name: String,
+    pub debug_dump_failed_tx: bool,
+    pub curve_subscriptions: bool,
+    pub max_curve_subscriptions: Option<usize>,
+    pub curve_reconnect_delay_ms: u64,
+    pub latency_window: usize,
+    pub position_tags: std::collections::BTreeMap<String, String>,
+    pub report_dir: Option<String>,
+    pub report_checkpoint_ms: u64,
+    pub lot_accounting: Option<LotPolicy>,
+    pub control_listen_addr: Option<String>,
+    pub control_token: Option<String>,
+
+    // Storage
+    pub storage_backend: StorageBackend,
+    pub storage_path: String,
+    pub track_pending_transactions: bool,
+
+    // Fleet Coordination
+    pub redis_url: Option<String>,
+    pub claim_ttl_secs: u64,
 
     // Simulation Mode
     pub simulation_mode: bool,
+    pub read_only: bool,
+    pub watchlist_only: bool,
+    pub paper_profiles: Vec<String>,
+    pub paper_starting_balance_sol: f64,
+    pub paper_check_interval_ms: u64,
+
+    #[serde(skip)]
+    pub version_hash: ConfigVersion,
+}
+
+/// Settings left out of the config version: credentials, and endpoints that may embed them
+const UNVERSIONED_FIELDS: &[&str] = &[
+    "rpc_url",
+    "ws_url",
+    "extra_ws_urls",
+    "private_key",
+    "main_wallet_private_key",
+    "enrichment_url",
+    "enrichment_api_key",
+    "jito_block_engine_url",
+    "telegram_bot_token",
+    "control_token",
+    "redis_url",
+    "otlp_endpoint",
+];
+
+/// A config's version, hashed on first use
+///
+/// A config is not edited once shared, so the hash holds for its lifetime. A clone may
+/// still be edited, so it starts without one.
+#[derive(Debug, Default)]
+pub struct ConfigVersion(std::sync::OnceLock<String>);
+
+impl Clone for ConfigVersion {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+/// Backend used to persist positions and trade history
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StorageBackend {
+    Memory,
+    Json,
+    Sqlite,
+}
+
+impl std::str::FromStr for StorageBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "memory" => Ok(StorageBackend::Memory),
+            "json" => Ok(StorageBackend::Json),
+            "sqlite" => Ok(StorageBackend::Sqlite),
+            other => Err(format!("Unknown STORAGE_BACKEND: {}", other)),
+        }
+    }
+}
+
+/// Version of the Pump.fun instruction account list to build against
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AccountLayout {
+    /// Original IDL: rent sysvar, no creator vault
+    Legacy,
+    /// Current IDL: creator fee vault on buys and sells
+    CreatorFee,
+}
+
+impl std::str::FromStr for AccountLayout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "legacy" => Ok(AccountLayout::Legacy),
+            "creator_fee" => Ok(AccountLayout::CreatorFee),
+            other => Err(format!("Unknown ACCOUNT_LAYOUT: {}", other)),
+        }
+    }
+}
+
+/// External token data service queried during analysis
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EnrichmentProvider {
+    /// DexScreener token pairs (`/tokens/v1/solana/{mint}`)
+    DexScreener,
+    /// Birdeye token overview (`/defi/token_overview?address={mint}`)
+    Birdeye,
+}
+
+impl EnrichmentProvider {
+    /// Public endpoint for the provider, with `{mint}` standing in for the token address
+    pub fn default_url(&self) -> &'static str {
+        match self {
+            EnrichmentProvider::DexScreener => "https://api.dexscreener.com/tokens/v1/solana/{mint}",
+            EnrichmentProvider::Birdeye => "https://public-api.birdeye.so/defi/token_overview?address={mint}",
+        }
+    }
+}
+
+impl std::str::FromStr for EnrichmentProvider {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "dexscreener" => Ok(EnrichmentProvider::DexScreener),
+            "birdeye" => Ok(EnrichmentProvider::Birdeye),
+            other => Err(format!("Unknown ENRICHMENT_PROVIDER: {}", other)),
+        }
+    }
+}
+
+/// What to do once the RPC has been unhealthy for `health_failure_after_ms`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HealthFailureAction {
+    /// Alert only
+    Alert,
+    /// Alert and stop new buys until the RPC recovers
+    Halt,
+    /// Alert, stop new buys and sell every open position
+    Liquidate,
+}
+
+impl std::str::FromStr for HealthFailureAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "alert" => Ok(HealthFailureAction::Alert),
+            "halt" => Ok(HealthFailureAction::Halt),
+            "liquidate" => Ok(HealthFailureAction::Liquidate),
+            other => Err(format!("Unknown HEALTH_FAILURE_ACTION: {}", other)),
+        }
+    }
+}
+
+/// What a buy's configured size is expressed in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BuyMode {
+    /// Spend `buy_amount_sol`, taking whatever tokens it buys
+    BySol,
+    /// Buy exactly `buy_amount_tokens`, with the quoted cost plus slippage as the SOL ceiling
+    ByTokens,
+}
+
+impl std::str::FromStr for BuyMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "by_sol" => Ok(BuyMode::BySol),
+            "by_tokens" => Ok(BuyMode::ByTokens),
+            other => Err(format!("Unknown BUY_MODE: {}", other)),
+        }
+    }
+}
+
+/// Order in which sells consume a position's lots
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LotPolicy {
+    /// Oldest lot first
+    Fifo,
+    /// Newest lot first
+    Lifo,
+}
+
+impl std::str::FromStr for LotPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "fifo" => Ok(LotPolicy::Fifo),
+            "lifo" => Ok(LotPolicy::Lifo),
+            other => Err(format!("Unknown LOT_ACCOUNTING: {}", other)),
+        }
+    }
+}
+
+/// Solana cluster the RPC endpoint serves
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Cluster {
+    Mainnet,
+    Devnet,
+    Testnet,
+}
+
+impl Cluster {
+    /// Identify a public cluster from its genesis hash; `None` for local or private clusters
+    pub fn from_genesis_hash(genesis_hash: &str) -> Option<Self> {
+        match genesis_hash {
+            constants::MAINNET_GENESIS_HASH => Some(Cluster::Mainnet),
+            constants::DEVNET_GENESIS_HASH => Some(Cluster::Devnet),
+            constants::TESTNET_GENESIS_HASH => Some(Cluster::Testnet),
+            _ => None,
+        }
+    }
+}
+
+impl std::str::FromStr for Cluster {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "mainnet" | "mainnet-beta" => Ok(Cluster::Mainnet),
+            "devnet" => Ok(Cluster::Devnet),
+            "testnet" => Ok(Cluster::Testnet),
+            other => Err(format!("Unknown CLUSTER: {}", other)),
+        }
+    }
+}
+
+/// Buy size used once the wallet balance reaches `min_balance_sol`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BalanceTier {
+    pub min_balance_sol: f64,
+    pub buy_amount_sol: f64,
+}
+
+impl std::str::FromStr for BalanceTier {
+    type Err = String;
+
+    /// Parse `min_balance:buy_amount`, e.g. `2:0.1`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid BALANCE_TIERS entry (expected min_balance:buy_amount): {}", s);
+
+        let (min_balance, buy_amount) = s.split_once(':').ok_or_else(invalid)?;
+        let number = |value: &str| value.trim().parse::<f64>().map_err(|_| invalid());
+
+        Ok(BalanceTier {
+            min_balance_sol: number(min_balance)?,
+            buy_amount_sol: number(buy_amount)?,
+        })
+    }
+}
+
+/// Take-profit and stop-loss for tokens entered within a market-cap band
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExitPreset {
+    pub min_market_cap: f64,
+    pub max_market_cap: f64,
+    pub take_profit_percentage: f64,
+    pub stop_loss_percentage: f64,
+}
+
+impl ExitPreset {
+    /// Whether a market cap falls in this preset's band (upper bound exclusive)
+    pub fn contains(&self, market_cap: f64) -> bool {
+        market_cap >= self.min_market_cap && market_cap < self.max_market_cap
+    }
+}
+
+impl std::str::FromStr for ExitPreset {
+    type Err = String;
+
+    /// Parse `min-max:take_profit:stop_loss`, e.g. `0-5000:200:40`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid EXIT_PRESETS entry (expected min-max:tp:sl): {}", s);
+
+        let mut parts = s.split(':');
+        let (band, tp, sl) = match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(band), Some(tp), Some(sl), None) => (band, tp, sl),
+            _ => return Err(invalid()),
+        };
+        let (min, max) = band.split_once('-').ok_or_else(invalid)?;
+        let number = |value: &str| value.trim().parse::<f64>().map_err(|_| invalid());
+
+        Ok(ExitPreset {
+            min_market_cap: number(min)?,
+            max_market_cap: number(max)?,
+            take_profit_percentage: number(tp)?,
+            stop_loss_percentage: number(sl)?,
+        })
+    }
+}
+
+impl BotConfig {
+    /// Short stable hash of the trading settings, identifying the config a decision was made under
+    ///
+    /// Credentials and endpoints are left out, so the version never depends on a secret.
+    pub fn version(&self) -> &str {
+        self.version_hash.0.get_or_init(|| {
+            let mut settings = serde_json::to_value(self).unwrap_or_default();
+            if let Some(settings) = settings.as_object_mut() {
+                for field in UNVERSIONED_FIELDS {
+                    settings.remove(*field);
+                }
+            }
+            solana_sdk::hash::hash(settings.to_string().as_bytes()).to_string()[..12].to_string()
+        })
+    }
+
+    /// Tag for positions opened from `source`, falling back to the source name
+    pub fn position_tag(&self, source: &str) -> String {
+        self.position_tags.get(source).cloned().unwrap_or_else(|| source.to_string())
+    }
+
+    /// Buy size multiplier after `losses` consecutive losing trades
+    ///
+    /// Each loss from `loss_streak_threshold` on multiplies the size by `loss_streak_size_factor`.
+    pub fn loss_streak_scale(&self, losses: u32) -> f64 {
+        match self.loss_streak_threshold {
+            Some(threshold) if threshold > 0 && losses >= threshold => {
+                self.loss_streak_size_factor.powi((losses - threshold + 1) as i32)
+            }
+            _ => 1.0,
+        }
+    }
+
+    /// Real SOL reserves, in lamports, at which a bonding curve graduates
+    pub fn graduation_lamports(&self) -> u64 {
+        (self.graduation_sol_target * constants::LAMPORTS_PER_SOL as f64) as u64
+    }
+
+    /// Jito tip for a buy, scaled linearly with the opportunity score between the min and max
+    ///
+    /// Zero when no maximum tip is configured.
+    pub fn jito_tip_lamports(&self, opportunity_score: u32) -> u64 {
+        let max = match self.jito_tip_max_lamports {
+            Some(max) => max.max(self.jito_tip_min_lamports),
+            None => return 0,
+        };
+        let span = (max - self.jito_tip_min_lamports) as f64;
+        self.jito_tip_min_lamports + (span * opportunity_score.min(100) as f64 / 100.0) as u64
+    }
+
+    /// Effective slippage in basis points, preferring `max_slippage_bps` when set
+    pub fn slippage_bps(&self) -> u64 {
+        self.max_slippage_bps.unwrap_or_else(|| percent_to_bps(self.max_slippage))
+    }
+
+    /// Slippage for the one retry after a slippage-exceeded buy, if enabled
+    ///
+    /// Doubles the effective slippage, bounded by `slippage_retry_max_bps` and `max_slippage`.
+    pub fn bumped_slippage_bps(&self) -> Option<u64> {
+        self.slippage_retry_max_bps
+            .map(|max| (self.slippage_bps() * 2).min(max).min(percent_to_bps(self.max_slippage)))
+            .filter(|bumped| *bumped > self.slippage_bps())
+    }
+
+    /// Effective take-profit in basis points, preferring `take_profit_bps` when set
+    pub fn take_profit_bps(&self) -> u64 {
+        self.take_profit_bps.unwrap_or_else(|| percent_to_bps(self.take_profit_percentage))
+    }
+
+    /// Effective stop-loss in basis points, preferring `stop_loss_bps` when set
+    pub fn stop_loss_bps(&self) -> u64 {
+        self.stop_loss_bps.unwrap_or_else(|| percent_to_bps(self.stop_loss_percentage))
+    }
+
+    /// Stop-loss in basis points scaled by safety score within `safety_stop_loss_range`
+    ///
+    /// The minimum safety score that passes the filters maps to the tightest stop and a
+    /// perfect score to the widest. Returns `None` when no range is configured.
+    pub fn safety_scaled_stop_loss_bps(&self, safety_score: u32) -> Option<u64> {
+        use crate::utils::safety_checker::MIN_SAFETY_SCORE;
+
+        let (min_pct, max_pct) = self.safety_stop_loss_range?;
+        let span = (100 - MIN_SAFETY_SCORE) as f64;
+        let conviction = (safety_score.saturating_sub(MIN_SAFETY_SCORE) as f64 / span).clamp(0.0, 1.0);
+        Some(percent_to_bps(min_pct + (max_pct - min_pct) * conviction))
+    }
+
+    /// Take-profit and stop-loss in basis points for a token entered at `market_cap`
+    ///
+    /// Uses the first matching exit preset, falling back to the flat settings.
+    pub fn exit_levels_bps(&self, market_cap: f64) -> (u64, u64) {
+        match self.exit_presets.iter().find(|preset| preset.contains(market_cap)) {
+            Some(preset) => (
+                percent_to_bps(preset.take_profit_percentage),
+                percent_to_bps(preset.stop_loss_percentage),
+            ),
+            None => (self.take_profit_bps(), self.stop_loss_bps()),
+        }
+    }
+
+    /// Whether buy size follows the wallet balance rather than `buy_amount_sol`
+    pub fn sizes_by_balance(&self) -> bool {
+        !self.balance_tiers.is_empty() || self.buy_balance_pct.is_some()
+    }
+
+    /// Buy size for a wallet balance, clamped to the configured min/max
+    ///
+    /// The highest tier the balance reaches wins; below every tier, or without
+    /// tiers, `buy_balance_pct` of the balance is used, then `buy_amount_sol`.
+    pub fn buy_amount_for_balance(&self, balance_sol: f64) -> f64 {
+        let tier = self.balance_tiers
+            .iter()
+            .filter(|tier| balance_sol >= tier.min_balance_sol)
+            .max_by(|a, b| a.min_balance_sol.total_cmp(&b.min_balance_sol));
+
+        let amount = match (tier, self.buy_balance_pct) {
+            (Some(tier), _) => tier.buy_amount_sol,
+            (None, Some(pct)) => balance_sol * pct / 100.0,
+            (None, None) => self.buy_amount_sol,
+        };
+
+        amount
+            .max(self.min_buy_amount_sol)
+            .min(self.max_buy_amount_sol.unwrap_or(f64::INFINITY))
+    }
+}
+
+/// Convert a percentage to whole basis points
+pub(crate) fn percent_to_bps(percentage: f64) -> u64 {
+    (percentage * 100.0).round().max(0.0) as u64
 }
 
 impl Default for BotConfig {
@@ -52,43 +590,178 @@ impl Default for BotConfig {
             // Solana Configuration
             rpc_url: "https://api.mainnet-beta.solana.com".to_string(),
             ws_url: None,
+            extra_ws_urls: Vec::new(),
+            source_priority: Vec::new(),
+            cluster: None,
 
             // Wallet Configuration
             private_key: None,
             main_wallet_private_key: None,
+            external_signer_socket: None,
 
             // Trading Configuration
             buy_amount_sol: 0.1,
+            buy_mode: BuyMode::BySol,
+            buy_amount_tokens: 0,
+            balance_tiers: Vec::new(),
+            buy_balance_pct: None,
+            min_buy_amount_sol: 0.0,
+            max_buy_amount_sol: None,
+            loss_streak_threshold: None,
+            loss_streak_size_factor: 0.5,
             min_liquidity: 5.0,
             max_slippage: 25.0,
+            max_slippage_bps: None,
+            slippage_retry_max_bps: None,
+            emergency_slippage_bps: 5_000,
+            check_token_account_before_buy: true,
+            amm_route_progress_pct: 100.0,
+            graduation_sol_target: constants::GRADUATION_SOL_RESERVES as f64 / constants::LAMPORTS_PER_SOL as f64,
+            scout_then_scale: false,
+            scout_amount_sol: 0.005,
+            scout_sell_percentage: 50.0,
+            sell_price_guard_pct: None,
+            buy_price_guard_pct: None,
             take_profit_percentage: 100.0,
+            take_profit_bps: None,
+            min_net_profit_sol: 0.0,
             stop_loss_percentage: 30.0,
+            stop_loss_bps: None,
+            safety_stop_loss_range: None,
+            exit_presets: Vec::new(),
             trailing_stop_loss_percentage: 10.0,
+            trailing_activation_pct: 20.0,
+            volume_drop_exit_fraction: None,
+            volume_window_secs: 60,
+            exit_rules: Vec::new(),
+            recover_cost_at_pct: None,
+            sell_on_migration: false,
+            sell_on_creator_dump: false,
+            insider_wallets: Vec::new(),
 
             // Safety Settings
             trading_cooldown_ms: 5000,
+            startup_warmup_ms: 0,
             max_loss_per_trade_sol: 0.5,
+            max_daily_loss_sol: None,
             max_trades_per_hour: 10,
+            max_exposure_per_token_sol: None,
+            min_hold_seconds: 0,
+            min_hold_applies_to_take_profit: false,
+            max_total_exposure_sol: None,
+            safety_recheck_interval_ms: 0,
+            safety_recheck_min_score: 40,
+            max_inflight_analyses: 0,
+            max_event_age_secs: None,
+            catch_up_minutes: None,
+            adopt_untracked_holdings: false,
+            max_notifications_per_sec: 0,
+            max_ws_message_bytes: 4 * 1024 * 1024,
+            health_failure_after_ms: None,
+            health_failure_action: HealthFailureAction::Halt,
+            health_check_interval_ms: 5000,
 
             // Token Filtering
             min_market_cap: 1000.0,
             max_market_cap: 50000.0,
+            min_buy_price: None,
+            max_buy_price: None,
+            max_price_impact_pct: None,
+            max_exit_impact_pct: None,
+            max_curve_idle_secs: None,
+            min_unique_buyers: None,
+            exclude_creator_from_buyers: true,
+            buyer_observation_ms: 3_000,
+            max_creation_slot_buyers: None,
+            reject_bundle_launches: false,
+            allow_token_2022: true,
+            min_opportunity_score: 0,
             min_holders: 10,
             max_holders: 1000,
             require_social_links: false,
             require_creator_verification: false,
+            creator_buy_bonus_min_sol: 0.5,
+            creator_buy_penalty_max_sol: 5.0,
+            creator_buy_bonus: 10,
+            creator_buy_penalty: 20,
+            enrichment_provider: None,
+            enrichment_url: None,
+            enrichment_api_key: None,
+            enrichment_timeout_ms: 1500,
+
+            // Filter Tuning
+            shadow_track_rejected: false,
+            shadow_window_ms: 30 * 60 * 1000,
+            shadow_refresh_ms: 10_000,
 
             // Gas Optimization
             priority_fee_lamports: 10000,
             max_priority_fee_lamports: 100000,
+            max_send_retries: 2,
+            balance_tolerance_pct: 5.0,
+            fee_reserve_sol: 0.01,
+            max_fee_ratio_pct: None,
+            min_sell_proceeds_sol: 0.0,
+            batch_sells: false,
+            max_sells_per_batch: 4,
+            require_buy_finalization: true,
+            buy_count_commitment: solana_sdk::commitment_config::CommitmentLevel::Confirmed,
+            require_sell_confirmation: true,
+            priority_fee_escalation_multiplier: 1.5,
+            adaptive_priority_fee: false,
+            adaptive_fee_min_lamports: 1000,
+            blockhash_refresh_ms: 2000,
+            blockhash_max_age_ms: 10_000,
+            metadata_fetch_timeout_ms: 1500,
+            curve_not_found_retries: 3,
+            curve_not_found_retry_delay_ms: 200,
+            jito_tip_min_lamports: 10_000,
+            jito_tip_max_lamports: None,
+            jito_block_engine_url: None,
+
+            // Pump.fun Program
+            fee_recipients: Vec::new(),
+            fee_recipient_refresh_ms: 10 * 60 * 1000,
+            account_layout: AccountLayout::CreatorFee,
+            prepared_buys: Vec::new(),
 
             // Monitoring
             log_level: "info".to_string(),
             telegram_bot_token: None,
             telegram_chat_id: None,
+            event_log_path: None,
+            otlp_endpoint: None,
+            otel_service_name: "pumpfun-sniper".to_string(),
+            debug_dump_failed_tx: false,
+            curve_subscriptions: false,
+            max_curve_subscriptions: None,
+            curve_reconnect_delay_ms: 2000,
+            latency_window: 1000,
+            position_tags: std::collections::BTreeMap::new(),
+            report_dir: None,
+            report_checkpoint_ms: 60_000,
+            lot_accounting: None,
+            control_listen_addr: None,
+            control_token: None,
+
+            // Storage
+            storage_backend: StorageBackend::Memory,
+            storage_path: "data/positions.json".to_string(),
+            track_pending_transactions: true,
+
+            // Fleet Coordination
+            redis_url: None,
+            claim_ttl_secs: 300,
 
             // Simulation Mode
             simulation_mode: true,
+            read_only: false,
+            watchlist_only: false,
+            paper_profiles: Vec::new(),
+            paper_starting_balance_sol: 10.0,
+            paper_check_interval_ms: 1000,
+
+            version_hash: ConfigVersion::default(),
         }
     }
 }
@@ -96,7 +769,7 @@ impl Default for BotConfig {
 /// Load configuration from environment variables
 pub fn load_config() -> Result<BotConfig, Box<dyn std::error::Error>> {
     // Load .env file if it exists
-    dotenv::dotenv().ok();
+    dotenvy::dotenv().ok();
 
     let mut config = BotConfig::default();
 
@@ -107,41 +780,222 @@ pub fn load_config() -> Result<BotConfig, Box<dyn std::error::Error>> {
     if let Ok(ws_url) = env::var("WS_URL") {
         config.ws_url = Some(ws_url);
     }
+    if let Ok(val) = env::var("EXTRA_WS_URLS") {
+        config.extra_ws_urls = val
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+    }
+    if let Ok(val) = env::var("SOURCE_PRIORITY") {
+        config.source_priority = val
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+    }
+    if let Ok(val) = env::var("CLUSTER") {
+        config.cluster = Some(val.parse()?);
+    }
 
     // Wallet Configuration
     config.private_key = env::var("PRIVATE_KEY").ok();
     config.main_wallet_private_key = env::var("MAIN_WALLET_PRIVATE_KEY").ok();
+    config.external_signer_socket = env::var("EXTERNAL_SIGNER_SOCKET").ok();
 
     // Trading Configuration
     if let Ok(val) = env::var("BUY_AMOUNT_SOL") {
         config.buy_amount_sol = val.parse()?;
     }
+    if let Ok(val) = env::var("BUY_MODE") {
+        config.buy_mode = val.parse()?;
+    }
+    if let Ok(val) = env::var("BUY_AMOUNT_TOKENS") {
+        config.buy_amount_tokens = val.parse()?;
+    }
+    if let Ok(val) = env::var("BALANCE_TIERS") {
+        config.balance_tiers = val
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::parse)
+            .collect::<Result<_, _>>()?;
+    }
+    if let Ok(val) = env::var("BUY_BALANCE_PCT") {
+        config.buy_balance_pct = Some(val.parse()?);
+    }
+    if let Ok(val) = env::var("MIN_BUY_AMOUNT_SOL") {
+        config.min_buy_amount_sol = val.parse()?;
+    }
+    if let Ok(val) = env::var("MAX_BUY_AMOUNT_SOL") {
+        config.max_buy_amount_sol = Some(val.parse()?);
+    }
+    if let Ok(val) = env::var("LOSS_STREAK_THRESHOLD") {
+        config.loss_streak_threshold = Some(val.parse()?);
+    }
+    if let Ok(val) = env::var("LOSS_STREAK_SIZE_FACTOR") {
+        config.loss_streak_size_factor = val.parse()?;
+    }
     if let Ok(val) = env::var("MIN_LIQUIDITY") {
         config.min_liquidity = val.parse()?;
     }
     if let Ok(val) = env::var("MAX_SLIPPAGE") {
         config.max_slippage = val.parse()?;
     }
+    if let Ok(val) = env::var("MAX_SLIPPAGE_BPS") {
+        config.max_slippage_bps = Some(val.parse()?);
+    }
+    if let Ok(val) = env::var("EMERGENCY_SLIPPAGE_BPS") {
+        config.emergency_slippage_bps = val.parse()?;
+    }
+    if let Ok(val) = env::var("SLIPPAGE_RETRY_MAX_BPS") {
+        config.slippage_retry_max_bps = Some(val.parse()?);
+    }
+    if let Ok(val) = env::var("BUY_PRICE_GUARD_PCT") {
+        config.buy_price_guard_pct = Some(val.parse()?);
+    }
+    if let Ok(val) = env::var("SELL_PRICE_GUARD_PCT") {
+        config.sell_price_guard_pct = Some(val.parse()?);
+    }
+    if let Ok(val) = env::var("CHECK_TOKEN_ACCOUNT_BEFORE_BUY") {
+        config.check_token_account_before_buy = val.parse()?;
+    }
+    if let Ok(val) = env::var("AMM_ROUTE_PROGRESS_PCT") {
+        config.amm_route_progress_pct = val.parse()?;
+    }
+    if let Ok(val) = env::var("GRADUATION_SOL_TARGET") {
+        config.graduation_sol_target = val.parse()?;
+    }
+    if let Ok(val) = env::var("SCOUT_THEN_SCALE") {
+        config.scout_then_scale = val.parse()?;
+    }
+    if let Ok(val) = env::var("SCOUT_AMOUNT_SOL") {
+        config.scout_amount_sol = val.parse()?;
+    }
+    if let Ok(val) = env::var("SCOUT_SELL_PERCENTAGE") {
+        config.scout_sell_percentage = val.parse()?;
+    }
     if let Ok(val) = env::var("TAKE_PROFIT_PERCENTAGE") {
         config.take_profit_percentage = val.parse()?;
     }
+    if let Ok(val) = env::var("TAKE_PROFIT_BPS") {
+        config.take_profit_bps = Some(val.parse()?);
+    }
+    if let Ok(val) = env::var("MIN_NET_PROFIT_SOL") {
+        config.min_net_profit_sol = val.parse()?;
+    }
     if let Ok(val) = env::var("STOP_LOSS_PERCENTAGE") {
         config.stop_loss_percentage = val.parse()?;
     }
+    if let Ok(val) = env::var("STOP_LOSS_BPS") {
+        config.stop_loss_bps = Some(val.parse()?);
+    }
+    if let Ok(val) = env::var("SAFETY_STOP_LOSS_RANGE") {
+        let (min, max) = val
+            .split_once('-')
+            .ok_or("SAFETY_STOP_LOSS_RANGE must be min-max, e.g. 15-40")?;
+        config.safety_stop_loss_range = Some((min.trim().parse()?, max.trim().parse()?));
+    }
+    if let Ok(val) = env::var("EXIT_PRESETS") {
+        config.exit_presets = val
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::parse)
+            .collect::<Result<_, _>>()?;
+    }
     if let Ok(val) = env::var("TRAILING_STOP_LOSS_PERCENTAGE") {
         config.trailing_stop_loss_percentage = val.parse()?;
     }
+    if let Ok(val) = env::var("TRAILING_ACTIVATION_PCT") {
+        config.trailing_activation_pct = val.parse()?;
+    }
+    if let Ok(val) = env::var("VOLUME_DROP_EXIT_FRACTION") {
+        config.volume_drop_exit_fraction = Some(val.parse()?);
+    }
+    if let Ok(val) = env::var("VOLUME_WINDOW_SECS") {
+        config.volume_window_secs = val.parse()?;
+    }
+    if let Ok(val) = env::var("EXIT_RULES") {
+        config.exit_rules = crate::traders::exit_rules::parse_rules(&val)?;
+    }
+    if let Ok(val) = env::var("RECOVER_COST_AT_PCT") {
+        config.recover_cost_at_pct = Some(val.parse()?);
+    }
+    if let Ok(val) = env::var("SELL_ON_MIGRATION") {
+        config.sell_on_migration = val.parse()?;
+    }
+    if let Ok(val) = env::var("SELL_ON_CREATOR_DUMP") {
+        config.sell_on_creator_dump = val.parse()?;
+    }
+    if let Ok(val) = env::var("INSIDER_WALLETS") {
+        config.insider_wallets = val.split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+    }
 
     // Safety Settings
     if let Ok(val) = env::var("TRADING_COOLDOWN_MS") {
         config.trading_cooldown_ms = val.parse()?;
     }
+    if let Ok(val) = env::var("STARTUP_WARMUP_MS") {
+        config.startup_warmup_ms = val.parse()?;
+    }
     if let Ok(val) = env::var("MAX_LOSS_PER_TRADE_SOL") {
         config.max_loss_per_trade_sol = val.parse()?;
     }
+    if let Ok(val) = env::var("MAX_DAILY_LOSS_SOL") {
+        config.max_daily_loss_sol = Some(val.parse()?);
+    }
     if let Ok(val) = env::var("MAX_TRADES_PER_HOUR") {
         config.max_trades_per_hour = val.parse()?;
     }
+    if let Ok(val) = env::var("MIN_HOLD_SECONDS") {
+        config.min_hold_seconds = val.parse()?;
+    }
+    if let Ok(val) = env::var("MIN_HOLD_APPLIES_TO_TAKE_PROFIT") {
+        config.min_hold_applies_to_take_profit = val.parse()?;
+    }
+    if let Ok(val) = env::var("MAX_EXPOSURE_PER_TOKEN_SOL") {
+        config.max_exposure_per_token_sol = Some(val.parse()?);
+    }
+    if let Ok(val) = env::var("MAX_TOTAL_EXPOSURE_SOL") {
+        config.max_total_exposure_sol = Some(val.parse()?);
+    }
+    if let Ok(val) = env::var("SAFETY_RECHECK_INTERVAL_MS") {
+        config.safety_recheck_interval_ms = val.parse()?;
+    }
+    if let Ok(val) = env::var("SAFETY_RECHECK_MIN_SCORE") {
+        config.safety_recheck_min_score = val.parse()?;
+    }
+    if let Ok(val) = env::var("ADOPT_UNTRACKED_HOLDINGS") {
+        config.adopt_untracked_holdings = val.parse()?;
+    }
+    if let Ok(val) = env::var("MAX_INFLIGHT_ANALYSES") {
+        config.max_inflight_analyses = val.parse()?;
+    }
+    if let Ok(val) = env::var("MAX_EVENT_AGE_SECS") {
+        config.max_event_age_secs = Some(val.parse()?);
+    }
+    if let Ok(val) = env::var("CATCH_UP_MINUTES") {
+        config.catch_up_minutes = Some(val.parse()?);
+    }
+    if let Ok(val) = env::var("MAX_NOTIFICATIONS_PER_SEC") {
+        config.max_notifications_per_sec = val.parse()?;
+    }
+    if let Ok(val) = env::var("MAX_WS_MESSAGE_BYTES") {
+        config.max_ws_message_bytes = val.parse()?;
+    }
+    if let Ok(val) = env::var("HEALTH_FAILURE_AFTER_MS") {
+        config.health_failure_after_ms = Some(val.parse()?);
+    }
+    if let Ok(val) = env::var("HEALTH_FAILURE_ACTION") {
+        config.health_failure_action = val.parse()?;
+    }
+    if let Ok(val) = env::var("HEALTH_CHECK_INTERVAL_MS") {
+        config.health_check_interval_ms = val.parse()?;
+    }
 
     // Token Filtering
     if let Ok(val) = env::var("MIN_MARKET_CAP") {
@@ -150,6 +1004,42 @@ pub fn load_config() -> Result<BotConfig, Box<dyn std::error::Error>> {
     if let Ok(val) = env::var("MAX_MARKET_CAP") {
         config.max_market_cap = val.parse()?;
     }
+    if let Ok(val) = env::var("MIN_BUY_PRICE") {
+        config.min_buy_price = Some(val.parse()?);
+    }
+    if let Ok(val) = env::var("MAX_BUY_PRICE") {
+        config.max_buy_price = Some(val.parse()?);
+    }
+    if let Ok(val) = env::var("MAX_PRICE_IMPACT_PCT") {
+        config.max_price_impact_pct = Some(val.parse()?);
+    }
+    if let Ok(val) = env::var("MAX_EXIT_IMPACT_PCT") {
+        config.max_exit_impact_pct = Some(val.parse()?);
+    }
+    if let Ok(val) = env::var("MAX_CURVE_IDLE_SECS") {
+        config.max_curve_idle_secs = Some(val.parse()?);
+    }
+    if let Ok(val) = env::var("MIN_UNIQUE_BUYERS") {
+        config.min_unique_buyers = Some(val.parse()?);
+    }
+    if let Ok(val) = env::var("EXCLUDE_CREATOR_FROM_BUYERS") {
+        config.exclude_creator_from_buyers = val.parse()?;
+    }
+    if let Ok(val) = env::var("BUYER_OBSERVATION_MS") {
+        config.buyer_observation_ms = val.parse()?;
+    }
+    if let Ok(val) = env::var("MAX_CREATION_SLOT_BUYERS") {
+        config.max_creation_slot_buyers = Some(val.parse()?);
+    }
+    if let Ok(val) = env::var("REJECT_BUNDLE_LAUNCHES") {
+        config.reject_bundle_launches = val.parse()?;
+    }
+    if let Ok(val) = env::var("ALLOW_TOKEN_2022") {
+        config.allow_token_2022 = val.parse()?;
+    }
+    if let Ok(val) = env::var("MIN_OPPORTUNITY_SCORE") {
+        config.min_opportunity_score = val.parse()?;
+    }
     if let Ok(val) = env::var("MIN_HOLDERS") {
         config.min_holders = val.parse()?;
     }
@@ -162,6 +1052,37 @@ pub fn load_config() -> Result<BotConfig, Box<dyn std::error::Error>> {
     if let Ok(val) = env::var("REQUIRE_CREATOR_VERIFICATION") {
         config.require_creator_verification = val.parse()?;
     }
+    if let Ok(val) = env::var("CREATOR_BUY_BONUS_MIN_SOL") {
+        config.creator_buy_bonus_min_sol = val.parse()?;
+    }
+    if let Ok(val) = env::var("CREATOR_BUY_PENALTY_MAX_SOL") {
+        config.creator_buy_penalty_max_sol = val.parse()?;
+    }
+    if let Ok(val) = env::var("CREATOR_BUY_BONUS") {
+        config.creator_buy_bonus = val.parse()?;
+    }
+    if let Ok(val) = env::var("CREATOR_BUY_PENALTY") {
+        config.creator_buy_penalty = val.parse()?;
+    }
+    if let Ok(val) = env::var("ENRICHMENT_PROVIDER") {
+        config.enrichment_provider = Some(val.parse()?);
+    }
+    config.enrichment_url = env::var("ENRICHMENT_URL").ok();
+    config.enrichment_api_key = env::var("ENRICHMENT_API_KEY").ok();
+    if let Ok(val) = env::var("ENRICHMENT_TIMEOUT_MS") {
+        config.enrichment_timeout_ms = val.parse()?;
+    }
+
+    // Filter Tuning
+    if let Ok(val) = env::var("SHADOW_TRACK_REJECTED") {
+        config.shadow_track_rejected = val.parse()?;
+    }
+    if let Ok(val) = env::var("SHADOW_WINDOW_MS") {
+        config.shadow_window_ms = val.parse()?;
+    }
+    if let Ok(val) = env::var("SHADOW_REFRESH_MS") {
+        config.shadow_refresh_ms = val.parse()?;
+    }
 
     // Gas Optimization
     if let Ok(val) = env::var("PRIORITY_FEE_LAMPORTS") {
@@ -170,6 +1091,89 @@ pub fn load_config() -> Result<BotConfig, Box<dyn std::error::Error>> {
     if let Ok(val) = env::var("MAX_PRIORITY_FEE_LAMPORTS") {
         config.max_priority_fee_lamports = val.parse()?;
     }
+    if let Ok(val) = env::var("MAX_SEND_RETRIES") {
+        config.max_send_retries = val.parse()?;
+    }
+    if let Ok(val) = env::var("REQUIRE_BUY_FINALIZATION") {
+        config.require_buy_finalization = val.parse()?;
+    }
+    if let Ok(val) = env::var("BUY_COUNT_COMMITMENT") {
+        config.buy_count_commitment = val.parse()?;
+    }
+    if let Ok(val) = env::var("REQUIRE_SELL_CONFIRMATION") {
+        config.require_sell_confirmation = val.parse()?;
+    }
+    if let Ok(val) = env::var("BALANCE_TOLERANCE_PCT") {
+        config.balance_tolerance_pct = val.parse()?;
+    }
+    if let Ok(val) = env::var("FEE_RESERVE_SOL") {
+        config.fee_reserve_sol = val.parse()?;
+    }
+    if let Ok(val) = env::var("MAX_FEE_RATIO_PCT") {
+        config.max_fee_ratio_pct = Some(val.parse()?);
+    }
+    if let Ok(val) = env::var("MIN_SELL_PROCEEDS_SOL") {
+        config.min_sell_proceeds_sol = val.parse()?;
+    }
+    if let Ok(val) = env::var("BATCH_SELLS") {
+        config.batch_sells = val.parse()?;
+    }
+    if let Ok(val) = env::var("MAX_SELLS_PER_BATCH") {
+        config.max_sells_per_batch = val.parse()?;
+    }
+    if let Ok(val) = env::var("PRIORITY_FEE_ESCALATION_MULTIPLIER") {
+        config.priority_fee_escalation_multiplier = val.parse()?;
+    }
+    if let Ok(val) = env::var("ADAPTIVE_PRIORITY_FEE") {
+        config.adaptive_priority_fee = val.parse()?;
+    }
+    if let Ok(val) = env::var("ADAPTIVE_FEE_MIN_LAMPORTS") {
+        config.adaptive_fee_min_lamports = val.parse()?;
+    }
+    if let Ok(val) = env::var("BLOCKHASH_REFRESH_MS") {
+        config.blockhash_refresh_ms = val.parse()?;
+    }
+    if let Ok(val) = env::var("BLOCKHASH_MAX_AGE_MS") {
+        config.blockhash_max_age_ms = val.parse()?;
+    }
+    if let Ok(val) = env::var("METADATA_FETCH_TIMEOUT_MS") {
+        config.metadata_fetch_timeout_ms = val.parse()?;
+    }
+    if let Ok(val) = env::var("CURVE_NOT_FOUND_RETRIES") {
+        config.curve_not_found_retries = val.parse()?;
+    }
+    if let Ok(val) = env::var("CURVE_NOT_FOUND_RETRY_DELAY_MS") {
+        config.curve_not_found_retry_delay_ms = val.parse()?;
+    }
+    if let Ok(val) = env::var("JITO_TIP_MIN_LAMPORTS") {
+        config.jito_tip_min_lamports = val.parse()?;
+    }
+    if let Ok(val) = env::var("JITO_TIP_MAX_LAMPORTS") {
+        config.jito_tip_max_lamports = Some(val.parse()?);
+    }
+    config.jito_block_engine_url = env::var("JITO_BLOCK_ENGINE_URL").ok();
+
+    // Pump.fun Program
+    if let Ok(val) = env::var("FEE_RECIPIENTS") {
+        config.fee_recipients = val
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+    }
+    if let Ok(val) = env::var("FEE_RECIPIENT_REFRESH_MS") {
+        config.fee_recipient_refresh_ms = val.parse()?;
+    }
+    if let Ok(val) = env::var("ACCOUNT_LAYOUT") {
+        config.account_layout = val.parse()?;
+    }
+    if let Ok(val) = env::var("PREPARED_BUYS") {
+        config.prepared_buys = val
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+    }
 
     // Monitoring
     if let Ok(val) = env::var("LOG_LEVEL") {
@@ -177,11 +1181,97 @@ pub fn load_config() -> Result<BotConfig, Box<dyn std::error::Error>> {
     }
     config.telegram_bot_token = env::var("TELEGRAM_BOT_TOKEN").ok();
     config.telegram_chat_id = env::var("TELEGRAM_CHAT_ID").ok();
+    config.event_log_path = env::var("EVENT_LOG_PATH").ok();
+    config.otlp_endpoint = env::var("OTLP_ENDPOINT").ok();
+    if let Ok(val) = env::var("OTEL_SERVICE_NAME") {
+        config.otel_service_name = val;
+    }
+    if let Ok(val) = env::var("DEBUG_DUMP_FAILED_TX") {
+        config.debug_dump_failed_tx = val.parse()?;
+    }
+    if let Ok(val) = env::var("CURVE_SUBSCRIPTIONS") {
+        config.curve_subscriptions = val.parse()?;
+    }
+    if let Ok(val) = env::var("MAX_CURVE_SUBSCRIPTIONS") {
+        config.max_curve_subscriptions = Some(val.parse()?);
+    }
+    if let Ok(val) = env::var("CURVE_RECONNECT_DELAY_MS") {
+        config.curve_reconnect_delay_ms = val.parse()?;
+    }
+    if let Ok(val) = env::var("LATENCY_WINDOW") {
+        config.latency_window = val.parse()?;
+    }
+    if let Ok(val) = env::var("POSITION_TAGS") {
+        config.position_tags = val
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|pair| {
+                pair.split_once(':')
+                    .map(|(source, tag)| (source.trim().to_string(), tag.trim().to_string()))
+                    .ok_or_else(|| format!("POSITION_TAGS entries must be source:tag, got {}", pair))
+            })
+            .collect::<Result<_, _>>()?;
+    }
+    config.report_dir = env::var("REPORT_DIR").ok();
+    if let Ok(val) = env::var("REPORT_CHECKPOINT_MS") {
+        config.report_checkpoint_ms = val.parse()?;
+    }
+    if let Ok(val) = env::var("LOT_ACCOUNTING") {
+        config.lot_accounting = Some(val.parse()?);
+    }
+    config.control_listen_addr = env::var("CONTROL_LISTEN_ADDR").ok();
+    config.control_token = env::var("CONTROL_TOKEN").ok();
+
+    // Storage
+    if let Ok(val) = env::var("STORAGE_BACKEND") {
+        config.storage_backend = val.parse()?;
+    }
+    if let Ok(val) = env::var("STORAGE_PATH") {
+        config.storage_path = val;
+    }
+    if let Ok(val) = env::var("TRACK_PENDING_TRANSACTIONS") {
+        config.track_pending_transactions = val.parse()?;
+    }
+
+    // Fleet Coordination
+    if let Ok(val) = env::var("REDIS_URL") {
+        config.redis_url = Some(val);
+    }
+    if let Ok(val) = env::var("CLAIM_TTL_SECS") {
+        config.claim_ttl_secs = val.parse()?;
+    }
 
     // Simulation Mode
     if let Ok(val) = env::var("SIMULATION_MODE") {
         config.simulation_mode = val.parse()?;
     }
+    if let Ok(val) = env::var("READ_ONLY") {
+        config.read_only = val.parse()?;
+    }
+    if let Ok(val) = env::var("WATCHLIST_ONLY") {
+        config.watchlist_only = val.parse()?;
+    }
+    if let Ok(val) = env::var("PAPER_PROFILES") {
+        config.paper_profiles = val
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+    }
+    if let Ok(val) = env::var("PAPER_STARTING_BALANCE_SOL") {
+        config.paper_starting_balance_sol = val.parse()?;
+    }
+    if let Ok(val) = env::var("PAPER_CHECK_INTERVAL_MS") {
+        config.paper_check_interval_ms = val.parse()?;
+    }
+
+    // Strategy profile overrides the environment
+    if let Some(profile) = selected_profile() {
+        let path = profiles_path();
+        config = apply_profile(config, &path, &profile)?;
+        tracing::info!("Applied config profile '{}' from {}", profile, path);
+    }
 
     // Validate configuration
     validate_config(&config)?;
@@ -189,10 +1279,123 @@ pub fn load_config() -> Result<BotConfig, Box<dyn std::error::Error>> {
     Ok(config)
 }
 
+/// Get the profile selected with `--profile <name>` or the `PROFILE` variable
+fn selected_profile() -> Option<String> {
+    let args: Vec<String> = env::args().collect();
+    args.iter()
+        .position(|arg| arg == "--profile")
+        .and_then(|pos| args.get(pos + 1).cloned())
+        .or_else(|| env::var("PROFILE").ok())
+}
+
+/// Path of the strategy profiles file, from `PROFILES_PATH`
+pub fn profiles_path() -> String {
+    env::var("PROFILES_PATH").unwrap_or_else(|_| "profiles.toml".to_string())
+}
+
+/// Merge a named profile from a TOML file over the given configuration
+///
+/// The file maps profile names to tables of `BotConfig` fields, e.g.
+/// `[aggressive]` followed by `buy_amount_sol = 0.2`.
+pub fn apply_profile(
+    config: BotConfig,
+    path: &str,
+    profile: &str,
+) -> Result<BotConfig, Box<dyn std::error::Error>> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read profiles file {}: {}", path, e))?;
+    let profiles: toml::Table = contents.parse()?;
+
+    let overrides = profiles
+        .get(profile)
+        .and_then(|value| value.as_table())
+        .ok_or_else(|| {
+            let available: Vec<&String> = profiles.keys().collect();
+            format!("Profile '{}' not found in {} (available: {:?})", profile, path, available)
+        })?;
+
+    let mut merged = serde_json::to_value(&config)?;
+    if let Some(fields) = merged.as_object_mut() {
+        for (key, value) in overrides {
+            if !fields.contains_key(key) {
+                return Err(format!("Unknown config field '{}' in profile '{}'", key, profile).into());
+            }
+            fields.insert(key.clone(), serde_json::to_value(value)?);
+        }
+    }
+
+    Ok(serde_json::from_value(merged)?)
+}
+
+/// Wrap a configuration for sharing between components
+pub fn shared(config: BotConfig) -> SharedConfig {
+    Arc::new(ArcSwap::from_pointee(config))
+}
+
+/// Reload mutable configuration fields from the environment
+///
+/// Wallet, connection and mode settings are fixed for the lifetime of the
+/// process; changes to them are ignored with a warning.
+pub fn reload_config(shared: &SharedConfig) -> Result<(), Box<dyn std::error::Error>> {
+    reload_config_from(shared, dotenvy::dotenv().ok().as_deref())
+}
+
+/// Reload mutable configuration fields from the environment, re-reading `env_file` first
+fn reload_config_from(shared: &SharedConfig, env_file: Option<&std::path::Path>) -> Result<(), Box<dyn std::error::Error>> {
+    let current = shared.load_full();
+
+    // Loading `.env` never overrides variables already set, which after startup is all of them
+    if let Some(path) = env_file {
+        dotenvy::from_path_override(path)?;
+    }
+    let mut reloaded = load_config()?;
+
+    macro_rules! keep_immutable {
+        ($($field:ident),*) => {
+            $(
+                if reloaded.$field != current.$field {
+                    tracing::warn!("Ignoring change to {} on reload; restart to apply", stringify!($field));
+                    reloaded.$field = current.$field.clone();
+                }
+            )*
+        };
+    }
+
+    keep_immutable!(
+        rpc_url,
+        ws_url,
+        extra_ws_urls,
+        source_priority,
+        cluster,
+        private_key,
+        main_wallet_private_key,
+        external_signer_socket,
+        simulation_mode,
+        read_only,
+        event_log_path,
+        paper_profiles,
+        otlp_endpoint,
+        otel_service_name,
+        debug_dump_failed_tx,
+        storage_backend,
+        storage_path,
+        redis_url,
+        control_listen_addr,
+        control_token,
+        jito_block_engine_url,
+        blockhash_max_age_ms
+    );
+
+    let version = reloaded.version().to_string();
+    shared.store(Arc::new(reloaded));
+    tracing::info!("Configuration reloaded (version {})", version);
+    Ok(())
+}
+
 /// Validate configuration
-fn validate_config(config: &BotConfig) -> Result<(), Box<dyn std::error::Error>> {
-    if !config.simulation_mode && config.private_key.is_none() {
-        return Err("PRIVATE_KEY is required when not in simulation mode".into());
+pub(crate) fn validate_config(config: &BotConfig) -> Result<(), Box<dyn std::error::Error>> {
+    if !config.simulation_mode && config.private_key.is_none() && config.external_signer_socket.is_none() {
+        return Err("PRIVATE_KEY or EXTERNAL_SIGNER_SOCKET is required when not in simulation mode".into());
     }
 
     if config.rpc_url.is_empty() {
@@ -203,6 +1406,211 @@ fn validate_config(config: &BotConfig) -> Result<(), Box<dyn std::error::Error>>
         return Err("BUY_AMOUNT_SOL must be greater than 0".into());
     }
 
+    if config.buy_mode == BuyMode::ByTokens {
+        if config.buy_amount_tokens == 0 {
+            return Err("BUY_AMOUNT_TOKENS must be greater than 0 when BUY_MODE=by_tokens".into());
+        }
+        // Scouting splits a SOL amount, which a fixed token quantity cannot follow
+        if config.scout_then_scale {
+            return Err("SCOUT_THEN_SCALE cannot be combined with BUY_MODE=by_tokens".into());
+        }
+    }
+
+    for tier in &config.balance_tiers {
+        if tier.min_balance_sol < 0.0 || tier.buy_amount_sol <= 0.0 {
+            return Err(format!("Invalid BALANCE_TIERS entry: {:?}", tier).into());
+        }
+    }
+
+    if let Some(pct) = config.buy_balance_pct {
+        if pct <= 0.0 || pct > 100.0 {
+            return Err(format!("BUY_BALANCE_PCT must be between 0 (exclusive) and 100, got {}", pct).into());
+        }
+    }
+
+    if let Some(max) = config.max_buy_amount_sol {
+        if max <= 0.0 || max < config.min_buy_amount_sol {
+            return Err(format!(
+                "MAX_BUY_AMOUNT_SOL ({}) must be positive and at least MIN_BUY_AMOUNT_SOL ({})",
+                max, config.min_buy_amount_sol
+            ).into());
+        }
+    }
+
+    if let Some(max_loss) = config.max_daily_loss_sol {
+        if max_loss <= 0.0 {
+            return Err(format!("MAX_DAILY_LOSS_SOL must be positive, got {}", max_loss).into());
+        }
+    }
+
+    if !(config.loss_streak_size_factor > 0.0 && config.loss_streak_size_factor <= 1.0) {
+        return Err(format!("LOSS_STREAK_SIZE_FACTOR must be in (0, 1], got {}", config.loss_streak_size_factor).into());
+    }
+
+    if config.graduation_sol_target <= 0.0 {
+        return Err(format!("GRADUATION_SOL_TARGET must be positive, got {}", config.graduation_sol_target).into());
+    }
+
+    if !(0.0..=100.0).contains(&config.amm_route_progress_pct) {
+        return Err(format!("AMM_ROUTE_PROGRESS_PCT must be between 0 and 100, got {}", config.amm_route_progress_pct).into());
+    }
+
+    if !config.paper_profiles.is_empty() && config.paper_starting_balance_sol <= 0.0 {
+        return Err(format!("PAPER_STARTING_BALANCE_SOL must be positive, got {}", config.paper_starting_balance_sol).into());
+    }
+
+    // Replayed launches are minutes old; without an age gate catch-up would buy them
+    if config.catch_up_minutes.is_some() && config.max_event_age_secs.is_none() {
+        return Err("CATCH_UP_MINUTES requires MAX_EVENT_AGE_SECS".into());
+    }
+
+    if config.health_failure_after_ms.is_some() && config.health_check_interval_ms == 0 {
+        return Err("HEALTH_CHECK_INTERVAL_MS must be greater than 0".into());
+    }
+
+    if config.report_dir.is_some() && config.report_checkpoint_ms == 0 {
+        return Err("REPORT_CHECKPOINT_MS must be greater than 0".into());
+    }
+
+    // A blockhash lands for roughly 60s; signing with one near the end of that risks expiry
+    if config.blockhash_max_age_ms == 0 || config.blockhash_max_age_ms > 45_000 {
+        return Err(format!("BLOCKHASH_MAX_AGE_MS must be between 1 and 45000, got {}", config.blockhash_max_age_ms).into());
+    }
+    if config.blockhash_refresh_ms >= config.blockhash_max_age_ms {
+        return Err(format!(
+            "BLOCKHASH_REFRESH_MS ({}) must be below BLOCKHASH_MAX_AGE_MS ({}), or the cache is always stale",
+            config.blockhash_refresh_ms,
+            config.blockhash_max_age_ms
+        ).into());
+    }
+
+    if config.fee_recipient_refresh_ms == 0 {
+        return Err("FEE_RECIPIENT_REFRESH_MS must be greater than 0".into());
+    }
+
+    if config.shadow_track_rejected && config.shadow_refresh_ms == 0 {
+        return Err("SHADOW_REFRESH_MS must be greater than 0".into());
+    }
+
+    if !config.paper_profiles.is_empty() && config.paper_check_interval_ms == 0 {
+        return Err("PAPER_CHECK_INTERVAL_MS must be greater than 0".into());
+    }
+
+    if config.batch_sells && config.max_sells_per_batch < 2 {
+        return Err(format!("MAX_SELLS_PER_BATCH must be at least 2 when BATCH_SELLS is on, got {}", config.max_sells_per_batch).into());
+    }
+
+    if config.scout_then_scale {
+        if config.scout_amount_sol <= 0.0 || config.scout_amount_sol >= config.buy_amount_sol {
+            return Err(format!("SCOUT_AMOUNT_SOL must be between 0 and BUY_AMOUNT_SOL (exclusive), got {}", config.scout_amount_sol).into());
+        }
+        if config.scout_sell_percentage <= 0.0 || config.scout_sell_percentage >= 100.0 {
+            return Err(format!("SCOUT_SELL_PERCENTAGE must be between 0 and 100 (exclusive), got {}", config.scout_sell_percentage).into());
+        }
+    }
+
+    if !(0.0..=100.0).contains(&config.max_slippage) {
+        return Err(format!("MAX_SLIPPAGE must be between 0 and 100, got {}", config.max_slippage).into());
+    }
+
+    if config.take_profit_percentage <= 0.0 {
+        return Err(format!("TAKE_PROFIT_PERCENTAGE must be greater than 0, got {}", config.take_profit_percentage).into());
+    }
+
+    if config.stop_loss_percentage <= 0.0 || config.stop_loss_percentage >= 100.0 {
+        return Err(format!("STOP_LOSS_PERCENTAGE must be between 0 and 100 (exclusive), got {}", config.stop_loss_percentage).into());
+    }
+
+    if config.slippage_bps() > 10_000 {
+        return Err(format!("MAX_SLIPPAGE_BPS must be at most 10000, got {}", config.slippage_bps()).into());
+    }
+
+    if config.volume_window_secs == 0 {
+        return Err("VOLUME_WINDOW_SECS must be greater than 0".into());
+    }
+
+    if config.jito_tip_max_lamports.is_some() && config.jito_block_engine_url.is_none() {
+        return Err("JITO_BLOCK_ENGINE_URL is required when JITO_TIP_MAX_LAMPORTS is set".into());
+    }
+
+    if let Some(addr) = &config.control_listen_addr {
+        let addr: std::net::SocketAddr = addr
+            .parse()
+            .map_err(|e| format!("CONTROL_LISTEN_ADDR must be host:port, got {}: {}", addr, e))?;
+        // Anyone who can reach the endpoint can dump every position
+        if !addr.ip().is_loopback() && config.control_token.is_none() {
+            return Err("CONTROL_TOKEN is required when CONTROL_LISTEN_ADDR is not a loopback address".into());
+        }
+    }
+
+    if config.emergency_slippage_bps > 10_000 {
+        return Err(format!("EMERGENCY_SLIPPAGE_BPS must be at most 10000, got {}", config.emergency_slippage_bps).into());
+    }
+
+    if let Some(retry_max) = config.slippage_retry_max_bps {
+        if retry_max <= config.slippage_bps() || retry_max > 10_000 {
+            return Err(format!(
+                "SLIPPAGE_RETRY_MAX_BPS must be above the configured slippage ({} bps) and at most 10000, got {}",
+                config.slippage_bps(),
+                retry_max
+            ).into());
+        }
+        if percent_to_bps(config.max_slippage) <= config.slippage_bps() {
+            return Err(format!(
+                "SLIPPAGE_RETRY_MAX_BPS needs MAX_SLIPPAGE_BPS ({} bps) below MAX_SLIPPAGE ({}%), which caps the retry",
+                config.slippage_bps(),
+                config.max_slippage
+            ).into());
+        }
+    }
+
+    if config.take_profit_bps() == 0 {
+        return Err("TAKE_PROFIT_BPS must be greater than 0".into());
+    }
+
+    if config.stop_loss_bps() == 0 || config.stop_loss_bps() >= 10_000 {
+        return Err(format!("STOP_LOSS_BPS must be between 0 and 10000 (exclusive), got {}", config.stop_loss_bps()).into());
+    }
+
+    if let Some((min, max)) = config.safety_stop_loss_range {
+        if min <= 0.0 || max >= 100.0 || min > max {
+            return Err(format!("SAFETY_STOP_LOSS_RANGE must satisfy 0 < min <= max < 100, got {}-{}", min, max).into());
+        }
+    }
+
+    for preset in &config.exit_presets {
+        if preset.min_market_cap >= preset.max_market_cap
+            || preset.take_profit_percentage <= 0.0
+            || preset.stop_loss_percentage <= 0.0
+            || preset.stop_loss_percentage >= 100.0
+        {
+            return Err(format!("Invalid EXIT_PRESETS entry: {:?}", preset).into());
+        }
+    }
+
+    if config.min_market_cap > config.max_market_cap {
+        return Err(format!(
+            "MIN_MARKET_CAP ({}) must not exceed MAX_MARKET_CAP ({})",
+            config.min_market_cap, config.max_market_cap
+        ).into());
+    }
+
+    if let (Some(min_price), Some(max_price)) = (config.min_buy_price, config.max_buy_price) {
+        if min_price > max_price {
+            return Err(format!(
+                "MIN_BUY_PRICE ({}) must not exceed MAX_BUY_PRICE ({})",
+                min_price, max_price
+            ).into());
+        }
+    }
+
+    if config.min_holders > config.max_holders {
+        return Err(format!(
+            "MIN_HOLDERS ({}) must not exceed MAX_HOLDERS ({})",
+            config.min_holders, config.max_holders
+        ).into());
+    }
+
     Ok(())
 }
 
@@ -222,22 +1630,96 @@ pub mod constants {
     // Token Program ID
     pub const TOKEN_PROGRAM_ID: Pubkey = solana_sdk::pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
 
+    // Token-2022 Program ID
+    pub const TOKEN_2022_PROGRAM_ID: Pubkey = solana_sdk::pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
+    // Jito tip account credited by buy tips
+    pub const JITO_TIP_ACCOUNT: Pubkey = solana_sdk::pubkey!("96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5");
+
+    // Genesis hashes identifying each public cluster
+    pub const MAINNET_GENESIS_HASH: &str = "5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N9d";
+    pub const DEVNET_GENESIS_HASH: &str = "EtWTRABZaYq6iMfeYKouRu166VU2xqa1wcaWoxPkrZBG";
+    pub const TESTNET_GENESIS_HASH: &str = "4uhcVJyU9pJkvQyS88uRDiswHXSCkY3zQawwpjk2NsNY";
+
     // Rent Program ID
     pub const RENT_PROGRAM_ID: Pubkey = solana_sdk::pubkey!("SysvarRent111111111111111111111111111111111");
 
+    // Pump.fun AMM (PumpSwap) Program ID, where graduated tokens trade
+    pub const PUMP_AMM_PROGRAM_ID: Pubkey = solana_sdk::pubkey!("pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA");
+
+    // Pump.fun AMM protocol fee recipient
+    pub const PUMP_AMM_PROTOCOL_FEE_RECIPIENT: Pubkey = solana_sdk::pubkey!("62qc2CNXwrYqQScmEdiZFFAnJR262PxWEuNQtxfafNgV");
+
     // Pump.fun Fee Recipient
     pub const PUMP_FUN_FEE_RECIPIENT: Pubkey = solana_sdk::pubkey!("CebN5WGQ4jvEPvsVU4EoHEpgzq1VV7AbicfhtW4xC9iM");
 
+    // Global config seed
+    pub const GLOBAL_SEED: &str = "global";
+
+    // Event authority seed
+    pub const EVENT_AUTHORITY_SEED: &str = "__event_authority";
+
+    // Creator fee vault seed
+    pub const CREATOR_VAULT_SEED: &str = "creator-vault";
+
     // Bonding curve seed
     pub const BONDING_CURVE_SEED: &str = "bonding-curve";
 
+    // Pump.fun AMM seeds
+    pub const POOL_AUTHORITY_SEED: &str = "pool-authority";
+    pub const POOL_SEED: &str = "pool";
+    pub const AMM_GLOBAL_CONFIG_SEED: &str = "global_config";
+    pub const AMM_CREATOR_VAULT_SEED: &str = "creator_vault";
+
     // Metadata seed
     pub const METADATA_SEED: &str = "metadata";
 
+    // Metaplex Token Metadata program, which holds Pump.fun mints' name, symbol and URI
+    pub const TOKEN_METADATA_PROGRAM_ID: Pubkey = solana_sdk::pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
     // Default commitment
     pub const DEFAULT_COMMITMENT: solana_sdk::commitment_config::CommitmentLevel =
         solana_sdk::commitment_config::CommitmentLevel::Confirmed;
 
+    // Pump.fun trading fee (basis points, charged on both buys and sells)
+    pub const PUMP_FUN_FEE_BPS: u64 = 100;
+
+    // Pump.fun AMM LP plus protocol fee (basis points)
+    pub const PUMP_AMM_FEE_BPS: u64 = 25;
+
+    // Real SOL reserves at which a mainnet bonding curve completes; override with GRADUATION_SOL_TARGET
+    pub const GRADUATION_SOL_RESERVES: u64 = 85 * LAMPORTS_PER_SOL;
+
+    // Anchor discriminator of the Pump.fun bonding curve buy instruction
+    pub const BUY_DISCRIMINATOR: [u8; 8] = [102, 6, 61, 18, 1, 218, 235, 234];
+
+    // Anchor discriminator of the Pump.fun bonding curve sell instruction
+    pub const SELL_DISCRIMINATOR: [u8; 8] = [51, 230, 133, 164, 1, 127, 131, 173];
+
+    // Anchor discriminator of the Pump.fun AMM buy instruction
+    pub const AMM_BUY_DISCRIMINATOR: [u8; 8] = [102, 6, 61, 18, 1, 218, 235, 234];
+
+    // Anchor discriminator of the Pump.fun AMM sell instruction
+    pub const AMM_SELL_DISCRIMINATOR: [u8; 8] = [51, 230, 133, 164, 1, 127, 131, 173];
+
+    // Base network fee per signature
+    pub const BASE_FEE_LAMPORTS: u64 = 5_000;
+
+    // Compute unit limit requested for swaps
+    pub const SWAP_COMPUTE_UNIT_LIMIT: u32 = 200_000;
+
+    // Most compute units a single transaction may request
+    pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+    // Anchor discriminator of the Pump.fun CreateEvent
+    pub const CREATE_EVENT_DISCRIMINATOR: [u8; 8] = [27, 114, 169, 77, 222, 235, 99, 118];
+
+    // Anchor discriminator of the Pump.fun TradeEvent
+    pub const TRADE_EVENT_DISCRIMINATOR: [u8; 8] = [189, 219, 127, 211, 78, 230, 97, 238];
+
+    // Anchor discriminator of the Pump.fun CompleteEvent
+    pub const COMPLETE_EVENT_DISCRIMINATOR: [u8; 8] = [95, 114, 97, 156, 212, 46, 152, 8];
+
     // Time constants (in milliseconds)
     pub const ONE_MINUTE_MS: u64 = 60 * 1000;
     pub const ONE_HOUR_MS: u64 = 60 * ONE_MINUTE_MS;
@@ -245,6 +1727,7 @@ pub mod constants {
 
     // Solana constants
     pub const SOL_DECIMALS: u32 = 9;
+    pub const PUMP_FUN_TOKEN_DECIMALS: u32 = 6;
     pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
 }
 
@@ -257,7 +1740,7 @@ pub enum TransactionType {
 }
 
 /// Token safety status
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum TokenSafetyStatus {
     Safe,
     Suspicious,
@@ -271,3 +1754,210 @@ pub enum TradingStatus {
     Paused,
     Stopped,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reload_picks_up_edited_env_file_values() {
+        let env_file = std::env::temp_dir().join(format!("sniper-reload-{}.env", std::process::id()));
+        std::fs::write(&env_file, "MIN_MARKET_CAP=1000\n").unwrap();
+        let shared = shared(BotConfig { min_market_cap: 10_000.0, ..Default::default() });
+        let analysis = crate::types::test_analysis();
+        assert_eq!(crate::filter_decision(&analysis, &shared.load()), crate::types::FilterDecision::MarketCapTooLow);
+
+        reload_config_from(&shared, Some(&env_file)).unwrap();
+        env::remove_var("MIN_MARKET_CAP");
+        let _ = std::fs::remove_file(&env_file);
+
+        // The filters see the threshold from the edited file
+        assert_eq!(shared.load().min_market_cap, 1_000.0);
+        assert_eq!(crate::filter_decision(&analysis, &shared.load()), crate::types::FilterDecision::Pass);
+    }
+
+    #[test]
+    fn jito_tip_scales_with_opportunity_score_within_bounds() {
+        let config = BotConfig {
+            jito_tip_min_lamports: 10_000,
+            jito_tip_max_lamports: Some(110_000),
+            jito_block_engine_url: Some("https://block-engine.test".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(config.jito_tip_lamports(0), 10_000);
+        assert_eq!(config.jito_tip_lamports(50), 60_000);
+        assert_eq!(config.jito_tip_lamports(100), 110_000);
+        assert_eq!(config.jito_tip_lamports(250), 110_000);
+        assert_eq!(BotConfig::default().jito_tip_lamports(100), 0);
+    }
+
+    #[test]
+    fn catch_up_requires_an_age_gate() {
+        let config = BotConfig { catch_up_minutes: Some(5), ..Default::default() };
+        assert!(validate_config(&config).is_err());
+        assert!(validate_config(&BotConfig { max_event_age_secs: Some(60), ..config }).is_ok());
+    }
+
+    #[test]
+    fn slippage_retry_is_capped_by_max_slippage() {
+        let config = BotConfig {
+            max_slippage: 30.0,
+            max_slippage_bps: Some(2_000),
+            slippage_retry_max_bps: Some(5_000),
+            ..Default::default()
+        };
+        assert!(validate_config(&config).is_ok());
+        assert_eq!(config.bumped_slippage_bps(), Some(3_000));
+
+        let uncapped = BotConfig { max_slippage_bps: None, ..config };
+        assert!(validate_config(&uncapped).is_err());
+        assert_eq!(uncapped.bumped_slippage_bps(), None);
+    }
+
+    #[test]
+    fn zero_refresh_intervals_are_rejected() {
+        assert!(validate_config(&BotConfig { fee_recipient_refresh_ms: 0, ..Default::default() }).is_err());
+        assert!(validate_config(&BotConfig { blockhash_max_age_ms: 0, ..Default::default() }).is_err());
+        assert!(validate_config(&BotConfig { blockhash_refresh_ms: 10_000, ..Default::default() }).is_err());
+        assert!(validate_config(&BotConfig {
+            report_dir: Some("reports".to_string()),
+            report_checkpoint_ms: 0,
+            ..Default::default()
+        }).is_err());
+        assert!(validate_config(&BotConfig {
+            health_failure_after_ms: Some(30_000),
+            health_check_interval_ms: 0,
+            ..Default::default()
+        }).is_err());
+    }
+
+    #[test]
+    fn version_tracks_trading_settings_but_not_secrets() {
+        let config = BotConfig::default();
+        let version = config.version().to_string();
+
+        let with_key = BotConfig { private_key: Some("secret".to_string()), ..config.clone() };
+        assert_eq!(with_key.version(), version);
+
+        let retuned = BotConfig { take_profit_percentage: 75.0, ..config.clone() };
+        assert_ne!(retuned.version(), version);
+    }
+
+    #[test]
+    fn jito_tips_require_a_block_engine() {
+        let mut config = BotConfig {
+            simulation_mode: true,
+            jito_tip_max_lamports: Some(100_000),
+            ..Default::default()
+        };
+        assert!(validate_config(&config).is_err());
+
+        config.jito_block_engine_url = Some("https://block-engine.test".to_string());
+        assert!(validate_config(&config).is_ok());
+    }
+
+    #[test]
+    fn absurd_trading_params_are_rejected_naming_the_field() {
+        assert!(validate_config(&BotConfig::default()).is_ok());
+
+        let invalid = [
+            (BotConfig { max_slippage: 2_500.0, ..Default::default() }, "MAX_SLIPPAGE"),
+            (BotConfig { max_slippage: -1.0, ..Default::default() }, "MAX_SLIPPAGE"),
+            (BotConfig { take_profit_percentage: 0.0, ..Default::default() }, "TAKE_PROFIT_PERCENTAGE"),
+            (BotConfig { take_profit_percentage: -20.0, ..Default::default() }, "TAKE_PROFIT_PERCENTAGE"),
+            (BotConfig { stop_loss_percentage: 0.0, ..Default::default() }, "STOP_LOSS_PERCENTAGE"),
+            (BotConfig { stop_loss_percentage: 100.0, ..Default::default() }, "STOP_LOSS_PERCENTAGE"),
+            (BotConfig { min_market_cap: 50_000.0, max_market_cap: 10_000.0, ..Default::default() }, "MIN_MARKET_CAP"),
+            (BotConfig { min_holders: 500, max_holders: 100, ..Default::default() }, "MIN_HOLDERS"),
+        ];
+        for (config, field) in invalid {
+            let error = validate_config(&config).unwrap_err().to_string();
+            assert!(error.starts_with(field), "expected {} error, got: {}", field, error);
+        }
+    }
+
+    #[test]
+    fn bps_and_percent_slippage_agree_without_float_drift() {
+        use crate::utils::bonding_curve::{max_input_with_slippage, min_output_with_slippage};
+
+        // 0.29 * 100.0 is 28.999..., which a plain cast would truncate to 28 bps
+        assert!(((0.29f64 * 100.0) as u64) < 29);
+        let by_percent = BotConfig { max_slippage: 0.29, ..Default::default() };
+        let by_bps = BotConfig { max_slippage_bps: Some(29), ..Default::default() };
+        assert_eq!(by_percent.slippage_bps(), 29);
+        assert_eq!(by_bps.slippage_bps(), 29);
+
+        let cost = 1_000_000_007;
+        assert_eq!(max_input_with_slippage(cost, by_bps.slippage_bps()), 1_002_900_007);
+        assert_eq!(min_output_with_slippage(cost, by_bps.slippage_bps()), 997_100_006);
+        assert_eq!(
+            max_input_with_slippage(cost, by_percent.slippage_bps()),
+            max_input_with_slippage(cost, by_bps.slippage_bps()),
+        );
+
+        // Exits take the same path
+        let exits = BotConfig { take_profit_percentage: 14.35, ..Default::default() };
+        assert_eq!(exits.take_profit_bps(), 1_435);
+        let exits = BotConfig { take_profit_bps: Some(1_435), ..Default::default() };
+        assert_eq!(exits.take_profit_bps(), 1_435);
+    }
+
+    #[test]
+    fn market_cap_selects_its_presets_exit_levels() {
+        let preset = |min_market_cap, max_market_cap, take_profit_percentage, stop_loss_percentage| ExitPreset {
+            min_market_cap,
+            max_market_cap,
+            take_profit_percentage,
+            stop_loss_percentage,
+        };
+        let config = BotConfig {
+            take_profit_percentage: 50.0,
+            stop_loss_percentage: 20.0,
+            exit_presets: vec![preset(0.0, 10_000.0, 100.0, 30.0), preset(10_000.0, 50_000.0, 40.0, 15.0)],
+            ..Default::default()
+        };
+
+        assert_eq!(config.exit_levels_bps(5_000.0), (10_000, 3_000));
+        assert_eq!(config.exit_levels_bps(10_000.0), (4_000, 1_500));
+        assert_eq!(config.exit_levels_bps(49_999.0), (4_000, 1_500));
+        assert_eq!(config.exit_levels_bps(50_000.0), (5_000, 2_000));
+    }
+
+    #[test]
+    fn safety_score_scales_the_stop_loss_across_its_range() {
+        use crate::utils::safety_checker::MIN_SAFETY_SCORE;
+
+        let config = BotConfig { safety_stop_loss_range: Some((10.0, 30.0)), ..Default::default() };
+        assert_eq!(config.safety_scaled_stop_loss_bps(MIN_SAFETY_SCORE), Some(1_000));
+        assert_eq!(config.safety_scaled_stop_loss_bps((MIN_SAFETY_SCORE + 100) / 2), Some(2_000));
+        assert_eq!(config.safety_scaled_stop_loss_bps(100), Some(3_000));
+        assert_eq!(config.safety_scaled_stop_loss_bps(0), Some(1_000));
+
+        let unscaled = BotConfig { safety_stop_loss_range: None, ..Default::default() };
+        assert_eq!(unscaled.safety_scaled_stop_loss_bps(100), None);
+    }
+
+    #[test]
+    fn wallet_balance_maps_to_its_tier_buy_size_within_bounds() {
+        let tier = |min_balance_sol, buy_amount_sol| BalanceTier { min_balance_sol, buy_amount_sol };
+        let tiered = BotConfig {
+            buy_amount_sol: 0.05,
+            balance_tiers: vec![tier(10.0, 0.5), tier(1.0, 0.1), tier(5.0, 0.25)],
+            buy_balance_pct: Some(4.0),
+            min_buy_amount_sol: 0.02,
+            max_buy_amount_sol: Some(0.4),
+            ..Default::default()
+        };
+        assert!(tiered.sizes_by_balance());
+        assert_eq!(tiered.buy_amount_for_balance(0.25), 0.02);
+        assert_eq!(tiered.buy_amount_for_balance(0.75), 0.03);
+        assert_eq!(tiered.buy_amount_for_balance(1.0), 0.1);
+        assert_eq!(tiered.buy_amount_for_balance(7.0), 0.25);
+        assert_eq!(tiered.buy_amount_for_balance(50.0), 0.4);
+
+        let flat = BotConfig { buy_amount_sol: 0.05, ..Default::default() };
+        assert!(!flat.sizes_by_balance());
+        assert_eq!(flat.buy_amount_for_balance(50.0), 0.05);
+    }
+}
@@ -0,0 +1,126 @@
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use crate::PumpFunSniper;
+
+/// Largest request head read from a control connection
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+
+/// Operator actions exposed over the control endpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ControlAction {
+    PanicSell,
+    Status,
+}
+
+/// Serve the operator control endpoint on `addr` until the listener fails
+///
+/// `POST /panic-sell` dumps every open position, the same as SIGUSR2, and
+/// `GET /status` returns the bot status. When `token` is set, requests must
+/// carry `Authorization: Bearer <token>`.
+pub async fn serve(
+    bot: Arc<PumpFunSniper>,
+    addr: &str,
+    token: Option<String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let listener = TcpListener::bind(addr).await?;
+    tracing::info!("Control endpoint listening on {}", addr);
+
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let bot = Arc::clone(&bot);
+        let token = token.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream, &bot, token.as_deref()).await {
+                tracing::warn!("Control request from {} failed: {}", peer, e);
+            }
+        });
+    }
+}
+
+/// Read one request, run its action and write the response
+async fn handle_connection(mut stream: TcpStream, bot: &PumpFunSniper, token: Option<&str>) -> std::io::Result<()> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 1024];
+    while !buf.windows(4).any(|w| w == b"\r\n\r\n") && buf.len() < MAX_REQUEST_BYTES {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+
+    let (status, body) = match route(&String::from_utf8_lossy(&buf), token) {
+        Ok(ControlAction::PanicSell) => {
+            tracing::warn!("Control endpoint requested panic sell of all positions");
+            let sold = bot.panic_sell().await;
+            tracing::warn!("Panic sell attempted for {} positions", sold);
+            ("200 OK", serde_json::json!({ "sold": sold }))
+        }
+        Ok(ControlAction::Status) => ("200 OK", bot.status().await),
+        Err(status) => (status, serde_json::json!({ "error": status })),
+    };
+
+    let body = body.to_string();
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// Match a request head to an action, or the HTTP status to reject it with
+fn route(request: &str, token: Option<&str>) -> Result<ControlAction, &'static str> {
+    let mut lines = request.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let (method, path) = match (request_line.next(), request_line.next()) {
+        (Some(method), Some(path)) => (method, path),
+        _ => return Err("400 Bad Request"),
+    };
+
+    if let Some(token) = token {
+        let authorized = lines
+            .take_while(|line| !line.is_empty())
+            .filter_map(|line| line.split_once(':'))
+            .any(|(name, value)| {
+                name.trim().eq_ignore_ascii_case("authorization")
+                    && value.trim().strip_prefix("Bearer ") == Some(token)
+            });
+        if !authorized {
+            return Err("401 Unauthorized");
+        }
+    }
+
+    match (method, path) {
+        ("POST", "/panic-sell") => Ok(ControlAction::PanicSell),
+        ("GET", "/status") => Ok(ControlAction::Status),
+        (_, "/panic-sell") | (_, "/status") => Err("405 Method Not Allowed"),
+        _ => Err("404 Not Found"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn routes_panic_sell_and_status() {
+        assert_eq!(route("POST /panic-sell HTTP/1.1\r\nHost: x\r\n\r\n", None), Ok(ControlAction::PanicSell));
+        assert_eq!(route("GET /status HTTP/1.1\r\n\r\n", None), Ok(ControlAction::Status));
+        assert_eq!(route("GET /panic-sell HTTP/1.1\r\n\r\n", None), Err("405 Method Not Allowed"));
+        assert_eq!(route("POST /other HTTP/1.1\r\n\r\n", None), Err("404 Not Found"));
+        assert_eq!(route("", None), Err("400 Bad Request"));
+    }
+
+    #[test]
+    fn requires_the_configured_bearer_token() {
+        let request = |auth: &str| format!("POST /panic-sell HTTP/1.1\r\nHost: x\r\n{}\r\n\r\n", auth);
+
+        assert_eq!(route(&request(""), Some("secret")), Err("401 Unauthorized"));
+        assert_eq!(route(&request("Authorization: Bearer wrong"), Some("secret")), Err("401 Unauthorized"));
+        assert_eq!(route(&request("authorization: Bearer secret"), Some("secret")), Ok(ControlAction::PanicSell));
+    }
+}
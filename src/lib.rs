@@ -1,28 +1,47 @@
 pub mod config;
+pub mod control;
 pub mod monitors;
 pub mod traders;
 pub mod utils;
 pub mod types;
 
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use tokio::sync::RwLock;
+use tracing::Instrument;
 
 /// Main Pump.fun sniper bot structure
 pub struct PumpFunSniper {
-    config: Arc<config::BotConfig>,
+    config: config::SharedConfig,
     client: Arc<utils::solana_client::SolanaClient>,
-    monitor: Arc<RwLock<Option<monitors::pump_fun_monitor::PumpFunMonitor>>>,
+    monitor: Arc<RwLock<Option<monitors::monitor_set::MonitorSet>>>,
     trader: Arc<traders::trader::Trader>,
+    shadow_tracker: Arc<traders::shadow_tracker::ShadowTracker>,
+    paper: Arc<traders::paper_leaderboard::PaperLeaderboard>,
+    event_log: Arc<utils::event_log::EventLog>,
+    notifier: Arc<utils::notifier::Notifier>,
+    enricher: Arc<utils::enrichment::Enricher>,
+    inflight_analyses: Arc<AtomicUsize>,
+    shed_events: Arc<AtomicU64>,
+    filter_counts: Arc<std::sync::Mutex<HashMap<types::FilterDecision, u64>>>,
+    curve_subscriber: Arc<monitors::curve_subscriber::CurveSubscriber>,
+    started_at: chrono::DateTime<chrono::Utc>,
+    pipeline_errors: Arc<AtomicU64>,
+    background_tasks: std::sync::Mutex<Vec<tokio::task::JoinHandle<()>>>,
 }
 
 impl PumpFunSniper {
     /// Create a new instance of the sniper bot
     pub async fn new() -> Result<Self, Box<dyn std::error::Error>> {
         // Load configuration
-        let config = Arc::new(config::load_config()?);
+        Self::with_config(config::shared(config::load_config()?)).await
+    }
 
+    /// Create the sniper bot around an already loaded configuration
+    pub async fn with_config(config: config::SharedConfig) -> Result<Self, Box<dyn std::error::Error>> {
         // Initialize Solana client
-        let client = Arc::new(utils::solana_client::SolanaClient::new(&config).await?);
+        let client = Arc::new(utils::solana_client::SolanaClient::new(&config.load()).await?);
 
         // Initialize trader
         let trader = Arc::new(traders::trader::Trader::new(
@@ -30,11 +49,46 @@ impl PumpFunSniper {
             Arc::clone(&config),
         ).await?);
 
+        // Initialize shadow tracker for rejected tokens
+        let shadow_tracker = Arc::new(traders::shadow_tracker::ShadowTracker::new(
+            Arc::clone(&config),
+        ));
+
+        // Paper trade config variants against the same events
+        let paper = Arc::new(traders::paper_leaderboard::PaperLeaderboard::new(
+            Arc::clone(&client),
+            &config.load(),
+        ).await?);
+
+        // Open the event log for post-mortem replay
+        let event_log = Arc::new(utils::event_log::EventLog::new(config.load().event_log_path.as_deref())?);
+
+        // Initialize notifier for alerts
+        let notifier = Arc::new(utils::notifier::Notifier::new(Arc::clone(&config)));
+
+        // External market data folded into analysis when a provider is configured
+        let enricher = Arc::new(utils::enrichment::Enricher::new(Arc::clone(&config))?);
+
+        // Push held curve updates to the trader when enabled
+        let curve_subscriber = Arc::new(monitors::curve_subscriber::CurveSubscriber::new(Arc::clone(&config)));
+
         Ok(Self {
             config,
             client,
             monitor: Arc::new(RwLock::new(None)),
             trader,
+            shadow_tracker,
+            paper,
+            event_log,
+            notifier,
+            enricher,
+            inflight_analyses: Arc::new(AtomicUsize::new(0)),
+            shed_events: Arc::new(AtomicU64::new(0)),
+            filter_counts: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            curve_subscriber,
+            started_at: chrono::Utc::now(),
+            pipeline_errors: Arc::new(AtomicU64::new(0)),
+            background_tasks: std::sync::Mutex::new(Vec::new()),
         })
     }
 
@@ -42,26 +96,152 @@ impl PumpFunSniper {
     pub async fn start(&self) -> Result<(), Box<dyn std::error::Error>> {
         tracing::info!("Starting Pump.fun sniper bot...");
 
-        // Start the monitor
-        let monitor = monitors::pump_fun_monitor::PumpFunMonitor::new(
-            Arc::clone(&self.client),
-            Arc::clone(&self.config),
-        ).await?;
+        // Suppress buys until the event stream settles
+        self.trader.start_warmup().await;
 
-        // Set up token event handler
-        let trader = Arc::clone(&self.trader);
-        let config = Arc::clone(&self.config);
-        monitor.on_new_token(move |event| {
-            let trader = Arc::clone(&trader);
-            let config = Arc::clone(&config);
-            tokio::spawn(async move {
-                if let Err(e) = handle_new_token(trader, config, event).await {
-                    tracing::error!("Error handling new token: {}", e);
+        // Bring persisted positions in line with what the wallet actually holds
+        if !self.config.load().simulation_mode {
+            match self.trader.resolve_pending_transactions(None).await {
+                Ok(waiting) if !waiting.is_empty() => {
+                    // Look again once their blockhashes can no longer land
+                    let trader = Arc::clone(&self.trader);
+                    let task = tokio::spawn(async move {
+                        let expiry = std::time::Duration::from_millis(traders::trader::UNCONFIRMED_BUY_EXPIRY_MS);
+                        tokio::time::sleep(expiry).await;
+                        if let Err(e) = trader.resolve_pending_transactions(Some(&waiting)).await.map_err(|e| e.to_string()) {
+                            tracing::warn!("Failed to resolve pending transactions: {}", e);
+                        }
+                    });
+                    self.background_tasks.lock().unwrap().push(task);
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Failed to resolve pending transactions: {}", e),
+            }
+            match self.trader.reconcile_on_startup().await {
+                Ok(report) => tracing::info!("Reconciliation report: {}", serde_json::to_string(&report)?),
+                Err(e) => tracing::warn!("Startup reconciliation failed: {}", e),
+            }
+        }
+
+        let monitor = self.build_monitor().await?;
+
+        // Keep the Pump.fun fee recipient set current
+        if let Err(e) = self.trader.transaction_builder().refresh_fee_recipients().await {
+            tracing::warn!("Failed to fetch Pump.fun fee recipients, using configured set: {}", e);
+        }
+        self.start_prepared_buy_refresh();
+        let transaction_builder = Arc::clone(self.trader.transaction_builder());
+        let refresh_ms = self.config.load().fee_recipient_refresh_ms;
+        let task = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_millis(refresh_ms));
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                if let Err(e) = transaction_builder.refresh_fee_recipients().await {
+                    tracing::warn!("Fee recipient refresh failed: {}", e);
+                }
+            }
+        });
+        self.background_tasks.lock().unwrap().push(task);
+
+        // Keep a recent blockhash cached so sends skip the fetch
+        let blockhash_refresh_ms = self.config.load().blockhash_refresh_ms;
+        if blockhash_refresh_ms > 0 {
+            let task = self.client.start_blockhash_refresh(std::time::Duration::from_millis(blockhash_refresh_ms));
+            self.background_tasks.lock().unwrap().push(task);
+        }
+
+        // Act on sustained RPC health failures
+        if let Some(failure_after_ms) = self.config.load().health_failure_after_ms {
+            let task = self.watch_health(failure_after_ms);
+            self.background_tasks.lock().unwrap().push(task);
+        }
+
+        // Periodically re-check safety of held positions
+        let recheck_ms = self.config.load().safety_recheck_interval_ms;
+        if recheck_ms > 0 {
+            let trader = Arc::clone(&self.trader);
+            let task = tokio::spawn(async move {
+                let mut interval = tokio::time::interval(std::time::Duration::from_millis(recheck_ms));
+                loop {
+                    interval.tick().await;
+                    trader.recheck_position_safety().await;
                 }
             });
-        }).await;
+            self.background_tasks.lock().unwrap().push(task);
+        }
+
+        // Periodically refresh prices of rejected tokens
+        if self.config.load().shadow_track_rejected {
+            let shadow_tracker = Arc::clone(&self.shadow_tracker);
+            let client = Arc::clone(&self.client);
+            let refresh_ms = self.config.load().shadow_refresh_ms;
+            let task = tokio::spawn(async move {
+                let mut interval = tokio::time::interval(std::time::Duration::from_millis(refresh_ms));
+                loop {
+                    interval.tick().await;
+                    shadow_tracker.refresh(&client).await;
+                }
+            });
+            self.background_tasks.lock().unwrap().push(task);
+        }
+
+        // Run the paper variants' exits on their own schedule
+        if !self.paper.is_empty() {
+            let paper = Arc::clone(&self.paper);
+            let check_ms = self.config.load().paper_check_interval_ms;
+            let task = tokio::spawn(async move {
+                let mut interval = tokio::time::interval(std::time::Duration::from_millis(check_ms));
+                loop {
+                    interval.tick().await;
+                    paper.check_exits().await;
+                }
+            });
+            self.background_tasks.lock().unwrap().push(task);
+        }
+
+        // Subscribe to the bonding curves of held positions as they open and close
+        if self.config.load().curve_subscriptions {
+            self.start_curve_subscriptions().await?;
+        }
+
+        // Checkpoint the run report so a crash still leaves a partial one
+        if let Some(report_dir) = self.config.load().report_dir.clone() {
+            let trader = Arc::clone(&self.trader);
+            let config = Arc::clone(&self.config);
+            let filter_counts = Arc::clone(&self.filter_counts);
+            let pipeline_errors = Arc::clone(&self.pipeline_errors);
+            let started_at = self.started_at;
+            let task = tokio::spawn(async move {
+                let mut interval = tokio::time::interval(std::time::Duration::from_millis(
+                    config.load().report_checkpoint_ms,
+                ));
+                loop {
+                    interval.tick().await;
+                    let report = run_report(&trader, &config, &filter_counts, &pipeline_errors, started_at).await;
+                    if let Err(e) = report.write(&report_dir) {
+                        tracing::warn!("Failed to checkpoint run report: {}", e);
+                    }
+                }
+            });
+            self.background_tasks.lock().unwrap().push(task);
+        }
+
+        // Start and store the monitors
+        monitor.start().await?;
 
-        // Store the monitor
+        // Recover launches missed while the bot was down, without holding up startup
+        if let Some(minutes) = self.config.load().catch_up_minutes {
+            let since = chrono::Utc::now() - chrono::Duration::minutes(minutes as i64);
+            let catch_up = monitor.catch_up(since);
+            let task = tokio::spawn(async move {
+                match catch_up.await {
+                    Ok(replayed) => tracing::info!("Replayed {} launches from the last {} minutes", replayed, minutes),
+                    Err(e) => tracing::warn!("Startup catch-up failed: {}", e),
+                }
+            });
+            self.background_tasks.lock().unwrap().push(task);
+        }
         *self.monitor.write().await = Some(monitor);
 
         tracing::info!("Pump.fun sniper bot started successfully");
@@ -75,79 +255,1034 @@ impl PumpFunSniper {
         if let Some(monitor) = self.monitor.write().await.take() {
             monitor.stop().await?;
         }
+        self.curve_subscriber.stop().await;
+        // Wait the aborted tasks out, so a checkpoint mid-write cannot land over the final report
+        let tasks: Vec<_> = self.background_tasks.lock().unwrap().drain(..).collect();
+        for task in tasks {
+            task.abort();
+            let _ = task.await;
+        }
 
         self.trader.stop().await?;
 
+        if let Some(report_dir) = self.config.load().report_dir.as_deref() {
+            let mut report = run_report(&self.trader, &self.config, &self.filter_counts, &self.pipeline_errors, self.started_at).await;
+            report.complete = true;
+            match report.write(report_dir) {
+                Ok(path) => tracing::info!("Run report written to {}", path.display()),
+                Err(e) => tracing::error!("Failed to write run report: {}", e),
+            }
+        }
+
         tracing::info!("Pump.fun sniper bot stopped successfully");
         Ok(())
     }
 
+    /// Alert, halt buys or liquidate once the RPC stays unhealthy for `failure_after_ms`
+    fn watch_health(&self, failure_after_ms: u64) -> tokio::task::JoinHandle<()> {
+        let client = Arc::clone(&self.client);
+        let trader = Arc::clone(&self.trader);
+        let notifier = Arc::clone(&self.notifier);
+        let config = Arc::clone(&self.config);
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_millis(
+                config.load().health_check_interval_ms,
+            ));
+            let mut failing_since: Option<std::time::Instant> = None;
+            let mut tripped = false;
+
+            loop {
+                interval.tick().await;
+                let healthy = client.health_check().await.unwrap_or(false);
+
+                if healthy {
+                    if tripped {
+                        trader.resume_buys().await;
+                        let _ = notifier.send("RPC health restored; buys resumed").await;
+                    }
+                    failing_since = None;
+                    tripped = false;
+                    continue;
+                }
+
+                let since = *failing_since.get_or_insert_with(std::time::Instant::now);
+                if tripped || since.elapsed() < std::time::Duration::from_millis(failure_after_ms) {
+                    continue;
+                }
+                tripped = true;
+
+                let action = config.load().health_failure_action;
+                let _ = notifier.send(&format!(
+                    "RPC unhealthy for {}s; taking action: {:?}",
+                    since.elapsed().as_secs(),
+                    action
+                )).await;
+                match action {
+                    config::HealthFailureAction::Alert => {}
+                    config::HealthFailureAction::Halt => trader.halt_buys("RPC unhealthy").await,
+                    config::HealthFailureAction::Liquidate => {
+                        trader.halt_buys("RPC unhealthy").await;
+                        let sold = trader.panic_sell().await;
+                        let _ = notifier.send(&format!("Liquidation attempted for {} positions", sold)).await;
+                    }
+                }
+            }
+        })
+    }
+
+    /// Deliver pushed curve updates to the trader and keep subscriptions matched to positions
+    async fn start_curve_subscriptions(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let trader = Arc::clone(&self.trader);
+        let paper = Arc::clone(&self.paper);
+        self.curve_subscriber.on_update(move |event| {
+            let trader = Arc::clone(&trader);
+            let paper = Arc::clone(&paper);
+            tokio::spawn(async move {
+                trader.apply_curve_update(&event).await;
+                paper.apply_curve_update(&event).await;
+                trader.check_pushed_exits(&event).await;
+            });
+        }).await?;
+        self.curve_subscriber.start().await?;
+
+        let trader = Arc::clone(&self.trader);
+        let curve_subscriber = Arc::clone(&self.curve_subscriber);
+        let task = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(1));
+            loop {
+                interval.tick().await;
+                // While the feed is down, poll every position rather than waiting for pushes to go stale
+                if curve_subscriber.reconnect_if_dropped().await {
+                    trader.expire_curve_pushes().await;
+                }
+                curve_subscriber.sync(&trader.open_curves().await).await;
+            }
+        });
+        self.background_tasks.lock().unwrap().push(task);
+
+        Ok(())
+    }
+
+    /// Build the monitor set with the token and migration handlers registered
+    async fn build_monitor(&self) -> Result<monitors::monitor_set::MonitorSet, Box<dyn std::error::Error>> {
+        // Build the monitors, merging events from every configured source
+        let mut monitor = monitors::monitor_set::MonitorSet::new();
+        monitor.add_monitor(monitors::pump_fun_monitor::PumpFunMonitor::new(
+            Arc::clone(&self.client),
+            Arc::clone(&self.config),
+            Arc::clone(&self.event_log),
+        )).await;
+        let extra_ws_urls = self.config.load().extra_ws_urls.clone();
+        for (i, ws_url) in extra_ws_urls.into_iter().enumerate() {
+            monitor.add_monitor(monitors::pump_fun_monitor::PumpFunMonitor::new(
+                Arc::clone(&self.client),
+                Arc::clone(&self.config),
+                Arc::clone(&self.event_log),
+            ).with_ws_url(&format!("websocket-{}", i + 1), ws_url)).await;
+        }
+        monitor.prioritize(&self.config.load().source_priority);
+
+        // Set up token event handler
+        let pipeline = self.token_pipeline();
+        let config = Arc::clone(&self.config);
+        let inflight_analyses = Arc::clone(&self.inflight_analyses);
+        let shed_events = Arc::clone(&self.shed_events);
+        let pipeline_errors = Arc::clone(&self.pipeline_errors);
+        monitor.on_new_token(move |event| {
+            let config = config.load_full();
+
+            // Shed load rather than queueing behind an overloaded pipeline
+            if !admit_analysis(&inflight_analyses, &shed_events, config.max_inflight_analyses) {
+                tracing::warn!("Shedding token event {}: {} analyses in flight", event.token_address, config.max_inflight_analyses);
+                return;
+            }
+
+            let pipeline = pipeline.clone();
+            let inflight_analyses = Arc::clone(&inflight_analyses);
+            let pipeline_errors = Arc::clone(&pipeline_errors);
+            // Root of the token's trace; a bought token's sells join it later
+            let span = tracing::info_span!("token", mint = %event.token_address, source = %event.source);
+            tokio::spawn(async move {
+                if let Err(e) = handle_new_token(pipeline, config, event).await {
+                    pipeline_errors.fetch_add(1, Ordering::Relaxed);
+                    tracing::error!("Error handling new token: {}", e);
+                }
+                inflight_analyses.fetch_sub(1, Ordering::Relaxed);
+            }.instrument(span));
+        }).await?;
+
+        // Sell held positions when their bonding curve completes
+        let trader = Arc::clone(&self.trader);
+        monitor.on_migration(move |event| {
+            let trader = Arc::clone(&trader);
+            tokio::spawn(async move {
+                if let Err(e) = trader.handle_migration(&event).await {
+                    tracing::error!("Error handling migration of {}: {}", event.token_address, e);
+                }
+            });
+        }).await?;
+
+        // Exit held positions when their creator or an insider sells
+        let trader = Arc::clone(&self.trader);
+        let config = Arc::clone(&self.config);
+        monitor.on_sell(move |event| {
+            // Every curve sell arrives here; skip spawning when the exit is disabled
+            if !config.load().sell_on_creator_dump {
+                return;
+            }
+            let trader = Arc::clone(&trader);
+            tokio::spawn(async move {
+                if let Err(e) = trader.handle_sell_activity(&event).await {
+                    tracing::error!("Error handling sell of {}: {}", event.token_address, e);
+                }
+            });
+        }).await?;
+
+        Ok(monitor)
+    }
+
+    /// Shared state a detected token is analyzed and traded with
+    fn token_pipeline(&self) -> TokenPipeline {
+        TokenPipeline {
+            trader: Arc::clone(&self.trader),
+            shadow_tracker: Arc::clone(&self.shadow_tracker),
+            paper: Arc::clone(&self.paper),
+            event_log: Arc::clone(&self.event_log),
+            notifier: Arc::clone(&self.notifier),
+            enricher: Arc::clone(&self.enricher),
+            filter_counts: Arc::clone(&self.filter_counts),
+        }
+    }
+
+    /// Replace the running monitors with fresh ones built from the current config
+    ///
+    /// The trader and its positions are untouched; only the event sources reconnect.
+    pub async fn restart_monitor(&self) -> Result<(), Box<dyn std::error::Error>> {
+        tracing::info!("Restarting monitors...");
+
+        // The old monitor keeps its slot until a replacement is running, so a failed
+        // restart can be retried and stop() still reaches it
+        let mut slot = self.monitor.write().await;
+        if let Some(monitor) = slot.as_ref() {
+            monitor.stop().await?;
+        }
+
+        let monitor = self.build_monitor().await?;
+        monitor.start().await?;
+        *slot = Some(monitor);
+
+        tracing::info!("Monitors restarted");
+        Ok(())
+    }
+
+    /// Sell every open position immediately at the emergency slippage
+    pub async fn panic_sell(&self) -> usize {
+        self.trader.panic_sell().await
+    }
+
+    /// Sell enough of the position in `token_address` to realize `target_sol`
+    pub async fn sell_to_sol_target(&self, token_address: &solana_sdk::pubkey::Pubkey, target_sol: f64) -> Result<(), Box<dyn std::error::Error>> {
+        self.trader.sell_to_sol_target(token_address, target_sol).await
+    }
+
+    /// Reload mutable configuration fields from the environment
+    ///
+    /// Prepared buys were built with the old settings; they are rebuilt on the next refresh.
+    pub fn reload_config(&self) -> Result<(), Box<dyn std::error::Error>> {
+        config::reload_config(&self.config)?;
+        self.trader.transaction_builder().clear_prepared_buys();
+        Ok(())
+    }
+
+    /// Keep buys for configured expected launches built, well within their expiry
+    fn start_prepared_buy_refresh(&self) {
+        let trader = Arc::clone(&self.trader);
+        let config = Arc::clone(&self.config);
+        let task = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(utils::transaction_builder::PREPARED_BUY_MAX_AGE / 2);
+            loop {
+                interval.tick().await;
+                Self::prepare_expected_buys(&trader, &config.load_full()).await;
+            }
+        });
+        self.background_tasks.lock().unwrap().push(task);
+    }
+
+    /// Pre-build buys for configured expected launches
+    async fn prepare_expected_buys(trader: &traders::trader::Trader, config: &config::BotConfig) {
+
+        // A token-quantity buy is costed from a curve that does not exist before launch
+        if config.buy_mode == crate::config::BuyMode::ByTokens && !config.prepared_buys.is_empty() {
+            tracing::warn!("Ignoring PREPARED_BUYS: prepared buys require BUY_MODE=by_sol");
+            return;
+        }
+
+        for entry in &config.prepared_buys {
+            let parsed = entry.split_once(':').and_then(|(mint, creator)| {
+                Some((mint.trim().parse::<solana_sdk::pubkey::Pubkey>().ok()?, creator.trim().parse().ok()?))
+            });
+            let (mint, creator) = match parsed {
+                Some(pair) => pair,
+                None => {
+                    tracing::warn!("Ignoring invalid prepared buy entry: {}", entry);
+                    continue;
+                }
+            };
+
+            if let Err(e) = trader.transaction_builder()
+                .prepare_buy(&mint, &creator, config.buy_amount_sol, config.slippage_bps())
+                .await
+            {
+                tracing::warn!("Failed to prepare buy for {}: {}", mint, e);
+            }
+        }
+    }
+
+    /// Filter outcomes so far, counted per decision
+    fn filter_status(&self) -> serde_json::Value {
+        filter_summary(&self.filter_counts.lock().unwrap())
+    }
+
     /// Get bot status
     pub async fn status(&self) -> serde_json::Value {
+        self.status_with_tag(None).await
+    }
+
+    /// Get bot status, listing only positions carrying `tag` when given
+    pub async fn status_with_tag(&self, tag: Option<&str>) -> serde_json::Value {
+        let config = self.config.load_full();
         serde_json::json!({
             "config": {
-                "simulation_mode": self.config.simulation_mode,
-                "rpc_url": self.config.rpc_url,
-                "buy_amount_sol": self.config.buy_amount_sol,
+                "version": config.version(),
+                "simulation_mode": config.simulation_mode,
+                "read_only": config.read_only,
+                "watchlist_only": config.watchlist_only,
+                "rpc_url": config.rpc_url,
+                "buy_amount_sol": config.buy_amount_sol,
             },
             "monitoring": {
                 "active": self.monitor.read().await.is_some(),
+                "sources": match self.monitor.read().await.as_ref() {
+                    Some(monitor) => monitor.status().await,
+                    None => serde_json::Value::Null,
+                },
+            },
+            "intake": {
+                "in_flight": self.inflight_analyses.load(Ordering::Relaxed),
+                "shed_events": self.shed_events.load(Ordering::Relaxed),
             },
-            "trading": self.trader.status().await,
+            "filters": self.filter_status(),
+            "latency": self.trader.latency().status(),
+            "curve_subscriptions": self.curve_subscriber.status().await,
+            "trading": self.trader.status_with_tag(tag).await,
+            "wallets": self.trader.wallet_status().await,
+            "shadow": self.shadow_tracker.status().await,
+            "paper": self.paper.leaderboard().await,
+        })
+    }
+}
+
+/// Distinct wallets among `buyers`, leaving out `excluded` when given
+fn count_unique_buyers(buyers: &[solana_sdk::pubkey::Pubkey], excluded: Option<&solana_sdk::pubkey::Pubkey>) -> usize {
+    buyers
+        .iter()
+        .filter(|buyer| Some(*buyer) != excluded)
+        .collect::<std::collections::HashSet<_>>()
+        .len()
+}
+
+/// Filter pass count and rejections keyed by reason
+fn filter_summary(counts: &HashMap<types::FilterDecision, u64>) -> serde_json::Value {
+    let mut rejected = serde_json::Map::new();
+    for (decision, count) in counts.iter() {
+        if let Some(reason) = decision.reason() {
+            rejected.insert(reason.to_string(), serde_json::json!(count));
+        }
+    }
+    serde_json::json!({
+        "passed": counts.get(&types::FilterDecision::Pass).copied().unwrap_or(0),
+        "rejected": rejected,
+    })
+}
+
+/// Summarize the run so far from the trade history, positions and filter counts
+async fn run_report(
+    trader: &traders::trader::Trader,
+    config: &config::SharedConfig,
+    filter_counts: &std::sync::Mutex<HashMap<types::FilterDecision, u64>>,
+    pipeline_errors: &AtomicU64,
+    started_at: chrono::DateTime<chrono::Utc>,
+) -> utils::run_report::RunReport {
+    let trades = trader.trade_history(None).unwrap_or_else(|e| {
+        tracing::warn!("Failed to load trades for run report: {}", e);
+        Vec::new()
+    });
+    let filters = filter_summary(&filter_counts.lock().unwrap());
+    utils::run_report::RunReport::build(
+        started_at,
+        config.load().version().to_string(),
+        &trades,
+        &trader.positions().await,
+        filters,
+        pipeline_errors.load(Ordering::Relaxed),
+    )
+}
+
+/// Shared handles used to process each detected token
+#[derive(Clone)]
+struct TokenPipeline {
+    trader: Arc<traders::trader::Trader>,
+    shadow_tracker: Arc<traders::shadow_tracker::ShadowTracker>,
+    paper: Arc<traders::paper_leaderboard::PaperLeaderboard>,
+    event_log: Arc<utils::event_log::EventLog>,
+    notifier: Arc<utils::notifier::Notifier>,
+    enricher: Arc<utils::enrichment::Enricher>,
+    filter_counts: Arc<std::sync::Mutex<HashMap<types::FilterDecision, u64>>>,
+}
+
+/// Take an analysis slot, or count the event as shed when all `max_inflight` are taken
+///
+/// A cap of 0 admits everything. The slot is released by decrementing `inflight` once
+/// the analysis finishes.
+fn admit_analysis(inflight: &AtomicUsize, shed: &AtomicU64, max_inflight: usize) -> bool {
+    let admitted = inflight
+        .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |count| {
+            (max_inflight == 0 || count < max_inflight).then_some(count + 1)
         })
+        .is_ok();
+    if !admitted {
+        shed.fetch_add(1, Ordering::Relaxed);
+    }
+    admitted
+}
+
+/// Analyze a just-created token, retrying briefly while its curve account propagates
+///
+/// Only a missing curve account is retried; other failures, and a curve still
+/// missing after `curve_not_found_retries`, are returned as is.
+async fn analyze_new_token(
+    event: &types::NewTokenEvent,
+    client: &utils::solana_client::SolanaClient,
+    config: &config::BotConfig,
+) -> Result<types::TokenAnalysis, Box<dyn std::error::Error>> {
+    let mut retries = 0;
+    loop {
+        // The error is dropped before sleeping so the pipeline future stays Send
+        match utils::token_analyzer::analyze_token(&event.token_address, &event.bonding_curve_address, client).await {
+            Err(e) if utils::token_analyzer::is_curve_not_found_error(&e.to_string())
+                && retries < config.curve_not_found_retries => {}
+            result => return result,
+        }
+
+        retries += 1;
+        tracing::debug!(
+            "Bonding curve for {} not visible yet, retry {}/{}",
+            event.token_address,
+            retries,
+            config.curve_not_found_retries
+        );
+        tokio::time::sleep(std::time::Duration::from_millis(config.curve_not_found_retry_delay_ms)).await;
     }
 }
 
+/// Age of an event launched longer ago than `max_event_age_secs`, if it was
+fn stale_event_age_secs(event: &types::NewTokenEvent, config: &config::BotConfig) -> Option<i64> {
+    let max_age = config.max_event_age_secs?;
+    let age = (chrono::Utc::now() - event.timestamp).num_seconds();
+    (age > max_age as i64).then_some(age)
+}
+
 /// Handle new token detection
 async fn handle_new_token(
-    trader: Arc<traders::trader::Trader>,
+    pipeline: TokenPipeline,
     config: Arc<config::BotConfig>,
-    event: monitors::pump_fun_monitor::NewTokenEvent,
+    event: types::NewTokenEvent,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    use utils::event_log::LoggedEvent;
+    let TokenPipeline { trader, shadow_tracker, paper, event_log, notifier, enricher, filter_counts } = pipeline;
+
+    // Launches replayed by catch-up may be long past the point of sniping
+    if let Some(age) = stale_event_age_secs(&event, &config) {
+        tracing::info!("Dropping {}: launched {}s ago", event.token_address, age);
+        return Ok(());
+    }
+
     tracing::info!(
         "Processing new token: {} (creator: {})",
         event.token_address,
         event.creator
     );
 
+    // Time each stage; the WebSocket receive and parse happened before the event got here
+    let mut latency = utils::latency::LatencyBreakdown::start(event.token_address);
+    let since = |earlier: chrono::DateTime<chrono::Utc>, later: chrono::DateTime<chrono::Utc>| {
+        (later - earlier).to_std().unwrap_or_default()
+    };
+    // Replayed events carry their block time, which says nothing about pipeline latency
+    if let Some(received_at) = event.received_at {
+        latency.add("parse", since(received_at, event.timestamp));
+        latency.add("dispatch", since(event.timestamp, chrono::Utc::now()));
+    }
+
     // Analyze the token
-    let analysis = utils::token_analyzer::analyze_token(
-        &event.token_address,
-        &event.bonding_curve_address,
-        trader.client(),
-    ).await?;
+    let mut analysis = analyze_new_token(&event, trader.client(), &config)
+        .instrument(tracing::info_span!("analyze"))
+        .await?;
+    // Impact is judged at the size the buy would actually spend; an unfillable token quantity is blocked at buy time
+    let planned_buy_sol = trader.planned_buy_amount_sol(&analysis).await.unwrap_or(config.buy_amount_sol);
+    utils::token_analyzer::TokenAnalyzer::apply_price_impact(
+        &mut analysis.metrics,
+        &analysis.bonding_curve,
+        planned_buy_sol,
+    );
+    utils::token_analyzer::TokenAnalyzer::apply_creator_contribution(
+        &mut analysis.opportunities,
+        event.creator_sol_contributed,
+        &config,
+    );
+    latency.mark("analysis");
+
+    // On-chain trading signals, each an extra RPC round trip
+    if config.min_unique_buyers.is_some() {
+        const BUYER_LOOKBACK_TRANSACTIONS: usize = 50;
+        // Buyers only show up after launch; give them time to arrive before counting
+        let observed_ms = (chrono::Utc::now() - event.timestamp).num_milliseconds().max(0) as u64;
+        if let Some(wait_ms) = config.buyer_observation_ms.checked_sub(observed_ms).filter(|ms| *ms > 0) {
+            tokio::time::sleep(std::time::Duration::from_millis(wait_ms)).await;
+        }
+        match trader.client().recent_curve_buyers(&event.bonding_curve_address, BUYER_LOOKBACK_TRANSACTIONS).await {
+            Ok(buyers) => analysis.metrics.recent_buyers = Some(buyers),
+            Err(e) => tracing::warn!("Failed to fetch recent buyers for {}: {}", event.token_address, e),
+        }
+    }
+    if let Some(max_buyers) = config.max_creation_slot_buyers {
+        const CREATION_LOOKBACK_TRANSACTIONS: usize = 100;
+        match trader.client().creation_slot_buyers(&event.bonding_curve_address, CREATION_LOOKBACK_TRANSACTIONS).await {
+            Ok(buyers) => utils::token_analyzer::TokenAnalyzer::apply_creation_slot_buyers(
+                &mut analysis.safety,
+                &buyers,
+                &analysis.bonding_curve.creator,
+                max_buyers,
+            ),
+            Err(e) => tracing::warn!("Failed to fetch creation slot buyers for {}: {}", event.token_address, e),
+        }
+    }
+    if config.max_curve_idle_secs.is_some() {
+        match trader.client().last_activity_time(&event.bonding_curve_address).await {
+            Ok(last_trade_at) => analysis.metrics.last_trade_at = last_trade_at,
+            Err(e) => tracing::warn!("Failed to fetch last trade for {}: {}", event.token_address, e),
+        }
+    }
+    latency.mark("onchain_signals");
+
+    match enricher.fetch(&event.token_address).instrument(tracing::info_span!("enrich")).await {
+        Ok(Some(enrichment)) => utils::token_analyzer::TokenAnalyzer::apply_enrichment(&mut analysis, &enrichment),
+        Ok(None) => {}
+        Err(e) => tracing::warn!("Enrichment failed for {}: {}", event.token_address, e),
+    }
+    latency.mark("enrichment");
+    event_log.record(LoggedEvent::Analysis { analysis: Box::new(analysis.clone()) });
 
     // Check if token passes filters
-    if should_trade_token(&analysis, &config) {
-        // Execute trade
-        trader.execute_buy(&analysis).await?;
-    } else {
-        tracing::info!("Token filtered out: {}", event.token_address);
+    let decision = filter_decision(&analysis, &config);
+    latency.mark("filter");
+    // A buy's build and send join the breakdown once it is sent
+    if !decision.passed() || config.watchlist_only {
+        latency.finish(trader.latency());
+    }
+    *filter_counts.lock().unwrap().entry(decision).or_insert(0) += 1;
+    let reason = decision.reason();
+    event_log.record(LoggedEvent::FilterDecision {
+        token_address: event.token_address,
+        passed: reason.is_none(),
+        reason: reason.map(str::to_string),
+    });
+
+    // Config variants trade the same token on paper under their own filters, off the snipe path
+    if !paper.is_empty() {
+        let analysis = analysis.clone();
+        let source = event.source.clone();
+        tokio::spawn(async move {
+            paper.handle_token(&analysis, &source, should_trade_token).await;
+        });
+    }
+
+    match reason {
+        None if config.watchlist_only => {
+            // Alert only; the operator decides whether to trade
+            notifier.send(&utils::notifier::Notifier::token_alert(&analysis)).await?;
+        }
+        None => {
+            // Execute trade
+            let result = trader.execute_buy(&analysis, &event.source)
+                .instrument(tracing::info_span!("buy"))
+                .await
+                .map_err(|e| e.to_string());
+            latency.finish(trader.latency());
+            event_log.record(LoggedEvent::TradeOutcome {
+                token_address: event.token_address,
+                success: result.is_ok(),
+                error: result.as_ref().err().cloned(),
+            });
+            result?;
+            if trader.holds(&event.token_address).await {
+                trader.attach_trace(event.token_address, tracing::Span::current()).await;
+                // The buy already went through; a failed alert must not count as a pipeline error
+                if let Err(e) = notifier.send(&utils::notifier::Notifier::buy_alert(&analysis)).await {
+                    tracing::warn!("Failed to send buy alert for {}: {}", analysis.token.symbol, e);
+                }
+            }
+        }
+        Some(reason) => {
+            tracing::info!("Token filtered out: {} ({})", event.token_address, reason);
+            if !analysis.safety.details.issues.is_empty() {
+                tracing::info!("Safety issues: {}", analysis.safety.details.issues.join(", "));
+            }
+            shadow_tracker.record(&analysis, reason).await;
+        }
     }
 
     Ok(())
 }
 
+/// Replay a recorded event log through the filters in dry-run
+///
+/// Returns the number of filter decisions that differ from the recorded ones.
+pub fn replay_event_log(
+    path: &str,
+    config: &config::BotConfig,
+) -> Result<usize, Box<dyn std::error::Error>> {
+    use utils::event_log::{EventLog, LoggedEvent};
+
+    // Each decision belongs to the latest analysis of its mint recorded before it,
+    // so a mint seen several times is replayed once per sighting
+    let records = EventLog::read(path)?;
+    let mut pending = HashMap::new();
+    let mut mismatches = 0;
+    for record in &records {
+        match &record.event {
+            LoggedEvent::Analysis { analysis } => {
+                pending.insert(analysis.token.address, analysis);
+            }
+            LoggedEvent::FilterDecision { token_address, reason: original, .. } => {
+                let Some(analysis) = pending.remove(token_address) else {
+                    continue;
+                };
+                let replayed = filter_decision(analysis, config).reason().map(str::to_string);
+
+                if replayed == *original {
+                    tracing::info!("[REPLAY] {} -> {:?}", analysis.token.address, replayed);
+                } else {
+                    mismatches += 1;
+                    tracing::warn!(
+                        "[REPLAY] {} decision changed: recorded {:?}, replayed {:?}",
+                        analysis.token.address,
+                        original,
+                        replayed
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(mismatches)
+}
+
 /// Check if token should be traded based on configuration
 fn should_trade_token(
-    analysis: &utils::token_analyzer::TokenAnalysis,
+    analysis: &types::TokenAnalysis,
     config: &config::BotConfig,
 ) -> bool {
+    filter_decision(analysis, config).passed()
+}
+
+/// Run the token filters, stopping at the first gate that fails
+fn filter_decision(
+    analysis: &types::TokenAnalysis,
+    config: &config::BotConfig,
+) -> types::FilterDecision {
+    use types::FilterDecision;
+
+    // Degenerate curve reserves give no price to judge anything else by
+    if analysis.metrics.price_indeterminate {
+        return FilterDecision::PriceIndeterminate;
+    }
+
+    // Bundled launch check; otherwise the flag only lowers the safety score
+    if config.reject_bundle_launches && analysis.safety.checks.bundle_launch {
+        return FilterDecision::BundleLaunch;
+    }
+
     // Safety score check
-    if analysis.safety.score < 60 {
-        return false;
+    if analysis.safety.score < utils::safety_checker::MIN_SAFETY_SCORE {
+        return FilterDecision::SafetyScoreTooLow;
+    }
+
+    // Opportunity score check
+    if analysis.opportunities.score < config.min_opportunity_score {
+        return FilterDecision::OpportunityScoreTooLow;
     }
 
     // Market cap check
-    if analysis.metrics.market_cap < config.min_market_cap ||
-       analysis.metrics.market_cap > config.max_market_cap {
-        return false;
+    if analysis.metrics.market_cap < config.min_market_cap {
+        return FilterDecision::MarketCapTooLow;
+    }
+    if analysis.metrics.market_cap > config.max_market_cap {
+        return FilterDecision::MarketCapTooHigh;
+    }
+
+    // Price band check, in SOL per whole token; the tolerance keeps prices on a bound inside it
+    const PRICE_BAND_TOLERANCE: f64 = 1e-9;
+    let price = utils::bonding_curve::price_per_token(analysis.metrics.price);
+    if let Some(min_price) = config.min_buy_price {
+        if price < min_price * (1.0 - PRICE_BAND_TOLERANCE) {
+            return FilterDecision::PriceBelowBand;
+        }
+    }
+    if let Some(max_price) = config.max_buy_price {
+        if price > max_price * (1.0 + PRICE_BAND_TOLERANCE) {
+            return FilterDecision::PriceAboveBand;
+        }
     }
 
     // Liquidity check
     if analysis.metrics.liquidity < config.min_liquidity {
-        return false;
+        return FilterDecision::InsufficientLiquidity;
+    }
+
+    // Token program check
+    if analysis.safety.checks.token_2022 && !config.allow_token_2022 {
+        return FilterDecision::Token2022NotAllowed;
+    }
+
+    // Price impact check
+    if let Some(max_impact) = config.max_price_impact_pct {
+        if analysis.metrics.estimated_price_impact_pct > max_impact {
+            return FilterDecision::PriceImpactTooHigh;
+        }
+    }
+
+    // Exit liquidity check: selling the position back must not crater the price
+    if let Some(max_impact) = config.max_exit_impact_pct {
+        if analysis.metrics.estimated_exit_impact_pct > max_impact {
+            return FilterDecision::ExitImpactTooHigh;
+        }
+    }
+
+    // Curve activity check; unknown activity passes
+    if let (Some(max_idle), Some(last_trade_at)) = (config.max_curve_idle_secs, analysis.metrics.last_trade_at) {
+        if (chrono::Utc::now() - last_trade_at).num_seconds() > max_idle as i64 {
+            return FilterDecision::CurveStale;
+        }
+    }
+
+    // Organic demand check; unknown buyers pass
+    if let (Some(min_buyers), Some(buyers)) = (config.min_unique_buyers, &analysis.metrics.recent_buyers) {
+        let creator = config.exclude_creator_from_buyers.then_some(&analysis.bonding_curve.creator);
+        if count_unique_buyers(buyers, creator) < min_buyers as usize {
+            return FilterDecision::TooFewBuyers;
+        }
+    }
+
+    FilterDecision::Pass
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::{test_analysis, FilterDecision};
+
+    #[test]
+    fn low_opportunity_score_is_filtered_even_when_safety_passes() {
+        let config = config::BotConfig { min_opportunity_score: 60, ..Default::default() };
+        let mut analysis = test_analysis();
+        assert!(analysis.safety.score >= utils::safety_checker::MIN_SAFETY_SCORE);
+
+        analysis.opportunities.score = 59;
+        assert_eq!(filter_decision(&analysis, &config), FilterDecision::OpportunityScoreTooLow);
+        assert!(!should_trade_token(&analysis, &config));
+
+        analysis.opportunities.score = 60;
+        assert_eq!(filter_decision(&analysis, &config), FilterDecision::Pass);
+    }
+
+    #[test]
+    fn indeterminate_price_is_rejected_before_any_other_gate() {
+        let mut analysis = test_analysis();
+        analysis.metrics.price_indeterminate = true;
+        assert_eq!(filter_decision(&analysis, &config::BotConfig::default()), FilterDecision::PriceIndeterminate);
+        assert!(!should_trade_token(&analysis, &config::BotConfig::default()));
+    }
+
+    #[test]
+    fn replay_pairs_each_decision_with_the_analysis_before_it() {
+        use utils::event_log::{EventLog, LoggedEvent};
+
+        let path = std::env::temp_dir().join(format!("event-log-{}.jsonl", solana_sdk::pubkey::Pubkey::new_unique()));
+        let config = config::BotConfig { min_opportunity_score: 60, ..Default::default() };
+        let mut rejected = test_analysis();
+        rejected.opportunities.score = 10;
+        let mut accepted = rejected.clone();
+        accepted.opportunities.score = 90;
+
+        {
+            let log = EventLog::new(path.to_str()).unwrap();
+            for analysis in [&rejected, &accepted] {
+                let decision = filter_decision(analysis, &config);
+                log.record(LoggedEvent::Analysis { analysis: Box::new(analysis.clone()) });
+                log.record(LoggedEvent::FilterDecision {
+                    token_address: analysis.token.address,
+                    passed: decision.passed(),
+                    reason: decision.reason().map(str::to_string),
+                });
+            }
+        }
+
+        // Both sightings of the mint replay against their own decision
+        assert_eq!(EventLog::read(path.to_str().unwrap()).unwrap().len(), 4);
+        assert_eq!(replay_event_log(path.to_str().unwrap(), &config).unwrap(), 0);
+        let stricter = config::BotConfig { min_opportunity_score: 95, ..config };
+        assert_eq!(replay_event_log(path.to_str().unwrap(), &stricter).unwrap(), 1);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn replayed_launches_past_the_age_gate_are_dropped() {
+        let config = config::BotConfig { max_event_age_secs: Some(60), ..Default::default() };
+        let mut event = types::NewTokenEvent {
+            token_address: solana_sdk::pubkey::Pubkey::new_unique(),
+            bonding_curve_address: solana_sdk::pubkey::Pubkey::new_unique(),
+            creator: solana_sdk::pubkey::Pubkey::new_unique(),
+            creator_sol_contributed: 0.0,
+            timestamp: chrono::Utc::now() - chrono::Duration::minutes(3),
+            source: "pump_fun".to_string(),
+            received_at: None,
+        };
+        assert!(stale_event_age_secs(&event, &config).is_some_and(|age| age >= 180));
+        assert_eq!(stale_event_age_secs(&event, &config::BotConfig::default()), None);
+
+        event.timestamp = chrono::Utc::now() - chrono::Duration::seconds(30);
+        assert_eq!(stale_event_age_secs(&event, &config), None);
+    }
+
+    /// A fresh launch on a mock chain holding its curve and its mint, with mint authority revoked
+    ///
+    /// Methods other than `getAccountInfo` fail. Returns the creation event with the chain's
+    /// accounts, its URL and its request log.
+    async fn mock_launch() -> (types::NewTokenEvent, utils::solana_client::MockAccounts, String, utils::solana_client::RpcCalls) {
+        use utils::solana_client::{mint_account, mock_chain};
+
+        let mut curve = test_analysis().bonding_curve;
+        let mint = curve.token_address;
+        curve.address = utils::pump_fun_pdas::bonding_curve(&mint).0;
+        let curve_account = solana_sdk::account::Account {
+            lamports: 1_000_000,
+            data: utils::bonding_curve::encode(&curve),
+            owner: config::constants::PUMP_FUN_PROGRAM_ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        let accounts = Arc::new(std::sync::Mutex::new(HashMap::from([
+            (mint, mint_account(None)),
+            (curve.address, curve_account),
+        ])));
+        let (rpc_url, calls) = mock_chain(Arc::clone(&accounts), |_, _| {
+            serde_json::json!({ "error": { "code": -32601, "message": "Method not found" } })
+        }).await;
+        let event = types::NewTokenEvent {
+            token_address: mint,
+            bonding_curve_address: curve.address,
+            creator: curve.creator,
+            creator_sol_contributed: 0.0,
+            timestamp: chrono::Utc::now(),
+            source: "pump_fun".to_string(),
+            received_at: None,
+        };
+        (event, accounts, rpc_url, calls)
+    }
+
+    /// Simulated trading against `rpc_url` with in-memory storage, open to a fresh launch's market cap
+    fn test_config(rpc_url: String) -> config::BotConfig {
+        config::BotConfig {
+            rpc_url,
+            storage_backend: config::StorageBackend::Memory,
+            simulation_mode: true,
+            min_market_cap: 0.0,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn watchlist_mode_alerts_on_a_qualifying_token_without_buying() {
+        for watchlist_only in [true, false] {
+            let (event, _, rpc_url, _) = mock_launch().await;
+            let mint = event.token_address;
+            let config = config::BotConfig { watchlist_only, ..test_config(rpc_url) };
+            let sniper = PumpFunSniper::with_config(config::shared(config.clone())).await.unwrap();
+
+            let (_guard, logs) = utils::telemetry::capture_logs();
+            handle_new_token(sniper.token_pipeline(), Arc::new(config), event).await.unwrap();
+
+            // The token qualifies: outside watchlist mode it is bought
+            let alerted = logs.contents().contains(&format!("passed filters\nMint: {}", mint));
+            assert_eq!(alerted, watchlist_only, "{}", logs.contents());
+            assert_eq!(sniper.trader.holds(&mint).await, !watchlist_only);
+            assert_eq!(sniper.trader.trade_history(None).unwrap().is_empty(), watchlist_only);
+        }
+    }
+
+    #[test]
+    fn events_past_the_inflight_cap_are_shed_and_counted() {
+        let (inflight, shed) = (AtomicUsize::new(0), AtomicU64::new(0));
+
+        let admitted: Vec<bool> = (0..4).map(|_| admit_analysis(&inflight, &shed, 2)).collect();
+        assert_eq!(admitted, [true, true, false, false]);
+        assert_eq!(inflight.load(Ordering::Relaxed), 2);
+        assert_eq!(shed.load(Ordering::Relaxed), 2);
+
+        // A finished analysis frees its slot
+        inflight.fetch_sub(1, Ordering::Relaxed);
+        assert!(admit_analysis(&inflight, &shed, 2));
+        assert_eq!(shed.load(Ordering::Relaxed), 2);
+
+        // No cap sheds nothing
+        assert!((0..10).all(|_| admit_analysis(&inflight, &shed, 0)));
+        assert_eq!(shed.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn each_failing_filter_reports_its_own_reason() {
+        let config = config::BotConfig {
+            min_market_cap: 1_000.0,
+            max_market_cap: 10_000.0,
+            min_liquidity: 10.0,
+            reject_bundle_launches: true,
+            allow_token_2022: false,
+            max_price_impact_pct: Some(5.0),
+            max_exit_impact_pct: Some(5.0),
+            max_curve_idle_secs: Some(60),
+            min_unique_buyers: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(filter_decision(&test_analysis(), &config), FilterDecision::Pass);
+
+        type Failure = fn(&mut types::TokenAnalysis);
+        let cases: [(Failure, FilterDecision); 11] = [
+            (|a| a.safety.checks.bundle_launch = true, FilterDecision::BundleLaunch),
+            (|a| a.safety.score = utils::safety_checker::MIN_SAFETY_SCORE - 1, FilterDecision::SafetyScoreTooLow),
+            (|a| a.metrics.market_cap = 999.0, FilterDecision::MarketCapTooLow),
+            (|a| a.metrics.market_cap = 10_001.0, FilterDecision::MarketCapTooHigh),
+            (|a| a.metrics.liquidity = 9.0, FilterDecision::InsufficientLiquidity),
+            (|a| a.safety.checks.token_2022 = true, FilterDecision::Token2022NotAllowed),
+            (|a| a.metrics.estimated_price_impact_pct = 6.0, FilterDecision::PriceImpactTooHigh),
+            (|a| a.metrics.estimated_exit_impact_pct = 6.0, FilterDecision::ExitImpactTooHigh),
+            (|a| a.metrics.last_trade_at = Some(chrono::Utc::now() - chrono::Duration::seconds(120)), FilterDecision::CurveStale),
+            (|a| a.metrics.recent_buyers = Some(vec![solana_sdk::pubkey::Pubkey::new_unique()]), FilterDecision::TooFewBuyers),
+            (|a| a.metrics.price_indeterminate = true, FilterDecision::PriceIndeterminate),
+        ];
+
+        let mut counts = HashMap::new();
+        for (fail, expected) in cases {
+            let mut analysis = test_analysis();
+            fail(&mut analysis);
+            let decision = filter_decision(&analysis, &config);
+            assert_eq!(decision, expected);
+            *counts.entry(decision).or_insert(0) += 1;
+        }
+
+        let summary = filter_summary(&counts);
+        assert_eq!(summary["passed"], 0);
+        assert_eq!(summary["rejected"].as_object().unwrap().len(), 11);
+        assert_eq!(summary["rejected"]["exit price impact too high"], 1);
+    }
+
+    #[test]
+    fn stale_curve_is_rejected_and_an_active_one_passes() {
+        let config = config::BotConfig { max_curve_idle_secs: Some(300), ..Default::default() };
+        let mut analysis = test_analysis();
+
+        analysis.metrics.last_trade_at = Some(chrono::Utc::now() - chrono::Duration::minutes(10));
+        assert_eq!(filter_decision(&analysis, &config), FilterDecision::CurveStale);
+
+        analysis.metrics.last_trade_at = Some(chrono::Utc::now() - chrono::Duration::seconds(30));
+        assert_eq!(filter_decision(&analysis, &config), FilterDecision::Pass);
+
+        // Unknown activity is not held against the token
+        analysis.metrics.last_trade_at = None;
+        assert_eq!(filter_decision(&analysis, &config), FilterDecision::Pass);
+    }
+
+    #[test]
+    fn tokens_outside_the_price_band_are_filtered() {
+        let mut analysis = test_analysis();
+        // Scaled to whole tokens this comes out at 0.041999..., just under 0.042
+        analysis.metrics.price = 4.2e-8;
+        assert!(utils::bonding_curve::price_per_token(analysis.metrics.price) < 0.042);
+        let band = |min_buy_price, max_buy_price| config::BotConfig { min_buy_price, max_buy_price, ..Default::default() };
+
+        assert_eq!(filter_decision(&analysis, &band(Some(0.042), Some(0.042))), FilterDecision::Pass);
+        assert_eq!(filter_decision(&analysis, &band(Some(0.01), Some(0.1))), FilterDecision::Pass);
+        assert_eq!(filter_decision(&analysis, &band(Some(0.0421), None)), FilterDecision::PriceBelowBand);
+        assert_eq!(filter_decision(&analysis, &band(None, Some(0.0419))), FilterDecision::PriceAboveBand);
+        assert_eq!(filter_decision(&analysis, &band(None, None)), FilterDecision::Pass);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn curve_account_that_appears_late_is_retried_and_analyzed() {
+        let (event, accounts, rpc_url, _) = mock_launch().await;
+        let config = config::BotConfig {
+            curve_not_found_retries: 5,
+            curve_not_found_retry_delay_ms: 50,
+            ..test_config(rpc_url)
+        };
+        let client = utils::solana_client::SolanaClient::new(&config).await.unwrap();
+
+        // The curve account propagates to the RPC node a few retries after the create
+        let curve = accounts.lock().unwrap().remove(&event.bonding_curve_address).unwrap();
+        let propagating = Arc::clone(&accounts);
+        let propagation = tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(120)).await;
+            propagating.lock().unwrap().insert(event.bonding_curve_address, curve);
+        });
+        let (guard, logs) = utils::telemetry::capture_logs();
+        let analysis = analyze_new_token(&event, &client, &config).await.unwrap();
+        assert_eq!(analysis.bonding_curve.address, event.bonding_curve_address);
+        assert!(logs.contents().contains("retry 1/5"));
+        assert!(!logs.contents().contains("retry 5/5"));
+        propagation.await.unwrap();
+        drop(guard);
+
+        // A curve that never appears gives up after the configured retries
+        accounts.lock().unwrap().remove(&event.bonding_curve_address);
+        let (_guard, logs) = utils::telemetry::capture_logs();
+        let error = analyze_new_token(&event, &client, &config).await.unwrap_err();
+        assert!(utils::token_analyzer::is_curve_not_found_error(&error.to_string()));
+        assert!(logs.contents().contains("retry 5/5"));
     }
 
-    true
+    #[test]
+    fn acceptable_entry_with_too_costly_an_exit_is_rejected() {
+        use utils::token_analyzer::TokenAnalyzer;
+
+        let config = config::BotConfig {
+            max_price_impact_pct: Some(25.0),
+            max_exit_impact_pct: Some(15.0),
+            ..Default::default()
+        };
+        let mut analysis = test_analysis();
+
+        // 3 SOL into a 30 SOL curve: the entry moves the price ~21%, selling it all back drops it ~17%
+        TokenAnalyzer::apply_price_impact(&mut analysis.metrics, &analysis.bonding_curve, 3.0);
+        assert!(analysis.metrics.estimated_price_impact_pct < 25.0);
+        assert!(analysis.metrics.estimated_exit_impact_pct > 15.0);
+        assert_eq!(filter_decision(&analysis, &config), FilterDecision::ExitImpactTooHigh);
+        assert!(!should_trade_token(&analysis, &config));
+
+        // A position small enough to exit cleanly passes
+        TokenAnalyzer::apply_price_impact(&mut analysis.metrics, &analysis.bonding_curve, 1.0);
+        assert!(analysis.metrics.estimated_exit_impact_pct < 15.0);
+        assert_eq!(filter_decision(&analysis, &config), FilterDecision::Pass);
+    }
 }
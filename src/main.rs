@@ -1,29 +1,34 @@
 use std::sync::Arc;
 use tokio::time::{self, Duration};
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
-
-mod config;
-mod monitors;
-mod traders;
-mod utils;
-mod types;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Initialize tracing
-    tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "solana_pumpfun_sniper=info".into()),
-        )
-        .with(tracing_subscriber::fmt::layer())
-        .init();
+    let telemetry = Arc::new(solana_pumpfun_sniper::utils::telemetry::Telemetry::init());
 
     tracing::info!("Starting Solana Pump.fun Sniper Bot (Rust Edition)");
 
     // Load configuration
-    let config = Arc::new(config::load_config()?);
-    tracing::info!("Configuration loaded successfully");
+    let config = Arc::new(solana_pumpfun_sniper::config::load_config()?);
+    tracing::info!("Configuration loaded successfully (version {})", config.version());
+    telemetry.configure(&config)?;
+
+    // Replay a recorded session instead of trading
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(pos) = args.iter().position(|arg| arg == "--replay") {
+        let path = args.get(pos + 1).ok_or("--replay requires a file path")?;
+        let mismatches = solana_pumpfun_sniper::replay_event_log(path, &config)?;
+        tracing::info!("Replay finished with {} changed decisions", mismatches);
+        return Ok(());
+    }
+
+    // Fund the trading wallet on devnet before trading
+    if let Some(pos) = args.iter().position(|arg| arg == "--airdrop") {
+        let amount_sol: f64 = args.get(pos + 1).ok_or("--airdrop requires an amount in SOL")?.parse()?;
+        let client = solana_pumpfun_sniper::utils::solana_client::SolanaClient::new(&config).await?;
+        let signature = client.request_airdrop(amount_sol).await?;
+        tracing::info!("Airdropped {} SOL to the trading wallet: {}", amount_sol, signature);
+    }
 
     // Create bot instance
     let bot = Arc::new(solana_pumpfun_sniper::PumpFunSniper::new().await?);
@@ -33,15 +38,93 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Set up signal handling for graceful shutdown
     let bot_clone = Arc::clone(&bot);
+    let telemetry_clone = Arc::clone(&telemetry);
     tokio::spawn(async move {
         tokio::signal::ctrl_c().await.unwrap();
         tracing::info!("Received shutdown signal");
         if let Err(e) = bot_clone.stop().await {
             tracing::error!("Error during shutdown: {}", e);
         }
+        telemetry_clone.shutdown();
         std::process::exit(0);
     });
 
+    // Reload mutable configuration on SIGHUP
+    #[cfg(unix)]
+    {
+        let bot_clone = Arc::clone(&bot);
+        tokio::spawn(async move {
+            use tokio::signal::unix::{signal, SignalKind};
+            let mut hangup = match signal(SignalKind::hangup()) {
+                Ok(hangup) => hangup,
+                Err(e) => {
+                    tracing::error!("Failed to install SIGHUP handler: {}", e);
+                    return;
+                }
+            };
+            while hangup.recv().await.is_some() {
+                tracing::info!("Received SIGHUP, reloading configuration");
+                if let Err(e) = bot_clone.reload_config() {
+                    tracing::error!("Configuration reload failed: {}", e);
+                }
+            }
+        });
+    }
+
+    // Reconnect the monitors on SIGUSR1, keeping trader state
+    #[cfg(unix)]
+    {
+        let bot_clone = Arc::clone(&bot);
+        tokio::spawn(async move {
+            use tokio::signal::unix::{signal, SignalKind};
+            let mut user_defined1 = match signal(SignalKind::user_defined1()) {
+                Ok(user_defined1) => user_defined1,
+                Err(e) => {
+                    tracing::error!("Failed to install SIGUSR1 handler: {}", e);
+                    return;
+                }
+            };
+            while user_defined1.recv().await.is_some() {
+                tracing::info!("Received SIGUSR1, restarting monitors");
+                if let Err(e) = bot_clone.restart_monitor().await {
+                    tracing::error!("Monitor restart failed: {}", e);
+                }
+            }
+        });
+    }
+
+    // Dump every position on SIGUSR2
+    #[cfg(unix)]
+    {
+        let bot_clone = Arc::clone(&bot);
+        tokio::spawn(async move {
+            use tokio::signal::unix::{signal, SignalKind};
+            let mut user_defined2 = match signal(SignalKind::user_defined2()) {
+                Ok(user_defined2) => user_defined2,
+                Err(e) => {
+                    tracing::error!("Failed to install SIGUSR2 handler: {}", e);
+                    return;
+                }
+            };
+            while user_defined2.recv().await.is_some() {
+                tracing::warn!("Received SIGUSR2, panic selling all positions");
+                let sold = bot_clone.panic_sell().await;
+                tracing::warn!("Panic sell attempted for {} positions", sold);
+            }
+        });
+    }
+
+    // Serve the operator control endpoint
+    if let Some(addr) = config.control_listen_addr.clone() {
+        let bot_clone = Arc::clone(&bot);
+        let token = config.control_token.clone();
+        tokio::spawn(async move {
+            if let Err(e) = solana_pumpfun_sniper::control::serve(bot_clone, &addr, token).await {
+                tracing::error!("Control endpoint on {} stopped: {}", addr, e);
+            }
+        });
+    }
+
     // Health check loop
     let mut interval = time::interval(Duration::from_secs(60));
     loop {
@@ -1 +1,21 @@
 pub mod pump_fun_monitor;
+pub mod monitor_set;
+pub mod curve_subscriber;
+
+/// Run an event callback, logging a panic instead of letting it end the dispatch loop
+pub(crate) fn dispatch<T, F: Fn(T)>(callback: &F, event: T) {
+    if let Err(panic) = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| callback(event))) {
+        let message = panic.downcast_ref::<&str>().map(|s| s.to_string())
+            .or_else(|| panic.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+        tracing::error!("Event callback panicked: {}", message);
+    }
+}
+
+/// Wait for stopped callback tasks to exit, by which point their receivers are back in their slots
+pub(crate) async fn join_callbacks(tasks: &std::sync::Mutex<Vec<tokio::task::JoinHandle<()>>>) {
+    let tasks: Vec<_> = tasks.lock().unwrap().drain(..).collect();
+    for task in tasks {
+        let _ = task.await;
+    }
+}
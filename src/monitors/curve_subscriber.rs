@@ -0,0 +1,552 @@
+use base64::Engine;
+use chrono::Utc;
+use futures_util::{SinkExt, StreamExt};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::time::Instant;
+use tokio::sync::{mpsc, watch, RwLock};
+use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
+use serde_json::json;
+use crate::{
+    config::{SharedConfig, constants::*},
+    types::CurveUpdateEvent,
+    utils::bonding_curve,
+};
+
+/// Change to the set of watched bonding curves
+enum CurveCommand {
+    Watch { curve: Pubkey },
+    Unwatch { curve: Pubkey },
+}
+
+/// Per-curve health of the current connection
+#[derive(Default)]
+struct FeedHealth {
+    /// Curves whose subscription the current connection confirmed
+    subscribed: HashSet<Pubkey>,
+    last_update: HashMap<Pubkey, chrono::DateTime<Utc>>,
+}
+
+/// Pushes state changes of held bonding curves over `accountSubscribe`
+pub struct CurveSubscriber {
+    config: SharedConfig,
+    command_sender: mpsc::UnboundedSender<CurveCommand>,
+    command_receiver: Arc<RwLock<Option<mpsc::UnboundedReceiver<CurveCommand>>>>,
+    update_sender: mpsc::UnboundedSender<CurveUpdateEvent>,
+    update_receiver: Arc<RwLock<Option<mpsc::UnboundedReceiver<CurveUpdateEvent>>>>,
+    watched: Arc<RwLock<HashMap<Pubkey, Pubkey>>>,
+    polled_curves: AtomicUsize,
+    feed_health: Arc<RwLock<FeedHealth>>,
+    is_running: Arc<RwLock<bool>>,
+    stopped: AtomicBool,
+    last_reconnect_attempt: std::sync::Mutex<Option<Instant>>,
+    reconnects: AtomicU64,
+    shutdown: watch::Sender<()>,
+}
+
+impl CurveSubscriber {
+    /// Create a subscriber for the configured WebSocket endpoint
+    pub fn new(config: SharedConfig) -> Self {
+        let (command_sender, command_receiver) = mpsc::unbounded_channel();
+        let (update_sender, update_receiver) = mpsc::unbounded_channel();
+        let (shutdown, _) = watch::channel(());
+
+        Self {
+            config,
+            command_sender,
+            command_receiver: Arc::new(RwLock::new(Some(command_receiver))),
+            update_sender,
+            update_receiver: Arc::new(RwLock::new(Some(update_receiver))),
+            watched: Arc::new(RwLock::new(HashMap::new())),
+            polled_curves: AtomicUsize::new(0),
+            feed_health: Arc::new(RwLock::new(FeedHealth::default())),
+            is_running: Arc::new(RwLock::new(false)),
+            stopped: AtomicBool::new(false),
+            last_reconnect_attempt: std::sync::Mutex::new(None),
+            reconnects: AtomicU64::new(0),
+            shutdown,
+        }
+    }
+
+    /// Connect and start delivering updates for watched curves
+    pub async fn start(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if *self.is_running.read().await {
+            return Ok(());
+        }
+
+        let ws_url = self.config.load().ws_url.clone().ok_or("WebSocket URL not configured")?;
+        let (ws_stream, _) = connect_async(ws_url.as_str()).await?;
+        let (mut write, mut read) = ws_stream.split();
+
+        let mut commands = self.command_receiver.write().await.take()
+            .ok_or("Curve subscriber is already running")?;
+        let command_slot = Arc::clone(&self.command_receiver);
+        let update_sender = self.update_sender.clone();
+        let watched = Arc::clone(&self.watched);
+        let feed_health = Arc::clone(&self.feed_health);
+        let is_running = Arc::clone(&self.is_running);
+        let mut shutdown = self.shutdown.subscribe();
+
+        // Curves already watched need subscribing on the new connection; commands queued
+        // while disconnected are superseded by that
+        while commands.try_recv().is_ok() {}
+        for curve in self.watched.read().await.keys() {
+            let _ = self.command_sender.send(CurveCommand::Watch { curve: *curve });
+        }
+        *self.is_running.write().await = true;
+        self.stopped.store(false, Ordering::Relaxed);
+
+        tokio::spawn(async move {
+            let mut next_request_id = 1u64;
+            let mut pending_requests: HashMap<u64, Pubkey> = HashMap::new();
+            let mut subscriptions: HashMap<u64, Pubkey> = HashMap::new();
+
+            loop {
+                tokio::select! {
+                    command = commands.recv() => {
+                        let request = match command {
+                            Some(CurveCommand::Watch { curve }) => {
+                                pending_requests.insert(next_request_id, curve);
+                                json!({
+                                    "jsonrpc": "2.0",
+                                    "id": next_request_id,
+                                    "method": "accountSubscribe",
+                                    "params": [
+                                        curve.to_string(),
+                                        { "encoding": "base64", "commitment": DEFAULT_COMMITMENT }
+                                    ]
+                                })
+                            }
+                            Some(CurveCommand::Unwatch { curve }) => {
+                                let subscription = subscriptions.iter()
+                                    .find(|(_, watched_curve)| **watched_curve == curve)
+                                    .map(|(id, _)| *id);
+                                match subscription {
+                                    Some(id) => {
+                                        subscriptions.remove(&id);
+                                        feed_health.write().await.subscribed.remove(&curve);
+                                        Self::unsubscribe_request(next_request_id, id)
+                                    }
+                                    // Not confirmed yet: its confirmation is unsubscribed on arrival
+                                    None => continue,
+                                }
+                            }
+                            None => break,
+                        };
+                        next_request_id += 1;
+                        if let Err(e) = write.send(Message::Text(request.to_string())).await {
+                            tracing::error!("Curve subscription request failed: {}", e);
+                            break;
+                        }
+                    }
+                    message = read.next() => match message {
+                        Some(Ok(Message::Text(text))) => {
+                            let stale = Self::handle_message(&text, &mut pending_requests, &mut subscriptions, &watched, &feed_health, &update_sender).await;
+                            if let Some(subscription) = stale {
+                                let request = Self::unsubscribe_request(next_request_id, subscription);
+                                next_request_id += 1;
+                                if let Err(e) = write.send(Message::Text(request.to_string())).await {
+                                    tracing::error!("Curve subscription request failed: {}", e);
+                                    break;
+                                }
+                            }
+                        }
+                        Some(Ok(Message::Close(_))) | None => {
+                            tracing::info!("Curve subscription connection closed");
+                            break;
+                        }
+                        Some(Err(e)) => {
+                            tracing::error!("Curve subscription WebSocket error: {}", e);
+                            break;
+                        }
+                        Some(Ok(_)) => {}
+                    },
+                    _ = shutdown.changed() => break,
+                }
+            }
+
+            // Subscriptions die with the connection
+            feed_health.write().await.subscribed.clear();
+
+            // Hand the command receiver back before reporting stopped, so a restart finds it
+            *command_slot.write().await = Some(commands);
+            *is_running.write().await = false;
+        });
+
+        tracing::info!("Curve subscriber started");
+        Ok(())
+    }
+
+    /// Stop delivering updates
+    pub async fn stop(&self) {
+        self.stopped.store(true, Ordering::Relaxed);
+        self.shutdown.send_replace(());
+    }
+
+    /// Whether the subscription connection is up
+    pub async fn is_running(&self) -> bool {
+        *self.is_running.read().await
+    }
+
+    /// Restart a dropped connection, at most once per `curve_reconnect_delay_ms`
+    ///
+    /// Every watched curve is resubscribed on the new connection. Does nothing after
+    /// `stop`. Returns whether the feed is down, so callers can poll meanwhile.
+    pub async fn reconnect_if_dropped(&self) -> bool {
+        if self.stopped.load(Ordering::Relaxed) || self.is_running().await {
+            return false;
+        }
+
+        let delay = std::time::Duration::from_millis(self.config.load().curve_reconnect_delay_ms);
+        {
+            let mut last_attempt = self.last_reconnect_attempt.lock().unwrap();
+            if last_attempt.is_some_and(|at| at.elapsed() < delay) {
+                return true;
+            }
+            *last_attempt = Some(Instant::now());
+        }
+
+        match self.start().await {
+            Ok(()) => {
+                self.reconnects.fetch_add(1, Ordering::Relaxed);
+                tracing::info!("Curve subscription feed reconnected");
+                false
+            }
+            Err(e) => {
+                tracing::warn!("Curve subscription reconnect failed: {}", e);
+                true
+            }
+        }
+    }
+
+    /// Subscribe to curves not yet watched and drop those no longer held
+    ///
+    /// `held` pairs each bonding curve with its mint. Curves beyond `max_curve_subscriptions`
+    /// stay unsubscribed, leaving the trader to poll them.
+    pub async fn sync(&self, held: &[(Pubkey, Pubkey)]) {
+        let max_subscriptions = self.config.load().max_curve_subscriptions.unwrap_or(usize::MAX);
+        let mut watched = self.watched.write().await;
+
+        // Drop stale curves first so their slots go to newly held ones
+        let stale: Vec<Pubkey> = watched.keys()
+            .filter(|curve| !held.iter().any(|(held_curve, _)| held_curve == *curve))
+            .copied()
+            .collect();
+        for curve in stale {
+            watched.remove(&curve);
+            self.feed_health.write().await.last_update.remove(&curve);
+            let _ = self.command_sender.send(CurveCommand::Unwatch { curve });
+        }
+
+        let mut polled = 0;
+        for (curve, mint) in held {
+            if watched.contains_key(curve) {
+                continue;
+            }
+            if watched.len() >= max_subscriptions {
+                polled += 1;
+                continue;
+            }
+            watched.insert(*curve, *mint);
+            let _ = self.command_sender.send(CurveCommand::Watch { curve: *curve });
+        }
+
+        let previously_polled = self.polled_curves.swap(polled, Ordering::Relaxed);
+        if polled > 0 && previously_polled == 0 {
+            tracing::warn!(
+                "Curve subscription cap of {} reached; polling {} positions instead",
+                max_subscriptions,
+                polled
+            );
+        }
+    }
+
+    /// Register callback for curve updates
+    ///
+    /// Only one callback can be registered at a time. It runs until the subscriber is
+    /// stopped, after which a new callback may be registered.
+    pub async fn on_update<F>(&self, callback: F) -> Result<(), Box<dyn std::error::Error>>
+    where
+        F: Fn(CurveUpdateEvent) + Send + Sync + 'static,
+    {
+        let mut receiver = self.update_receiver.write().await.take()
+            .ok_or("A curve update callback is already registered")?;
+        let slot = Arc::clone(&self.update_receiver);
+        let mut shutdown = self.shutdown.subscribe();
+
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    event = receiver.recv() => match event {
+                        Some(event) => crate::monitors::dispatch(&callback, event),
+                        None => break,
+                    },
+                    _ = shutdown.changed() => break,
+                }
+            }
+
+            // Hand the receiver back so a callback can be registered again
+            *slot.write().await = Some(receiver);
+        });
+
+        Ok(())
+    }
+
+    /// `accountUnsubscribe` request for a confirmed subscription
+    fn unsubscribe_request(request_id: u64, subscription: u64) -> serde_json::Value {
+        json!({
+            "jsonrpc": "2.0",
+            "id": request_id,
+            "method": "accountUnsubscribe",
+            "params": [subscription]
+        })
+    }
+
+    /// Track subscription confirmations and decode account notifications
+    ///
+    /// Returns a confirmed subscription whose curve was unwatched while it was pending,
+    /// for the caller to unsubscribe.
+    async fn handle_message(
+        text: &str,
+        pending_requests: &mut HashMap<u64, Pubkey>,
+        subscriptions: &mut HashMap<u64, Pubkey>,
+        watched: &RwLock<HashMap<Pubkey, Pubkey>>,
+        feed_health: &RwLock<FeedHealth>,
+        update_sender: &mpsc::UnboundedSender<CurveUpdateEvent>,
+    ) -> Option<u64> {
+        let message: serde_json::Value = match serde_json::from_str(text) {
+            Ok(message) => message,
+            Err(e) => {
+                tracing::warn!("Invalid curve subscription message: {}", e);
+                return None;
+            }
+        };
+
+        // Subscription confirmation: {"id": request, "result": subscription}
+        if let (Some(id), Some(subscription)) = (
+            message.get("id").and_then(|id| id.as_u64()),
+            message.get("result").and_then(|result| result.as_u64()),
+        ) {
+            let curve = pending_requests.remove(&id)?;
+            // A curve watched twice keeps a single subscription
+            if !watched.read().await.contains_key(&curve) || subscriptions.values().any(|c| *c == curve) {
+                return Some(subscription);
+            }
+            subscriptions.insert(subscription, curve);
+            feed_health.write().await.subscribed.insert(curve);
+            return None;
+        }
+
+        if message.get("method").and_then(|m| m.as_str()) != Some("accountNotification") {
+            return None;
+        }
+        let params = message.get("params")?;
+        let curve = *params.get("subscription").and_then(|s| s.as_u64()).and_then(|s| subscriptions.get(&s))?;
+        let mint = *watched.read().await.get(&curve)?;
+
+        let result = &params["result"];
+        let slot = result["context"]["slot"].as_u64().unwrap_or(0);
+        let data = result["value"]["data"][0].as_str()
+            .and_then(|data| base64::engine::general_purpose::STANDARD.decode(data).ok())?;
+
+        match bonding_curve::decode(&curve, &mint, &data).map_err(|e| e.to_string()) {
+            Ok(curve) => {
+                feed_health.write().await.last_update.insert(curve.address, Utc::now());
+                let event = CurveUpdateEvent { curve, slot, timestamp: Utc::now() };
+                if update_sender.send(event).is_err() {
+                    tracing::error!("Failed to send curve update - channel closed");
+                }
+            }
+            Err(e) => tracing::warn!("Failed to decode bonding curve {}: {}", curve, e),
+        }
+        None
+    }
+
+    /// Get subscriber status
+    pub async fn status(&self) -> serde_json::Value {
+        let watched = self.watched.read().await;
+        let health = self.feed_health.read().await;
+        let curves: Vec<serde_json::Value> = watched
+            .iter()
+            .map(|(curve, mint)| json!({
+                "curve": curve.to_string(),
+                "mint": mint.to_string(),
+                "subscribed": health.subscribed.contains(curve),
+                "last_update": health.last_update.get(curve),
+            }))
+            .collect();
+
+        json!({
+            "running": *self.is_running.read().await,
+            "reconnects": self.reconnects.load(Ordering::Relaxed),
+            "watched_curves": curves.len(),
+            "polled_curves": self.polled_curves.load(Ordering::Relaxed),
+            "curves": curves,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{self, BotConfig};
+    use tokio::net::TcpListener;
+
+    /// Base64 account data of a bonding curve with the given virtual SOL reserves
+    fn curve_data(virtual_sol_reserves: u64) -> String {
+        let mut data = vec![0u8; 8];
+        for value in [1_073_000_000_000_000u64, virtual_sol_reserves, 793_100_000_000_000, 0, 1_000_000_000_000_000] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.push(0);
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        base64::engine::general_purpose::STANDARD.encode(data)
+    }
+
+    /// Next JSON-RPC request the subscriber sent
+    async fn next_request<S>(ws: &mut tokio_tungstenite::WebSocketStream<S>) -> serde_json::Value
+    where
+        S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+    {
+        loop {
+            match ws.next().await {
+                Some(Ok(Message::Text(text))) => return serde_json::from_str(&text).unwrap(),
+                Some(Ok(_)) => continue,
+                other => panic!("connection ended before the next request: {:?}", other),
+            }
+        }
+    }
+
+    /// Accept one connection, confirm its subscription, push one update and hang up
+    async fn serve_one_connection(listener: &TcpListener, virtual_sol_reserves: u64) {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+
+        let request = next_request(&mut ws).await;
+        assert_eq!(request["method"], "accountSubscribe");
+
+        let confirmation = json!({ "jsonrpc": "2.0", "id": request["id"], "result": 7 });
+        ws.send(Message::Text(confirmation.to_string())).await.unwrap();
+        let notification = json!({
+            "jsonrpc": "2.0",
+            "method": "accountNotification",
+            "params": {
+                "subscription": 7,
+                "result": {
+                    "context": { "slot": 1 },
+                    "value": { "data": [curve_data(virtual_sol_reserves), "base64"] }
+                }
+            }
+        });
+        ws.send(Message::Text(notification.to_string())).await.unwrap();
+        ws.close(None).await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn dropped_feed_reconnects_and_resumes_updates() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let config = BotConfig {
+            ws_url: Some(format!("ws://{}", listener.local_addr().unwrap())),
+            curve_reconnect_delay_ms: 0,
+            ..Default::default()
+        };
+
+        let server = tokio::spawn(async move {
+            serve_one_connection(&listener, 30_000_000_000).await;
+            serve_one_connection(&listener, 31_000_000_000).await;
+        });
+
+        let subscriber = CurveSubscriber::new(config::shared(config));
+        let (updates, mut received) = mpsc::unbounded_channel();
+        subscriber.on_update(move |event| {
+            let _ = updates.send(event);
+        }).await.unwrap();
+
+        let (curve, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        subscriber.sync(&[(curve, mint)]).await;
+        subscriber.start().await.unwrap();
+
+        let wait = std::time::Duration::from_secs(5);
+        let first = tokio::time::timeout(wait, received.recv()).await.unwrap().unwrap();
+        assert_eq!(first.curve.address, curve);
+        assert_eq!(first.curve.virtual_sol_reserves, 30_000_000_000);
+
+        // The server hung up; the feed reports down until reconnected
+        tokio::time::timeout(wait, async {
+            while subscriber.is_running().await {
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            }
+        }).await.unwrap();
+        assert!(!subscriber.reconnect_if_dropped().await);
+
+        let resumed = tokio::time::timeout(wait, received.recv()).await.unwrap().unwrap();
+        assert_eq!(resumed.curve.address, curve);
+        assert_eq!(resumed.curve.virtual_sol_reserves, 31_000_000_000);
+        assert_eq!(subscriber.status().await["reconnects"], 1);
+
+        subscriber.stop().await;
+        server.await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn curve_unwatched_while_pending_is_unsubscribed_once_confirmed() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let config = BotConfig {
+            ws_url: Some(format!("ws://{}", listener.local_addr().unwrap())),
+            ..Default::default()
+        };
+        let subscriber = Arc::new(CurveSubscriber::new(config::shared(config)));
+        subscriber.sync(&[(Pubkey::new_unique(), Pubkey::new_unique())]).await;
+
+        let unwatching = Arc::clone(&subscriber);
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let subscribe = next_request(&mut ws).await;
+            assert_eq!(subscribe["method"], "accountSubscribe");
+
+            // The position closes before the subscription is confirmed
+            unwatching.sync(&[]).await;
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            let confirmation = json!({ "jsonrpc": "2.0", "id": subscribe["id"], "result": 7 });
+            ws.send(Message::Text(confirmation.to_string())).await.unwrap();
+
+            let unsubscribe = next_request(&mut ws).await;
+            assert_eq!(unsubscribe["method"], "accountUnsubscribe");
+            assert_eq!(unsubscribe["params"], json!([7]));
+        });
+
+        subscriber.start().await.unwrap();
+        tokio::time::timeout(std::time::Duration::from_secs(5), server).await.unwrap().unwrap();
+        assert_eq!(subscriber.status().await["watched_curves"], 0);
+        subscriber.stop().await;
+    }
+
+    #[tokio::test]
+    async fn curves_past_the_subscription_cap_are_left_to_polling_with_a_warning() {
+        let config = BotConfig { max_curve_subscriptions: Some(2), ..Default::default() };
+        let subscriber = CurveSubscriber::new(config::shared(config));
+        let held: Vec<(Pubkey, Pubkey)> = (0..3).map(|_| (Pubkey::new_unique(), Pubkey::new_unique())).collect();
+        let (_guard, logs) = crate::utils::telemetry::capture_logs();
+
+        subscriber.sync(&held).await;
+        let status = subscriber.status().await;
+        assert_eq!(status["watched_curves"], 2);
+        assert_eq!(status["polled_curves"], 1);
+        let watched = |curve: &Pubkey| status["curves"].as_array().unwrap().iter().any(|c| c["curve"] == curve.to_string());
+        assert!(watched(&held[0].0) && watched(&held[1].0) && !watched(&held[2].0));
+        assert!(logs.contents().contains("Curve subscription cap of 2 reached; polling 1 positions instead"));
+
+        // The warning fires when the cap is first hit, not on every sync
+        subscriber.sync(&held).await;
+        assert_eq!(logs.contents().matches("Curve subscription cap").count(), 1);
+
+        // A closed position frees its slot for the polled curve
+        subscriber.sync(&held[1..]).await;
+        let status = subscriber.status().await;
+        assert_eq!(status["watched_curves"], 2);
+        assert_eq!(status["polled_curves"], 0);
+    }
+}
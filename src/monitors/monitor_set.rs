@@ -0,0 +1,333 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use solana_sdk::pubkey::Pubkey;
+use tokio::sync::{mpsc, watch, Mutex, RwLock};
+use tokio::task::JoinHandle;
+use crate::{
+    monitors::pump_fun_monitor::PumpFunMonitor,
+    types::{MigrationEvent, NewTokenEvent, SellActivityEvent},
+};
+
+/// How long a detected mint is remembered for deduplication
+const DEDUPE_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Runs several monitors concurrently and merges their events
+pub struct MonitorSet {
+    monitors: Vec<PumpFunMonitor>,
+    event_sender: mpsc::UnboundedSender<NewTokenEvent>,
+    event_receiver: Arc<RwLock<Option<mpsc::UnboundedReceiver<NewTokenEvent>>>>,
+    migration_sender: mpsc::UnboundedSender<MigrationEvent>,
+    migration_receiver: Arc<RwLock<Option<mpsc::UnboundedReceiver<MigrationEvent>>>>,
+    sell_sender: mpsc::UnboundedSender<SellActivityEvent>,
+    sell_receiver: Arc<RwLock<Option<mpsc::UnboundedReceiver<SellActivityEvent>>>>,
+    seen: Arc<Mutex<HashMap<Pubkey, Instant>>>,
+    shutdown: watch::Sender<()>,
+    callback_tasks: std::sync::Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl MonitorSet {
+    /// Create an empty monitor set
+    pub fn new() -> Self {
+        let (event_sender, event_receiver) = mpsc::unbounded_channel();
+        let (migration_sender, migration_receiver) = mpsc::unbounded_channel();
+        let (sell_sender, sell_receiver) = mpsc::unbounded_channel();
+        let (shutdown, _) = watch::channel(());
+
+        Self {
+            monitors: Vec::new(),
+            event_sender,
+            event_receiver: Arc::new(RwLock::new(Some(event_receiver))),
+            migration_sender,
+            migration_receiver: Arc::new(RwLock::new(Some(migration_receiver))),
+            sell_sender,
+            sell_receiver: Arc::new(RwLock::new(Some(sell_receiver))),
+            seen: Arc::new(Mutex::new(HashMap::new())),
+            shutdown,
+            callback_tasks: std::sync::Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Add a monitor to the set
+    pub async fn add_monitor(&mut self, monitor: PumpFunMonitor) {
+        self.monitors.push(monitor);
+    }
+
+    /// Order the monitors by source name, listed sources first and the rest in the order added
+    ///
+    /// Monitors start in this order and the first one serves catch-up. Detection itself is
+    /// not held back for a preferred source: whichever source sees a mint first wins.
+    pub fn prioritize(&mut self, priority: &[String]) {
+        self.monitors.sort_by_key(|monitor| {
+            priority.iter().position(|source| source == monitor.source()).unwrap_or(priority.len())
+        });
+    }
+
+    /// Start all monitors, forwarding their events into the merged stream
+    ///
+    /// All or nothing: if any monitor fails to start, the ones already started are stopped
+    /// and the error is returned, so the caller can retry the whole set.
+    pub async fn start(&self) -> Result<(), Box<dyn std::error::Error>> {
+        for monitor in &self.monitors {
+            if monitor.is_running().await {
+                continue;
+            }
+
+            if let Err(e) = self.start_monitor(monitor).await.map_err(|e| e.to_string()) {
+                let message = format!("Failed to start {} monitor: {}", monitor.source(), e);
+                for started in &self.monitors {
+                    started.stop().await?;
+                }
+                return Err(message.into());
+            }
+        }
+        Ok(())
+    }
+
+    async fn start_monitor(&self, monitor: &PumpFunMonitor) -> Result<(), Box<dyn std::error::Error>> {
+        let sender = self.event_sender.clone();
+        monitor.on_new_token(move |event| {
+            let _ = sender.send(event);
+        }).await?;
+
+        let migration_sender = self.migration_sender.clone();
+        monitor.on_migration(move |event| {
+            let _ = migration_sender.send(event);
+        }).await?;
+
+        let sell_sender = self.sell_sender.clone();
+        monitor.on_sell(move |event| {
+            let _ = sell_sender.send(event);
+        }).await?;
+
+        monitor.start().await
+    }
+
+    /// Replay recent launches through the first monitor; duplicates of live detections are dropped
+    pub fn catch_up(
+        &self,
+        since: chrono::DateTime<chrono::Utc>,
+    ) -> impl std::future::Future<Output = Result<usize, String>> + Send + 'static {
+        let catch_up = self.monitors.first().map(|monitor| monitor.catch_up(since));
+        async move {
+            match catch_up {
+                Some(catch_up) => catch_up.await,
+                None => Ok(0),
+            }
+        }
+    }
+
+    /// Stop all monitors
+    pub async fn stop(&self) -> Result<(), Box<dyn std::error::Error>> {
+        for monitor in &self.monitors {
+            monitor.stop().await?;
+        }
+        self.shutdown.send_replace(());
+        crate::monitors::join_callbacks(&self.callback_tasks).await;
+        Ok(())
+    }
+
+    /// Register callback for deduplicated new token events
+    ///
+    /// When several sources detect the same mint, only the first is delivered. Only one
+    /// callback can be registered at a time; it runs until the set is stopped.
+    pub async fn on_new_token<F>(&self, callback: F) -> Result<(), Box<dyn std::error::Error>>
+    where
+        F: Fn(NewTokenEvent) + Send + Sync + 'static,
+    {
+        let mut receiver = self.event_receiver.write().await.take()
+            .ok_or("A new token callback is already registered")?;
+        let slot = Arc::clone(&self.event_receiver);
+        let seen = Arc::clone(&self.seen);
+        let mut shutdown = self.shutdown.subscribe();
+
+        let task = tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    event = receiver.recv() => match event {
+                        Some(event) => {
+                            if Self::is_first_detection(&seen, &event).await {
+                                crate::monitors::dispatch(&callback, event);
+                            }
+                        }
+                        None => break,
+                    },
+                    _ = shutdown.changed() => break,
+                }
+            }
+
+            // Hand the receiver back so a callback can be registered again
+            *slot.write().await = Some(receiver);
+        });
+        self.callback_tasks.lock().unwrap().push(task);
+
+        Ok(())
+    }
+
+    /// Register callback for bonding curve completion events from any source
+    pub async fn on_migration<F>(&self, callback: F) -> Result<(), Box<dyn std::error::Error>>
+    where
+        F: Fn(MigrationEvent) + Send + Sync + 'static,
+    {
+        let mut receiver = self.migration_receiver.write().await.take()
+            .ok_or("A migration callback is already registered")?;
+        let slot = Arc::clone(&self.migration_receiver);
+        let mut shutdown = self.shutdown.subscribe();
+
+        let task = tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    event = receiver.recv() => match event {
+                        Some(event) => crate::monitors::dispatch(&callback, event),
+                        None => break,
+                    },
+                    _ = shutdown.changed() => break,
+                }
+            }
+
+            // Hand the receiver back so a callback can be registered again
+            *slot.write().await = Some(receiver);
+        });
+        self.callback_tasks.lock().unwrap().push(task);
+
+        Ok(())
+    }
+
+    /// Register callback for bonding curve sells from any source
+    pub async fn on_sell<F>(&self, callback: F) -> Result<(), Box<dyn std::error::Error>>
+    where
+        F: Fn(SellActivityEvent) + Send + Sync + 'static,
+    {
+        let mut receiver = self.sell_receiver.write().await.take()
+            .ok_or("A sell callback is already registered")?;
+        let slot = Arc::clone(&self.sell_receiver);
+        let mut shutdown = self.shutdown.subscribe();
+
+        let task = tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    event = receiver.recv() => match event {
+                        Some(event) => crate::monitors::dispatch(&callback, event),
+                        None => break,
+                    },
+                    _ = shutdown.changed() => break,
+                }
+            }
+
+            // Hand the receiver back so a callback can be registered again
+            *slot.write().await = Some(receiver);
+        });
+        self.callback_tasks.lock().unwrap().push(task);
+
+        Ok(())
+    }
+
+    /// Record a detection, returning false if the mint was already seen
+    async fn is_first_detection(seen: &Mutex<HashMap<Pubkey, Instant>>, event: &NewTokenEvent) -> bool {
+        let now = Instant::now();
+        let mut seen = seen.lock().await;
+        seen.retain(|_, detected_at| now.duration_since(*detected_at) < DEDUPE_TTL);
+
+        if seen.contains_key(&event.token_address) {
+            tracing::debug!("Duplicate detection of {} from {}", event.token_address, event.source);
+            return false;
+        }
+
+        seen.insert(event.token_address, now);
+        true
+    }
+
+    /// Get status of all monitors
+    pub async fn status(&self) -> serde_json::Value {
+        let mut monitors = Vec::new();
+        for monitor in &self.monitors {
+            monitors.push(serde_json::json!({
+                "source": monitor.source(),
+                "status": monitor.status().await,
+            }));
+        }
+        serde_json::json!({ "monitors": monitors })
+    }
+}
+
+impl Default for MonitorSet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{event_log::EventLog, solana_client::SolanaClient};
+    use futures_util::StreamExt;
+
+    async fn monitor(source: &str, ws_url: String) -> PumpFunMonitor {
+        let config = crate::config::BotConfig { rpc_url: "http://127.0.0.1:1".to_string(), ..Default::default() };
+        let client = Arc::new(SolanaClient::new(&config).await.unwrap());
+        PumpFunMonitor::new(client, crate::config::shared(config), Arc::new(EventLog::new(None).unwrap()))
+            .with_ws_url(source, ws_url)
+    }
+
+    fn launch(token_address: Pubkey, source: &str) -> NewTokenEvent {
+        NewTokenEvent {
+            token_address,
+            bonding_curve_address: Pubkey::new_unique(),
+            creator: Pubkey::new_unique(),
+            creator_sol_contributed: 0.0,
+            timestamp: chrono::Utc::now(),
+            source: source.to_string(),
+            received_at: None,
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn same_launch_from_two_sources_is_delivered_once_from_the_faster() {
+        let set = MonitorSet::new();
+        let (delivered, mut received) = mpsc::unbounded_channel();
+        set.on_new_token(move |event: NewTokenEvent| {
+            let _ = delivered.send(event.source);
+        }).await.unwrap();
+
+        let mint = Pubkey::new_unique();
+        set.event_sender.send(launch(mint, "websocket-1")).unwrap();
+        set.event_sender.send(launch(mint, "websocket")).unwrap();
+
+        let first = tokio::time::timeout(Duration::from_secs(1), received.recv()).await.unwrap();
+        assert_eq!(first.as_deref(), Some("websocket-1"));
+        assert!(tokio::time::timeout(Duration::from_millis(200), received.recv()).await.is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn prioritized_sources_come_first_and_the_rest_keep_their_order() {
+        let mut set = MonitorSet::new();
+        for source in ["websocket", "websocket-1", "websocket-2"] {
+            set.add_monitor(monitor(source, "ws://127.0.0.1:1".to_string()).await).await;
+        }
+
+        set.prioritize(&["websocket-2".to_string()]);
+        let order: Vec<&str> = set.monitors.iter().map(|monitor| monitor.source()).collect();
+        assert_eq!(order, ["websocket-2", "websocket", "websocket-1"]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn failed_source_fails_the_start_and_stops_the_others() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            while let Some(Ok(_)) = ws.next().await {}
+        });
+
+        let mut set = MonitorSet::new();
+        set.add_monitor(monitor("websocket", url).await).await;
+        set.add_monitor(monitor("websocket-1", "ws://127.0.0.1:1".to_string()).await).await;
+
+        assert!(set.start().await.is_err());
+        for monitor in &set.monitors {
+            assert!(!monitor.is_running().await);
+        }
+        server.abort();
+    }
+}
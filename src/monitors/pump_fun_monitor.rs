@@ -1,42 +1,139 @@
 use futures_util::{SinkExt, StreamExt};
 use solana_client::rpc_config::{RpcTransactionLogsConfig, RpcTransactionLogsFilter};
 use solana_sdk::commitment_config::CommitmentConfig;
+use std::collections::VecDeque;
 use std::sync::Arc;
-use tokio::sync::{mpsc, RwLock};
-use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, watch, RwLock};
+use tokio::task::JoinHandle;
+use tokio_tungstenite::{connect_async_with_config, tungstenite::protocol::{Message, WebSocketConfig}};
 use serde_json::json;
 use crate::{
-    config::{BotConfig, constants::*},
-    types::NewTokenEvent,
-    utils::solana_client::SolanaClient,
+    config::{SharedConfig, constants::*},
+    types::{MigrationEvent, NewTokenEvent, SellActivityEvent},
+    utils::{event_log::{EventLog, LoggedEvent}, solana_client::SolanaClient},
 };
 
+/// Notifications waiting to be processed under the per-second budget
+///
+/// Holds at most one second's budget; when full the oldest notification is evicted, so a
+/// burst is worked off newest-first in arrival order.
+struct NotificationBuffer {
+    queue: VecDeque<(String, chrono::DateTime<chrono::Utc>)>,
+    window_start: Instant,
+    window_count: u32,
+}
+
+impl NotificationBuffer {
+    fn new() -> Self {
+        Self { queue: VecDeque::new(), window_start: Instant::now(), window_count: 0 }
+    }
+
+    /// Queue a notification, returning whether the oldest one was evicted for it
+    fn push(&mut self, text: String, received_at: chrono::DateTime<chrono::Utc>, max_per_sec: u32) -> bool {
+        let evicted = max_per_sec > 0 && self.queue.len() >= max_per_sec as usize;
+        if evicted {
+            self.queue.pop_front();
+        }
+        self.queue.push_back((text, received_at));
+        evicted
+    }
+
+    /// When the next notification may be processed; `now` while the budget lasts
+    fn ready_at(&self, max_per_sec: u32, now: Instant) -> Instant {
+        let window_end = self.window_start + Duration::from_secs(1);
+        if max_per_sec > 0 && self.window_count >= max_per_sec && now < window_end {
+            window_end
+        } else {
+            now
+        }
+    }
+
+    /// Take the oldest queued notification if the budget allows
+    fn pop(&mut self, max_per_sec: u32, now: Instant) -> Option<(String, chrono::DateTime<chrono::Utc>)> {
+        if now.duration_since(self.window_start) >= Duration::from_secs(1) {
+            self.window_start = now;
+            self.window_count = 0;
+        }
+        if max_per_sec > 0 && self.window_count >= max_per_sec {
+            return None;
+        }
+        let notification = self.queue.pop_front()?;
+        self.window_count += 1;
+        Some(notification)
+    }
+}
+
 /// Pump.fun token launch monitor
 pub struct PumpFunMonitor {
     client: Arc<SolanaClient>,
-    config: Arc<BotConfig>,
+    config: SharedConfig,
     event_sender: mpsc::UnboundedSender<NewTokenEvent>,
     event_receiver: Arc<RwLock<Option<mpsc::UnboundedReceiver<NewTokenEvent>>>>,
+    migration_sender: mpsc::UnboundedSender<MigrationEvent>,
+    migration_receiver: Arc<RwLock<Option<mpsc::UnboundedReceiver<MigrationEvent>>>>,
+    sell_sender: mpsc::UnboundedSender<SellActivityEvent>,
+    sell_receiver: Arc<RwLock<Option<mpsc::UnboundedReceiver<SellActivityEvent>>>>,
     is_monitoring: Arc<RwLock<bool>>,
+    shutdown: watch::Sender<()>,
+    callback_tasks: std::sync::Mutex<Vec<JoinHandle<()>>>,
+    dropped_notifications: Arc<AtomicU64>,
+    skipped_messages: Arc<AtomicU64>,
+    event_log: Arc<EventLog>,
+    source: String,
+    ws_url: Option<String>,
 }
 
 impl PumpFunMonitor {
     /// Create a new Pump.fun monitor
     pub fn new(
         client: Arc<SolanaClient>,
-        config: Arc<BotConfig>,
+        config: SharedConfig,
+        event_log: Arc<EventLog>,
     ) -> Self {
         let (event_sender, event_receiver) = mpsc::unbounded_channel();
+        let (migration_sender, migration_receiver) = mpsc::unbounded_channel();
+        let (sell_sender, sell_receiver) = mpsc::unbounded_channel();
+        let (shutdown, _) = watch::channel(());
 
         Self {
             client,
             config,
             event_sender,
             event_receiver: Arc::new(RwLock::new(Some(event_receiver))),
+            migration_sender,
+            migration_receiver: Arc::new(RwLock::new(Some(migration_receiver))),
+            sell_sender,
+            sell_receiver: Arc::new(RwLock::new(Some(sell_receiver))),
             is_monitoring: Arc::new(RwLock::new(false)),
+            shutdown,
+            callback_tasks: std::sync::Mutex::new(Vec::new()),
+            dropped_notifications: Arc::new(AtomicU64::new(0)),
+            skipped_messages: Arc::new(AtomicU64::new(0)),
+            event_log,
+            source: "websocket".to_string(),
+            ws_url: None,
         }
     }
 
+    /// Use a dedicated WebSocket endpoint and source name instead of the configured `ws_url`
+    pub fn with_ws_url(mut self, source: &str, ws_url: String) -> Self {
+        self.source = source.to_string();
+        self.ws_url = Some(ws_url);
+        self
+    }
+
+    /// Name of the detection source this monitor reports
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Whether the monitor is currently running
+    pub async fn is_running(&self) -> bool {
+        *self.is_monitoring.read().await
+    }
+
     /// Start monitoring for new token launches
     pub async fn start(&self) -> Result<(), Box<dyn std::error::Error>> {
         if *self.is_monitoring.read().await {
@@ -48,8 +145,14 @@ impl PumpFunMonitor {
 
         tracing::info!("Starting Pump.fun token launch monitor...");
 
-        // Start WebSocket monitoring
-        self.start_websocket_monitoring().await?;
+        // Start WebSocket monitoring, releasing the registered callbacks if it fails so they
+        // can be registered again on the next attempt
+        if let Err(e) = self.start_websocket_monitoring().await.map_err(|e| e.to_string()) {
+            *self.is_monitoring.write().await = false;
+            self.shutdown.send_replace(());
+            crate::monitors::join_callbacks(&self.callback_tasks).await;
+            return Err(e.into());
+        }
 
         tracing::info!("Pump.fun monitor started successfully");
         Ok(())
@@ -64,35 +167,169 @@ impl PumpFunMonitor {
 
         *self.is_monitoring.write().await = false;
 
-        // Close the event receiver
-        if let Some(receiver) = self.event_receiver.write().await.take() {
-            drop(receiver);
-        }
+        // End the WebSocket reader and callback tasks, which hand their receivers back as they exit
+        self.shutdown.send_replace(());
+        crate::monitors::join_callbacks(&self.callback_tasks).await;
 
         tracing::info!("Pump.fun monitor stopped successfully");
         Ok(())
     }
 
     /// Register callback for new token events
-    pub async fn on_new_token<F>(&self, callback: F)
+    ///
+    /// Only one callback can be registered at a time. It runs until the monitor is stopped,
+    /// after which a new callback may be registered.
+    pub async fn on_new_token<F>(&self, callback: F) -> Result<(), Box<dyn std::error::Error>>
     where
         F: Fn(NewTokenEvent) + Send + Sync + 'static,
     {
-        let mut receiver = self.event_receiver.write().await.take().unwrap();
+        let mut receiver = self.event_receiver.write().await.take()
+            .ok_or("A new token callback is already registered")?;
+        let slot = Arc::clone(&self.event_receiver);
+        let mut shutdown = self.shutdown.subscribe();
 
-        tokio::spawn(async move {
-            while let Some(event) = receiver.recv().await {
-                callback(event);
+        let task = tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    event = receiver.recv() => match event {
+                        Some(event) => crate::monitors::dispatch(&callback, event),
+                        None => break,
+                    },
+                    _ = shutdown.changed() => break,
+                }
+            }
+
+            // Hand the receiver back so a callback can be registered again
+            *slot.write().await = Some(receiver);
+        });
+        self.callback_tasks.lock().unwrap().push(task);
+
+        Ok(())
+    }
+
+    /// Register callback for bonding curve completion events
+    ///
+    /// Same lifecycle as `on_new_token`: one callback at a time, ending when the monitor stops.
+    pub async fn on_migration<F>(&self, callback: F) -> Result<(), Box<dyn std::error::Error>>
+    where
+        F: Fn(MigrationEvent) + Send + Sync + 'static,
+    {
+        let mut receiver = self.migration_receiver.write().await.take()
+            .ok_or("A migration callback is already registered")?;
+        let slot = Arc::clone(&self.migration_receiver);
+        let mut shutdown = self.shutdown.subscribe();
+
+        let task = tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    event = receiver.recv() => match event {
+                        Some(event) => crate::monitors::dispatch(&callback, event),
+                        None => break,
+                    },
+                    _ = shutdown.changed() => break,
+                }
             }
+
+            // Hand the receiver back so a callback can be registered again
+            *slot.write().await = Some(receiver);
         });
+        self.callback_tasks.lock().unwrap().push(task);
+
+        Ok(())
+    }
+
+    /// Register callback for sells on any bonding curve
+    ///
+    /// Same lifecycle as `on_new_token`: one callback at a time, ending when the monitor stops.
+    pub async fn on_sell<F>(&self, callback: F) -> Result<(), Box<dyn std::error::Error>>
+    where
+        F: Fn(SellActivityEvent) + Send + Sync + 'static,
+    {
+        let mut receiver = self.sell_receiver.write().await.take()
+            .ok_or("A sell callback is already registered")?;
+        let slot = Arc::clone(&self.sell_receiver);
+        let mut shutdown = self.shutdown.subscribe();
+
+        let task = tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    event = receiver.recv() => match event {
+                        Some(event) => crate::monitors::dispatch(&callback, event),
+                        None => break,
+                    },
+                    _ = shutdown.changed() => break,
+                }
+            }
+
+            // Hand the receiver back so a callback can be registered again
+            *slot.write().await = Some(receiver);
+        });
+        self.callback_tasks.lock().unwrap().push(task);
+
+        Ok(())
+    }
+
+    /// Replay launches from recent program transactions through the new token channel
+    ///
+    /// The returned future owns what it needs, so it can run in its own task while live
+    /// monitoring starts. Returns how many launches were replayed.
+    pub fn catch_up(
+        &self,
+        since: chrono::DateTime<chrono::Utc>,
+    ) -> impl std::future::Future<Output = Result<usize, String>> + Send + 'static {
+        const CATCH_UP_SIGNATURE_LIMIT: usize = 20_000;
+
+        let client = Arc::clone(&self.client);
+        let event_sender = self.event_sender.clone();
+        let event_log = Arc::clone(&self.event_log);
+        let source = self.source.clone();
+        async move {
+            let transactions = client
+                .recent_program_logs(&PUMP_FUN_PROGRAM_ID, since, CATCH_UP_SIGNATURE_LIMIT)
+                .await
+                .map_err(|e| e.to_string())?;
+            Ok(Self::replay_launches(transactions, &source, &event_sender, &event_log).await)
+        }
+    }
+
+    /// Send the launches among replayed transactions, oldest first
+    ///
+    /// Events carry the launch's block time as their timestamp so the age gate can drop
+    /// ones that are too old to trade.
+    async fn replay_launches(
+        transactions: Vec<(chrono::DateTime<chrono::Utc>, serde_json::Value)>,
+        source: &str,
+        event_sender: &mpsc::UnboundedSender<NewTokenEvent>,
+        event_log: &EventLog,
+    ) -> usize {
+        let mut replayed = 0;
+        for (block_time, logs) in transactions {
+            if let Some(mut event) = Self::parse_token_creation(&logs, source).await {
+                event.timestamp = block_time;
+                event_log.record(LoggedEvent::NewToken { event: event.clone() });
+                if event_sender.send(event).is_err() {
+                    break;
+                }
+                replayed += 1;
+            }
+        }
+        replayed
     }
 
     /// Start WebSocket monitoring for program logs
     async fn start_websocket_monitoring(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let ws_url = self.config.ws_url.as_ref()
+        let ws_url = self.ws_url.clone()
+            .or_else(|| self.config.load().ws_url.clone())
             .ok_or("WebSocket URL not configured")?;
 
-        let (ws_stream, _) = connect_async(ws_url).await?;
+        // tungstenite rejects oversized messages before buffering them, ending the connection
+        let max_bytes = self.config.load().max_ws_message_bytes;
+        let ws_config = WebSocketConfig {
+            max_message_size: Some(max_bytes),
+            max_frame_size: Some(max_bytes),
+            ..Default::default()
+        };
+        let (ws_stream, _) = connect_async_with_config(ws_url.as_str(), Some(ws_config), false).await?;
         let (mut write, mut read) = ws_stream.split();
 
         // Subscribe to program logs
@@ -114,20 +351,73 @@ impl PumpFunMonitor {
 
         // Handle incoming messages
         let event_sender = self.event_sender.clone();
+        let migration_sender = self.migration_sender.clone();
+        let sell_sender = self.sell_sender.clone();
         let is_monitoring = Arc::clone(&self.is_monitoring);
+        let event_log = Arc::clone(&self.event_log);
+        let source = self.source.clone();
+        let mut shutdown = self.shutdown.subscribe();
+        let config = Arc::clone(&self.config);
+        let dropped_notifications = Arc::clone(&self.dropped_notifications);
+        let skipped_messages = Arc::clone(&self.skipped_messages);
 
         tokio::spawn(async move {
-            while let Some(message) = read.next().await {
+            let mut buffer = NotificationBuffer::new();
+
+            loop {
+                let max_per_sec = config.load().max_notifications_per_sec;
+                let ready_at = buffer.ready_at(max_per_sec, Instant::now());
+                let message = tokio::select! {
+                    message = read.next() => match message {
+                        Some(message) => message,
+                        None => break,
+                    },
+                    _ = tokio::time::sleep_until(ready_at.into()), if !buffer.queue.is_empty() => {
+                        let (text, received_at) = match buffer.pop(max_per_sec, Instant::now()) {
+                            Some(notification) => notification,
+                            None => continue,
+                        };
+                        match Self::handle_websocket_message(
+                            &text,
+                            received_at,
+                            &source,
+                            &event_sender,
+                            &migration_sender,
+                            &sell_sender,
+                            &event_log,
+                        ).await.map_err(|e| e.to_string()) {
+                            Ok(true) => {}
+                            Ok(false) => {
+                                // Receivers go away during teardown; only an unexpected close is worth a warning
+                                if *is_monitoring.read().await {
+                                    tracing::warn!("Event receivers closed while monitoring, stopping WebSocket reader");
+                                } else {
+                                    tracing::debug!("Event receivers closed during shutdown, stopping WebSocket reader");
+                                }
+                                break;
+                            }
+                            Err(e) => tracing::error!("Error handling WebSocket message: {}", e),
+                        }
+                        continue;
+                    }
+                    _ = shutdown.changed() => break,
+                };
+
                 if !*is_monitoring.read().await {
                     break;
                 }
+                let received_at = chrono::Utc::now();
 
-                match message {
-                    Ok(Message::Text(text)) => {
-                        if let Err(e) = Self::handle_websocket_message(&text, &event_sender).await {
-                            tracing::error!("Error handling WebSocket message: {}", e);
+                let text = match message {
+                    Ok(Message::Text(text)) => text,
+                    Ok(Message::Binary(data)) => match String::from_utf8(data) {
+                        Ok(text) => text,
+                        Err(_) => {
+                            tracing::warn!("Skipping non-UTF-8 binary WebSocket message");
+                            skipped_messages.fetch_add(1, Ordering::Relaxed);
+                            continue;
                         }
-                    }
+                    },
                     Ok(Message::Close(_)) => {
                         tracing::info!("WebSocket connection closed");
                         break;
@@ -136,7 +426,12 @@ impl PumpFunMonitor {
                         tracing::error!("WebSocket error: {}", e);
                         break;
                     }
-                    _ => {}
+                    _ => continue,
+                };
+
+                // Beyond the per-second budget the oldest notifications give way to the newest
+                if buffer.push(text, received_at, max_per_sec) {
+                    dropped_notifications.fetch_add(1, Ordering::Relaxed);
                 }
             }
         });
@@ -145,65 +440,463 @@ impl PumpFunMonitor {
     }
 
     /// Handle WebSocket message
+    ///
+    /// Returns `false` once an event channel's receiver is gone, so the reader can stop.
     async fn handle_websocket_message(
         text: &str,
+        received_at: chrono::DateTime<chrono::Utc>,
+        source: &str,
         event_sender: &mpsc::UnboundedSender<NewTokenEvent>,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+        migration_sender: &mpsc::UnboundedSender<MigrationEvent>,
+        sell_sender: &mpsc::UnboundedSender<SellActivityEvent>,
+        event_log: &EventLog,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        event_log.record(LoggedEvent::RawNotification { text: text.to_string() });
+
         let message: serde_json::Value = serde_json::from_str(text)?;
 
         // Check if this is a logs notification
         if let Some(params) = message.get("params") {
             if let Some(result) = params.get("result") {
                 if let Some(logs) = Self::extract_logs_from_notification(result) {
-                    if let Some(token_event) = Self::parse_token_creation(logs).await {
+                    if let Some(mut token_event) = Self::parse_token_creation(logs, source).await {
+                        token_event.received_at = Some(received_at);
+                        event_log.record(LoggedEvent::NewToken { event: token_event.clone() });
                         if event_sender.send(token_event).is_err() {
-                            tracing::error!("Failed to send token event - channel closed");
+                            return Ok(false);
+                        }
+                    }
+                    for migration in Self::parse_migrations(logs, source) {
+                        if migration_sender.send(migration).is_err() {
+                            return Ok(false);
+                        }
+                    }
+                    for sell in Self::parse_sells(logs, source) {
+                        if sell_sender.send(sell).is_err() {
+                            return Ok(false);
                         }
                     }
                 }
             }
         }
 
-        Ok(())
+        Ok(true)
     }
 
     /// Extract logs from notification
+    ///
+    /// A failed transaction's logs can still carry events emitted before it reverted,
+    /// so its logs are skipped entirely.
     fn extract_logs_from_notification(result: &serde_json::Value) -> Option<&serde_json::Value> {
-        result.get("value").and_then(|v| v.get("logs"))
+        let value = result.get("value")?;
+        if !value.get("err").is_none_or(|err| err.is_null()) {
+            return None;
+        }
+        value.get("logs")
     }
 
-    /// Parse token creation from transaction logs
-    async fn parse_token_creation(logs: &serde_json::Value) -> Option<NewTokenEvent> {
-        if let Some(logs_array) = logs.as_array() {
-            // Look for Pump.fun specific log patterns
-            let has_create_log = logs_array.iter().any(|log| {
-                log.as_str()
-                    .map(|s| s.contains("Create") || s.contains("create"))
-                    .unwrap_or(false)
-            });
+    /// Parse a token launch from the `CreateEvent` program data of a creation transaction
+    ///
+    /// Layout after the discriminator: `name`, `symbol` and `uri` (each a u32 length and
+    /// bytes), then `mint` (32), `bonding_curve` (32) and `user` (32). Newer program versions
+    /// append the curve `creator` (32), which is preferred over the signing user.
+    async fn parse_token_creation(logs: &serde_json::Value, source: &str) -> Option<NewTokenEvent> {
+        use base64::Engine;
+
+        let logs_array = logs.as_array()?;
+        let data = logs_array
+            .iter()
+            .filter_map(|log| log.as_str()?.strip_prefix("Program data: "))
+            .filter_map(|data| base64::engine::general_purpose::STANDARD.decode(data).ok())
+            .find(|data| data.len() >= 8 && data[..8] == CREATE_EVENT_DISCRIMINATOR)?;
+
+        // Skip the three borsh strings to reach the account keys
+        let mut offset = 8;
+        for _ in 0..3 {
+            let len = u32::from_le_bytes(data.get(offset..offset + 4)?.try_into().ok()?) as usize;
+            offset += 4 + len;
+        }
+        let key = |index: usize| {
+            let start = offset + index * 32;
+            solana_sdk::pubkey::Pubkey::try_from(data.get(start..start + 32)?).ok()
+        };
+        let (token_address, bonding_curve_address, user) = (key(0)?, key(1)?, key(2)?);
+        let creator = key(3).unwrap_or(user);
+
+        Some(NewTokenEvent {
+            token_address,
+            bonding_curve_address,
+            creator,
+            creator_sol_contributed: Self::creator_sol_from_logs(logs_array, &creator),
+            timestamp: chrono::Utc::now(),
+            source: source.to_string(),
+            received_at: None,
+        })
+    }
+
+    /// Parse bonding curve completions from `CompleteEvent` program data
+    ///
+    /// Layout after the discriminator: `user` (32), `mint` (32), `bonding_curve` (32).
+    fn parse_migrations(logs: &serde_json::Value, source: &str) -> Vec<MigrationEvent> {
+        use base64::Engine;
 
-            if has_create_log {
-                // In a real implementation, you'd parse the transaction to get token details
-                // For now, return a placeholder event
-                Some(NewTokenEvent {
-                    token_address: solana_sdk::pubkey::Pubkey::new_unique(),
-                    bonding_curve_address: solana_sdk::pubkey::Pubkey::new_unique(),
-                    creator: solana_sdk::pubkey::Pubkey::new_unique(),
+        const MINT_OFFSET: usize = 8 + 32;
+        const BONDING_CURVE_OFFSET: usize = MINT_OFFSET + 32;
+
+        logs.as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|log| log.as_str()?.strip_prefix("Program data: "))
+            .filter_map(|data| base64::engine::general_purpose::STANDARD.decode(data).ok())
+            .filter(|data| data.len() >= BONDING_CURVE_OFFSET + 32 && data[..8] == COMPLETE_EVENT_DISCRIMINATOR)
+            .filter_map(|data| {
+                Some(MigrationEvent {
+                    token_address: solana_sdk::pubkey::Pubkey::try_from(&data[MINT_OFFSET..MINT_OFFSET + 32]).ok()?,
+                    bonding_curve_address: solana_sdk::pubkey::Pubkey::try_from(
+                        &data[BONDING_CURVE_OFFSET..BONDING_CURVE_OFFSET + 32],
+                    ).ok()?,
                     timestamp: chrono::Utc::now(),
+                    source: source.to_string(),
                 })
-            } else {
-                None
-            }
-        } else {
-            None
-        }
+            })
+            .collect()
+    }
+
+    /// Parse sells from `TradeEvent` program data
+    ///
+    /// Layout after the discriminator: `mint` (32), `sol_amount` (8), `token_amount` (8),
+    /// `is_buy` (1), `user` (32).
+    fn parse_sells(logs: &serde_json::Value, source: &str) -> Vec<SellActivityEvent> {
+        use base64::Engine;
+
+        const MINT_OFFSET: usize = 8;
+        const SOL_AMOUNT_OFFSET: usize = MINT_OFFSET + 32;
+        const TOKEN_AMOUNT_OFFSET: usize = SOL_AMOUNT_OFFSET + 8;
+        const IS_BUY_OFFSET: usize = TOKEN_AMOUNT_OFFSET + 8;
+        const USER_OFFSET: usize = IS_BUY_OFFSET + 1;
+
+        logs.as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|log| log.as_str()?.strip_prefix("Program data: "))
+            .filter_map(|data| base64::engine::general_purpose::STANDARD.decode(data).ok())
+            .filter(|data| data.len() >= USER_OFFSET + 32 && data[..8] == TRADE_EVENT_DISCRIMINATOR)
+            .filter(|data| data[IS_BUY_OFFSET] == 0)
+            .filter_map(|data| {
+                Some(SellActivityEvent {
+                    token_address: solana_sdk::pubkey::Pubkey::try_from(&data[MINT_OFFSET..MINT_OFFSET + 32]).ok()?,
+                    seller: solana_sdk::pubkey::Pubkey::try_from(&data[USER_OFFSET..USER_OFFSET + 32]).ok()?,
+                    sol_amount: u64::from_le_bytes(data[SOL_AMOUNT_OFFSET..SOL_AMOUNT_OFFSET + 8].try_into().ok()?),
+                    token_amount: u64::from_le_bytes(data[TOKEN_AMOUNT_OFFSET..TOKEN_AMOUNT_OFFSET + 8].try_into().ok()?),
+                    timestamp: chrono::Utc::now(),
+                    source: source.to_string(),
+                })
+            })
+            .collect()
+    }
+
+    /// Sum the SOL the creator spent on buys in the creation transaction
+    ///
+    /// Other wallets' buys bundled into the same transaction are not counted.
+    fn creator_sol_from_logs(logs: &[serde_json::Value], creator: &solana_sdk::pubkey::Pubkey) -> f64 {
+        use base64::Engine;
+
+        // discriminator (8) + mint (32) + sol_amount (8) + token_amount (8) + is_buy (1) + user (32)
+        const SOL_AMOUNT_OFFSET: usize = 8 + 32;
+        const IS_BUY_OFFSET: usize = SOL_AMOUNT_OFFSET + 8 + 8;
+        const USER_OFFSET: usize = IS_BUY_OFFSET + 1;
+
+        let lamports: u64 = logs
+            .iter()
+            .filter_map(|log| log.as_str()?.strip_prefix("Program data: "))
+            .filter_map(|data| base64::engine::general_purpose::STANDARD.decode(data).ok())
+            .filter(|data| data.len() >= USER_OFFSET + 32 && data[..8] == TRADE_EVENT_DISCRIMINATOR)
+            .filter(|data| data[IS_BUY_OFFSET] == 1 && data[USER_OFFSET..USER_OFFSET + 32] == creator.to_bytes())
+            .filter_map(|data| {
+                data[SOL_AMOUNT_OFFSET..SOL_AMOUNT_OFFSET + 8].try_into().ok().map(u64::from_le_bytes)
+            })
+            .sum();
+
+        lamports as f64 / LAMPORTS_PER_SOL as f64
     }
 
     /// Get monitor status
     pub async fn status(&self) -> serde_json::Value {
         json!({
             "is_monitoring": *self.is_monitoring.read().await,
+            "dropped_notifications": self.dropped_notifications.load(Ordering::Relaxed),
+            "skipped_messages": self.skipped_messages.load(Ordering::Relaxed),
             "program_id": PUMP_FUN_PROGRAM_ID.to_string(),
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use base64::Engine;
+    use solana_sdk::pubkey::Pubkey;
+
+    /// `Program data` log of a `CreateEvent` in the original layout, without a creator field
+    fn create_event_log(mint: &Pubkey, bonding_curve: &Pubkey, user: &Pubkey) -> String {
+        let mut data = CREATE_EVENT_DISCRIMINATOR.to_vec();
+        for text in ["Token", "TKN", "https://example.com/t.json"] {
+            data.extend_from_slice(&(text.len() as u32).to_le_bytes());
+            data.extend_from_slice(text.as_bytes());
+        }
+        for key in [mint, bonding_curve, user] {
+            data.extend_from_slice(key.as_ref());
+        }
+        format!("Program data: {}", base64::engine::general_purpose::STANDARD.encode(data))
+    }
+
+    /// `Program data` log of a buy's `TradeEvent`
+    fn buy_event_log(mint: &Pubkey, lamports: u64, user: &Pubkey) -> String {
+        let mut data = TRADE_EVENT_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(mint.as_ref());
+        data.extend_from_slice(&lamports.to_le_bytes());
+        data.extend_from_slice(&1_000u64.to_le_bytes());
+        data.push(1);
+        data.extend_from_slice(user.as_ref());
+        format!("Program data: {}", base64::engine::general_purpose::STANDARD.encode(data))
+    }
+
+    fn create_logs() -> serde_json::Value {
+        let (mint, curve, user) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        serde_json::json!(["Program log: Instruction: Create", create_event_log(&mint, &curve, &user)])
+    }
+
+    fn create_notification() -> String {
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "logsNotification",
+            "params": {
+                "result": {
+                    "value": {
+                        "signature": "sig",
+                        "logs": create_logs()
+                    }
+                }
+            }
+        }).to_string()
+    }
+
+    #[tokio::test]
+    async fn launch_is_read_from_the_create_event_and_counts_only_the_creators_buys() {
+        let (mint, curve, creator) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let logs = serde_json::json!([
+            "Program log: Instruction: Create",
+            create_event_log(&mint, &curve, &creator),
+            buy_event_log(&mint, 2_000_000_000, &creator),
+            buy_event_log(&mint, 5_000_000_000, &Pubkey::new_unique()),
+        ]);
+
+        let event = PumpFunMonitor::parse_token_creation(&logs, "pump_fun").await.unwrap();
+        assert_eq!(event.token_address, mint);
+        assert_eq!(event.bonding_curve_address, curve);
+        assert_eq!(event.creator, creator);
+        assert_eq!(event.creator_sol_contributed, 2.0);
+
+        // A create log without its event data is not a launch we can trade
+        let bare = serde_json::json!(["Program log: Instruction: Create"]);
+        assert!(PumpFunMonitor::parse_token_creation(&bare, "pump_fun").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn replayed_launches_carry_their_block_time() {
+        let event_log = EventLog::new(None).unwrap();
+        let (event_sender, mut event_receiver) = mpsc::unbounded_channel();
+        let launched_at = chrono::Utc::now() - chrono::Duration::minutes(3);
+        let transactions = vec![
+            (launched_at, create_logs()),
+            (launched_at, serde_json::json!(["Program log: Instruction: Buy"])),
+        ];
+
+        let replayed = PumpFunMonitor::replay_launches(transactions, "pump_fun", &event_sender, &event_log).await;
+        assert_eq!(replayed, 1);
+        let event = event_receiver.try_recv().unwrap();
+        assert_eq!(event.timestamp, launched_at);
+        assert!(event_receiver.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn closed_receivers_stop_the_reader_quietly() {
+        let event_log = EventLog::new(None).unwrap();
+        let (event_sender, event_receiver) = mpsc::unbounded_channel();
+        let (migration_sender, _migrations) = mpsc::unbounded_channel();
+        let (sell_sender, _sells) = mpsc::unbounded_channel();
+
+        let handled = PumpFunMonitor::handle_websocket_message(
+            &create_notification(),
+            chrono::Utc::now(),
+            "pump_fun",
+            &event_sender,
+            &migration_sender,
+            &sell_sender,
+            &event_log,
+        ).await.unwrap();
+        assert!(handled);
+
+        // Teardown drops the receiver while a notification is in flight: no error, just stop
+        drop(event_receiver);
+        let handled = PumpFunMonitor::handle_websocket_message(
+            &create_notification(),
+            chrono::Utc::now(),
+            "pump_fun",
+            &event_sender,
+            &migration_sender,
+            &sell_sender,
+            &event_log,
+        ).await.unwrap();
+        assert!(!handled);
+    }
+
+    #[test]
+    fn notification_burst_keeps_the_newest_within_the_budget() {
+        let mut buffer = NotificationBuffer::new();
+        let received_at = chrono::Utc::now();
+        let evicted: Vec<bool> = (0..5).map(|i| buffer.push(i.to_string(), received_at, 3)).collect();
+        assert_eq!(evicted, vec![false, false, false, true, true]);
+
+        let now = Instant::now();
+        let popped: Vec<String> = std::iter::from_fn(|| buffer.pop(2, now)).map(|(text, _)| text).collect();
+        assert_eq!(popped, vec!["2", "3"]);
+
+        // The budget is spent until the window rolls over
+        assert!(buffer.ready_at(2, now) > now);
+        let next_window = now + Duration::from_secs(1);
+        assert_eq!(buffer.ready_at(2, next_window), next_window);
+        assert_eq!(buffer.pop(2, next_window).map(|(text, _)| text).as_deref(), Some("4"));
+    }
+
+    /// A monitor for a local WebSocket endpoint, with the given config tweaks
+    async fn local_monitor(url: String, config: crate::config::BotConfig) -> PumpFunMonitor {
+        let config = crate::config::BotConfig { rpc_url: "http://127.0.0.1:1".to_string(), ..config };
+        let client = Arc::new(SolanaClient::new(&config).await.unwrap());
+        PumpFunMonitor::new(client, crate::config::shared(config), Arc::new(EventLog::new(None).unwrap()))
+            .with_ws_url("local", url)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn callback_can_be_registered_again_right_after_stop() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let monitor = local_monitor(url, Default::default()).await;
+
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            while let Some(Ok(_)) = ws.next().await {}
+        });
+
+        monitor.start().await.unwrap();
+        monitor.on_new_token(|_| {}).await.unwrap();
+        assert!(monitor.on_new_token(|_| {}).await.is_err());
+
+        monitor.stop().await.unwrap();
+        monitor.on_new_token(|_| {}).await.unwrap();
+        server.abort();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn oversized_message_drops_the_connection() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let config = crate::config::BotConfig { max_ws_message_bytes: 1024, ..Default::default() };
+        let monitor = local_monitor(url, config).await;
+
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            assert!(matches!(ws.next().await, Some(Ok(Message::Text(_)))));
+            ws.send(Message::Text("x".repeat(4096))).await.unwrap();
+
+            // The reader gives up on the connection instead of buffering the message
+            loop {
+                match ws.next().await {
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => continue,
+                }
+            }
+        });
+
+        monitor.start().await.unwrap();
+        tokio::time::timeout(Duration::from_secs(5), server).await.unwrap().unwrap();
+        monitor.stop().await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn panicking_callback_does_not_stop_later_events() {
+        let monitor = local_monitor("ws://127.0.0.1:1".to_string(), Default::default()).await;
+        let poisoned = Pubkey::new_unique();
+        let (delivered, mut received) = mpsc::unbounded_channel();
+        monitor.on_new_token(move |event| {
+            assert_ne!(event.token_address, poisoned, "callback bug");
+            let _ = delivered.send(event.token_address);
+        }).await.unwrap();
+
+        let launches = [poisoned, Pubkey::new_unique(), Pubkey::new_unique()];
+        for token_address in launches {
+            monitor.event_sender.send(NewTokenEvent {
+                token_address,
+                bonding_curve_address: Pubkey::new_unique(),
+                creator: Pubkey::new_unique(),
+                creator_sol_contributed: 0.0,
+                timestamp: chrono::Utc::now(),
+                source: "local".to_string(),
+                received_at: None,
+            }).unwrap();
+        }
+
+        for expected in &launches[1..] {
+            let token_address = tokio::time::timeout(Duration::from_secs(5), received.recv()).await.unwrap();
+            assert_eq!(token_address.as_ref(), Some(expected));
+        }
+    }
+
+    #[tokio::test]
+    async fn events_in_failed_transactions_are_ignored() {
+        use base64::Engine;
+
+        let event_log = EventLog::new(None).unwrap();
+        let (event_sender, _events) = mpsc::unbounded_channel();
+        let (migration_sender, mut migrations) = mpsc::unbounded_channel();
+        let (sell_sender, mut sells) = mpsc::unbounded_channel();
+
+        // A creator sell and a curve completion, both emitted before the transaction reverted
+        let (mint, curve, creator) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let mut sell = base64::engine::general_purpose::STANDARD
+            .decode(buy_event_log(&mint, 1_000_000_000, &creator).strip_prefix("Program data: ").unwrap())
+            .unwrap();
+        sell[8 + 32 + 8 + 8] = 0;
+        let mut complete = COMPLETE_EVENT_DISCRIMINATOR.to_vec();
+        for key in [&creator, &mint, &curve] {
+            complete.extend_from_slice(key.as_ref());
+        }
+        let logs: Vec<String> = [sell, complete]
+            .iter()
+            .map(|data| format!("Program data: {}", base64::engine::general_purpose::STANDARD.encode(data)))
+            .collect();
+
+        for (err, delivered) in [(serde_json::json!({ "InstructionError": [2, { "Custom": 6003 }] }), false), (serde_json::Value::Null, true)] {
+            let notification = serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "logsNotification",
+                "params": { "result": { "value": { "signature": "sig", "err": err, "logs": logs } } }
+            });
+            let handled = PumpFunMonitor::handle_websocket_message(
+                &notification.to_string(),
+                chrono::Utc::now(),
+                "pump_fun",
+                &event_sender,
+                &migration_sender,
+                &sell_sender,
+                &event_log,
+            ).await.unwrap();
+            assert!(handled);
+            assert_eq!(sells.try_recv().map(|sell| sell.seller).ok(), delivered.then_some(creator));
+            assert_eq!(migrations.try_recv().map(|migration| migration.bonding_curve_address).ok(), delivered.then_some(curve));
+        }
+    }
+}
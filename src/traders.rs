@@ -1 +1,4 @@
 pub mod trader;
+pub mod shadow_tracker;
+pub mod exit_rules;
+pub mod paper_leaderboard;
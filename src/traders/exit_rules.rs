@@ -0,0 +1,184 @@
+use serde::{Deserialize, Serialize};
+use chrono::Utc;
+use crate::types::Position;
+
+/// Position value a rule condition tests
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Metric {
+    /// Unrealized PnL in percent
+    PnlPct,
+    /// Seconds since the position was opened
+    AgeSecs,
+    /// Current price in SOL
+    Price,
+    /// Recent volume in SOL
+    Volume,
+}
+
+/// Comparison between a metric and a threshold
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Comparison {
+    Ge,
+    Le,
+    Gt,
+    Lt,
+}
+
+/// A single `metric <op> value` condition
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Condition {
+    pub metric: Metric,
+    pub comparison: Comparison,
+    pub value: f64,
+}
+
+/// What a rule does when all its conditions hold
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ExitAction {
+    /// Sell this percentage of the remaining position
+    Sell(f64),
+    /// Move the stop loss to this PnL percentage relative to entry (0 = breakeven)
+    MoveStop(f64),
+}
+
+/// A conditional exit rule, fired at most once per position
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExitRule {
+    pub conditions: Vec<Condition>,
+    pub action: ExitAction,
+}
+
+impl ExitRule {
+    /// Check whether every condition holds for the position
+    pub fn matches(&self, position: &Position) -> bool {
+        self.conditions.iter().all(|condition| {
+            let actual = match condition.metric {
+                Metric::PnlPct => position.pnl_percentage,
+                Metric::AgeSecs => (Utc::now() - position.opened_at).num_seconds() as f64,
+                Metric::Price => position.current_price,
+                Metric::Volume => position.recent_volume_sol,
+            };
+            match condition.comparison {
+                Comparison::Ge => actual >= condition.value,
+                Comparison::Le => actual <= condition.value,
+                Comparison::Gt => actual > condition.value,
+                Comparison::Lt => actual < condition.value,
+            }
+        })
+    }
+
+    /// Whether the rule needs volume sampling
+    pub fn uses_volume(&self) -> bool {
+        self.conditions.iter().any(|condition| condition.metric == Metric::Volume)
+    }
+
+    /// Identity of the rule by content, stable across reloads that reorder or add rules
+    pub fn key(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl std::fmt::Display for ExitRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let conditions: Vec<String> = self.conditions.iter().map(|condition| {
+            let metric = match condition.metric {
+                Metric::PnlPct => "pnl",
+                Metric::AgeSecs => "age",
+                Metric::Price => "price",
+                Metric::Volume => "volume",
+            };
+            let comparison = match condition.comparison {
+                Comparison::Ge => ">=",
+                Comparison::Le => "<=",
+                Comparison::Gt => ">",
+                Comparison::Lt => "<",
+            };
+            format!("{}{}{}", metric, comparison, condition.value)
+        }).collect();
+
+        match self.action {
+            ExitAction::Sell(pct) => write!(f, "{} => sell {}", conditions.join(" & "), pct),
+            ExitAction::MoveStop(pct) => write!(f, "{} => stop {}", conditions.join(" & "), pct),
+        }
+    }
+}
+
+/// Parse rules of the form `pnl>=100 & age>=60 => sell 50; pnl>=100 => stop 0`
+///
+/// Metrics are `pnl`, `age`, `price` and `volume`; actions are `sell <pct>` and `stop <pnl pct>`.
+pub fn parse_rules(input: &str) -> Result<Vec<ExitRule>, Box<dyn std::error::Error>> {
+    input
+        .split(';')
+        .map(str::trim)
+        .filter(|rule| !rule.is_empty())
+        .map(parse_rule)
+        .collect()
+}
+
+/// Parse a single `conditions => action` rule
+fn parse_rule(rule: &str) -> Result<ExitRule, Box<dyn std::error::Error>> {
+    let (conditions, action) = rule
+        .split_once("=>")
+        .ok_or_else(|| format!("Exit rule missing '=>': {}", rule))?;
+
+    let conditions = conditions
+        .split('&')
+        .map(|condition| parse_condition(condition.trim()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut parts = action.split_whitespace();
+    let action = match (parts.next(), parts.next().map(str::parse::<f64>), parts.next()) {
+        (Some("sell"), Some(Ok(pct)), None) if pct > 0.0 && pct <= 100.0 => ExitAction::Sell(pct),
+        (Some("stop"), Some(Ok(pct)), None) => ExitAction::MoveStop(pct),
+        _ => return Err(format!("Invalid exit rule action: {}", action.trim()).into()),
+    };
+
+    Ok(ExitRule { conditions, action })
+}
+
+/// Parse a `metric <op> value` condition
+fn parse_condition(condition: &str) -> Result<Condition, Box<dyn std::error::Error>> {
+    const OPERATORS: [(&str, Comparison); 4] = [
+        (">=", Comparison::Ge),
+        ("<=", Comparison::Le),
+        (">", Comparison::Gt),
+        ("<", Comparison::Lt),
+    ];
+
+    let (metric, comparison, value) = OPERATORS
+        .iter()
+        .find_map(|(op, comparison)| {
+            condition.split_once(op).map(|(metric, value)| (metric, *comparison, value))
+        })
+        .ok_or_else(|| format!("Exit rule condition missing comparison: {}", condition))?;
+
+    let metric = match metric.trim() {
+        "pnl" => Metric::PnlPct,
+        "age" => Metric::AgeSecs,
+        "price" => Metric::Price,
+        "volume" => Metric::Volume,
+        other => return Err(format!("Unknown exit rule metric: {}", other).into()),
+    };
+
+    Ok(Condition {
+        metric,
+        comparison,
+        value: value.trim().parse()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rule_keys_follow_content_not_position() {
+        let before = parse_rules("pnl>=100 & age>=60 => sell 50; pnl>=200 => stop 0").unwrap();
+        let after = parse_rules("volume<1 => sell 100;  pnl >= 200 => stop 0 ; pnl>=100&age>=60=>sell 50").unwrap();
+
+        assert_eq!(before[0].key(), "pnl>=100 & age>=60 => sell 50");
+        assert_eq!(before[0].key(), after[2].key());
+        assert_eq!(before[1].key(), after[1].key());
+        assert_ne!(before[0].key(), after[0].key());
+    }
+}
@@ -0,0 +1,192 @@
+use std::sync::Arc;
+use crate::{
+    config::{self, BotConfig, SharedConfig, StorageBackend},
+    traders::trader::{self, Trader},
+    types::{CurveUpdateEvent, PaperStanding, PositionStatus, TokenAnalysis},
+    utils::solana_client::SolanaClient,
+};
+
+/// A config variant paper-traded against the live event stream
+struct PaperVariant {
+    profile: String,
+    config: SharedConfig,
+    trader: Trader,
+}
+
+/// Simulated traders, one per strategy profile, ranked by virtual equity
+///
+/// Every variant sees the same analyzed tokens as the bot but applies its own filters,
+/// sizing and exits against its own virtual balance. Variants never send a transaction.
+pub struct PaperLeaderboard {
+    variants: Vec<PaperVariant>,
+    starting_balance_sol: f64,
+}
+
+impl PaperLeaderboard {
+    /// Build a simulated trader for each of `paper_profiles`
+    pub async fn new(client: Arc<SolanaClient>, base: &BotConfig) -> Result<Self, Box<dyn std::error::Error>> {
+        let path = config::profiles_path();
+        let mut variants = Vec::new();
+
+        for profile in &base.paper_profiles {
+            let mut variant = config::apply_profile(base.clone(), &path, profile)?;
+            // Variants share no storage, claims or alerts with the bot, and never send
+            variant.simulation_mode = true;
+            variant.storage_backend = StorageBackend::Memory;
+            variant.redis_url = None;
+            variant.telegram_bot_token = None;
+            variant.paper_profiles.clear();
+            config::validate_config(&variant)
+                .map_err(|e| format!("Paper profile '{}' is invalid: {}", profile, e))?;
+
+            let config = config::shared(variant);
+            let trader = Trader::new(Arc::clone(&client), Arc::clone(&config)).await?
+                .with_virtual_balance(base.paper_starting_balance_sol);
+            variants.push(PaperVariant {
+                profile: profile.clone(),
+                config,
+                trader,
+            });
+        }
+
+        if !variants.is_empty() {
+            tracing::info!("Paper trading {} config variants", variants.len());
+        }
+
+        Ok(Self {
+            variants,
+            starting_balance_sol: base.paper_starting_balance_sol,
+        })
+    }
+
+    /// Whether no variants are configured
+    pub fn is_empty(&self) -> bool {
+        self.variants.is_empty()
+    }
+
+    /// Offer an analyzed token to every variant whose filters pass it
+    ///
+    /// Each variant sizes and balance-checks the buy against its own virtual balance.
+    pub async fn handle_token(
+        &self,
+        analysis: &TokenAnalysis,
+        source: &str,
+        passes: fn(&TokenAnalysis, &BotConfig) -> bool,
+    ) {
+        for variant in &self.variants {
+            let config = variant.config.load_full();
+            if !passes(analysis, &config) {
+                continue;
+            }
+
+            if let Err(e) = variant.trader.execute_buy(analysis, source).await {
+                tracing::warn!("[PAPER {}] Buy of {} failed: {}", variant.profile, analysis.token.symbol, e);
+            }
+        }
+    }
+
+    /// Run every variant's exit checks
+    pub async fn check_exits(&self) {
+        for variant in &self.variants {
+            if let Err(e) = variant.trader.check_automated_sells().await {
+                tracing::warn!("[PAPER {}] Exit check failed: {}", variant.profile, e);
+            }
+        }
+    }
+
+    /// Apply a pushed curve state to every variant holding its mint
+    pub async fn apply_curve_update(&self, event: &CurveUpdateEvent) {
+        for variant in &self.variants {
+            variant.trader.apply_curve_update(event).await;
+        }
+    }
+
+    /// Every variant's standing, best virtual equity first
+    pub async fn leaderboard(&self) -> Vec<PaperStanding> {
+        let mut standings = Vec::new();
+        for variant in &self.variants {
+            standings.push(self.standing(variant).await);
+        }
+        standings.sort_by(|a, b| b.equity_sol.total_cmp(&a.equity_sol));
+        standings
+    }
+
+    /// Virtual balance from the variant's trades, plus its open positions at current prices
+    async fn standing(&self, variant: &PaperVariant) -> PaperStanding {
+        let trades = variant.trader.trade_history(None).unwrap_or_else(|e| {
+            tracing::warn!("[PAPER {}] Failed to load trades: {}", variant.profile, e);
+            Vec::new()
+        });
+        let balance_sol = trader::virtual_balance(self.starting_balance_sol, &trades);
+
+        let open: Vec<_> = variant.trader.positions().await
+            .into_iter()
+            .filter(|pos| pos.status != PositionStatus::Closed)
+            .collect();
+        let open_value_sol: f64 = open.iter().map(|pos| pos.amount as f64 * pos.current_price).sum();
+        let equity_sol = balance_sol + open_value_sol;
+
+        PaperStanding {
+            profile: variant.profile.clone(),
+            balance_sol,
+            open_value_sol,
+            equity_sol,
+            pnl_sol: equity_sol - self.starting_balance_sol,
+            trades: trades.len(),
+            open_positions: open.len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{types::test_analysis, utils::bonding_curve};
+    use chrono::Utc;
+
+    /// A leaderboard of simulated variants that differ only in buy size
+    async fn leaderboard(buy_sizes_sol: &[f64]) -> PaperLeaderboard {
+        let base = BotConfig {
+            rpc_url: "http://127.0.0.1:1".to_string(),
+            storage_backend: StorageBackend::Memory,
+            simulation_mode: true,
+            ..Default::default()
+        };
+        let client = Arc::new(SolanaClient::new(&base).await.unwrap());
+
+        let mut variants = Vec::new();
+        for buy_amount_sol in buy_sizes_sol {
+            let config = config::shared(BotConfig { buy_amount_sol: *buy_amount_sol, ..base.clone() });
+            let trader = Trader::new(Arc::clone(&client), Arc::clone(&config)).await.unwrap()
+                .with_virtual_balance(base.paper_starting_balance_sol);
+            variants.push(PaperVariant { profile: format!("buy-{}", buy_amount_sol), config, trader });
+        }
+
+        PaperLeaderboard { variants, starting_balance_sol: base.paper_starting_balance_sol }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn variants_diverge_and_never_spend_past_their_virtual_balance() {
+        let paper = leaderboard(&[1.0, 3.0, 20.0]).await;
+        let mut analysis = test_analysis();
+        analysis.metrics.price = bonding_curve::spot_price(&analysis.bonding_curve);
+        paper.handle_token(&analysis, "pump_fun", |_, _| true).await;
+
+        // The curve doubles in price after every variant has bought
+        let mut curve = analysis.bonding_curve.clone();
+        curve.virtual_sol_reserves *= 2;
+        paper.apply_curve_update(&CurveUpdateEvent { curve, slot: 1, timestamp: Utc::now() }).await;
+
+        let standings = paper.leaderboard().await;
+        let standing = |profile: &str| standings.iter().find(|s| s.profile == profile).unwrap().clone();
+        let (small, large, oversized) = (standing("buy-1"), standing("buy-3"), standing("buy-20"));
+
+        assert!(small.pnl_sol > 0.0);
+        assert!(large.pnl_sol > 2.0 * small.pnl_sol);
+        assert_eq!(standings[0].profile, "buy-3");
+
+        // A buy larger than the virtual balance is skipped rather than overdrawn
+        assert_eq!(oversized.trades, 0);
+        assert_eq!(oversized.balance_sol, paper.starting_balance_sol);
+    }
+}
@@ -0,0 +1,193 @@
+use std::sync::Arc;
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+use chrono::Utc;
+use crate::{
+    config::{self, SharedConfig},
+    types::{ShadowEntry, TokenAnalysis},
+    utils::{solana_client::SolanaClient, token_analyzer},
+};
+
+/// Outcomes of shadow entries whose window has closed, kept after the entries are evicted
+#[derive(Debug, Default)]
+struct ShadowTotals {
+    completed: usize,
+    missed_pumps: usize,
+    hypothetical_pnl_percentage: f64,
+}
+
+/// Tracks tokens rejected by the filters to evaluate missed opportunities
+pub struct ShadowTracker {
+    config: SharedConfig,
+    entries: Arc<RwLock<HashMap<String, ShadowEntry>>>,
+    totals: Arc<RwLock<ShadowTotals>>,
+}
+
+impl ShadowTracker {
+    /// Create a new shadow tracker
+    pub fn new(config: SharedConfig) -> Self {
+        Self {
+            config,
+            entries: Arc::new(RwLock::new(HashMap::new())),
+            totals: Arc::new(RwLock::new(ShadowTotals::default())),
+        }
+    }
+
+    /// Record a rejected token as a shadow entry
+    pub async fn record(&self, analysis: &TokenAnalysis, reason: &str) {
+        if !self.config.load().shadow_track_rejected {
+            return;
+        }
+
+        let entry = ShadowEntry {
+            token_address: analysis.token.address,
+            bonding_curve_address: analysis.bonding_curve.address,
+            token_symbol: analysis.token.symbol.clone(),
+            rejection_reason: reason.to_string(),
+            entry_price: analysis.metrics.price,
+            current_price: analysis.metrics.price,
+            peak_price: analysis.metrics.price,
+            hypothetical_pnl_percentage: 0.0,
+            rejected_at: Utc::now(),
+        };
+
+        self.entries.write().await.insert(entry.token_address.to_string(), entry);
+    }
+
+    /// Apply a fresh price observation to a shadow entry
+    pub async fn update_price(&self, token_address: &str, price: f64) {
+        let mut entries = self.entries.write().await;
+        if let Some(entry) = entries.get_mut(token_address) {
+            entry.current_price = price;
+            entry.peak_price = entry.peak_price.max(price);
+            if entry.entry_price > 0.0 {
+                entry.hypothetical_pnl_percentage = ((price - entry.entry_price) / entry.entry_price) * 100.0;
+            }
+        }
+    }
+
+    /// Refresh prices for all active shadow entries, then evict those whose window has closed
+    pub async fn refresh(&self, client: &SolanaClient) {
+        let active: Vec<ShadowEntry> = self.entries.read().await.values().cloned().collect();
+
+        for entry in active {
+            let analysis = token_analyzer::analyze_token(&entry.token_address, &entry.bonding_curve_address, client)
+                .await
+                .map_err(|e| e.to_string());
+            match analysis {
+                Ok(analysis) => self.update_price(&entry.token_address.to_string(), analysis.metrics.price).await,
+                Err(e) => tracing::debug!("Shadow refresh failed for {}: {}", entry.token_symbol, e),
+            }
+        }
+
+        self.evict_completed().await;
+    }
+
+    /// Fold entries past the tracking window into the totals and drop them
+    ///
+    /// An entry whose price can no longer be read still closes on schedule, at its last known price.
+    async fn evict_completed(&self) {
+        let config = self.config.load();
+        let window = chrono::Duration::milliseconds(config.shadow_window_ms as i64);
+        let now = Utc::now();
+
+        let mut entries = self.entries.write().await;
+        let mut totals = self.totals.write().await;
+        entries.retain(|_, entry| {
+            if now - entry.rejected_at < window {
+                return true;
+            }
+            totals.completed += 1;
+            totals.hypothetical_pnl_percentage += entry.hypothetical_pnl_percentage;
+            // Compared in whole basis points, like live take-profits, so float drift cannot miss the level
+            if config::percent_to_bps(entry.hypothetical_pnl_percentage) >= config.take_profit_bps() {
+                totals.missed_pumps += 1;
+            }
+            false
+        });
+    }
+
+    /// Get aggregate missed-gain statistics
+    pub async fn status(&self) -> serde_json::Value {
+        let config = self.config.load();
+        let entries = self.entries.read().await;
+        let totals = self.totals.read().await;
+
+        // Missed gains are the peak upside we would have had on rejected tokens still in their window
+        let missed_gains_sol: f64 = entries.values()
+            .filter(|e| e.entry_price > 0.0 && e.peak_price > e.entry_price)
+            .map(|e| (e.peak_price - e.entry_price) / e.entry_price * config.buy_amount_sol)
+            .sum();
+
+        serde_json::json!({
+            "tracked": entries.len() + totals.completed,
+            "completed": totals.completed,
+            "missed_pumps": totals.missed_pumps,
+            "missed_gains_sol": missed_gains_sol,
+            "hypothetical_pnl_sol": totals.hypothetical_pnl_percentage / 100.0 * config.buy_amount_sol,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::BotConfig;
+    use crate::types::test_analysis;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn refresh_survives_rpc_errors_and_evicts_closed_windows() {
+        let config = BotConfig {
+            rpc_url: "http://127.0.0.1:1".to_string(),
+            shadow_track_rejected: true,
+            take_profit_percentage: 50.0,
+            ..Default::default()
+        };
+        let client = SolanaClient::new(&config).await.unwrap();
+        let tracker = ShadowTracker::new(config::shared(config));
+
+        let mut expired = test_analysis();
+        expired.token.address = solana_sdk::pubkey::Pubkey::new_unique();
+        let mut open = test_analysis();
+        open.token.address = solana_sdk::pubkey::Pubkey::new_unique();
+        tracker.record(&expired, "low liquidity").await;
+        tracker.record(&open, "low liquidity").await;
+
+        let expired_key = expired.token.address.to_string();
+        tracker.update_price(&expired_key, expired.metrics.price * 2.0).await;
+        tracker.entries.write().await.get_mut(&expired_key).unwrap().rejected_at -= chrono::Duration::hours(1);
+
+        // Neither price can be read; the refresh still runs to the end and closes the expired entry
+        tracker.refresh(&client).await;
+
+        let entries = tracker.entries.read().await;
+        assert_eq!(entries.len(), 1);
+        assert!(entries.contains_key(&open.token.address.to_string()));
+        drop(entries);
+
+        let status = tracker.status().await;
+        assert_eq!(status["tracked"], 2);
+        assert_eq!(status["completed"], 1);
+        assert_eq!(status["missed_pumps"], 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn gain_exactly_at_take_profit_counts_as_a_missed_pump() {
+        let config = BotConfig { shadow_track_rejected: true, take_profit_percentage: 50.0, ..Default::default() };
+        let tracker = ShadowTracker::new(config::shared(config));
+
+        let mut analysis = test_analysis();
+        analysis.metrics.price = 0.1;
+        tracker.record(&analysis, "low liquidity").await;
+        let key = analysis.token.address.to_string();
+
+        // (0.15 - 0.1) / 0.1 * 100 is 49.999... in floating point
+        tracker.update_price(&key, 0.15).await;
+        assert!(tracker.entries.read().await[&key].hypothetical_pnl_percentage < 50.0);
+
+        tracker.entries.write().await.get_mut(&key).unwrap().rejected_at -= chrono::Duration::hours(1);
+        tracker.evict_completed().await;
+        assert_eq!(tracker.status().await["missed_pumps"], 1);
+    }
+
+}
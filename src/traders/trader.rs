@@ -2,98 +2,389 @@ use std::sync::Arc;
 use std::collections::HashMap;
 use tokio::sync::RwLock;
 use chrono::Utc;
+use tracing::Instrument;
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
 use crate::{
-    config::BotConfig,
-    types::{TokenAnalysis, TradeResult, TradeType, Position, PositionStatus},
-    utils::{solana_client::SolanaClient, transaction_builder::TransactionBuilder},
+    config::{BotConfig, BuyMode, LotPolicy, SharedConfig},
+    traders::exit_rules::ExitAction,
+    types::{BondingCurveInfo, CurveUpdateEvent, Lot, MigrationEvent, PendingLeg, PendingTransaction, ReconciliationReport, SellActivityEvent, TokenAnalysis, TradeResult, TradeType, Position, PositionStatus, SellAmount, SellReason},
+    utils::{
+        bonding_curve,
+        solana_client::{SignatureState, SolanaClient},
+        latency::LatencyStats,
+        fee_tuner::{self, FeeTuner},
+        notifier::Notifier,
+        coordination::{self, Coordinator},
+        storage::{self, Storage},
+        token_analyzer,
+        token_program::TokenAccountState,
+        transaction_builder::{BuyRoute, SellLeg, TransactionBuilder},
+    },
 };
 
+/// How many times a migration sell checks for the AMM pool before giving up
+const MIGRATION_POOL_ATTEMPTS: u32 = 15;
+
+/// Delay between checks for a migrating token's AMM pool
+const MIGRATION_POOL_RETRY_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// How long a sent buy may stay unconfirmed before it is treated as failed
+pub(crate) const UNCONFIRMED_BUY_EXPIRY_MS: u64 = 90_000;
+
+/// How long a pushed curve price stands in for polling
+const CURVE_PUSH_FRESH_MS: u64 = 30_000;
+
+/// How long a fetched wallet balance is reused for buy sizing
+const BALANCE_CACHE_TTL: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Halt reason set by the daily loss circuit breaker
+const DAILY_LOSS_HALT_REASON: &str = "daily loss limit reached";
+
+/// Storage key of today's realized PnL and breaker state
+const DAILY_LOSS_STATE_KEY: &str = "daily_loss";
+
+/// A sent buy whose outcome is not yet known
+///
+/// A counted buy already holds a daily-trade slot, taken on `counted_on`; the slot
+/// is refunded if the buy fails.
+#[derive(Debug, Clone, Copy)]
+struct UnconfirmedBuy {
+    previous_last_buy: u64,
+    started_at: u64,
+    counted_on: Option<chrono::NaiveDate>,
+}
+
+/// Distinguishes simulated signatures created within the same millisecond
+static SIMULATED_SIGNATURE_SEQ: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
 /// Trading bot for executing buy/sell orders
 pub struct Trader {
     client: Arc<SolanaClient>,
-    config: Arc<BotConfig>,
+    config: SharedConfig,
     transaction_builder: Arc<TransactionBuilder>,
+    storage: Arc<dyn Storage>,
+    coordinator: Option<Arc<dyn Coordinator>>,
     positions: Arc<RwLock<HashMap<String, Position>>>,
     is_buying: Arc<RwLock<bool>>,
     is_selling: Arc<RwLock<bool>>,
+    deferred_sells: Arc<RwLock<std::collections::HashSet<String>>>,
     last_buy_time: Arc<RwLock<u64>>,
+    unconfirmed_buys: Arc<RwLock<HashMap<String, UnconfirmedBuy>>>,
+    cached_balance: Arc<RwLock<Option<(f64, std::time::Instant)>>>,
+    curve_pushed_at: Arc<RwLock<HashMap<String, u64>>>,
+    token_spans: Arc<RwLock<HashMap<String, tracing::Span>>>,
+    warmup_until: Arc<RwLock<u64>>,
+    halt_reason: Arc<RwLock<Option<String>>>,
+    loss_streak: Arc<RwLock<u32>>,
+    latency: Arc<LatencyStats>,
+    fee_tuner: FeeTuner,
     daily_trades: Arc<RwLock<u32>>,
+    daily_realized_pnl: Arc<RwLock<f64>>,
+    daily_loss_tripped: Arc<RwLock<bool>>,
     last_reset_date: Arc<RwLock<String>>,
+    notifier: Notifier,
+    source_stats: std::sync::Mutex<Option<serde_json::Value>>,
+    virtual_starting_balance_sol: Option<f64>,
+    claims_renewed_at: std::sync::Mutex<Option<std::time::Instant>>,
 }
 
 impl Trader {
     /// Create a new trader
     pub async fn new(
         client: Arc<SolanaClient>,
-        config: Arc<BotConfig>,
+        config: SharedConfig,
     ) -> Result<Self, Box<dyn std::error::Error>> {
         let transaction_builder = Arc::new(TransactionBuilder::new(
             Arc::clone(&client),
             Arc::clone(&config),
         ));
 
+        // Restore persisted positions
+        let storage: Arc<dyn Storage> = Arc::from(storage::open(&config.load())?);
+        let positions: HashMap<String, Position> = storage.load_positions()?
+            .into_iter()
+            .map(|pos| (pos.token_address.to_string(), pos))
+            .collect();
+        if !positions.is_empty() {
+            tracing::info!("Restored {} persisted positions", positions.len());
+        }
+
+        // Resume fee learning where the last run left off
+        let learned_fee = storage.load_state(fee_tuner::LEARNED_FEE_KEY)?.and_then(|fee| fee.as_u64());
+
+        // Share buy claims with other instances when configured
+        let coordinator: Option<Arc<dyn Coordinator>> = coordination::open(&config.load())?.map(Arc::from);
+
+        // Today's realized losses survive a restart, so restarting cannot bypass the breaker
+        let today = Utc::now().format("%Y-%m-%d").to_string();
+        let daily_loss = storage.load_state(DAILY_LOSS_STATE_KEY)?.filter(|state| state["date"] == today.as_str());
+        let daily_realized_pnl = daily_loss.as_ref().and_then(|state| state["realized_pnl_sol"].as_f64()).unwrap_or(0.0);
+        let daily_loss_tripped = daily_loss.as_ref().and_then(|state| state["tripped"].as_bool()).unwrap_or(false);
+        if daily_loss_tripped {
+            tracing::warn!("Daily loss limit already reached today; buys stay halted until the next UTC day");
+        }
+
+        let latency = Arc::new(LatencyStats::new(Arc::clone(&config)));
+        let notifier = Notifier::new(Arc::clone(&config));
+
         Ok(Self {
             client,
             config,
             transaction_builder,
-            positions: Arc::new(RwLock::new(HashMap::new())),
+            storage,
+            coordinator,
+            positions: Arc::new(RwLock::new(positions)),
             is_buying: Arc::new(RwLock::new(false)),
             is_selling: Arc::new(RwLock::new(false)),
+            deferred_sells: Arc::new(RwLock::new(std::collections::HashSet::new())),
             last_buy_time: Arc::new(RwLock::new(0)),
+            unconfirmed_buys: Arc::new(RwLock::new(HashMap::new())),
+            cached_balance: Arc::new(RwLock::new(None)),
+            curve_pushed_at: Arc::new(RwLock::new(HashMap::new())),
+            token_spans: Arc::new(RwLock::new(HashMap::new())),
+            warmup_until: Arc::new(RwLock::new(0)),
+            halt_reason: Arc::new(RwLock::new(daily_loss_tripped.then(|| DAILY_LOSS_HALT_REASON.to_string()))),
+            loss_streak: Arc::new(RwLock::new(0)),
+            latency,
+            fee_tuner: FeeTuner::new(learned_fee),
             daily_trades: Arc::new(RwLock::new(0)),
-            last_reset_date: Arc::new(RwLock::new(Utc::now().format("%Y-%m-%d").to_string())),
+            daily_realized_pnl: Arc::new(RwLock::new(daily_realized_pnl)),
+            daily_loss_tripped: Arc::new(RwLock::new(daily_loss_tripped)),
+            last_reset_date: Arc::new(RwLock::new(today)),
+            notifier,
+            source_stats: std::sync::Mutex::new(None),
+            virtual_starting_balance_sol: None,
+            claims_renewed_at: std::sync::Mutex::new(None),
         })
     }
 
+    /// Size and fund simulated buys from a virtual balance instead of the wallet
+    pub fn with_virtual_balance(mut self, starting_balance_sol: f64) -> Self {
+        self.virtual_starting_balance_sol = Some(starting_balance_sol);
+        self
+    }
+
     /// Get client reference
     pub fn client(&self) -> &Arc<SolanaClient> {
         &self.client
     }
 
+    /// Per-stage pipeline latency shared with the detection path
+    pub fn latency(&self) -> &Arc<LatencyStats> {
+        &self.latency
+    }
+
+    /// Get transaction builder reference
+    pub fn transaction_builder(&self) -> &Arc<TransactionBuilder> {
+        &self.transaction_builder
+    }
+
+    /// Begin the startup warmup window during which buys are ignored
+    pub async fn start_warmup(&self) {
+        let warmup_ms = self.config.load().startup_warmup_ms;
+        *self.warmup_until.write().await = now_millis() + warmup_ms;
+        if warmup_ms > 0 {
+            tracing::info!("Trading warmup for {} ms", warmup_ms);
+        }
+    }
+
+    /// Check if the trader is still in its startup warmup
+    pub async fn in_warmup(&self) -> bool {
+        now_millis() < *self.warmup_until.read().await
+    }
+
+    /// Stop new buys until `resume_buys` is called; exits keep running
+    pub async fn halt_buys(&self, reason: &str) {
+        tracing::warn!("Halting buys: {}", reason);
+        *self.halt_reason.write().await = Some(reason.to_string());
+    }
+
+    /// Allow buys again after a halt
+    pub async fn resume_buys(&self) {
+        if self.halt_reason.write().await.take().is_some() {
+            tracing::info!("Resuming buys");
+        }
+    }
+
+    /// Why buys are halted, if they are
+    pub async fn halt_reason(&self) -> Option<String> {
+        self.halt_reason.read().await.clone()
+    }
+
+    /// Keep a bought token's trace open so its sells join the same span tree
+    pub async fn attach_trace(&self, token_address: Pubkey, span: tracing::Span) {
+        self.token_spans.write().await.insert(token_address.to_string(), span);
+    }
+
+    /// Span for a sell, parented to the token's trace when it was bought this run
+    async fn sell_span(&self, token_address: &Pubkey, reason: SellReason) -> tracing::Span {
+        let parent = self.token_spans.read().await
+            .get(&token_address.to_string())
+            .and_then(|span| span.id());
+        tracing::info_span!(parent: parent, "sell", mint = %token_address, reason = ?reason)
+    }
+
+    /// Check if the trader holds an open position in a mint
+    pub async fn holds(&self, token_address: &Pubkey) -> bool {
+        self.positions
+            .read()
+            .await
+            .get(&token_address.to_string())
+            .is_some_and(|pos| pos.status != PositionStatus::Closed)
+    }
+
     /// Execute a buy order
-    pub async fn execute_buy(&self, analysis: &TokenAnalysis) -> Result<(), Box<dyn std::error::Error>> {
+    ///
+    /// `source` names the monitor that detected the token and is carried onto the position.
+    pub async fn execute_buy(&self, analysis: &TokenAnalysis, source: &str) -> Result<(), Box<dyn std::error::Error>> {
+        // The trade is stamped with the config this decision was made under, even if it reloads meanwhile
+        let decision_config = self.config.load_full();
+
+        // Ignore buys while the monitor connection stabilizes
+        if self.in_warmup().await {
+            tracing::info!("[WARMUP] Skipping buy for {}", analysis.token.symbol);
+            return Ok(());
+        }
+
+        if let Some(reason) = self.halt_reason().await {
+            tracing::warn!("Buy for {} blocked: trading halted ({})", analysis.token.symbol, reason);
+            return Ok(());
+        }
+
         // Check if buying is allowed
         if !self.can_buy().await {
             tracing::warn!("Buy blocked by safety limits");
             return Ok(());
         }
 
+        let by_tokens = self.config.load().buy_mode == BuyMode::ByTokens;
+        let base_amount_sol = match self.planned_buy_amount_sol(analysis).await {
+            Some(amount_sol) => amount_sol,
+            None => {
+                tracing::warn!("Buy blocked: curve for {} cannot fill BUY_AMOUNT_TOKENS", analysis.token.symbol);
+                return Ok(());
+            }
+        };
+
+        // Enforce per-mint exposure cap; a fixed token quantity cannot be scaled down to fit
+        let amount_sol = match self.size_for_token_exposure(analysis, base_amount_sol).await {
+            Some(amount_sol) if !by_tokens || amount_sol >= base_amount_sol => amount_sol,
+            _ => {
+                tracing::warn!("Buy blocked by exposure cap for {}", analysis.token.symbol);
+                return Ok(());
+            }
+        };
+
+        // Enforce portfolio-wide exposure cap
+        if !self.within_total_exposure(amount_sol).await {
+            tracing::warn!("Buy blocked: total exposure cap reached");
+            return Ok(());
+        }
+
         // Check simulation mode
-        if self.config.simulation_mode {
-            return self.simulate_buy(analysis).await;
+        if self.config.load().simulation_mode {
+            if let Some(starting_balance_sol) = self.virtual_starting_balance_sol {
+                let balance = virtual_balance(starting_balance_sol, &self.trade_history(None)?);
+                if balance < amount_sol {
+                    tracing::debug!(
+                        "[SIMULATION] Skipping {}: virtual balance {:.4} SOL below {} SOL",
+                        analysis.token.symbol,
+                        balance,
+                        amount_sol
+                    );
+                    return Ok(());
+                }
+            }
+            return self.simulate_buy(analysis, amount_sol, source, &decision_config).await;
+        }
+
+        // A frozen or foreign account at the token account address would fail the buy opaquely
+        if self.config.load().check_token_account_before_buy {
+            // An RPC hiccup should not cost the snipe; the buy's own ATA creation is idempotent
+            let state = self.client.token_account_state(&analysis.token.address).await
+                .map_err(|e| e.to_string())
+                .unwrap_or_else(|e| {
+                    tracing::warn!("Token account check for {} failed, assuming none: {}", analysis.token.symbol, e);
+                    TokenAccountState::Missing
+                });
+            match state {
+                TokenAccountState::Frozen => {
+                    tracing::warn!("Buy for {} skipped: the wallet's token account is frozen", analysis.token.symbol);
+                    return Ok(());
+                }
+                TokenAccountState::Nonstandard(reason) => {
+                    tracing::warn!("Buy for {} skipped: unusable token account ({})", analysis.token.symbol, reason);
+                    return Ok(());
+                }
+                TokenAccountState::Missing | TokenAccountState::Usable => {}
+            }
         }
 
-        // Check balance
+        // Check balance, including rent for a token account the buy creates
+        let rent = self.client.token_account_rent(&analysis.token.address).await?;
+        let reserve = self.config.load().fee_reserve_sol + rent;
         let balance = self.client.get_wallet_balance().await?;
-        if balance < self.config.buy_amount_sol + 0.01 {
-            tracing::warn!("Insufficient balance for buy: {} SOL", balance);
+        if balance < amount_sol + reserve {
+            tracing::warn!(
+                "Insufficient balance for buy: {} SOL (need {} SOL plus {:.6} SOL reserve)",
+                balance,
+                amount_sol,
+                reserve
+            );
+            return Ok(());
+        }
+
+        // Skip trades whose fees make a net profit structurally unlikely
+        if let Some(max_ratio_pct) = self.config.load().max_fee_ratio_pct {
+            let fees = self.estimated_round_trip_fees_sol(amount_sol).await? + rent;
+            let ratio_pct = fees / amount_sol * 100.0;
+            if ratio_pct > max_ratio_pct {
+                tracing::warn!(
+                    "Buy for {} skipped: estimated fees {:.6} SOL are {:.1}% of {} SOL (max {}%)",
+                    analysis.token.symbol,
+                    fees,
+                    ratio_pct,
+                    amount_sol,
+                    max_ratio_pct
+                );
+                return Ok(());
+            }
+        }
+
+        // Leave the token to whichever instance in the fleet claimed it first
+        if !self.claim_for_buy(&analysis.token.address).await {
+            tracing::info!("Buy for {} skipped: claimed by another instance", analysis.token.symbol);
             return Ok(());
         }
 
+        // Prove the token sells with a small position before committing the full size
+        if self.config.load().scout_then_scale {
+            *self.is_buying.write().await = true;
+            let result = self.scout_then_scale(analysis, amount_sol, source, &decision_config).await
+                .map_err(|e| e.to_string());
+            *self.is_buying.write().await = false;
+            self.release_claim_unless_held(&analysis.token.address).await;
+            return Ok(result?);
+        }
+
         tracing::info!(
             "Executing buy for {}: {} SOL",
             analysis.token.symbol,
-            self.config.buy_amount_sol
+            amount_sol
         );
 
         *self.is_buying.write().await = true;
+        let result = self.send_buy_with_retries(analysis, amount_sol, source).await
+            .map_err(|e| e.to_string());
+        *self.is_buying.write().await = false;
+        if !matches!(result, Ok(Some(_))) {
+            self.release_claim(&analysis.token.address).await;
+        }
 
-        // Build transaction
-        let transaction = self.transaction_builder.build_buy_transaction(
-            &analysis.token.address,
-            &analysis.bonding_curve.address,
-            self.config.buy_amount_sol,
-            self.config.max_slippage,
-        ).await?;
-
-        // Send transaction
-        match self.client.send_transaction(transaction).await {
-            Ok(signature) => {
+        match result? {
+            Some((signature, amount)) => {
                 // Update tracking
-                self.update_buy_tracking().await;
-
-                // Create position
-                self.create_position(analysis, signature).await;
+                self.start_buy_tracking(&signature, true).await;
 
                 tracing::info!(
                     "Buy executed successfully: {} - {}",
@@ -101,56 +392,512 @@ impl Trader {
                     signature
                 );
 
+                // Create position
+                self.record_trade(&signature, analysis.token.address, TradeType::Buy, amount_sol, analysis.metrics.price, source, &decision_config);
+                self.create_position(analysis, amount_sol, amount, signature.clone(), source).await;
+                self.clear_pending_transaction(&signature);
+                if let Some(position) = self.positions.read().await.get(&analysis.token.address.to_string()).cloned() {
+                    self.confirm_position(&position).await;
+                }
+
+                // Verify the fill against on-chain balances
+                self.verify_sol_spent(balance, amount_sol, reserve).await;
+                if let Err(e) = self.reconcile_position_amount(&analysis.token.address).await {
+                    tracing::warn!("Post-buy balance check failed for {}: {}", analysis.token.symbol, e);
+                }
+
                 Ok(())
             }
-            Err(e) => {
-                tracing::error!("Buy execution failed: {}", e);
+            None => {
+                tracing::error!("Buy execution failed for {} after all retries", analysis.token.symbol);
                 Ok(())
             }
         }
     }
 
+    /// Run a coordinator call on the blocking pool, or `None` without a fleet
+    async fn coordinate<T, F>(&self, call: F) -> Option<Result<T, String>>
+    where
+        T: Send + 'static,
+        F: FnOnce(&dyn Coordinator) -> Result<T, Box<dyn std::error::Error>> + Send + 'static,
+    {
+        let coordinator = Arc::clone(self.coordinator.as_ref()?);
+        let result = tokio::task::spawn_blocking(move || call(coordinator.as_ref()).map_err(|e| e.to_string())).await;
+        Some(result.unwrap_or_else(|e| Err(e.to_string())))
+    }
+
+    /// Claim a mint across the fleet before buying it
+    ///
+    /// Coordination failures are logged and the buy proceeds, as without a fleet.
+    async fn claim_for_buy(&self, mint: &Pubkey) -> bool {
+        let (mint, owner) = (*mint, self.claim_owner());
+        let ttl = std::time::Duration::from_secs(self.config.load().claim_ttl_secs);
+        match self.coordinate(move |coordinator| coordinator.try_claim(&mint, &owner, ttl)).await {
+            None => true,
+            Some(Ok(claimed)) => claimed,
+            Some(Err(e)) => {
+                tracing::warn!("Failed to claim {} with the fleet, buying anyway: {}", mint, e);
+                true
+            }
+        }
+    }
+
+    /// Give up this instance's claim on a mint so others may buy it
+    async fn release_claim(&self, mint: &Pubkey) {
+        let (mint, owner) = (*mint, self.claim_owner());
+        if let Some(Err(e)) = self.coordinate(move |coordinator| coordinator.release(&mint, &owner)).await {
+            tracing::warn!("Failed to release fleet claim on {}: {}", mint, e);
+        }
+    }
+
+    /// Keep the fleet claims on held mints alive, at most every third of the claim TTL
+    ///
+    /// A claim that lapsed or went to another instance is only logged; the position is kept.
+    async fn renew_held_claims(&self) {
+        let ttl = std::time::Duration::from_secs(self.config.load().claim_ttl_secs);
+        {
+            let mut renewed_at = self.claims_renewed_at.lock().unwrap();
+            if self.coordinator.is_none() || renewed_at.is_some_and(|at| at.elapsed() < ttl / 3) {
+                return;
+            }
+            *renewed_at = Some(std::time::Instant::now());
+        }
+
+        let held: Vec<Pubkey> = self.positions.read().await
+            .values()
+            .filter(|pos| pos.status != PositionStatus::Closed)
+            .map(|pos| pos.token_address)
+            .collect();
+        for mint in held {
+            let owner = self.claim_owner();
+            match self.coordinate(move |coordinator| coordinator.renew(&mint, &owner, ttl)).await {
+                Some(Ok(false)) => tracing::warn!("Fleet claim on held {} lapsed", mint),
+                Some(Err(e)) => tracing::warn!("Failed to renew fleet claim on {}: {}", mint, e),
+                _ => {}
+            }
+        }
+    }
+
+    /// Release the claim on a mint unless a position in it is open
+    async fn release_claim_unless_held(&self, mint: &Pubkey) {
+        let held = self.positions.read().await
+            .get(&mint.to_string())
+            .is_some_and(|pos| pos.status != PositionStatus::Closed);
+        if !held {
+            self.release_claim(mint).await;
+        }
+    }
+
+    /// Identity this instance claims mints under
+    fn claim_owner(&self) -> String {
+        self.client.public_key().map(|key| key.to_string()).unwrap_or_default()
+    }
+
+    /// Buy a scout position, sell part of it, and scale in only if that sell lands
+    ///
+    /// A scout that cannot be sold is treated as a honeypot and exited in full.
+    async fn scout_then_scale(
+        &self,
+        analysis: &TokenAnalysis,
+        amount_sol: f64,
+        source: &str,
+        config: &BotConfig,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let scout_sol = config.scout_amount_sol.min(amount_sol);
+        let key = analysis.token.address.to_string();
+
+        tracing::info!("Scouting {} with {} SOL", analysis.token.symbol, scout_sol);
+        let (signature, amount) = match self.send_buy_with_retries(analysis, scout_sol, source).await? {
+            Some(sent) => sent,
+            None => {
+                tracing::error!("Scout buy failed for {}", analysis.token.symbol);
+                return Ok(());
+            }
+        };
+
+        self.start_buy_tracking(&signature, true).await;
+        self.record_trade(&signature, analysis.token.address, TradeType::Buy, scout_sol, analysis.metrics.price, source, config);
+        self.create_position(analysis, scout_sol, amount, signature.clone(), source).await;
+        self.clear_pending_transaction(&signature);
+        if let Some(position) = self.positions.read().await.get(&key).cloned() {
+            self.confirm_position(&position).await;
+        }
+        if let Err(e) = self.reconcile_position_amount(&analysis.token.address).await {
+            tracing::warn!("Post-buy balance check failed for {}: {}", analysis.token.symbol, e);
+        }
+
+        let position = match self.positions.read().await.get(&key).cloned() {
+            Some(position) if position.status == PositionStatus::Open => position,
+            _ => {
+                tracing::warn!("Scout buy for {} did not produce a sellable position", analysis.token.symbol);
+                return Ok(());
+            }
+        };
+
+        if !self.verify_scout_sell(&position, config).await {
+            tracing::error!(
+                "Scout sell for {} failed, treating as honeypot and exiting",
+                analysis.token.symbol
+            );
+            let position = self.positions.read().await.get(&key).cloned().unwrap_or(position);
+            return self.execute_sell(&position, 100.0, SellReason::ScoutFailed).await;
+        }
+
+        let remaining_sol = amount_sol - scout_sol;
+        tracing::info!("Scout verified for {}, scaling in with {} SOL", analysis.token.symbol, remaining_sol);
+        // The scale-in completes the trade the scout started, so it takes no trade slot of its own
+        let scaled = self.send_buy_with_retries(analysis, remaining_sol, source).await?;
+        match scaled {
+            Some((signature, amount)) => {
+                self.start_buy_tracking(&signature, false).await;
+                self.record_trade(&signature, analysis.token.address, TradeType::Buy, remaining_sol, analysis.metrics.price, source, config);
+                self.create_position(analysis, remaining_sol, amount, signature.clone(), source).await;
+                self.clear_pending_transaction(&signature);
+                if let Err(e) = self.reconcile_position_amount(&analysis.token.address).await {
+                    tracing::warn!("Post-buy balance check failed for {}: {}", analysis.token.symbol, e);
+                }
+            }
+            None => tracing::error!("Scale-in buy failed for {}, keeping scout position", analysis.token.symbol),
+        }
+
+        Ok(())
+    }
+
+    /// Take the sell lock unless another sell holds it; the caller releases what it claimed
+    async fn claim_selling(&self) -> bool {
+        let mut is_selling = self.is_selling.write().await;
+        !std::mem::replace(&mut *is_selling, true)
+    }
+
+    /// Sell part of a scout position, requiring the sell to land and pay out
+    async fn verify_scout_sell(&self, position: &Position, config: &BotConfig) -> bool {
+        let amount_to_sell = ((position.amount as f64) * config.scout_sell_percentage / 100.0) as u64;
+        if amount_to_sell == 0 {
+            return false;
+        }
+
+        let result = async {
+            let curve = token_analyzer::fetch_bonding_curve(&position.bonding_curve_address, &self.client).await?;
+            let quoted_output = self.sell_quote(position, &curve, amount_to_sell).await?;
+            let min_sol_output = bonding_curve::min_output_with_slippage(quoted_output, config.slippage_bps());
+            if !self.claim_selling().await {
+                return Err("another sell is in progress".into());
+            }
+            let sent = self.send_sell(position, &curve, amount_to_sell, quoted_output, min_sol_output).await
+                .map_err(|e| e.to_string());
+            *self.is_selling.write().await = false;
+
+            let signature = sent?;
+            Ok::<_, Box<dyn std::error::Error>>((signature, quoted_output))
+        }.await.map_err(|e| e.to_string());
+
+        let (signature, quoted_output) = match result {
+            Ok(sent) => sent,
+            Err(e) => {
+                tracing::warn!("Scout sell for {} failed: {}", position.token_symbol, e);
+                return false;
+            }
+        };
+
+        // Always confirm here: an unconfirmed sell proves nothing about sellability
+        if !self.sell_landed(&signature).await {
+            self.clear_pending_transaction(&signature);
+            return false;
+        }
+
+        self.record_trade(
+            &signature,
+            position.token_address,
+            TradeType::Sell,
+            quoted_output as f64 / crate::config::constants::LAMPORTS_PER_SOL as f64,
+            position.current_price,
+            &position.source,
+            config,
+        );
+        self.update_position_after_sell(position, amount_to_sell, quoted_output as f64 / crate::config::constants::LAMPORTS_PER_SOL as f64).await;
+        self.clear_pending_transaction(&signature);
+        true
+    }
+
+    /// Send a buy, escalating the priority fee on each retry
+    ///
+    /// A revert from the on-chain slippage check is retried once at a bumped
+    /// slippage without consuming a retry; other program errors are final.
+    /// Returns the signature with the token amount quoted for the route it took.
+    async fn send_buy_with_retries(
+        &self,
+        analysis: &TokenAnalysis,
+        amount_sol: f64,
+        source: &str,
+    ) -> Result<Option<(String, u64)>, Box<dyn std::error::Error>> {
+        let config = self.config.load_full();
+        let base_fee = match self.fee_tuner.fee() {
+            Some(learned) if config.adaptive_priority_fee => learned,
+            _ => self.client.get_priority_fee_estimate().await?,
+        };
+
+        // Honeypots fail permanently; retrying only burns fees
+        let max_retries = if analysis.safety.checks.is_honeypot { 0 } else { config.max_send_retries };
+
+        // Near or past graduation the curve no longer fills; buy from the AMM pool instead
+        let mut route = TransactionBuilder::select_buy_route(&analysis.bonding_curve, &config);
+        if route == BuyRoute::PumpAmm && !self.transaction_builder.amm_pool_exists(&analysis.token.address).await? {
+            // The pool only exists once migration lands; until then only the curve can fill
+            if analysis.bonding_curve.complete {
+                tracing::warn!("Buy for {} aborted: curve complete but AMM pool not created yet", analysis.token.symbol);
+                return Ok(None);
+            }
+            route = BuyRoute::BondingCurve;
+        }
+        if route == BuyRoute::PumpAmm {
+            tracing::info!("Routing buy for {} through the Pump.fun AMM", analysis.token.symbol);
+        }
+        let lamports = (amount_sol * crate::config::constants::LAMPORTS_PER_SOL as f64) as u64;
+
+        // Spend more on landing for higher-conviction tokens
+        let tip_lamports = config.jito_tip_lamports(analysis.opportunities.score);
+
+        let mut slippage_bps = config.slippage_bps();
+        let mut slippage_bumped = false;
+        let mut attempt = 0;
+        while attempt <= max_retries {
+            if !self.buy_price_guard_passes(analysis).await? {
+                tracing::warn!("Buy for {} aborted: price moved beyond guard", analysis.token.symbol);
+                return Ok(None);
+            }
+
+            let priority_fee = TransactionBuilder::escalated_priority_fee(
+                base_fee,
+                attempt,
+                config.priority_fee_escalation_multiplier,
+                config.max_priority_fee_lamports,
+            );
+
+            // Use a prepared transaction on the first attempt, otherwise build one
+            let build_started = std::time::Instant::now();
+            let prepared = if attempt == 0 && route == BuyRoute::BondingCurve {
+                self.transaction_builder.take_prepared_buy(&analysis.token.address, amount_sol)
+            } else {
+                None
+            };
+            let expected_tokens = match route {
+                BuyRoute::PumpAmm => {
+                    let (base_reserves, quote_reserves) = self.transaction_builder.amm_pool_reserves(&analysis.token.address).await?;
+                    bonding_curve::amm_buy_quote(base_reserves, quote_reserves, lamports)
+                }
+                BuyRoute::BondingCurve => bonding_curve::buy_quote(&analysis.bonding_curve, lamports),
+            };
+            let instructions = match prepared {
+                Some(instructions) => instructions,
+                None if route == BuyRoute::PumpAmm => self.transaction_builder.build_amm_buy_instructions(
+                    &analysis.token.address,
+                    &analysis.bonding_curve.creator,
+                    amount_sol,
+                    slippage_bps,
+                    priority_fee,
+                ).await?,
+                None => self.transaction_builder.build_buy_instructions(
+                    &analysis.token.address,
+                    &analysis.bonding_curve.address,
+                    &analysis.bonding_curve.creator,
+                    amount_sol,
+                    slippage_bps,
+                    priority_fee,
+                ).await?,
+            };
+            let transaction = self.transaction_builder.buy_transaction(instructions, tip_lamports)?;
+            self.latency.record_for(&analysis.token.address, "build", build_started.elapsed());
+
+            // Send transaction
+            let pending = PendingTransaction {
+                signature: String::new(),
+                intent: TradeType::Buy,
+                token_address: analysis.token.address,
+                bonding_curve_address: analysis.bonding_curve.address,
+                amount_sol,
+                token_amount: 0,
+                price: analysis.metrics.price,
+                source: source.to_string(),
+                sent_at: Utc::now(),
+                batch_legs: Vec::new(),
+            };
+            let send_started = std::time::Instant::now();
+            let sent = self.send_tracked(transaction, pending).await;
+            self.latency.record_for(&analysis.token.address, "send", send_started.elapsed());
+            match sent {
+                Ok(signature) => {
+                    self.learn_priority_fee(priority_fee, true);
+                    return Ok(Some((signature, expected_tokens)));
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "Buy attempt {} failed with priority fee {}: {}",
+                        attempt + 1,
+                        priority_fee,
+                        e
+                    );
+                    if is_slippage_exceeded_error(&e.to_string()) {
+                        match config.bumped_slippage_bps() {
+                            Some(bumped) if !slippage_bumped => {
+                                tracing::warn!(
+                                    "Buy for {} exceeded slippage, retrying at {} bps",
+                                    analysis.token.symbol,
+                                    bumped
+                                );
+                                slippage_bps = bumped;
+                                slippage_bumped = true;
+                                continue;
+                            }
+                            _ => return Ok(None),
+                        }
+                    }
+                    if !is_retryable_send_error(&e.to_string()) {
+                        tracing::warn!("Buy failure for {} is not retryable", analysis.token.symbol);
+                        return Ok(None);
+                    }
+                    // Only failures to land say anything about the fee
+                    self.learn_priority_fee(priority_fee, false);
+                }
+            }
+            attempt += 1;
+        }
+
+        Ok(None)
+    }
+
+    /// Check the curve price has not run away from the analyzed price
+    async fn buy_price_guard_passes(&self, analysis: &TokenAnalysis) -> Result<bool, Box<dyn std::error::Error>> {
+        let guard_pct = match self.config.load().buy_price_guard_pct {
+            Some(guard_pct) => guard_pct,
+            None => return Ok(true),
+        };
+
+        let curve = token_analyzer::fetch_bonding_curve(&analysis.bonding_curve.address, &self.client).await?;
+        let current_price = bonding_curve::spot_price(&curve);
+        Ok(current_price <= analysis.metrics.price * (1.0 + guard_pct / 100.0))
+    }
+
+    /// Sell just enough of a position to realize `target_sol` at the current curve quote
+    ///
+    /// The sell is capped at the whole position; proceeds can fall short of the target by
+    /// up to the slippage tolerance.
+    pub async fn sell_to_sol_target(&self, token_address: &Pubkey, target_sol: f64) -> Result<(), Box<dyn std::error::Error>> {
+        let position = self.positions.read().await
+            .get(&token_address.to_string())
+            .filter(|pos| pos.status != PositionStatus::Closed && pos.amount > 0)
+            .cloned()
+            .ok_or_else(|| format!("No open position in {}", token_address))?;
+
+        self.execute_sell(&position, SellAmount::SolTarget(target_sol), SellReason::Manual).await
+    }
+
     /// Execute a sell order
+    ///
+    /// `amount` is a percentage of the position, or a `SellAmount::SolTarget`.
     pub async fn execute_sell(
         &self,
         position: &Position,
-        percentage: f64,
+        amount: impl Into<SellAmount>,
+        reason: SellReason,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        if *self.is_selling.read().await {
+        let span = self.sell_span(&position.token_address, reason).await;
+        self.sell_position(position, amount.into(), reason).instrument(span).await
+    }
+
+    /// Quote, guard and send a sell of `amount` of a position
+    async fn sell_position(
+        &self,
+        position: &Position,
+        amount: SellAmount,
+        reason: SellReason,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        // Panic sells run concurrently and ignore the single-sell lock
+        let exclusive = reason != SellReason::Panic;
+        if exclusive && *self.is_selling.read().await {
             tracing::warn!("Sell already in progress");
             return Ok(());
         }
 
-        if self.config.simulation_mode {
-            return self.simulate_sell(position, percentage).await;
+        let decision_config = self.config.load_full();
+        if decision_config.simulation_mode {
+            return self.simulate_sell(position, amount, reason, &decision_config).await;
         }
 
-        let amount_to_sell = ((position.amount as f64) * percentage / 100.0) as u64;
-        let estimated_value = (amount_to_sell as f64) * position.current_price;
-        let min_sol_output = ((estimated_value * (1.0 - self.config.max_slippage / 100.0)) * 1_000_000_000.0) as u64;
+        // Quote the exit against the current curve so the on-chain guard is meaningful
+        let curve = token_analyzer::fetch_bonding_curve(&position.bonding_curve_address, &self.client).await?;
+        let amount_to_sell = tokens_to_sell(amount, position.amount, &curve);
+        let quoted_output = self.sell_quote(position, &curve, amount_to_sell).await?;
+        let slippage_bps = if reason == SellReason::Panic {
+            decision_config.emergency_slippage_bps
+        } else {
+            decision_config.slippage_bps()
+        };
+        let min_sol_output = bonding_curve::min_output_with_slippage(quoted_output, slippage_bps);
+
+        // Selling dust costs more in fees than it returns
+        let net_proceeds = self.net_sell_proceeds_sol(quoted_output);
+        if skips_dust_sell(reason, net_proceeds, self.config.load().min_sell_proceeds_sol) {
+            tracing::info!(
+                "Skipping sell of {} ({:?}): net proceeds {:.6} SOL below minimum",
+                position.token_symbol,
+                reason,
+                net_proceeds
+            );
+            return Ok(());
+        }
+
+        // Avoid dumping into a momentary wick unless this is a hard exit
+        if self.should_defer_sell(position, amount_to_sell, quoted_output, reason).await {
+            tracing::warn!(
+                "Deferring sell of {}: quote {} lamports far below expected",
+                position.token_symbol,
+                quoted_output
+            );
+            return Ok(());
+        }
 
         tracing::info!(
-            "Executing sell for {}: {}% ({} tokens)",
+            "Executing sell for {} ({:?}): {} ({} tokens)",
             position.token_symbol,
-            percentage,
+            reason,
+            amount,
             amount_to_sell
         );
 
-        *self.is_selling.write().await = true;
-
-        // Build transaction
-        let transaction = self.transaction_builder.build_sell_transaction(
-            &position.token_address,
-            &solana_sdk::pubkey::Pubkey::new_unique(), // Would need actual bonding curve
-            amount_to_sell,
-            min_sol_output,
-        ).await?;
+        // Claim the lock atomically: a panic sell may have taken it while this one quoted
+        if exclusive && !self.claim_selling().await {
+            tracing::warn!("Sell already in progress, dropping sell of {}", position.token_symbol);
+            return Ok(());
+        }
+        let result = self.send_sell(position, &curve, amount_to_sell, quoted_output, min_sol_output).await
+            .map_err(|e| e.to_string());
+        if exclusive {
+            *self.is_selling.write().await = false;
+        }
 
-        // Send transaction
-        match self.client.send_transaction(transaction).await {
+        match result {
+            Ok(signature) if !self.sell_proceeds_confirmed(&signature).await => {
+                // Keep the position as it was; tokens may still be held
+                tracing::error!("Sell for {} not confirmed, keeping position open: {}", position.token_symbol, signature);
+                if let Err(e) = self.reconcile_position_amount(&position.token_address).await {
+                    tracing::warn!("Post-sell balance check failed for {}: {}", position.token_symbol, e);
+                }
+                // The record stays so the next startup finds out whether it landed after all
+                Ok(())
+            }
             Ok(signature) => {
                 // Update position
-                self.update_position_after_sell(position, amount_to_sell).await;
+                self.record_trade(
+                    &signature,
+                    position.token_address,
+                    TradeType::Sell,
+                    quoted_output as f64 / crate::config::constants::LAMPORTS_PER_SOL as f64,
+                    position.current_price,
+                    &position.source,
+                    &decision_config,
+                );
+                self.update_position_after_sell(position, amount_to_sell, quoted_output as f64 / crate::config::constants::LAMPORTS_PER_SOL as f64).await;
+                self.clear_pending_transaction(&signature);
 
                 tracing::info!(
                     "Sell executed successfully: {} - {}",
@@ -158,6 +905,10 @@ impl Trader {
                     signature
                 );
 
+                if let Err(e) = self.reconcile_position_amount(&position.token_address).await {
+                    tracing::warn!("Post-sell balance check failed for {}: {}", position.token_symbol, e);
+                }
+
                 Ok(())
             }
             Err(e) => {
@@ -167,193 +918,3171 @@ impl Trader {
         }
     }
 
-    /// Check automated sells for take-profit/stop-loss
-    pub async fn check_automated_sells(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let positions: Vec<Position> = self.positions.read().await.values().cloned().collect();
-
-        for position in positions {
-            // Update position price (simplified)
-            self.update_position_price(&position).await?;
+    /// Check that a sell landed, unless confirmation is disabled
+    async fn sell_proceeds_confirmed(&self, signature: &str) -> bool {
+        if !self.config.load().require_sell_confirmation {
+            return true;
+        }
+        self.sell_landed(signature).await
+    }
 
-            // Check take profit
-            if self.should_take_profit(&position) {
-                self.execute_sell(&position, 100.0).await?;
+    /// Check a sell signature is confirmed and succeeded on-chain
+    ///
+    /// Uses the signature status rather than the wallet balance, which fees and
+    /// concurrent buys move independently of the sell.
+    async fn sell_landed(&self, signature: &str) -> bool {
+        let commitment = solana_sdk::commitment_config::CommitmentConfig::confirmed();
+        match self.client.signature_state(signature, commitment).await {
+            Ok(SignatureState::Confirmed) => true,
+            Ok(SignatureState::Pending) => false,
+            Ok(SignatureState::Failed(e)) => {
+                tracing::warn!("Sell {} failed on-chain: {}", signature, e);
+                false
             }
-            // Check stop loss
-            else if self.should_stop_loss(&position) {
-                self.execute_sell(&position, 100.0).await?;
+            Err(e) => {
+                tracing::warn!("Failed to check sell {}: {}", signature, e);
+                false
             }
         }
-
-        Ok(())
     }
 
-    /// Simulate a buy for testing
-    async fn simulate_buy(&self, analysis: &TokenAnalysis) -> Result<(), Box<dyn std::error::Error>> {
-        tracing::info!(
-            "[SIMULATION] Buy executed for {}: {} SOL",
-            analysis.token.symbol,
-            self.config.buy_amount_sol
-        );
+    /// Check if a sell should wait one cycle because the quote is far worse than expected
+    async fn should_defer_sell(
+        &self,
+        position: &Position,
+        amount_to_sell: u64,
+        quoted_output: u64,
+        reason: SellReason,
+    ) -> bool {
+        let guard_pct = match self.config.load().sell_price_guard_pct {
+            Some(guard_pct) => guard_pct,
+            None => return false,
+        };
+
+        let key = position.token_address.to_string();
+        // A sell is only ever deferred once; the next cycle goes through
+        if reason.is_hard_exit() || self.deferred_sells.write().await.remove(&key) {
+            return false;
+        }
 
-        self.update_buy_tracking().await;
-        self.create_position(analysis, "sim_".to_string() + &Utc::now().timestamp().to_string()).await;
+        let expected = amount_to_sell as f64 * position.current_price * crate::config::constants::LAMPORTS_PER_SOL as f64;
+        if (quoted_output as f64) < expected * (1.0 - guard_pct / 100.0) {
+            self.deferred_sells.write().await.insert(key);
+            return true;
+        }
 
-        Ok(())
+        false
     }
 
-    /// Simulate a sell for testing
-    async fn simulate_sell(&self, position: &Position, percentage: f64) -> Result<(), Box<dyn std::error::Error>> {
-        tracing::info!(
-            "[SIMULATION] Sell executed for {}: {}%",
-            position.token_symbol,
-            percentage
-        );
+    /// Lamports a sell of `amount` tokens is quoted at, from the AMM pool once the curve completes
+    async fn sell_quote(
+        &self,
+        position: &Position,
+        curve: &BondingCurveInfo,
+        amount: u64,
+    ) -> Result<u64, Box<dyn std::error::Error>> {
+        if !curve.complete {
+            return Ok(bonding_curve::sell_quote(curve, amount));
+        }
+        let (base_reserves, quote_reserves) = self.transaction_builder.amm_pool_reserves(&position.token_address).await?;
+        Ok(bonding_curve::amm_sell_quote(base_reserves, quote_reserves, amount))
+    }
 
-        let amount_to_sell = ((position.amount as f64) * percentage / 100.0) as u64;
-        self.update_position_after_sell(position, amount_to_sell).await;
+    /// Build and send a sell transaction, into the AMM pool once the curve completes
+    async fn send_sell(
+        &self,
+        position: &Position,
+        curve: &BondingCurveInfo,
+        amount_to_sell: u64,
+        quoted_output: u64,
+        min_sol_output: u64,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let transaction = if curve.complete {
+            self.transaction_builder.build_amm_sell_transaction(
+                &position.token_address,
+                &curve.creator,
+                amount_to_sell,
+                min_sol_output,
+            ).await?
+        } else {
+            self.transaction_builder.build_sell_transaction(
+                &position.token_address,
+                &position.bonding_curve_address,
+                &curve.creator,
+                amount_to_sell,
+                min_sol_output,
+            ).await?
+        };
 
-        Ok(())
+        let pending = PendingTransaction {
+            signature: String::new(),
+            intent: TradeType::Sell,
+            token_address: position.token_address,
+            bonding_curve_address: position.bonding_curve_address,
+            amount_sol: quoted_output as f64 / crate::config::constants::LAMPORTS_PER_SOL as f64,
+            token_amount: amount_to_sell,
+            price: position.current_price,
+            source: position.source.clone(),
+            sent_at: Utc::now(),
+            batch_legs: Vec::new(),
+        };
+        self.send_tracked(transaction, pending).await
     }
 
-    /// Check if buying is allowed
-    async fn can_buy(&self) -> bool {
-        // Check cooldown
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_millis() as u64;
-
-        let last_buy = *self.last_buy_time.read().await;
-        if now - last_buy < self.config.trading_cooldown_ms {
-            return false;
+    /// Sign and send a transaction, persisting it as pending until its trade is applied
+    ///
+    /// A failed send clears the record; after a successful one the caller clears it with
+    /// [`Self::clear_pending_transaction`] once the position reflects the trade, so a crash
+    /// at any point in between lets the next startup find out whether it landed.
+    async fn send_tracked(
+        &self,
+        mut transaction: solana_sdk::transaction::Transaction,
+        mut pending: PendingTransaction,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        if !self.config.load().track_pending_transactions {
+            return self.client.send_transaction(transaction).await;
         }
 
-        // Check daily trade limit
-        self.reset_daily_trades_if_needed().await;
-        if *self.daily_trades.read().await >= self.config.max_trades_per_hour * 24 {
-            return false;
+        pending.signature = self.client.sign_transaction(&mut transaction)?;
+        if let Err(e) = self.storage.save_pending_transaction(&pending) {
+            tracing::warn!("Failed to persist pending transaction {}: {}", pending.signature, e);
         }
 
-        // Check if another buy is in progress
-        if *self.is_buying.read().await {
-            return false;
+        let result = self.client.send_signed_transaction(&transaction).await;
+        if result.is_err() {
+            self.clear_pending_transaction(&pending.signature);
         }
+        result
+    }
 
-        true
+    /// Drop the pending record of a transaction whose outcome has been applied
+    fn clear_pending_transaction(&self, signature: &str) {
+        if !self.config.load().track_pending_transactions {
+            return;
+        }
+        if let Err(e) = self.storage.remove_pending_transaction(signature) {
+            tracing::warn!("Failed to clear pending transaction {}: {}", signature, e);
+        }
     }
 
-    /// Update buy tracking
-    async fn update_buy_tracking(&self) {
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_millis() as u64;
+    /// Sell a held position whose bonding curve has completed
+    pub async fn handle_migration(&self, event: &MigrationEvent) -> Result<(), Box<dyn std::error::Error>> {
+        if !self.config.load().sell_on_migration {
+            return Ok(());
+        }
+
+        let position = match self.positions.read().await.get(&event.token_address.to_string()) {
+            Some(pos) if matches!(pos.status, PositionStatus::Open | PositionStatus::Partial) => pos.clone(),
+            _ => return Ok(()),
+        };
 
-        *self.last_buy_time.write().await = now;
-        *self.daily_trades.write().await += 1;
+        // A completed curve no longer fills; wait for the AMM pool the sell routes through
+        if !self.config.load().simulation_mode && !self.wait_for_amm_pool(&event.token_address).await {
+            tracing::error!("AMM pool of {} not created, leaving position to the exit checks", position.token_symbol);
+            return Ok(());
+        }
+
+        tracing::info!("Bonding curve completed for held {}, selling into its AMM pool", position.token_symbol);
+        let position = self.positions.read().await
+            .get(&event.token_address.to_string())
+            .cloned()
+            .unwrap_or(position);
+        self.execute_sell(&position, 100.0, SellReason::Migration).await
     }
 
-    /// Reset daily trades if needed
-    async fn reset_daily_trades_if_needed(&self) {
-        let today = Utc::now().format("%Y-%m-%d").to_string();
-        if today != *self.last_reset_date.read().await {
-            *self.daily_trades.write().await = 0;
-            *self.last_reset_date.write().await = today;
+    /// Poll until a migrating mint's AMM pool exists, giving up after a bounded wait
+    async fn wait_for_amm_pool(&self, token_address: &Pubkey) -> bool {
+        for attempt in 0..MIGRATION_POOL_ATTEMPTS {
+            if attempt > 0 {
+                tokio::time::sleep(MIGRATION_POOL_RETRY_INTERVAL).await;
+            }
+            let exists = self.transaction_builder.amm_pool_exists(token_address).await
+                .map_err(|e| e.to_string());
+            match exists {
+                Ok(true) => return true,
+                Ok(false) => {}
+                Err(e) => tracing::warn!("Failed to check AMM pool of {}: {}", token_address, e),
+            }
         }
+        false
     }
 
-    /// Create a new position after successful buy
-    async fn create_position(&self, analysis: &TokenAnalysis, signature: String) {
-        let position = Position {
-            token_address: analysis.token.address,
-            token_symbol: analysis.token.symbol.clone(),
-            amount: (self.config.buy_amount_sol * 1_000_000.0) as u64, // Approximate
-            entry_price: analysis.metrics.price,
-            current_price: analysis.metrics.price,
-            pnl: 0.0,
-            pnl_percentage: 0.0,
-            opened_at: Utc::now(),
-            last_updated: Utc::now(),
-            take_profit_price: Some(analysis.metrics.price * (1.0 + self.config.take_profit_percentage / 100.0)),
-            stop_loss_price: Some(analysis.metrics.price * (1.0 - self.config.stop_loss_percentage / 100.0)),
-            trailing_stop_price: None,
-            status: PositionStatus::Open,
+    /// Sell every open position at once at the emergency slippage
+    ///
+    /// Skips the minimum hold and price guards; returns how many sells were attempted.
+    pub async fn panic_sell(&self) -> usize {
+        // Wait out any sell already in flight, then hold the lock so no other sell
+        // can send for a position this one is dumping
+        loop {
+            {
+                let mut is_selling = self.is_selling.write().await;
+                if !*is_selling {
+                    *is_selling = true;
+                    break;
+                }
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+
+        let positions: Vec<Position> = self.positions.read().await
+            .values()
+            .filter(|pos| matches!(pos.status, PositionStatus::Open | PositionStatus::Partial))
+            .cloned()
+            .collect();
+
+        tracing::warn!("PANIC SELL: dumping {} positions", positions.len());
+        let sells = positions.iter().map(|position| async move {
+            if let Err(e) = self.execute_sell(position, 100.0, SellReason::Panic).await {
+                tracing::error!("Panic sell of {} failed: {}", position.token_symbol, e);
+            }
+        });
+        futures::future::join_all(sells).await;
+        *self.is_selling.write().await = false;
+
+        positions.len()
+    }
+
+    /// Exit a held position when its creator or a configured insider sells
+    pub async fn handle_sell_activity(&self, event: &SellActivityEvent) -> Result<(), Box<dyn std::error::Error>> {
+        let config = self.config.load_full();
+        if !config.sell_on_creator_dump {
+            return Ok(());
+        }
+
+        let position = match self.positions.read().await.get(&event.token_address.to_string()) {
+            Some(pos) if matches!(pos.status, PositionStatus::Open | PositionStatus::Partial) => pos.clone(),
+            _ => return Ok(()),
         };
 
-        self.positions.write().await.insert(
-            position.token_address.to_string(),
-            position
+        let is_insider = config.insider_wallets.iter().any(|wallet| *wallet == event.seller.to_string());
+        if event.seller != position.creator && !is_insider {
+            return Ok(());
+        }
+
+        tracing::warn!(
+            "{} {} sold {} tokens of held {}, exiting",
+            if is_insider { "Insider" } else { "Creator" },
+            event.seller,
+            event.token_amount,
+            position.token_symbol
         );
+        self.execute_sell(&position, 100.0, SellReason::CreatorDumping).await
     }
 
-    /// Update position after sell
-    async fn update_position_after_sell(&self, position: &Position, amount_sold: u64) {
-        let mut positions = self.positions.write().await;
-        if let Some(pos) = positions.get_mut(&position.token_address.to_string()) {
-            pos.amount -= amount_sold;
-            if pos.amount == 0 {
-                pos.status = PositionStatus::Closed;
-            } else {
-                pos.status = PositionStatus::Partial;
+    /// Re-run safety analysis on open positions and exit any that degraded
+    ///
+    /// A failure on one position is logged and the rest are still re-checked.
+    pub async fn recheck_position_safety(&self) {
+        let positions: Vec<Position> = self.positions.read().await
+            .values()
+            .filter(|pos| matches!(pos.status, PositionStatus::Open | PositionStatus::Partial))
+            .cloned()
+            .collect();
+
+        for position in positions {
+            if let Err(e) = self.recheck_one_position(&position).await.map_err(|e| e.to_string()) {
+                tracing::warn!("Safety re-check failed for {}: {}", position.token_symbol, e);
             }
-            pos.last_updated = Utc::now();
         }
     }
 
-    /// Update position price (simplified)
-    async fn update_position_price(&self, position: &Position) -> Result<(), Box<dyn std::error::Error>> {
-        // In a real implementation, you'd fetch current price from the blockchain
-        // For now, simulate small price movements
-        let price_change = (rand::random::<f64>() - 0.5) * 0.1; // -5% to +5%
-        let new_price = position.current_price * (1.0 + price_change);
+    async fn recheck_one_position(&self, position: &Position) -> Result<(), Box<dyn std::error::Error>> {
+        let analysis = token_analyzer::analyze_token(
+            &position.token_address,
+            &position.bonding_curve_address,
+            &self.client,
+        ).await?;
 
-        let mut positions = self.positions.write().await;
-        if let Some(pos) = positions.get_mut(&position.token_address.to_string()) {
-            pos.current_price = new_price;
-            pos.pnl = (new_price - pos.entry_price) * pos.amount as f64;
-            pos.pnl_percentage = ((new_price - pos.entry_price) / pos.entry_price) * 100.0;
-            pos.last_updated = Utc::now();
+        if analysis.safety.checks.is_honeypot
+            || analysis.safety.score < self.config.load().safety_recheck_min_score
+        {
+            tracing::warn!(
+                "Safety degraded for {} (score {}): {}",
+                position.token_symbol,
+                analysis.safety.score,
+                analysis.safety.details.issues.join(", ")
+            );
+            self.execute_sell(position, 100.0, SellReason::SafetyDegraded).await?;
         }
 
         Ok(())
     }
 
-    /// Check if position should take profit
-    fn should_take_profit(&self, position: &Position) -> bool {
-        if let Some(tp_price) = position.take_profit_price {
-            return position.current_price >= tp_price;
+    /// Check automated sells for take-profit/stop-loss
+    pub async fn check_automated_sells(&self) -> Result<(), Box<dyn std::error::Error>> {
+        self.settle_buy_tracking().await;
+        self.renew_held_claims().await;
+
+        let positions: Vec<Position> = self.positions.read().await.values().cloned().collect();
+        let mut batch = Vec::new();
+
+        // End the traces of tokens no longer held
+        self.token_spans.write().await.retain(|key, _| {
+            positions.iter().any(|pos| pos.token_address.to_string() == *key && pos.status != PositionStatus::Closed)
+        });
+
+        for position in positions {
+            // One position's RPC failure must not skip the exit checks of the others
+            if let Err(e) = self.check_position_exits(&position, &mut batch).await.map_err(|e| e.to_string()) {
+                tracing::warn!("Exit checks for {} failed: {}", position.token_symbol, e);
+            }
         }
-        false
+
+        self.execute_batch_sell(batch).await
     }
 
-    /// Check if position should stop loss
-    fn should_stop_loss(&self, position: &Position) -> bool {
-        if let Some(sl_price) = position.stop_loss_price {
-            return position.current_price <= sl_price;
+    /// Refresh one position's price and volume, then sell or queue it if an exit fires
+    async fn check_position_exits(
+        &self,
+        position: &Position,
+        batch: &mut Vec<(Position, SellReason)>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        // Poll the price unless a curve subscription pushed it recently
+        let pushed_at = self.curve_pushed_at.read().await.get(&position.token_address.to_string()).copied();
+        if pushed_at.is_none_or(|at| now_millis().saturating_sub(at) > CURVE_PUSH_FRESH_MS) {
+            self.update_position_price(position).await?;
         }
-        false
+        let samples_volume = {
+            let config = self.config.load();
+            config.volume_drop_exit_fraction.is_some() || config.exit_rules.iter().any(|rule| rule.uses_volume())
+        };
+        if samples_volume {
+            self.update_position_volume(position).await?;
+        }
+
+        // Re-read the position so exit checks see the refreshed price
+        let position = match self.positions.read().await.get(&position.token_address.to_string()) {
+            Some(pos) => pos.clone(),
+            None => return Ok(()),
+        };
+
+        if position.status == PositionStatus::Closed {
+            return Ok(());
+        }
+
+        // Tokens may not be credited yet; only confirmed positions are sellable
+        if position.status == PositionStatus::Pending {
+            self.confirm_position(&position).await;
+            return Ok(());
+        }
+
+        // Hard loss backstop ignores the minimum hold
+        if self.exceeds_max_loss(&position) {
+            self.execute_sell(&position, 100.0, SellReason::MaxLoss).await?;
+            return Ok(());
+        }
+
+        // Custom exit rules take precedence over the fixed exits
+        if self.apply_exit_rules(&position).await? {
+            return Ok(());
+        }
+
+        // Take the initial SOL back off the table and ride the rest risk-free
+        if self.recover_cost(&position).await? {
+            return Ok(());
+        }
+
+        let config = self.config.load_full();
+        let held = self.held_min_duration(&position);
+
+        // Check take profit
+        if self.should_take_profit(&position) {
+            if held || !config.min_hold_applies_to_take_profit {
+                self.exit_or_batch(&position, SellReason::TakeProfit, batch).await?;
+            }
+        }
+        // Check trailing stop once armed, otherwise the fixed stop loss
+        else if self.should_trailing_stop(&position) || self.should_stop_loss(&position) {
+            let reason = if position.trailing_stop_price.is_some() {
+                SellReason::TrailingStop
+            } else {
+                SellReason::StopLoss
+            };
+            if held {
+                self.exit_or_batch(&position, reason, batch).await?;
+            } else {
+                tracing::debug!("Stop deferred for {}: minimum hold not reached", position.token_symbol);
+            }
+        }
+        // Exit fading pumps once volume collapses from its peak
+        else if held && self.should_exit_on_volume_drop(&position) {
+            self.exit_or_batch(&position, SellReason::VolumeDrop, batch).await?;
+        }
+        Ok(())
     }
 
-    /// Stop the trader
-    pub async fn stop(&self) -> Result<(), Box<dyn std::error::Error>> {
-        *self.is_buying.write().await = false;
+    /// Sell a whole position now, or queue it for one batched transaction when batching is on
+    async fn exit_or_batch(
+        &self,
+        position: &Position,
+        reason: SellReason,
+        batch: &mut Vec<(Position, SellReason)>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let config = self.config.load();
+        if config.batch_sells && !config.simulation_mode {
+            batch.push((position.clone(), reason));
+            return Ok(());
+        }
+        self.execute_sell(position, 100.0, reason).await
+    }
+
+    /// Sell queued full exits, packing up to `max_sells_per_batch` into each transaction
+    ///
+    /// Each leg gets the same quote, dust and price guard checks as a single sell. A batch
+    /// that would not fit in one transaction is sold one position at a time instead.
+    async fn execute_batch_sell(&self, exits: Vec<(Position, SellReason)>) -> Result<(), Box<dyn std::error::Error>> {
+        if exits.len() < 2 {
+            for (position, reason) in exits {
+                self.execute_sell(&position, 100.0, reason).await?;
+            }
+            return Ok(());
+        }
+
+        if *self.is_selling.read().await {
+            tracing::warn!("Sell already in progress");
+            return Ok(());
+        }
+
+        let config = self.config.load_full();
+        let mut legs = Vec::new();
+        for (position, reason) in exits {
+            let curve = match token_analyzer::fetch_bonding_curve(&position.bonding_curve_address, &self.client).await {
+                Ok(curve) => curve,
+                Err(e) => {
+                    tracing::warn!("Failed to quote batched sell of {}: {}", position.token_symbol, e);
+                    continue;
+                }
+            };
+
+            // Batches only carry curve sells; graduated tokens sell into their AMM pool alone
+            if curve.complete {
+                if let Err(e) = self.execute_sell(&position, 100.0, reason).await.map_err(|e| e.to_string()) {
+                    tracing::warn!("AMM sell of {} failed: {}", position.token_symbol, e);
+                }
+                continue;
+            }
+            let quoted_output = bonding_curve::sell_quote(&curve, position.amount);
+
+            let net_proceeds = self.net_sell_proceeds_sol(quoted_output);
+            if skips_dust_sell(reason, net_proceeds, config.min_sell_proceeds_sol) {
+                tracing::info!(
+                    "Skipping sell of {} ({:?}): net proceeds {:.6} SOL below minimum",
+                    position.token_symbol,
+                    reason,
+                    net_proceeds
+                );
+                continue;
+            }
+
+            if self.should_defer_sell(&position, position.amount, quoted_output, reason).await {
+                tracing::warn!(
+                    "Deferring sell of {}: quote {} lamports far below expected",
+                    position.token_symbol,
+                    quoted_output
+                );
+                continue;
+            }
+
+            let leg = SellLeg {
+                token_address: position.token_address,
+                bonding_curve_address: position.bonding_curve_address,
+                creator: curve.creator,
+                amount: position.amount,
+                min_sol_output: bonding_curve::min_output_with_slippage(quoted_output, config.slippage_bps()),
+            };
+            legs.push((position, reason, leg, quoted_output));
+        }
+
+        for chunk in legs.chunks(config.max_sells_per_batch.max(1)) {
+            self.send_batch_sell(chunk).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Send one batch of already quoted sells, falling back to individual sells if it does not fit
+    async fn send_batch_sell(
+        &self,
+        chunk: &[(Position, SellReason, SellLeg, u64)],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let decision_config = self.config.load_full();
+        let sell_legs: Vec<SellLeg> = chunk.iter().map(|(_, _, leg, _)| leg.clone()).collect();
+        let transaction = match chunk.len() {
+            1 => None,
+            _ => self.transaction_builder.build_batch_sell_transaction(&sell_legs).await?,
+        };
+        let transaction = match transaction {
+            Some(transaction) => transaction,
+            None => {
+                if chunk.len() > 1 {
+                    tracing::info!("Batch of {} sells exceeds transaction limits, selling individually", chunk.len());
+                }
+                for (position, reason, _, _) in chunk {
+                    self.execute_sell(position, 100.0, *reason).await?;
+                }
+                return Ok(());
+            }
+        };
+
+        let symbols: Vec<&str> = chunk.iter().map(|(position, _, _, _)| position.token_symbol.as_str()).collect();
+        tracing::info!("Executing batched sell of {} positions: {}", chunk.len(), symbols.join(", "));
+
+        // One span for the batch, linked to the trace of every token in it
+        let span = tracing::info_span!("batch_sell", legs = chunk.len());
+        {
+            let token_spans = self.token_spans.read().await;
+            for (position, _, _, _) in chunk {
+                if let Some(id) = token_spans.get(&position.token_address.to_string()).and_then(|span| span.id()) {
+                    span.follows_from(id);
+                }
+            }
+        }
+
+        // One pending record covers every leg, so a crash mid-send still resolves them all
+        let (first, _, first_leg, first_quote) = &chunk[0];
+        let pending = PendingTransaction {
+            signature: String::new(),
+            intent: TradeType::Sell,
+            token_address: first.token_address,
+            bonding_curve_address: first.bonding_curve_address,
+            amount_sol: *first_quote as f64 / crate::config::constants::LAMPORTS_PER_SOL as f64,
+            token_amount: first_leg.amount,
+            price: first.current_price,
+            source: first.source.clone(),
+            sent_at: Utc::now(),
+            batch_legs: chunk[1..].iter().map(|(position, _, leg, quoted_output)| PendingLeg {
+                token_address: position.token_address,
+                amount_sol: *quoted_output as f64 / crate::config::constants::LAMPORTS_PER_SOL as f64,
+                token_amount: leg.amount,
+                price: position.current_price,
+                source: position.source.clone(),
+            }).collect(),
+        };
+
+        if !self.claim_selling().await {
+            tracing::warn!("Sell already in progress, dropping batched sell of {}", symbols.join(", "));
+            return Ok(());
+        }
+        let result = self.send_tracked(transaction, pending).instrument(span).await
+            .map_err(|e| e.to_string());
         *self.is_selling.write().await = false;
-        tracing::info!("Trader stopped");
+
+        let signature = match result {
+            Ok(signature) if self.sell_proceeds_confirmed(&signature).await => signature,
+            failed => {
+                match &failed {
+                    Ok(signature) => {
+                        tracing::error!("Batched sell not confirmed, selling {} positions individually: {}", chunk.len(), signature);
+                        self.clear_pending_transaction(signature);
+                    }
+                    Err(e) => tracing::error!("Batched sell failed, selling {} positions individually: {}", chunk.len(), e),
+                }
+                self.sell_individually_after_failed_batch(chunk).await;
+                return Ok(());
+            }
+        };
+
+        for (position, _, leg, quoted_output) in chunk {
+            let sol_received = *quoted_output as f64 / crate::config::constants::LAMPORTS_PER_SOL as f64;
+            self.record_trade(
+                &signature,
+                position.token_address,
+                TradeType::Sell,
+                sol_received,
+                position.current_price,
+                &position.source,
+                &decision_config,
+            );
+            self.update_position_after_sell(position, leg.amount, sol_received).await;
+
+            if let Err(e) = self.reconcile_position_amount(&position.token_address).await {
+                tracing::warn!("Post-sell balance check failed for {}: {}", position.token_symbol, e);
+            }
+        }
+        self.clear_pending_transaction(&signature);
+        tracing::info!("Batched sell executed successfully: {}", signature);
+
         Ok(())
     }
 
-    /// Get trader status
-    pub async fn status(&self) -> serde_json::Value {
-        let positions_count = self.positions.read().await.len();
-        let is_buying = *self.is_buying.read().await;
-        let is_selling = *self.is_selling.read().await;
+    /// Sell what is still held of each leg of a batch that did not go through
+    ///
+    /// The batch may have landed after all, so holdings are checked first; a leg
+    /// whose balance cannot be read is left for the next exit cycle.
+    async fn sell_individually_after_failed_batch(&self, chunk: &[(Position, SellReason, SellLeg, u64)]) {
+        for (position, reason, _, _) in chunk {
+            if let Err(e) = self.reconcile_position_amount(&position.token_address).await {
+                tracing::warn!("Post-sell balance check failed for {}, retrying next cycle: {}", position.token_symbol, e);
+                continue;
+            }
 
-        serde_json::json!({
-            "is_buying": is_buying,
-            "is_selling": is_selling,
-            "active_positions": positions_count,
-            "daily_trades": *self.daily_trades.read().await,
-        })
+            let current = self.positions.read().await.get(&position.token_address.to_string()).cloned();
+            let current = match current {
+                Some(current) if current.status != PositionStatus::Closed && current.amount > 0 => current,
+                _ => continue,
+            };
+            if let Err(e) = self.execute_sell(&current, 100.0, *reason).await {
+                tracing::error!("Fallback sell of {} failed: {}", current.token_symbol, e);
+            }
+        }
+    }
+
+    /// Fire the first matching exit rule not yet applied to the position
+    ///
+    /// Returns true if a sell was executed.
+    async fn apply_exit_rules(&self, position: &Position) -> Result<bool, Box<dyn std::error::Error>> {
+        let config = self.config.load_full();
+        let rule = match config.exit_rules.iter().find(|rule| {
+            !position.fired_exit_rules.contains(&rule.key()) && rule.matches(position)
+        }) {
+            Some(rule) => rule,
+            None => return Ok(false),
+        };
+
+        tracing::info!("Exit rule '{}' fired for {}", rule, position.token_symbol);
+        let sold = match rule.action {
+            ExitAction::Sell(percentage) => {
+                self.execute_sell(position, percentage, SellReason::ExitRule).await?;
+                // Leave the rule unfired so a sell that did not go through is retried
+                if !self.sold_since(position).await {
+                    tracing::warn!("Exit rule sell for {} did not go through, will retry", position.token_symbol);
+                    return Ok(false);
+                }
+                true
+            }
+            ExitAction::MoveStop(_) => false,
+        };
+
+        let mut positions = self.positions.write().await;
+        if let Some(pos) = positions.get_mut(&position.token_address.to_string()) {
+            pos.fired_exit_rules.push(rule.key());
+            if let ExitAction::MoveStop(pnl_pct) = rule.action {
+                pos.stop_loss_price = Some(pos.entry_price * (1.0 + pnl_pct / 100.0));
+            }
+            self.save_position(pos);
+        }
+        Ok(sold)
+    }
+
+    /// Whether the held amount fell since `position` was read, meaning a sell went through
+    async fn sold_since(&self, position: &Position) -> bool {
+        self.positions.read().await
+            .get(&position.token_address.to_string())
+            .is_none_or(|pos| pos.amount < position.amount || pos.status == PositionStatus::Closed)
+    }
+
+    /// Sell enough of a position to recover its cost once it is up `recover_cost_at_pct`
+    ///
+    /// Fires once per position and moves the stop on the remainder to the entry price.
+    /// Returns true if a sell was executed.
+    async fn recover_cost(&self, position: &Position) -> Result<bool, Box<dyn std::error::Error>> {
+        let trigger_pct = match self.config.load().recover_cost_at_pct {
+            Some(trigger_pct) => trigger_pct,
+            None => return Ok(false),
+        };
+        if position.cost_recovered || position.pnl_percentage < trigger_pct {
+            return Ok(false);
+        }
+        let percentage = match recover_cost_percentage(position) {
+            Some(percentage) => percentage,
+            None => return Ok(false),
+        };
+
+        tracing::info!(
+            "Recovering cost of {}: selling {:.1}% and moving stop to breakeven",
+            position.token_symbol,
+            percentage
+        );
+        self.execute_sell(position, percentage, SellReason::RecoverCost).await?;
+        // Stay armed so a sell that did not go through is retried
+        if !self.sold_since(position).await {
+            tracing::warn!("Cost recovery sell for {} did not go through, will retry", position.token_symbol);
+            return Ok(false);
+        }
+
+        let mut positions = self.positions.write().await;
+        if let Some(pos) = positions.get_mut(&position.token_address.to_string()) {
+            pos.cost_recovered = true;
+            pos.stop_loss_price = Some(pos.entry_price);
+            self.save_position(pos);
+        }
+        Ok(true)
+    }
+
+    /// Simulate a buy for testing
+    async fn simulate_buy(
+        &self,
+        analysis: &TokenAnalysis,
+        amount_sol: f64,
+        source: &str,
+        config: &BotConfig,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        tracing::info!(
+            "[SIMULATION] Buy executed for {}: {} SOL",
+            analysis.token.symbol,
+            amount_sol
+        );
+
+        let signature = simulated_signature();
+        self.start_buy_tracking(&signature, true).await;
+        self.settle_buy_tracking().await;
+        self.record_trade(&signature, analysis.token.address, TradeType::Buy, amount_sol, analysis.metrics.price, source, config);
+        let lamports = (amount_sol * crate::config::constants::LAMPORTS_PER_SOL as f64) as u64;
+        let amount = bonding_curve::buy_quote(&analysis.bonding_curve, lamports);
+        self.create_position(analysis, amount_sol, amount, signature, source).await;
+
+        Ok(())
+    }
+
+    /// Simulate a sell for testing
+    async fn simulate_sell(
+        &self,
+        position: &Position,
+        amount: SellAmount,
+        reason: SellReason,
+        config: &BotConfig,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        tracing::info!(
+            "[SIMULATION] Sell executed for {} ({:?}): {}",
+            position.token_symbol,
+            reason,
+            amount
+        );
+
+        let amount_to_sell = match amount {
+            SellAmount::Percentage(percentage) => ((position.amount as f64) * percentage / 100.0) as u64,
+            SellAmount::SolTarget(_) => {
+                let curve = token_analyzer::fetch_bonding_curve(&position.bonding_curve_address, &self.client).await?;
+                tokens_to_sell(amount, position.amount, &curve)
+            }
+        };
+        self.record_trade(
+            &simulated_signature(),
+            position.token_address,
+            TradeType::Sell,
+            amount_to_sell as f64 * position.current_price,
+            position.current_price,
+            &position.source,
+            config,
+        );
+        self.update_position_after_sell(position, amount_to_sell, amount_to_sell as f64 * position.current_price).await;
+
+        Ok(())
+    }
+
+    /// Check if buying is allowed
+    async fn can_buy(&self) -> bool {
+        // Check cooldown
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+
+        let last_buy = *self.last_buy_time.read().await;
+        if now - last_buy < self.config.load().trading_cooldown_ms {
+            return false;
+        }
+
+        // Check daily trade limit
+        self.reset_daily_trades_if_needed().await;
+        if *self.daily_trades.read().await >= self.config.load().max_trades_per_hour * 24 {
+            return false;
+        }
+
+        // Check the daily loss circuit breaker
+        if self.trip_daily_loss_breaker().await {
+            return false;
+        }
+
+        // Check if another buy is in progress
+        if *self.is_buying.read().await {
+            return false;
+        }
+
+        true
+    }
+
+    /// SOL a buy of the analyzed token is sized at, before the exposure cap
+    ///
+    /// A fixed token quantity is costed from the analyzed curve (`None` when it cannot fill);
+    /// a SOL size follows the balance tiers and trades smaller through a losing streak.
+    pub async fn planned_buy_amount_sol(&self, analysis: &TokenAnalysis) -> Option<f64> {
+        if self.config.load().buy_mode == BuyMode::ByTokens {
+            return self.token_buy_cost_sol(analysis);
+        }
+        Some(self.streak_sized(self.base_buy_amount().await).await)
+    }
+
+    /// Configured buy size, following the cached wallet balance when tiers are set
+    async fn base_buy_amount(&self) -> f64 {
+        let config = self.config.load_full();
+        if !config.sizes_by_balance() {
+            return config.buy_amount_sol;
+        }
+
+        match self.cached_wallet_balance().await {
+            Ok(balance) => config.buy_amount_for_balance(balance),
+            Err(e) => {
+                tracing::warn!("Failed to fetch balance for sizing, using BUY_AMOUNT_SOL: {}", e);
+                config.buy_amount_sol
+            }
+        }
+    }
+
+    /// Quoted SOL cost of `buy_amount_tokens` on the analyzed curve
+    fn token_buy_cost_sol(&self, analysis: &TokenAnalysis) -> Option<f64> {
+        let tokens = self.config.load().buy_amount_tokens;
+        bonding_curve::buy_cost_quote(&analysis.bonding_curve, tokens)
+            .map(|lamports| lamports as f64 / crate::config::constants::LAMPORTS_PER_SOL as f64)
+    }
+
+    /// Feed a send outcome into the adaptive fee and persist what was learned
+    fn learn_priority_fee(&self, fee_paid: u64, landed: bool) {
+        let config = self.config.load();
+        if !config.adaptive_priority_fee {
+            return;
+        }
+
+        let learned = self.fee_tuner.record(fee_paid, landed, config.adaptive_fee_min_lamports, config.max_priority_fee_lamports);
+        tracing::debug!("Send at {} {}; learned priority fee now {}", fee_paid, if landed { "landed" } else { "dropped" }, learned);
+        if let Err(e) = self.storage.save_state(fee_tuner::LEARNED_FEE_KEY, &serde_json::json!(learned)) {
+            tracing::warn!("Failed to persist learned priority fee: {}", e);
+        }
+    }
+
+    /// Scale a buy size down for the current loss streak, keeping it at least `min_buy_amount_sol`
+    async fn streak_sized(&self, amount_sol: f64) -> f64 {
+        let config = self.config.load();
+        let losses = *self.loss_streak.read().await;
+        let scale = config.loss_streak_scale(losses);
+        if scale >= 1.0 {
+            return amount_sol;
+        }
+
+        let sized = (amount_sol * scale).max(config.min_buy_amount_sol);
+        tracing::debug!("{} consecutive losses: sizing {:.4} SOL instead of {:.4} SOL", losses, sized, amount_sol);
+        sized
+    }
+
+    /// Extend or reset the loss streak from a closed position's round trip
+    async fn record_outcome(&self, position: &Position) {
+        let trades = match self.storage.load_trades(Some(&position.token_address)) {
+            Ok(trades) => trades,
+            Err(e) => {
+                tracing::warn!("Failed to load trades for {}: {}", position.token_symbol, e);
+                return;
+            }
+        };
+        let net_sol: f64 = trades
+            .iter()
+            .filter(|trade| trade.timestamp >= position.opened_at)
+            .map(|trade| match trade.trade_type {
+                TradeType::Buy => -trade.total_value,
+                TradeType::Sell => trade.total_value,
+            })
+            .sum();
+
+        let mut loss_streak = self.loss_streak.write().await;
+        if net_sol < 0.0 {
+            *loss_streak += 1;
+        } else {
+            *loss_streak = 0;
+        }
+    }
+
+    /// Wallet balance, refetched at most every `BALANCE_CACHE_TTL`, or the virtual balance of a paper trader
+    async fn cached_wallet_balance(&self) -> Result<f64, Box<dyn std::error::Error>> {
+        if let Some(starting_balance_sol) = self.virtual_starting_balance_sol {
+            return Ok(virtual_balance(starting_balance_sol, &self.trade_history(None)?));
+        }
+
+        if let Some((balance, fetched_at)) = *self.cached_balance.read().await {
+            if fetched_at.elapsed() < BALANCE_CACHE_TTL {
+                return Ok(balance);
+            }
+        }
+
+        let balance = self.client.get_wallet_balance().await?;
+        *self.cached_balance.write().await = Some((balance, std::time::Instant::now()));
+        Ok(balance)
+    }
+
+    /// Size a buy so the mint's total cost basis stays within the exposure cap
+    async fn size_for_token_exposure(&self, analysis: &TokenAnalysis, amount_sol: f64) -> Option<f64> {
+        let cap = match self.config.load().max_exposure_per_token_sol {
+            Some(cap) => cap,
+            None => return Some(amount_sol),
+        };
+
+        let current_exposure = self.positions.read().await
+            .get(&analysis.token.address.to_string())
+            .filter(|pos| pos.status != PositionStatus::Closed)
+            .map(|pos| pos.cost_basis_sol)
+            .unwrap_or(0.0);
+
+        let remaining = cap - current_exposure;
+        if remaining <= 0.0 {
+            return None;
+        }
+
+        // Downsize rather than reject when only part of the buy fits
+        Some(amount_sol.min(remaining))
+    }
+
+    /// Check the pending buy keeps total open exposure within the cap
+    async fn within_total_exposure(&self, amount_sol: f64) -> bool {
+        let cap = match self.config.load().max_total_exposure_sol {
+            Some(cap) => cap,
+            None => return true,
+        };
+
+        self.total_exposure().await + amount_sol <= cap
+    }
+
+    /// Summed cost basis of all open positions
+    async fn total_exposure(&self) -> f64 {
+        self.positions.read().await
+            .values()
+            .filter(|pos| pos.status != PositionStatus::Closed)
+            .map(|pos| pos.cost_basis_sol)
+            .sum()
+    }
+
+    /// Start tracking a sent buy
+    ///
+    /// The cooldown starts and, for a `counted` buy, a daily-trade slot is taken at once,
+    /// so buys sent while earlier ones confirm cannot overrun the limit;
+    /// [`Self::settle_buy_tracking`] gives both back if the buy fails.
+    async fn start_buy_tracking(&self, signature: &str, counted: bool) {
+        let now = now_millis();
+        let previous_last_buy = std::mem::replace(&mut *self.last_buy_time.write().await, now);
+
+        let counted_on = if counted {
+            self.reset_daily_trades_if_needed().await;
+            *self.daily_trades.write().await += 1;
+            Some(Utc::now().date_naive())
+        } else {
+            None
+        };
+
+        self.unconfirmed_buys.write().await.insert(
+            signature.to_string(),
+            UnconfirmedBuy { previous_last_buy, started_at: now, counted_on },
+        );
+    }
+
+    /// Stop tracking buys that landed and roll back the slot and cooldown of failed ones
+    pub async fn settle_buy_tracking(&self) {
+        let config = self.config.load_full();
+        let commitment = solana_sdk::commitment_config::CommitmentConfig { commitment: config.buy_count_commitment };
+        let signatures: Vec<(String, UnconfirmedBuy)> = self.unconfirmed_buys.read().await
+            .iter()
+            .map(|(signature, buy)| (signature.clone(), *buy))
+            .collect();
+
+        for (signature, buy) in signatures {
+            // Simulated buys never land on-chain
+            let state = if config.simulation_mode {
+                Ok(SignatureState::Confirmed)
+            } else {
+                self.client.signature_state(&signature, commitment).await.map_err(|e| e.to_string())
+            };
+            self.settle_buy(&signature, buy, state).await;
+        }
+    }
+
+    /// Apply one tracked buy's state
+    ///
+    /// An RPC error says nothing about the buy, so it keeps its slot until the
+    /// cluster answers; only a failed or expired buy is refunded.
+    async fn settle_buy(&self, signature: &str, buy: UnconfirmedBuy, state: Result<SignatureState, String>) {
+        let expired = now_millis().saturating_sub(buy.started_at) >= UNCONFIRMED_BUY_EXPIRY_MS;
+        match state {
+            Ok(SignatureState::Confirmed) => {
+                self.unconfirmed_buys.write().await.remove(signature);
+            }
+            Ok(SignatureState::Pending) if !expired => {}
+            Ok(SignatureState::Pending) | Ok(SignatureState::Failed(_)) => {
+                self.unconfirmed_buys.write().await.remove(signature);
+                self.reset_daily_trades_if_needed().await;
+                if buy.counted_on == Some(Utc::now().date_naive()) {
+                    let mut daily_trades = self.daily_trades.write().await;
+                    *daily_trades = daily_trades.saturating_sub(1);
+                }
+                // Restore the cooldown unless a later buy has already restarted it
+                let mut last_buy_time = self.last_buy_time.write().await;
+                if *last_buy_time == buy.started_at {
+                    *last_buy_time = buy.previous_last_buy;
+                }
+                tracing::info!("Buy {} did not land; not counting it toward trade limits", signature);
+            }
+            Err(e) => {
+                tracing::warn!("Could not check buy {}, keeping it counted: {}", signature, e);
+            }
+        }
+    }
+
+    /// Reset daily trades and the daily loss breaker at the UTC day boundary
+    async fn reset_daily_trades_if_needed(&self) {
+        let today = Utc::now().format("%Y-%m-%d").to_string();
+        if today != *self.last_reset_date.read().await {
+            *self.daily_trades.write().await = 0;
+            *self.daily_realized_pnl.write().await = 0.0;
+            *self.last_reset_date.write().await = today;
+
+            // Only lift a halt the breaker itself imposed
+            let was_tripped = std::mem::take(&mut *self.daily_loss_tripped.write().await);
+            self.save_daily_loss_state().await;
+            if was_tripped && self.halt_reason().await.as_deref() == Some(DAILY_LOSS_HALT_REASON) {
+                self.resume_buys().await;
+            }
+        }
+    }
+
+    /// Persist today's realized PnL and breaker state
+    async fn save_daily_loss_state(&self) {
+        let state = serde_json::json!({
+            "date": *self.last_reset_date.read().await,
+            "realized_pnl_sol": *self.daily_realized_pnl.read().await,
+            "tripped": *self.daily_loss_tripped.read().await,
+        });
+        if let Err(e) = self.storage.save_state(DAILY_LOSS_STATE_KEY, &state) {
+            tracing::warn!("Failed to persist daily loss state: {}", e);
+        }
+    }
+
+    /// Today's realized PnL plus the unrealized PnL of open positions, in SOL
+    async fn daily_pnl(&self) -> f64 {
+        let unrealized: f64 = self.positions.read().await
+            .values()
+            .filter(|pos| pos.status != PositionStatus::Closed)
+            .map(|pos| pos.pnl)
+            .sum();
+        *self.daily_realized_pnl.read().await + unrealized
+    }
+
+    /// Halt buys and alert once today's losses pass `max_daily_loss_sol`
+    ///
+    /// Trips at most once per UTC day, so a manual `resume_buys` holds until the next day.
+    /// Returns whether it tripped now.
+    async fn trip_daily_loss_breaker(&self) -> bool {
+        let max_loss = match self.config.load().max_daily_loss_sol {
+            Some(max_loss) => max_loss,
+            None => return false,
+        };
+        if *self.daily_loss_tripped.read().await {
+            return false;
+        }
+
+        let pnl = self.daily_pnl().await;
+        if pnl > -max_loss {
+            return false;
+        }
+
+        *self.daily_loss_tripped.write().await = true;
+        self.save_daily_loss_state().await;
+        self.halt_buys(DAILY_LOSS_HALT_REASON).await;
+        let _ = self.notifier.send(&format!(
+            "Daily loss limit hit: PnL {:.4} SOL against a {:.4} SOL cap; buys halted until the next UTC day",
+            pnl,
+            max_loss
+        )).await;
+        true
+    }
+
+    /// Create a new position after successful buy of `amount` tokens, or add to an existing one
+    async fn create_position(&self, analysis: &TokenAnalysis, amount_sol: f64, amount: u64, signature: String, source: &str) {
+        let config = self.config.load_full();
+        let mut positions = self.positions.write().await;
+
+        if let Some(pos) = positions.get_mut(&analysis.token.address.to_string()) {
+            if pos.status != PositionStatus::Closed {
+                // Scale into the existing position with a weighted entry price
+                let total_amount = pos.amount + amount;
+                pos.entry_price = (pos.entry_price * pos.amount as f64 + analysis.metrics.price * amount as f64)
+                    / total_amount.max(1) as f64;
+                if config.lot_accounting.is_some() {
+                    // Lot accounting was enabled after the position opened: its holding so far is one lot
+                    if pos.lots.is_empty() && pos.amount > 0 {
+                        pos.lots.push(Lot {
+                            signature: pos.entry_signature.clone(),
+                            amount: pos.amount,
+                            cost_sol: pos.cost_basis_sol,
+                            opened_at: pos.opened_at,
+                        });
+                    }
+                    pos.lots.push(Lot { signature: signature.clone(), amount, cost_sol: amount_sol, opened_at: Utc::now() });
+                }
+                pos.amount = total_amount;
+                pos.cost_basis_sol += amount_sol;
+                pos.last_updated = Utc::now();
+                tracing::debug!("Scaled into {} ({})", pos.token_symbol, signature);
+                self.save_position(pos);
+                return;
+            }
+        }
+
+        // Real buys wait for finalization before becoming sellable
+        let status = if config.require_buy_finalization && !config.simulation_mode {
+            PositionStatus::Pending
+        } else {
+            PositionStatus::Open
+        };
+
+        // Exit levels are tailored to the token's stage at entry, with room scaled by safety
+        let (take_profit_bps, stop_loss_bps) = config.exit_levels_bps(analysis.metrics.market_cap);
+        let stop_loss_bps = config.safety_scaled_stop_loss_bps(analysis.safety.score).unwrap_or(stop_loss_bps);
+
+        let position = Position {
+            token_address: analysis.token.address,
+            bonding_curve_address: analysis.bonding_curve.address,
+            creator: analysis.bonding_curve.creator,
+            token_symbol: analysis.token.symbol.clone(),
+            entry_signature: signature.clone(),
+            amount,
+            cost_basis_sol: amount_sol,
+            entry_price: analysis.metrics.price,
+            current_price: analysis.metrics.price,
+            highest_price: analysis.metrics.price,
+            pnl: 0.0,
+            pnl_percentage: 0.0,
+            opened_at: Utc::now(),
+            last_updated: Utc::now(),
+            take_profit_price: Some(analysis.metrics.price * (10_000 + take_profit_bps) as f64 / 10_000.0),
+            stop_loss_price: Some(analysis.metrics.price * 10_000u64.saturating_sub(stop_loss_bps) as f64 / 10_000.0),
+            trailing_stop_price: None,
+            last_sol_reserves: analysis.bonding_curve.real_sol_reserves,
+            recent_volume_sol: 0.0,
+            peak_volume_sol: 0.0,
+            volume_samples: Vec::new(),
+            fired_exit_rules: Vec::new(),
+            cost_recovered: false,
+            lots: match config.lot_accounting {
+                Some(_) => vec![Lot { signature: signature.clone(), amount, cost_sol: amount_sol, opened_at: Utc::now() }],
+                None => Vec::new(),
+            },
+            realized_pnl_sol: 0.0,
+            source: source.to_string(),
+            tag: config.position_tag(source),
+            safety_issues: analysis.safety.details.issues.clone(),
+            status,
+        };
+
+        self.save_position(&position);
+        positions.insert(
+            position.token_address.to_string(),
+            position
+        );
+    }
+
+    /// Promote a pending position to open once its buy is finalized
+    async fn confirm_position(&self, position: &Position) {
+        let commitment = solana_sdk::commitment_config::CommitmentConfig::finalized();
+        let state = self.client.signature_state(&position.entry_signature, commitment).await
+            .map_err(|e| e.to_string());
+        self.settle_entry(position, state).await;
+    }
+
+    /// Open or close a pending position from its entry transaction's state
+    ///
+    /// Only a failed transaction, or one still unseen after its blockhash expired,
+    /// closes the position; an RPC error leaves it pending for the next check.
+    async fn settle_entry(&self, position: &Position, state: Result<SignatureState, String>) {
+        let landed = match state {
+            Ok(SignatureState::Confirmed) => true,
+            Ok(SignatureState::Failed(e)) => {
+                tracing::warn!("Buy for {} failed on-chain: {}", position.token_symbol, e);
+                false
+            }
+            Ok(SignatureState::Pending) => {
+                let age_ms = (Utc::now() - position.opened_at).num_milliseconds().max(0) as u64;
+                if age_ms < UNCONFIRMED_BUY_EXPIRY_MS {
+                    return;
+                }
+                tracing::warn!("Buy for {} was never seen on-chain, treating it as dropped", position.token_symbol);
+                false
+            }
+            Err(e) => {
+                tracing::warn!("Could not check the buy for {}, retrying later: {}", position.token_symbol, e);
+                return;
+            }
+        };
+
+        let mut positions = self.positions.write().await;
+        if let Some(pos) = positions.get_mut(&position.token_address.to_string()) {
+            // Only a pending position needs opening; any live one is closed by a failed entry
+            match (landed, pos.status) {
+                (_, PositionStatus::Closed) | (true, PositionStatus::Open | PositionStatus::Partial) => return,
+                _ => {}
+            }
+            if landed {
+                pos.status = PositionStatus::Open;
+                tracing::info!("Buy confirmed for {}", pos.token_symbol);
+            } else {
+                // The buy never executed, so nothing is held
+                pos.amount = 0;
+                pos.cost_basis_sol = 0.0;
+                pos.status = PositionStatus::Closed;
+            }
+            pos.last_updated = Utc::now();
+            self.save_position(pos);
+        }
+    }
+
+    /// Persist a position, logging rather than failing on storage errors
+    fn save_position(&self, position: &Position) {
+        // A closed position joins the per-source aggregates
+        if position.status == PositionStatus::Closed {
+            *self.source_stats.lock().unwrap() = None;
+        }
+        if let Err(e) = self.storage.save_position(position) {
+            tracing::warn!("Failed to persist position {}: {}", position.token_symbol, e);
+        }
+    }
+
+    /// Record a completed trade in the trade history
+    ///
+    /// `config` is the snapshot the trade was decided under, which a hot reload since may have replaced.
+    #[allow(clippy::too_many_arguments)]
+    fn record_trade(
+        &self,
+        signature: &str,
+        token_address: Pubkey,
+        trade_type: TradeType,
+        total_value: f64,
+        price: f64,
+        source: &str,
+        config: &BotConfig,
+    ) {
+        let fee_rate = crate::config::constants::PUMP_FUN_FEE_BPS as f64 / 10_000.0;
+        let config_version = config.version().to_string();
+
+        // Audit the thresholds that were in effect for this decision
+        tracing::info!(
+            config_version = %config_version,
+            slippage_bps = config.slippage_bps(),
+            take_profit_pct = config.take_profit_percentage,
+            stop_loss_pct = config.stop_loss_percentage,
+            buy_amount_sol = config.buy_amount_sol,
+            "{:?} {} recorded for {}",
+            trade_type,
+            signature,
+            token_address
+        );
+
+        let trade = TradeResult {
+            signature: signature.to_string(),
+            token_address,
+            trade_type,
+            amount: if price > 0.0 { (total_value / price) as u64 } else { 0 },
+            price,
+            total_value,
+            fee: total_value * fee_rate,
+            timestamp: Utc::now(),
+            success: true,
+            error: None,
+            source: source.to_string(),
+            config_version,
+        };
+
+        if let Err(e) = self.storage.record_trade(&trade) {
+            tracing::warn!("Failed to record trade {}: {}", signature, e);
+        }
+        *self.source_stats.lock().unwrap() = None;
+    }
+
+    /// Per-source realized PnL and win counts over closed positions in the trade history
+    ///
+    /// A token counts as a win when its sells returned more SOL than its buys cost. The
+    /// aggregates are cached until the next trade or closed position.
+    pub async fn source_stats(&self) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+        if let Some(stats) = self.source_stats.lock().unwrap().clone() {
+            return Ok(stats);
+        }
+
+        // Open positions have not realized their PnL yet
+        let open: std::collections::HashSet<Pubkey> = self.positions.read().await
+            .values()
+            .filter(|pos| pos.status != PositionStatus::Closed)
+            .map(|pos| pos.token_address)
+            .collect();
+
+        let mut net_by_token: HashMap<(String, Pubkey), f64> = HashMap::new();
+        for trade in self.storage.load_trades(None)? {
+            if open.contains(&trade.token_address) {
+                continue;
+            }
+            let signed_value = match trade.trade_type {
+                TradeType::Buy => -trade.total_value,
+                TradeType::Sell => trade.total_value,
+            };
+            *net_by_token.entry((trade.source, trade.token_address)).or_insert(0.0) += signed_value;
+        }
+
+        let mut stats: HashMap<String, (u32, u32, f64)> = HashMap::new();
+        for ((source, _), net) in net_by_token {
+            let entry = stats.entry(source).or_insert((0, 0, 0.0));
+            entry.0 += 1;
+            if net > 0.0 {
+                entry.1 += 1;
+            }
+            entry.2 += net;
+        }
+
+        let stats: serde_json::Value = stats
+            .into_iter()
+            .map(|(source, (tokens, wins, pnl_sol))| {
+                (source, serde_json::json!({ "tokens": tokens, "wins": wins, "pnl_sol": pnl_sol }))
+            })
+            .collect::<serde_json::Map<_, _>>()
+            .into();
+        *self.source_stats.lock().unwrap() = Some(stats.clone());
+        Ok(stats)
+    }
+
+    /// Snapshot of every tracked position
+    pub async fn positions(&self) -> Vec<Position> {
+        self.positions.read().await.values().cloned().collect()
+    }
+
+    /// Get trade history from storage
+    pub fn trade_history(&self, token_address: Option<&Pubkey>) -> Result<Vec<TradeResult>, Box<dyn std::error::Error>> {
+        self.storage.load_trades(token_address)
+    }
+
+    /// Warn when a buy cost materially more SOL than intended
+    async fn verify_sol_spent(&self, balance_before: f64, amount_sol: f64, reserve: f64) {
+        let balance_after = match self.client.get_wallet_balance().await {
+            Ok(balance) => balance,
+            Err(e) => {
+                tracing::warn!("Failed to fetch post-buy SOL balance: {}", e);
+                return;
+            }
+        };
+
+        let config = self.config.load();
+        let max_expected = amount_sol * (10_000 + config.slippage_bps()) as f64 / 10_000.0 + reserve;
+        let spent = balance_before - balance_after;
+        if spent > max_expected {
+            tracing::warn!("Buy spent {:.6} SOL, more than the expected maximum {:.6} SOL", spent, max_expected);
+        }
+    }
+
+    /// Reconcile persisted positions with the wallet's on-chain holdings
+    ///
+    /// Positions whose tokens are gone are closed, mismatched amounts are corrected, and
+    /// untracked Pump.fun holdings are adopted when configured.
+    pub async fn reconcile_on_startup(&self) -> Result<ReconciliationReport, Box<dyn std::error::Error>> {
+        let mut holdings = self.client.get_token_holdings().await?;
+        let tolerance = self.config.load().balance_tolerance_pct / 100.0;
+        let mut report = ReconciliationReport::default();
+
+        {
+            let mut positions = self.positions.write().await;
+            for pos in positions.values_mut() {
+                if pos.status == PositionStatus::Closed {
+                    continue;
+                }
+
+                let actual = holdings.remove(&pos.token_address).unwrap_or(0);
+                if actual == 0 {
+                    // Sold elsewhere, or the buy never landed
+                    pos.amount = 0;
+                    pos.cost_basis_sol = 0.0;
+                    pos.status = PositionStatus::Closed;
+                    report.closed.push(pos.token_address);
+                } else if (actual as f64 - pos.amount as f64).abs() > pos.amount.max(1) as f64 * tolerance {
+                    pos.cost_basis_sol *= actual as f64 / pos.amount.max(1) as f64;
+                    pos.amount = actual;
+                    report.adjusted.push(pos.token_address);
+                } else {
+                    report.matched.push(pos.token_address);
+                    continue;
+                }
+                pos.last_updated = Utc::now();
+                self.save_position(pos);
+            }
+        }
+
+        let adopt = self.config.load().adopt_untracked_holdings;
+        for (mint, amount) in holdings {
+            if mint == spl_token::native_mint::id() {
+                continue;
+            }
+            if adopt && self.adopt_holding(&mint, amount).await.is_ok() {
+                report.adopted.push(mint);
+            } else {
+                report.untracked.push(mint);
+            }
+        }
+
+        tracing::info!(
+            "Startup reconciliation: {} matched, {} adjusted, {} closed, {} adopted, {} untracked",
+            report.matched.len(),
+            report.adjusted.len(),
+            report.closed.len(),
+            report.adopted.len(),
+            report.untracked.len()
+        );
+        Ok(report)
+    }
+
+    /// Resolve transactions left pending by a previous run
+    ///
+    /// Landed buys open positions at their recorded cost and landed sells are recorded and
+    /// reconciled; failed transactions, and unknown ones whose blockhash has expired, are
+    /// dropped. Unknown ones that may still land, and landed ones whose position could not
+    /// be updated yet, are kept, and their signatures returned so they can be resolved again
+    /// later. With `only` set, just those signatures are looked at, leaving the live run's
+    /// own records alone.
+    pub async fn resolve_pending_transactions(
+        &self,
+        only: Option<&[String]>,
+    ) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let pending: Vec<PendingTransaction> = self.storage.load_pending_transactions()?
+            .into_iter()
+            .filter(|tx| only.is_none_or(|only| only.contains(&tx.signature)))
+            .collect();
+        if pending.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let signatures: Vec<String> = pending.iter().map(|p| p.signature.clone()).collect();
+        let outcomes = self.client.get_signature_outcomes(&signatures).await?;
+        Ok(self.apply_pending_outcomes(&pending, outcomes).await)
+    }
+
+    /// Apply the on-chain outcomes of pending transactions, returning the ones still waiting
+    ///
+    /// A record is only cleared once its outcome is fully applied, so a crash or a failed
+    /// update leaves it for the next attempt.
+    async fn apply_pending_outcomes(&self, pending: &[PendingTransaction], outcomes: Vec<Option<bool>>) -> Vec<String> {
+        let mut waiting = Vec::new();
+        // The config the trades were sent under did not survive the restart; stamp the current one
+        let config = self.config.load_full();
+
+        // The previous run may have applied a trade and died before clearing its record,
+        // so trades already in the history are not recorded again
+        for (tx, outcome) in pending.iter().zip(outcomes) {
+            match (outcome, &tx.intent) {
+                (Some(true), TradeType::Buy) => {
+                    tracing::info!("Pending buy {} for {} landed; opening position", tx.signature, tx.token_address);
+                    if !self.trade_recorded(&tx.token_address, &tx.signature) {
+                        self.record_trade(&tx.signature, tx.token_address, TradeType::Buy, tx.amount_sol, tx.price, &tx.source, &config);
+                    }
+                    if let Err(e) = self.adopt_pending_buy(tx).await.map_err(|e| e.to_string()) {
+                        tracing::warn!("Failed to open position for pending buy {}, will retry: {}", tx.signature, e);
+                        waiting.push(tx.signature.clone());
+                        continue;
+                    }
+                }
+                (Some(true), TradeType::Sell) => {
+                    let first = PendingLeg {
+                        token_address: tx.token_address,
+                        amount_sol: tx.amount_sol,
+                        token_amount: tx.token_amount,
+                        price: tx.price,
+                        source: tx.source.clone(),
+                    };
+                    let mut reconciled = true;
+                    for leg in std::iter::once(&first).chain(&tx.batch_legs) {
+                        tracing::info!("Pending sell {} for {} landed", tx.signature, leg.token_address);
+                        if !self.trade_recorded(&leg.token_address, &tx.signature) {
+                            self.record_trade(&tx.signature, leg.token_address, TradeType::Sell, leg.amount_sol, leg.price, &leg.source, &config);
+                        }
+                        if let Err(e) = self.reconcile_position_amount(&leg.token_address).await.map_err(|e| e.to_string()) {
+                            tracing::warn!("Failed to reconcile {} after pending sell, will retry: {}", leg.token_address, e);
+                            reconciled = false;
+                        }
+                    }
+                    if !reconciled {
+                        waiting.push(tx.signature.clone());
+                        continue;
+                    }
+                }
+                (Some(false), _) => {
+                    tracing::info!("Pending {:?} {} failed on-chain; dropping", tx.intent, tx.signature);
+                }
+                (None, _) if pending_may_still_land(tx) => {
+                    tracing::info!("Pending {:?} {} not seen yet; checking again later", tx.intent, tx.signature);
+                    waiting.push(tx.signature.clone());
+                    continue;
+                }
+                (None, _) => {
+                    tracing::warn!("Pending {:?} {} is unknown to the cluster; dropping", tx.intent, tx.signature);
+                }
+            }
+
+            if let Err(e) = self.storage.remove_pending_transaction(&tx.signature) {
+                tracing::warn!("Failed to clear pending transaction {}: {}", tx.signature, e);
+            }
+        }
+
+        waiting
+    }
+
+    /// Whether the trade history already has `signature` for a token
+    fn trade_recorded(&self, token_address: &Pubkey, signature: &str) -> bool {
+        self.trade_history(Some(token_address))
+            .map(|trades| trades.iter().any(|trade| trade.signature == signature))
+            .unwrap_or(false)
+    }
+
+    /// Open a position for a buy that landed while the bot was down
+    async fn adopt_pending_buy(&self, tx: &PendingTransaction) -> Result<(), Box<dyn std::error::Error>> {
+        if self.positions.read().await.contains_key(&tx.token_address.to_string()) {
+            return self.reconcile_position_amount(&tx.token_address).await;
+        }
+
+        let amount = self.client.get_token_balance(&tx.token_address).await?;
+        if amount == 0 {
+            return Err("no tokens held".into());
+        }
+        self.adopt_holding(&tx.token_address, amount).await?;
+
+        // Carry the recorded cost over the holding's current-price valuation
+        let mut positions = self.positions.write().await;
+        if let Some(pos) = positions.get_mut(&tx.token_address.to_string()) {
+            pos.cost_basis_sol = tx.amount_sol;
+            pos.entry_price = tx.price;
+            pos.entry_signature = tx.signature.clone();
+            pos.source = tx.source.clone();
+            pos.tag = self.config.load().position_tag(&tx.source);
+            pos.opened_at = tx.sent_at;
+            self.save_position(pos);
+        }
+        Ok(())
+    }
+
+    /// Start tracking a Pump.fun token held by the wallet, valued at the current curve price
+    async fn adopt_holding(&self, mint: &Pubkey, amount: u64) -> Result<(), Box<dyn std::error::Error>> {
+        let (bonding_curve_address, _) = crate::utils::pump_fun_pdas::bonding_curve(mint);
+        let analysis = token_analyzer::analyze_token(mint, &bonding_curve_address, &self.client).await?;
+        let price = analysis.metrics.price;
+        let config = self.config.load_full();
+        let (take_profit_bps, stop_loss_bps) = config.exit_levels_bps(analysis.metrics.market_cap);
+
+        let position = Position {
+            token_address: *mint,
+            bonding_curve_address,
+            creator: analysis.bonding_curve.creator,
+            token_symbol: analysis.token.symbol.clone(),
+            entry_signature: "adopted".to_string(),
+            amount,
+            // The original cost is unknown; treat the holding as bought at the current price
+            cost_basis_sol: bonding_curve::whole_tokens(amount) * bonding_curve::price_per_token(price),
+            entry_price: price,
+            current_price: price,
+            highest_price: price,
+            pnl: 0.0,
+            pnl_percentage: 0.0,
+            opened_at: Utc::now(),
+            last_updated: Utc::now(),
+            take_profit_price: Some(price * (10_000 + take_profit_bps) as f64 / 10_000.0),
+            stop_loss_price: Some(price * 10_000u64.saturating_sub(stop_loss_bps) as f64 / 10_000.0),
+            trailing_stop_price: None,
+            last_sol_reserves: analysis.bonding_curve.real_sol_reserves,
+            recent_volume_sol: 0.0,
+            peak_volume_sol: 0.0,
+            volume_samples: Vec::new(),
+            fired_exit_rules: Vec::new(),
+            cost_recovered: false,
+            lots: Vec::new(),
+            realized_pnl_sol: 0.0,
+            source: "adopted".to_string(),
+            tag: config.position_tag("adopted"),
+            safety_issues: analysis.safety.details.issues.clone(),
+            status: PositionStatus::Open,
+        };
+
+        tracing::info!("Adopted untracked holding of {} ({} tokens)", position.token_symbol, amount);
+        self.save_position(&position);
+        self.positions.write().await.insert(mint.to_string(), position);
+        Ok(())
+    }
+
+    /// Reconcile a position's token amount with the on-chain balance
+    async fn reconcile_position_amount(&self, token_address: &Pubkey) -> Result<(), Box<dyn std::error::Error>> {
+        let actual = self.client.get_token_balance(token_address).await?;
+        let tolerance = self.config.load().balance_tolerance_pct / 100.0;
+
+        let mut positions = self.positions.write().await;
+        if let Some(pos) = positions.get_mut(&token_address.to_string()) {
+            let expected = pos.amount;
+            let difference = (actual as f64 - expected as f64).abs();
+            if difference > expected.max(1) as f64 * tolerance {
+                tracing::warn!(
+                    "Token balance for {} is {} but position expects {}; reconciling",
+                    pos.token_symbol,
+                    actual,
+                    expected
+                );
+                pos.amount = actual;
+                if actual == 0 {
+                    pos.status = PositionStatus::Closed;
+                }
+                pos.last_updated = Utc::now();
+                self.save_position(pos);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Update position after sell, realizing PnL on `proceeds_sol`
+    async fn update_position_after_sell(&self, position: &Position, amount_sold: u64, proceeds_sol: f64) {
+        let lot_policy = self.config.load().lot_accounting;
+        let mut closed = None;
+        let mut realized = 0.0;
+        let mut positions = self.positions.write().await;
+        if let Some(pos) = positions.get_mut(&position.token_address.to_string()) {
+            let realized_before = pos.realized_pnl_sol;
+            match lot_policy {
+                Some(policy) if !pos.lots.is_empty() => {
+                    // Release the cost of exactly the lots the sell consumed
+                    pos.realized_pnl_sol += consume_lots(&mut pos.lots, amount_sold, proceeds_sol, policy);
+                    pos.cost_basis_sol = pos.lots.iter().map(|lot| lot.cost_sol).sum();
+                }
+                _ => {
+                    // Release exposure in proportion to the tokens sold
+                    if pos.amount > 0 {
+                        let remaining_fraction = pos.amount.saturating_sub(amount_sold) as f64 / pos.amount as f64;
+                        let released = pos.cost_basis_sol * (1.0 - remaining_fraction);
+                        pos.realized_pnl_sol += proceeds_sol - released;
+                        pos.cost_basis_sol -= released;
+                    }
+                }
+            }
+            realized = pos.realized_pnl_sol - realized_before;
+            pos.amount -= amount_sold;
+            if pos.amount == 0 {
+                pos.status = PositionStatus::Closed;
+                closed = Some(pos.clone());
+            } else {
+                pos.status = PositionStatus::Partial;
+            }
+            pos.last_updated = Utc::now();
+            self.save_position(pos);
+        }
+        drop(positions);
+
+        // Roll the day over first so a sell just past midnight counts toward the new day
+        self.reset_daily_trades_if_needed().await;
+        *self.daily_realized_pnl.write().await += realized;
+        self.save_daily_loss_state().await;
+
+        if let Some(position) = closed {
+            self.record_outcome(&position).await;
+        }
+    }
+
+    /// Update position price from its bonding curve, or its AMM pool once the curve completes
+    async fn update_position_price(&self, position: &Position) -> Result<(), Box<dyn std::error::Error>> {
+        let curve = token_analyzer::fetch_bonding_curve(&position.bonding_curve_address, &self.client).await?;
+        // Until the pool is readable the final curve price is the best available
+        let pool_reserves = match curve.complete {
+            true => self.transaction_builder.amm_pool_reserves(&position.token_address).await.ok(),
+            false => None,
+        };
+        let new_price = match pool_reserves {
+            Some((base_reserves, quote_reserves)) => bonding_curve::amm_spot_price(base_reserves, quote_reserves),
+            None => bonding_curve::spot_price(&curve),
+        };
+
+        let mut positions = self.positions.write().await;
+        if let Some(pos) = positions.get_mut(&position.token_address.to_string()) {
+            // Positions saved before the creator was tracked learn it from the curve
+            if pos.creator == Pubkey::default() {
+                pos.creator = curve.creator;
+            }
+            self.set_position_price(pos, new_price);
+        }
+
+        Ok(())
+    }
+
+    /// Apply a pushed bonding curve state to the position holding its mint
+    pub async fn apply_curve_update(&self, event: &CurveUpdateEvent) {
+        let key = event.curve.token_address.to_string();
+        let new_price = bonding_curve::spot_price(&event.curve);
+
+        let mut positions = self.positions.write().await;
+        if let Some(pos) = positions.get_mut(&key) {
+            if pos.status == PositionStatus::Closed {
+                return;
+            }
+            self.set_position_price(pos, new_price);
+            self.curve_pushed_at.write().await.insert(key, now_millis());
+        }
+    }
+
+    /// Run the exit checks of the position a pushed curve update belongs to
+    ///
+    /// Lets stops and targets fire on the push instead of waiting for the next poll.
+    pub async fn check_pushed_exits(&self, event: &CurveUpdateEvent) {
+        let position = match self.positions.read().await.get(&event.curve.token_address.to_string()) {
+            Some(pos) if pos.status != PositionStatus::Closed => pos.clone(),
+            _ => return,
+        };
+
+        let mut batch = Vec::new();
+        if let Err(e) = self.check_position_exits(&position, &mut batch).await.map_err(|e| e.to_string()) {
+            tracing::warn!("Exit checks for {} failed: {}", position.token_symbol, e);
+        }
+        if let Err(e) = self.execute_batch_sell(batch).await.map_err(|e| e.to_string()) {
+            tracing::warn!("Batched sell of {} failed: {}", position.token_symbol, e);
+        }
+    }
+
+    /// Forget pushed prices so every position is polled until pushes resume
+    pub async fn expire_curve_pushes(&self) {
+        self.curve_pushed_at.write().await.clear();
+    }
+
+    /// Bonding curves of open positions, paired with their mints
+    pub async fn open_curves(&self) -> Vec<(Pubkey, Pubkey)> {
+        self.positions
+            .read()
+            .await
+            .values()
+            .filter(|pos| pos.status != PositionStatus::Closed)
+            .map(|pos| (pos.bonding_curve_address, pos.token_address))
+            .collect()
+    }
+
+    /// Set a position's current price, updating PnL and the trailing stop
+    fn set_position_price(&self, pos: &mut Position, new_price: f64) {
+        pos.current_price = new_price;
+        pos.pnl = (new_price - pos.entry_price) * pos.amount as f64;
+        pos.pnl_percentage = ((new_price - pos.entry_price) / pos.entry_price) * 100.0;
+        pos.highest_price = pos.highest_price.max(new_price);
+        pos.last_updated = Utc::now();
+
+        // Arm or ratchet the trailing stop once past the activation threshold
+        let config = self.config.load();
+        if pos.trailing_stop_price.is_some() || pos.pnl_percentage >= config.trailing_activation_pct {
+            let trail_price = pos.highest_price * (1.0 - config.trailing_stop_loss_percentage / 100.0);
+            pos.trailing_stop_price = Some(pos.trailing_stop_price.map_or(trail_price, |p| p.max(trail_price)));
+        }
+    }
+
+    /// Sample curve activity since the last check into the position's recent volume
+    ///
+    /// Each check adds the change in real SOL reserves as a sample; recent volume is the
+    /// sum of the samples inside `volume_window_secs`, so one quiet check does not zero it.
+    async fn update_position_volume(&self, position: &Position) -> Result<(), Box<dyn std::error::Error>> {
+        let curve = token_analyzer::fetch_bonding_curve(&position.bonding_curve_address, &self.client).await?;
+        let window = chrono::Duration::seconds(self.config.load().volume_window_secs as i64);
+
+        let mut positions = self.positions.write().await;
+        if let Some(pos) = positions.get_mut(&position.token_address.to_string()) {
+            let delta = curve.real_sol_reserves.abs_diff(pos.last_sol_reserves);
+            Self::record_volume_sample(pos, delta as f64 / crate::config::constants::LAMPORTS_PER_SOL as f64, window);
+            pos.last_sol_reserves = curve.real_sol_reserves;
+        }
+
+        Ok(())
+    }
+
+    /// Add a volume sample, drop samples older than the window and refresh the totals
+    ///
+    /// The peak only counts full windows, so a young position's first samples are not
+    /// compared against a window that was still filling.
+    fn record_volume_sample(position: &mut Position, sol: f64, window: chrono::Duration) {
+        let now = Utc::now();
+        position.volume_samples.push(crate::types::VolumeSample { at: now, sol });
+        position.volume_samples.retain(|sample| now - sample.at <= window);
+        position.recent_volume_sol = position.volume_samples.iter().map(|sample| sample.sol).sum();
+        if now - position.opened_at >= window {
+            position.peak_volume_sol = position.peak_volume_sol.max(position.recent_volume_sol);
+        }
+    }
+
+    /// Check if a position's recent volume has collapsed relative to its peak
+    fn should_exit_on_volume_drop(&self, position: &Position) -> bool {
+        match self.config.load().volume_drop_exit_fraction {
+            Some(fraction) if position.peak_volume_sol > 0.0 => {
+                position.recent_volume_sol < position.peak_volume_sol * fraction
+            }
+            _ => false,
+        }
+    }
+
+    /// Check if position should take profit
+    fn should_take_profit(&self, position: &Position) -> bool {
+        if let Some(tp_price) = position.take_profit_price {
+            if position.current_price < tp_price {
+                return false;
+            }
+
+            // Suppress nominal take-profits that would lose money to fees
+            let net_profit = self.estimated_net_profit_sol(position);
+            if net_profit < self.config.load().min_net_profit_sol {
+                tracing::debug!(
+                    "Take-profit suppressed for {}: net profit {:.6} SOL below floor",
+                    position.token_symbol,
+                    net_profit
+                );
+                return false;
+            }
+            return true;
+        }
+        false
+    }
+
+    /// Estimate realizable profit after protocol and network fees for a full exit
+    fn estimated_net_profit_sol(&self, position: &Position) -> f64 {
+        use crate::config::constants::*;
+
+        if position.entry_price <= 0.0 {
+            return 0.0;
+        }
+
+        let fee_rate = PUMP_FUN_FEE_BPS as f64 / 10_000.0;
+        let gross_value = position.cost_basis_sol * (position.current_price / position.entry_price);
+        let protocol_fees = (position.cost_basis_sol + gross_value) * fee_rate;
+
+        // Round trip: one buy and one sell, each paying base and priority fees
+        let priority_fee_lamports = self.config.load().priority_fee_lamports * SWAP_COMPUTE_UNIT_LIMIT as u64 / 1_000_000;
+        let network_fees = 2.0 * (BASE_FEE_LAMPORTS + priority_fee_lamports) as f64 / LAMPORTS_PER_SOL as f64;
+
+        gross_value - position.cost_basis_sol - protocol_fees - network_fees
+    }
+
+    /// Quoted sell output in SOL less the base and priority fee of sending the sell
+    fn net_sell_proceeds_sol(&self, quoted_output_lamports: u64) -> f64 {
+        use crate::config::constants::*;
+
+        let priority_fee_lamports = self.config.load().priority_fee_lamports * SWAP_COMPUTE_UNIT_LIMIT as u64 / 1_000_000;
+        (quoted_output_lamports as f64 - (BASE_FEE_LAMPORTS + priority_fee_lamports) as f64) / LAMPORTS_PER_SOL as f64
+    }
+
+    /// Estimate protocol and network fees for buying and later selling `amount_sol`
+    async fn estimated_round_trip_fees_sol(&self, amount_sol: f64) -> Result<f64, Box<dyn std::error::Error>> {
+        use crate::config::constants::*;
+
+        let protocol_fees = 2.0 * amount_sol * PUMP_FUN_FEE_BPS as f64 / 10_000.0;
+
+        let priority_fee = self.client.get_priority_fee_estimate().await?;
+        let priority_fee_lamports = priority_fee * SWAP_COMPUTE_UNIT_LIMIT as u64 / 1_000_000;
+        let network_fees = 2.0 * (BASE_FEE_LAMPORTS + priority_fee_lamports) as f64 / LAMPORTS_PER_SOL as f64;
+
+        Ok(protocol_fees + network_fees)
+    }
+
+    /// Check if a position has been held for the configured minimum
+    fn held_min_duration(&self, position: &Position) -> bool {
+        let min_hold = chrono::Duration::seconds(self.config.load().min_hold_seconds as i64);
+        Utc::now() - position.opened_at >= min_hold
+    }
+
+    /// Check if a position's unrealized loss exceeds the per-trade maximum
+    fn exceeds_max_loss(&self, position: &Position) -> bool {
+        if position.entry_price <= 0.0 {
+            return false;
+        }
+        let value = position.cost_basis_sol * (position.current_price / position.entry_price);
+        position.cost_basis_sol - value >= self.config.load().max_loss_per_trade_sol
+    }
+
+    /// Check if position should exit on its trailing stop
+    fn should_trailing_stop(&self, position: &Position) -> bool {
+        if let Some(trail_price) = position.trailing_stop_price {
+            return position.current_price <= trail_price;
+        }
+        false
+    }
+
+    /// Check if position should stop loss
+    fn should_stop_loss(&self, position: &Position) -> bool {
+        // The fixed stop only governs until the trailing stop is armed
+        if position.trailing_stop_price.is_some() {
+            return false;
+        }
+        if let Some(sl_price) = position.stop_loss_price {
+            return position.current_price <= sl_price;
+        }
+        false
+    }
+
+    /// Stop the trader
+    pub async fn stop(&self) -> Result<(), Box<dyn std::error::Error>> {
+        *self.is_buying.write().await = false;
+        *self.is_selling.write().await = false;
+        tracing::info!("Trader stopped");
+        Ok(())
+    }
+
+    /// Get trader status
+    pub async fn status(&self) -> serde_json::Value {
+        self.status_with_tag(None).await
+    }
+
+    /// Balance of each configured wallet and the SOL available for buys
+    ///
+    /// The trading wallet is flagged low when it cannot fund another buy above the fee
+    /// reserve; the main wallet only refuels it, so it does not count as deployable.
+    pub async fn wallet_status(&self) -> serde_json::Value {
+        let config = self.config.load_full();
+        let mut wallets = Vec::new();
+        let mut deployable_sol = 0.0;
+
+        let trading = self.client.public_key().ok().map(|pubkey| ("trading", pubkey));
+        let main = self.client.main_keypair().map(|keypair| ("main", keypair.pubkey()));
+        for (role, pubkey) in trading.into_iter().chain(main) {
+            let wallet = match self.client.get_balance(&pubkey).await {
+                Ok(balance) if role == "trading" => {
+                    deployable_sol += (balance - config.fee_reserve_sol).max(0.0);
+                    serde_json::json!({
+                        "role": role,
+                        "address": pubkey.to_string(),
+                        "balance_sol": balance,
+                        "low": balance < config.fee_reserve_sol + config.buy_amount_sol,
+                    })
+                }
+                Ok(balance) => serde_json::json!({ "role": role, "address": pubkey.to_string(), "balance_sol": balance }),
+                Err(e) => serde_json::json!({ "role": role, "address": pubkey.to_string(), "error": e.to_string() }),
+            };
+            wallets.push(wallet);
+        }
+
+        serde_json::json!({
+            "wallets": wallets,
+            "deployable_sol": deployable_sol,
+        })
+    }
+
+    /// Get trader status, listing only positions carrying `tag` when given
+    pub async fn status_with_tag(&self, tag: Option<&str>) -> serde_json::Value {
+        let positions_count = self.positions.read().await.len();
+        let mut tags: HashMap<String, usize> = HashMap::new();
+        for pos in self.positions.read().await.values() {
+            *tags.entry(pos.tag.clone()).or_insert(0) += 1;
+        }
+        let positions: Vec<serde_json::Value> = self.positions.read().await
+            .values()
+            .filter(|pos| tag.is_none_or(|tag| pos.tag == tag))
+            .map(|pos| serde_json::json!({
+                "token_address": pos.token_address.to_string(),
+                "token_symbol": pos.token_symbol,
+                "status": pos.status,
+                "pnl_percentage": pos.pnl_percentage,
+                "realized_pnl_sol": pos.realized_pnl_sol,
+                "lots": pos.lots.len(),
+                "source": pos.source,
+                "tag": pos.tag,
+                "safety_issues": pos.safety_issues,
+            }))
+            .collect();
+        let is_buying = *self.is_buying.read().await;
+        let is_selling = *self.is_selling.read().await;
+
+        serde_json::json!({
+            "is_buying": is_buying,
+            "is_selling": is_selling,
+            "halted": *self.halt_reason.read().await,
+            "loss_streak": *self.loss_streak.read().await,
+            "learned_priority_fee": self.fee_tuner.fee(),
+            "active_positions": positions_count,
+            "total_exposure_sol": self.total_exposure().await,
+            "daily_trades": *self.daily_trades.read().await,
+            "daily_pnl_sol": self.daily_pnl().await,
+            "tags": tags,
+            "positions": positions,
+            "sources": self.source_stats().await.unwrap_or_else(|e| serde_json::json!({ "error": e.to_string() })),
+        })
+    }
+}
+
+/// Whether a transaction the cluster has not seen could still land, its blockhash unexpired
+fn pending_may_still_land(tx: &PendingTransaction) -> bool {
+    (Utc::now() - tx.sent_at).num_milliseconds() < UNCONFIRMED_BUY_EXPIRY_MS as i64
+}
+
+/// A signature for a simulated trade, unique across trades and restarts
+fn simulated_signature() -> String {
+    let seq = SIMULATED_SIGNATURE_SEQ.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    format!("sim_{}_{}", Utc::now().timestamp_millis(), seq)
+}
+
+/// Current time in milliseconds since the Unix epoch
+fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
+/// Tokens a sell of `amount` disposes of out of `held`, quoting SOL targets against `curve`
+///
+/// A target the curve cannot pay out sells the whole position.
+fn tokens_to_sell(amount: SellAmount, held: u64, curve: &BondingCurveInfo) -> u64 {
+    match amount {
+        SellAmount::Percentage(percentage) => ((held as f64) * percentage / 100.0) as u64,
+        SellAmount::SolTarget(target_sol) => {
+            let target_lamports = (target_sol * crate::config::constants::LAMPORTS_PER_SOL as f64) as u64;
+            bonding_curve::sell_tokens_for_sol(curve, target_lamports).unwrap_or(held).min(held)
+        }
+    }
+}
+
+/// Whether a sell is too small to be worth its fees
+///
+/// Manual sells and hard exits always go through, so a stop can close a dust position.
+fn skips_dust_sell(reason: SellReason, net_proceeds_sol: f64, min_proceeds_sol: f64) -> bool {
+    reason != SellReason::Manual && !reason.is_hard_exit() && net_proceeds_sol < min_proceeds_sol
+}
+
+/// Check whether a send failed on the Pump.fun slippage check
+fn is_slippage_exceeded_error(error: &str) -> bool {
+    // Pump.fun TooMuchSolRequired (6002) and TooLittleSolReceived (6003)
+    const SLIPPAGE_ERRORS: [&str; 2] = ["custom program error: 0x1772", "custom program error: 0x1773"];
+    let error = error.to_lowercase();
+    SLIPPAGE_ERRORS.iter().any(|code| error.contains(code))
+}
+
+/// Percentage of a position to sell so the proceeds, after the Pump.fun fee, equal its cost basis
+///
+/// `None` when the whole position is worth less than its cost.
+fn recover_cost_percentage(position: &Position) -> Option<f64> {
+    let fee_rate = crate::config::constants::PUMP_FUN_FEE_BPS as f64 / 10_000.0;
+    let net_value = position.amount as f64 * position.current_price * (1.0 - fee_rate);
+    if net_value <= 0.0 || position.cost_basis_sol >= net_value {
+        return None;
+    }
+    Some(position.cost_basis_sol / net_value * 100.0)
+}
+
+/// Take `amount` tokens from `lots` in policy order, returning the PnL realized on `proceeds_sol`
+///
+/// Proceeds are spread evenly over the tokens sold; each lot contributes its own cost per token.
+/// Emptied lots are removed. Tokens sold beyond the recorded lots realize their proceeds at
+/// zero cost.
+fn consume_lots(lots: &mut Vec<Lot>, amount: u64, proceeds_sol: f64, policy: LotPolicy) -> f64 {
+    if amount == 0 {
+        return 0.0;
+    }
+    let proceeds_per_token = proceeds_sol / amount as f64;
+    let mut remaining = amount;
+    let mut realized = 0.0;
+
+    while remaining > 0 {
+        let index = match policy {
+            LotPolicy::Fifo => 0,
+            LotPolicy::Lifo => match lots.len().checked_sub(1) {
+                Some(last) => last,
+                None => break,
+            },
+        };
+        let lot = match lots.get_mut(index) {
+            Some(lot) => lot,
+            None => break,
+        };
+
+        let taken = remaining.min(lot.amount);
+        let cost = lot.cost_sol * taken as f64 / lot.amount.max(1) as f64;
+        realized += taken as f64 * proceeds_per_token - cost;
+        lot.amount -= taken;
+        lot.cost_sol -= cost;
+        remaining -= taken;
+        if lot.amount == 0 {
+            lots.remove(index);
+        }
+    }
+
+    if remaining > 0 {
+        tracing::warn!("Sold {} tokens beyond the recorded lots; realizing them at zero cost", remaining);
+        realized += remaining as f64 * proceeds_per_token;
+    }
+    realized
+}
+
+/// Check whether a send failure is transient and worth retrying
+///
+/// Landing problems (expired blockhash, timeouts, rate limits) are retryable;
+/// program errors and insufficient funds will fail the same way again.
+fn is_retryable_send_error(error: &str) -> bool {
+    let error = error.to_lowercase();
+    const PERMANENT: [&str; 3] = ["custom program error", "insufficient funds", "insufficient lamports"];
+    if PERMANENT.iter().any(|p| error.contains(p)) {
+        return false;
+    }
+
+    const TRANSIENT: [&str; 6] = [
+        "blockhash not found",
+        "block height exceeded",
+        "unable to confirm",
+        "timed out",
+        "429",
+        "node is behind",
+    ];
+    TRANSIENT.iter().any(|t| error.contains(t))
+}
+
+/// Balance left from a starting balance after a history of simulated trades
+pub fn virtual_balance(starting_balance_sol: f64, trades: &[TradeResult]) -> f64 {
+    trades.iter().fold(starting_balance_sol, |balance, trade| match trade.trade_type {
+        TradeType::Buy => balance - trade.total_value,
+        TradeType::Sell => balance + trade.total_value,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{self, StorageBackend};
+    use crate::types::test_position;
+
+    /// Configuration whose RPC endpoint refuses connections, so no test reaches the network
+    fn test_config() -> BotConfig {
+        BotConfig {
+            rpc_url: "http://127.0.0.1:1".to_string(),
+            storage_backend: StorageBackend::Memory,
+            simulation_mode: true,
+            ..Default::default()
+        }
+    }
+
+    async fn test_trader(config: BotConfig) -> Trader {
+        let client = Arc::new(SolanaClient::new(&config).await.unwrap());
+        Trader::new(client, config::shared(config)).await.unwrap()
+    }
+
+    /// A JSON storage file unique to one test
+    fn temp_storage_path(name: &str) -> String {
+        let path = std::env::temp_dir().join(format!("sniper-{}-{}.json", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path.to_string_lossy().into_owned()
+    }
+
+    async fn insert_position(trader: &Trader, position: &Position) {
+        trader.positions.write().await.insert(position.token_address.to_string(), position.clone());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn panic_sell_closes_all_positions_despite_guards() {
+        let mut config = test_config();
+        config.min_hold_seconds = 3600;
+        config.sell_price_guard_pct = Some(1.0);
+        let trader = Arc::new(test_trader(config).await);
+        let positions = [test_position("AAA"), test_position("BBB")];
+        for position in &positions {
+            insert_position(&trader, position).await;
+        }
+
+        // Both guards would hold a normal exit back
+        assert!(!trader.held_min_duration(&positions[0]));
+        assert!(trader.should_defer_sell(&positions[0], 1_000_000, 1, SellReason::TakeProfit).await);
+        assert!(!trader.should_defer_sell(&positions[0], 1_000_000, 1, SellReason::Panic).await);
+
+        // A sell already in flight finishes before the panic sell starts
+        *trader.is_selling.write().await = true;
+        let panic = tokio::spawn({
+            let trader = Arc::clone(&trader);
+            async move { trader.panic_sell().await }
+        });
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        assert!(trader.positions().await.iter().all(|pos| pos.status == PositionStatus::Open));
+        *trader.is_selling.write().await = false;
+
+        assert_eq!(panic.await.unwrap(), 2);
+        assert!(trader.positions().await.iter().all(|pos| pos.status == PositionStatus::Closed));
+        assert!(!*trader.is_selling.read().await);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn exit_rule_fires_only_once_its_sell_goes_through() {
+        let mut config = test_config();
+        config.exit_rules = crate::traders::exit_rules::parse_rules("pnl>=100 => sell 50").unwrap();
+        let trader = test_trader(config).await;
+        let mut position = test_position("AAA");
+        position.pnl_percentage = 150.0;
+        insert_position(&trader, &position).await;
+
+        // A sell already in flight drops this one, so the rule stays armed
+        *trader.is_selling.write().await = true;
+        assert!(!trader.apply_exit_rules(&position).await.unwrap());
+        assert!(trader.positions().await[0].fired_exit_rules.is_empty());
+        *trader.is_selling.write().await = false;
+
+        assert!(trader.apply_exit_rules(&position).await.unwrap());
+        let after = trader.positions().await.remove(0);
+        assert_eq!(after.amount, 500_000);
+        assert_eq!(after.fired_exit_rules, vec!["pnl>=100 => sell 50".to_string()]);
+        assert!(!trader.apply_exit_rules(&after).await.unwrap());
+    }
+
+    #[test]
+    fn selling_past_the_recorded_lots_still_realizes_the_proceeds() {
+        let lot = |amount, cost_sol| Lot { signature: String::new(), amount, cost_sol, opened_at: Utc::now() };
+        let mut lots = vec![lot(100, 1.0), lot(100, 2.0)];
+
+        // FIFO takes the cheaper lot first
+        let realized = consume_lots(&mut lots, 150, 3.0, LotPolicy::Fifo);
+        assert!((realized - (3.0 - 2.0)).abs() < 1e-9);
+        assert_eq!(lots.len(), 1);
+        assert_eq!(lots[0].amount, 50);
+
+        let realized = consume_lots(&mut lots, 100, 2.0, LotPolicy::Fifo);
+        assert!((realized - (2.0 - 1.0)).abs() < 1e-9);
+        assert!(lots.is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn scaling_in_seeds_a_lot_for_the_holding_bought_before_lot_accounting() {
+        let config = BotConfig { lot_accounting: Some(LotPolicy::Fifo), ..test_config() };
+        let trader = test_trader(config).await;
+        let analysis = crate::types::test_analysis();
+        let mut position = test_position("AAA");
+        position.token_address = analysis.token.address;
+        insert_position(&trader, &position).await;
+
+        trader.create_position(&analysis, 0.5, 400_000, "scale".to_string(), "pump_fun").await;
+        let scaled = trader.positions().await.remove(0);
+        assert_eq!(scaled.amount, 1_400_000);
+        assert_eq!(scaled.cost_basis_sol, 1.5);
+        assert_eq!(scaled.lots.len(), 2);
+        assert_eq!((scaled.lots[0].amount, scaled.lots[0].cost_sol), (1_000_000, 1.0));
+        assert_eq!(scaled.lots.iter().map(|lot| lot.cost_sol).sum::<f64>(), scaled.cost_basis_sol);
+    }
+
+    #[test]
+    fn hard_exits_sell_dust_that_other_exits_skip() {
+        assert!(skips_dust_sell(SellReason::TakeProfit, 0.001, 0.01));
+        assert!(!skips_dust_sell(SellReason::TakeProfit, 0.02, 0.01));
+        for reason in [SellReason::StopLoss, SellReason::Panic, SellReason::Manual] {
+            assert!(!skips_dust_sell(reason, 0.001, 0.01));
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn cost_recovery_stays_armed_until_its_sell_goes_through() {
+        let config = BotConfig { recover_cost_at_pct: Some(100.0), ..test_config() };
+        let trader = test_trader(config).await;
+        let mut position = test_position("AAA");
+        position.current_price = 3e-6;
+        position.pnl_percentage = 200.0;
+        insert_position(&trader, &position).await;
+
+        // A dropped sell leaves the position untouched
+        *trader.is_selling.write().await = true;
+        assert!(!trader.recover_cost(&position).await.unwrap());
+        let unchanged = trader.positions().await.remove(0);
+        assert!(!unchanged.cost_recovered);
+        assert_eq!(unchanged.stop_loss_price, None);
+        *trader.is_selling.write().await = false;
+
+        assert!(trader.recover_cost(&position).await.unwrap());
+        let after = trader.positions().await.remove(0);
+        assert!(after.cost_recovered);
+        assert_eq!(after.stop_loss_price, Some(after.entry_price));
+        assert!(after.amount < position.amount);
+        assert!(!trader.recover_cost(&after).await.unwrap());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn pushed_price_fires_the_exit_without_a_poll() {
+        let config = BotConfig { max_loss_per_trade_sol: 0.5, ..test_config() };
+        let trader = test_trader(config).await;
+        let curve = crate::types::test_analysis().bonding_curve;
+        let mut position = test_position("AAA");
+        position.token_address = curve.token_address;
+        position.bonding_curve_address = curve.address;
+        position.entry_price = bonding_curve::spot_price(&curve) * 10.0;
+        insert_position(&trader, &position).await;
+
+        let event = CurveUpdateEvent { curve, slot: 1, timestamp: Utc::now() };
+        trader.apply_curve_update(&event).await;
+        trader.check_pushed_exits(&event).await;
+        assert_eq!(trader.positions().await[0].status, PositionStatus::Closed);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn source_stats_count_only_closed_positions() {
+        let trader = test_trader(test_config()).await;
+        let mut won = test_position("WIN");
+        won.status = PositionStatus::Closed;
+        let held = test_position("HELD");
+        insert_position(&trader, &won).await;
+        insert_position(&trader, &held).await;
+
+        let config = trader.config.load();
+        trader.record_trade("a", won.token_address, TradeType::Buy, 1.0, 1e-6, "pump_fun", &config);
+        trader.record_trade("b", won.token_address, TradeType::Sell, 1.5, 1.5e-6, "pump_fun", &config);
+        trader.record_trade("c", held.token_address, TradeType::Buy, 1.0, 1e-6, "pump_fun", &config);
+
+        let stats = trader.source_stats().await.unwrap();
+        assert_eq!(stats["pump_fun"]["tokens"], 1);
+        assert_eq!(stats["pump_fun"]["wins"], 1);
+        assert_eq!(stats["pump_fun"]["pnl_sol"], 0.5);
+
+        // Closing the held position refreshes the cached aggregates
+        let mut closed = held.clone();
+        closed.status = PositionStatus::Closed;
+        trader.save_position(&closed);
+        insert_position(&trader, &closed).await;
+        let stats = trader.source_stats().await.unwrap();
+        assert_eq!(stats["pump_fun"]["tokens"], 2);
+        assert_eq!(stats["pump_fun"]["wins"], 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn one_failing_position_does_not_skip_the_others_exits() {
+        let trader = test_trader(test_config()).await;
+
+        // Its price poll hits the unreachable RPC endpoint and fails
+        let failing = test_position("FAIL");
+        insert_position(&trader, &failing).await;
+
+        // A pushed price skips the poll and is past take profit
+        let mut winner = test_position("WIN");
+        winner.current_price = 2e-6;
+        winner.highest_price = 2e-6;
+        winner.pnl_percentage = 100.0;
+        winner.take_profit_price = Some(1.5e-6);
+        winner.opened_at = Utc::now() - chrono::Duration::hours(1);
+        insert_position(&trader, &winner).await;
+        trader.curve_pushed_at.write().await.insert(winner.token_address.to_string(), now_millis());
+
+        trader.check_automated_sells().await.unwrap();
+        let positions = trader.positions().await;
+        let status = |mint: Pubkey| positions.iter().find(|pos| pos.token_address == mint).unwrap().status;
+        assert_eq!(status(winner.token_address), PositionStatus::Closed);
+        assert_eq!(status(failing.token_address), PositionStatus::Open);
+    }
+
+    #[test]
+    fn recent_volume_is_summed_over_the_window() {
+        let window = chrono::Duration::seconds(60);
+        let mut position = test_position("VOL");
+        position.opened_at = Utc::now() - chrono::Duration::minutes(10);
+        position.volume_samples.push(crate::types::VolumeSample { at: Utc::now() - chrono::Duration::minutes(2), sol: 9.0 });
+
+        Trader::record_volume_sample(&mut position, 3.0, window);
+        assert_eq!(position.volume_samples.len(), 1);
+        assert_eq!(position.recent_volume_sol, 3.0);
+        assert_eq!(position.peak_volume_sol, 3.0);
+
+        // A quiet check keeps the window's earlier volume
+        Trader::record_volume_sample(&mut position, 0.0, window);
+        assert_eq!(position.recent_volume_sol, 3.0);
+
+        // A young position's partial window does not set the peak
+        let mut young = test_position("NEW");
+        Trader::record_volume_sample(&mut young, 5.0, window);
+        assert_eq!(young.recent_volume_sol, 5.0);
+        assert_eq!(young.peak_volume_sol, 0.0);
+    }
+
+    #[test]
+    fn positions_saved_before_newer_fields_still_load() {
+        let mut saved = serde_json::to_value(test_position("OLD")).unwrap();
+        let fields = saved.as_object_mut().unwrap();
+        for field in ["creator", "source", "fired_exit_rules", "last_sol_reserves", "recent_volume_sol", "peak_volume_sol", "volume_samples"] {
+            fields.remove(field);
+        }
+
+        let position: Position = serde_json::from_value(saved).unwrap();
+        assert_eq!(position.creator, Pubkey::default());
+        assert!(position.source.is_empty());
+        assert!(position.fired_exit_rules.is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn migration_sells_a_held_position_only_when_enabled() {
+        let position = test_position("GRAD");
+        let event = MigrationEvent {
+            token_address: position.token_address,
+            bonding_curve_address: position.bonding_curve_address,
+            timestamp: Utc::now(),
+            source: "pump_fun".to_string(),
+        };
+
+        let trader = test_trader(test_config()).await;
+        insert_position(&trader, &position).await;
+        trader.handle_migration(&event).await.unwrap();
+        assert_eq!(trader.positions.read().await[&position.token_address.to_string()].status, PositionStatus::Open);
+
+        let trader = test_trader(BotConfig { sell_on_migration: true, ..test_config() }).await;
+        insert_position(&trader, &position).await;
+        trader.handle_migration(&event).await.unwrap();
+        assert_eq!(trader.positions.read().await[&position.token_address.to_string()].status, PositionStatus::Closed);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn fleet_claims_block_other_instances_while_the_position_is_held() {
+        let fleet: Arc<dyn Coordinator> = Arc::new(coordination::MemoryCoordinator::default());
+        let config = BotConfig { claim_ttl_secs: 1, ..test_config() };
+        let mut first = test_trader(config.clone()).await;
+        first.coordinator = Some(Arc::clone(&fleet));
+        let mut second = test_trader(config).await;
+        second.coordinator = Some(Arc::clone(&fleet));
+
+        let position = test_position("HELD");
+        assert!(first.claim_for_buy(&position.token_address).await);
+        assert!(!second.claim_for_buy(&position.token_address).await);
+
+        // Renewal keeps the claim past its original TTL while the position is open
+        insert_position(&first, &position).await;
+        tokio::time::sleep(std::time::Duration::from_millis(600)).await;
+        first.renew_held_claims().await;
+        tokio::time::sleep(std::time::Duration::from_millis(600)).await;
+        assert!(!second.claim_for_buy(&position.token_address).await);
+
+        // The holder's release frees the mint for the rest of the fleet
+        first.release_claim(&position.token_address).await;
+        assert!(second.claim_for_buy(&position.token_address).await);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn pending_position_is_skipped_until_its_buy_confirms() {
+        let mut config = test_config();
+        config.max_loss_per_trade_sol = 0.1;
+        let trader = test_trader(config).await;
+        let mut position = test_position("AAA");
+        position.status = PositionStatus::Pending;
+        // 90% down, well past the max-loss backstop
+        position.current_price = 1e-7;
+        insert_position(&trader, &position).await;
+        trader.curve_pushed_at.write().await.insert(position.token_address.to_string(), now_millis());
+
+        // The RPC cannot answer, which says nothing about the buy: keep it pending and unsold
+        trader.check_automated_sells().await.unwrap();
+        let pending = trader.positions().await.pop().unwrap();
+        assert_eq!(pending.status, PositionStatus::Pending);
+        assert_eq!(pending.amount, 1_000_000);
+
+        trader.settle_entry(&position, Ok(SignatureState::Confirmed)).await;
+        assert_eq!(trader.positions().await[0].status, PositionStatus::Open);
+
+        trader.check_automated_sells().await.unwrap();
+        assert_eq!(trader.positions().await[0].status, PositionStatus::Closed);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn only_a_definitive_outcome_closes_a_pending_position() {
+        let trader = test_trader(test_config()).await;
+        let mut position = test_position("AAA");
+        position.status = PositionStatus::Pending;
+        insert_position(&trader, &position).await;
+
+        // Unseen but still inside the blockhash window: wait
+        trader.settle_entry(&position, Ok(SignatureState::Pending)).await;
+        assert_eq!(trader.positions().await[0].status, PositionStatus::Pending);
+
+        trader.settle_entry(&position, Ok(SignatureState::Failed("custom program error".to_string()))).await;
+        let closed = trader.positions().await.pop().unwrap();
+        assert_eq!(closed.status, PositionStatus::Closed);
+        assert_eq!(closed.amount, 0);
+
+        // Unseen long after its blockhash expired: dropped
+        let mut stale = test_position("BBB");
+        stale.status = PositionStatus::Pending;
+        stale.opened_at = Utc::now() - chrono::Duration::minutes(5);
+        insert_position(&trader, &stale).await;
+        trader.settle_entry(&stale, Ok(SignatureState::Pending)).await;
+        let stale_key = stale.token_address.to_string();
+        assert_eq!(trader.positions.read().await[&stale_key].status, PositionStatus::Closed);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn buys_hold_a_daily_trade_slot_unless_they_fail() {
+        let trader = test_trader(test_config()).await;
+        trader.start_buy_tracking("first", true).await;
+        trader.start_buy_tracking("second", true).await;
+        assert_eq!(*trader.daily_trades.read().await, 2);
+
+        // An unanswered status check leaves the slot taken
+        let first = trader.unconfirmed_buys.read().await["first"];
+        trader.settle_buy("first", first, Err("connection refused".to_string())).await;
+        assert_eq!(*trader.daily_trades.read().await, 2);
+        assert!(trader.unconfirmed_buys.read().await.contains_key("first"));
+
+        trader.settle_buy("first", first, Ok(SignatureState::Failed("slippage".to_string()))).await;
+        assert_eq!(*trader.daily_trades.read().await, 1);
+        assert!(!trader.unconfirmed_buys.read().await.contains_key("first"));
+
+        let second = trader.unconfirmed_buys.read().await["second"];
+        trader.settle_buy("second", second, Ok(SignatureState::Confirmed)).await;
+        assert_eq!(*trader.daily_trades.read().await, 1);
+        assert!(trader.unconfirmed_buys.read().await.is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn scout_and_scale_in_take_one_daily_trade_slot() {
+        let trader = test_trader(test_config()).await;
+        trader.start_buy_tracking("scout", true).await;
+        trader.start_buy_tracking("scale", false).await;
+        assert_eq!(*trader.daily_trades.read().await, 1);
+
+        // A failed scale-in gives back nothing it did not take
+        let scale = trader.unconfirmed_buys.read().await["scale"];
+        trader.settle_buy("scale", scale, Ok(SignatureState::Failed("slippage".to_string()))).await;
+        assert_eq!(*trader.daily_trades.read().await, 1);
+    }
+
+    #[test]
+    fn unknown_pending_transactions_wait_out_their_blockhash() {
+        let mut tx = PendingTransaction {
+            signature: solana_sdk::signature::Signature::new_unique().to_string(),
+            intent: TradeType::Sell,
+            token_address: Pubkey::new_unique(),
+            bonding_curve_address: Pubkey::new_unique(),
+            amount_sol: 0.5,
+            token_amount: 1_000_000,
+            price: 5e-7,
+            source: "pump_fun".to_string(),
+            sent_at: Utc::now(),
+            batch_legs: Vec::new(),
+        };
+        assert!(pending_may_still_land(&tx));
+
+        tx.sent_at = Utc::now() - chrono::Duration::minutes(5);
+        assert!(!pending_may_still_land(&tx));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn landed_buy_keeps_its_record_until_the_position_opens() {
+        let trader = test_trader(test_config()).await;
+        let pending = |intent| PendingTransaction {
+            signature: solana_sdk::signature::Signature::new_unique().to_string(),
+            intent,
+            token_address: Pubkey::new_unique(),
+            bonding_curve_address: Pubkey::new_unique(),
+            amount_sol: 0.5,
+            token_amount: 0,
+            price: 5e-7,
+            source: "pump_fun".to_string(),
+            sent_at: Utc::now(),
+            batch_legs: Vec::new(),
+        };
+        let (landed, failed) = (pending(TradeType::Buy), pending(TradeType::Buy));
+        for tx in [&landed, &failed] {
+            trader.storage.save_pending_transaction(tx).unwrap();
+        }
+
+        // The balance lookup behind the new position hits the unreachable RPC endpoint
+        let waiting = trader.apply_pending_outcomes(&[landed.clone(), failed], vec![Some(true), Some(false)]).await;
+        assert_eq!(waiting, vec![landed.signature.clone()]);
+        let kept: Vec<String> = trader.storage.load_pending_transactions().unwrap()
+            .into_iter()
+            .map(|tx| tx.signature)
+            .collect();
+        assert_eq!(kept, vec![landed.signature]);
+    }
+
+    #[test]
+    fn simulated_signatures_are_unique() {
+        assert_ne!(simulated_signature(), simulated_signature());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn daily_loss_breaker_halts_buys_until_the_next_utc_day() {
+        let mut config = test_config();
+        config.max_daily_loss_sol = Some(1.0);
+        let trader = test_trader(config).await;
+
+        *trader.daily_realized_pnl.write().await = -0.5;
+        assert!(trader.can_buy().await);
+
+        *trader.daily_realized_pnl.write().await = -1.5;
+        assert!(!trader.can_buy().await);
+        assert_eq!(trader.halt_reason().await.as_deref(), Some(DAILY_LOSS_HALT_REASON));
+
+        // Next UTC day: counters reset and the breaker's own halt is lifted
+        *trader.last_reset_date.write().await = "2000-01-01".to_string();
+        assert!(trader.can_buy().await);
+        assert_eq!(trader.halt_reason().await, None);
+        assert_eq!(*trader.daily_realized_pnl.read().await, 0.0);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn daily_loss_breaker_leaves_other_halts_in_place() {
+        let mut config = test_config();
+        config.max_daily_loss_sol = Some(1.0);
+        let trader = test_trader(config).await;
+
+        *trader.daily_realized_pnl.write().await = -1.5;
+        assert!(!trader.can_buy().await);
+        trader.halt_buys("RPC unhealthy").await;
+
+        *trader.last_reset_date.write().await = "2000-01-01".to_string();
+        trader.reset_daily_trades_if_needed().await;
+        assert_eq!(trader.halt_reason().await.as_deref(), Some("RPC unhealthy"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn daily_loss_survives_a_restart() {
+        let mut config = test_config();
+        config.max_daily_loss_sol = Some(1.0);
+        config.storage_backend = StorageBackend::Json;
+        config.storage_path = temp_storage_path("daily-loss");
+
+        let trader = test_trader(config.clone()).await;
+        *trader.daily_realized_pnl.write().await = -1.5;
+        assert!(!trader.can_buy().await);
+        drop(trader);
+
+        let restarted = test_trader(config.clone()).await;
+        assert_eq!(restarted.halt_reason().await.as_deref(), Some(DAILY_LOSS_HALT_REASON));
+        assert_eq!(*restarted.daily_realized_pnl.read().await, -1.5);
+        let _ = std::fs::remove_file(&config.storage_path);
+    }
+
+    #[test]
+    fn sol_target_sells_just_enough_tokens_capped_at_the_position() {
+        let curve = crate::types::test_analysis().bonding_curve;
+        let held = 100_000_000_000_000;
+
+        let tokens = tokens_to_sell(SellAmount::SolTarget(0.5), held, &curve);
+        assert!(bonding_curve::sell_quote(&curve, tokens) >= 500_000_000);
+        assert!(bonding_curve::sell_quote(&curve, tokens - 1_000) < 500_000_000);
+
+        assert_eq!(tokens_to_sell(SellAmount::SolTarget(20.0), held, &curve), held);
+        assert_eq!(tokens_to_sell(SellAmount::SolTarget(1_000.0), held, &curve), held);
+        assert_eq!(tokens_to_sell(SellAmount::Percentage(25.0), held, &curve), held / 4);
+    }
+
+    #[cfg(feature = "otel")]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn token_trace_connects_detect_analyze_buy_and_sell() {
+        use opentelemetry::trace::TracerProvider as _;
+        use opentelemetry_sdk::testing::trace::InMemorySpanExporter;
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let exporter = InMemorySpanExporter::default();
+        let provider = opentelemetry_sdk::trace::TracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        // The pipeline's shape: a root span per detected token, analysis and buy beneath it
+        let trader = test_trader(test_config()).await;
+        let analysis = crate::types::test_analysis();
+        let mint = analysis.token.address;
+        async {
+            async {}.instrument(tracing::info_span!("analyze")).await;
+            trader.execute_buy(&analysis, "test").instrument(tracing::info_span!("buy")).await.unwrap();
+            trader.attach_trace(mint, tracing::Span::current()).await;
+        }
+        .instrument(tracing::info_span!("token", mint = %mint))
+        .await;
+
+        // The sell runs later, outside the token's task, and still joins its trace
+        let position = trader.positions.read().await.get(&mint.to_string()).cloned().unwrap();
+        trader.execute_sell(&position, 100.0, SellReason::Manual).await.unwrap();
+        drop(trader);
+        provider.force_flush();
+
+        let spans = exporter.get_finished_spans().unwrap();
+        let span = |name: &str| spans.iter().find(|span| span.name == name).unwrap_or_else(|| panic!("no {} span", name));
+        let token = span("token");
+        for stage in ["analyze", "buy", "sell"] {
+            assert_eq!(span(stage).parent_span_id, token.span_context.span_id(), "{} is not under token", stage);
+            assert_eq!(span(stage).span_context.trace_id(), token.span_context.trace_id());
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn trades_carry_the_config_version_they_were_decided_under() {
+        let trader = test_trader(test_config()).await;
+        let analysis = crate::types::test_analysis();
+        let mint = analysis.token.address;
+        let first_version = trader.config.load().version().to_string();
+
+        trader.execute_buy(&analysis, "test").await.unwrap();
+
+        // A hot reload changes the version stamped on later trades only
+        trader.config.store(Arc::new(BotConfig { take_profit_percentage: 75.0, ..test_config() }));
+        let reloaded_version = trader.config.load().version().to_string();
+        assert_ne!(first_version, reloaded_version);
+        let position = trader.positions.read().await.get(&mint.to_string()).cloned().unwrap();
+        trader.execute_sell(&position, 100.0, SellReason::Manual).await.unwrap();
+
+        let versions: Vec<(TradeType, String)> = trader.trade_history(Some(&mint)).unwrap()
+            .into_iter()
+            .map(|trade| (trade.trade_type, trade.config_version))
+            .collect();
+        assert_eq!(versions, [(TradeType::Buy, first_version), (TradeType::Sell, reloaded_version)]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn trailing_stop_arms_only_past_the_activation_threshold() {
+        let mut config = test_config();
+        config.trailing_activation_pct = 20.0;
+        config.trailing_stop_loss_percentage = 10.0;
+        let trader = test_trader(config).await;
+        let mut position = test_position("TRL");
+        position.stop_loss_price = Some(0.9e-6);
+
+        // Up 15%: the trail stays unarmed and the fixed stop governs
+        trader.set_position_price(&mut position, 1.15e-6);
+        assert_eq!(position.trailing_stop_price, None);
+        trader.set_position_price(&mut position, 0.85e-6);
+        assert!(trader.should_stop_loss(&position));
+        assert!(!trader.should_trailing_stop(&position));
+
+        // Up 25%: the trail arms below the high and takes over from the fixed stop
+        trader.set_position_price(&mut position, 1.25e-6);
+        let trail = position.trailing_stop_price.unwrap();
+        assert!((trail - 1.125e-6).abs() < 1e-12);
+        assert!(!trader.should_stop_loss(&position));
+
+        // Once armed it stays armed below the threshold and fires on the pullback
+        trader.set_position_price(&mut position, 1.1e-6);
+        assert_eq!(position.trailing_stop_price, Some(trail));
+        assert!(trader.should_trailing_stop(&position));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn repeated_buys_into_one_mint_stop_at_its_exposure_cap() {
+        let mut config = test_config();
+        config.trading_cooldown_ms = 0;
+        config.buy_amount_sol = 0.4;
+        config.max_exposure_per_token_sol = Some(1.0);
+        let trader = test_trader(config).await;
+        let analysis = crate::types::test_analysis();
+        let mint = analysis.token.address;
+
+        // The third buy is downsized to what fits and the fourth is blocked
+        let mut exposures = Vec::new();
+        for _ in 0..4 {
+            trader.execute_buy(&analysis, "test").await.unwrap();
+            exposures.push(trader.positions.read().await[&mint.to_string()].cost_basis_sol);
+        }
+        for (exposure, expected) in exposures.iter().zip([0.4, 0.8, 1.0, 1.0]) {
+            assert!((exposure - expected).abs() < 1e-9, "{:?}", exposures);
+        }
+        assert_eq!(trader.trade_history(Some(&mint)).unwrap().len(), 3);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn new_buys_are_blocked_once_total_exposure_hits_the_cap() {
+        let mut config = test_config();
+        config.trading_cooldown_ms = 0;
+        config.buy_amount_sol = 0.5;
+        config.max_total_exposure_sol = Some(1.2);
+        let trader = test_trader(config).await;
+        let mut held = test_position("HLD");
+        held.cost_basis_sol = 0.5;
+        insert_position(&trader, &held).await;
+
+        // 0.5 held plus 0.5 fits under the cap; another 0.5 would not
+        let fits = crate::types::test_analysis();
+        trader.execute_buy(&fits, "test").await.unwrap();
+        assert!(trader.holds(&fits.token.address).await);
+        let over = crate::types::test_analysis();
+        trader.execute_buy(&over, "test").await.unwrap();
+        assert!(!trader.holds(&over.token.address).await);
+        assert!((trader.total_exposure().await - 1.0).abs() < 1e-9);
+
+        // Closing a position frees room under the cap
+        trader.positions.write().await.get_mut(&held.token_address.to_string()).unwrap().status = PositionStatus::Closed;
+        trader.execute_buy(&over, "test").await.unwrap();
+        assert!(trader.holds(&over.token.address).await);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn take_profit_is_suppressed_while_net_profit_is_below_the_floor() {
+        let mut position = test_position("NET");
+        position.take_profit_price = Some(1.1e-6);
+        position.current_price = 1.1e-6;
+
+        // A 10% gain on 1 SOL nets under 0.08 SOL once both legs pay their fees
+        let mut config = test_config();
+        config.min_net_profit_sol = 0.08;
+        let trader = test_trader(config.clone()).await;
+        let net_profit = trader.estimated_net_profit_sol(&position);
+        assert!(net_profit > 0.05 && net_profit < 0.08, "net profit {}", net_profit);
+        assert!(!trader.should_take_profit(&position));
+
+        config.min_net_profit_sol = 0.05;
+        trader.config.store(Arc::new(config));
+        assert!(trader.should_take_profit(&position));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn stop_loss_is_deferred_until_the_minimum_hold_elapses() {
+        let config = BotConfig { min_hold_seconds: 60, max_loss_per_trade_sol: 100.0, ..test_config() };
+        let trader = test_trader(config).await;
+        let curve = crate::types::test_analysis().bonding_curve;
+        let mut position = test_position("HLD");
+        position.token_address = curve.token_address;
+        position.bonding_curve_address = curve.address;
+        position.entry_price = bonding_curve::spot_price(&curve) * 1.5;
+        position.stop_loss_price = Some(position.entry_price * 0.9);
+        insert_position(&trader, &position).await;
+        let event = CurveUpdateEvent { curve, slot: 1, timestamp: Utc::now() };
+
+        // Below the stop but inside the minimum hold
+        trader.apply_curve_update(&event).await;
+        trader.check_pushed_exits(&event).await;
+        assert_eq!(trader.positions().await[0].status, PositionStatus::Open);
+
+        // The same price stops out once the hold has elapsed
+        trader.positions.write().await.get_mut(&position.token_address.to_string()).unwrap().opened_at =
+            Utc::now() - chrono::Duration::seconds(61);
+        trader.check_pushed_exits(&event).await;
+        assert_eq!(trader.positions().await[0].status, PositionStatus::Closed);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn sell_quoted_far_below_the_price_is_deferred_for_one_cycle() {
+        let config = BotConfig { sell_price_guard_pct: Some(10.0), ..test_config() };
+        let trader = test_trader(config).await;
+        // 1M units at 1e-6 SOL are expected to fetch 1 SOL
+        let position = test_position("WCK");
+        let amount = position.amount;
+
+        assert!(!trader.should_defer_sell(&position, amount, 950_000_000, SellReason::TakeProfit).await);
+
+        // A wick past the guard defers the sell once, then the next cycle sells anyway
+        assert!(trader.should_defer_sell(&position, amount, 500_000_000, SellReason::TakeProfit).await);
+        assert!(!trader.should_defer_sell(&position, amount, 500_000_000, SellReason::TakeProfit).await);
+        assert!(trader.should_defer_sell(&position, amount, 500_000_000, SellReason::TrailingStop).await);
+
+        // A hard stop never waits
+        assert!(!trader.should_defer_sell(&position, amount, 500_000_000, SellReason::StopLoss).await);
+
+        trader.config.store(Arc::new(test_config()));
+        let other = test_position("OFF");
+        assert!(!trader.should_defer_sell(&other, amount, 1, SellReason::TakeProfit).await);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn buys_are_suppressed_during_the_startup_warmup() {
+        let config = BotConfig { startup_warmup_ms: 200, trading_cooldown_ms: 0, ..test_config() };
+        let trader = test_trader(config).await;
+        trader.start_warmup().await;
+
+        let early = crate::types::test_analysis();
+        trader.execute_buy(&early, "test").await.unwrap();
+        assert!(!trader.holds(&early.token.address).await);
+
+        tokio::time::sleep(std::time::Duration::from_millis(250)).await;
+        assert!(!trader.in_warmup().await);
+        let late = crate::types::test_analysis();
+        trader.execute_buy(&late, "test").await.unwrap();
+        assert!(trader.holds(&late.token.address).await);
+    }
+
+    /// Configuration for live trading against a mock RPC
+    ///
+    /// The RPC knows only the classic SPL `mint`, reports `balance_lamports` for the wallet
+    /// and fails every `sendTransaction` with `send_error`.
+    async fn live_config(
+        mint: Pubkey,
+        balance_lamports: u64,
+        send_error: &'static str,
+    ) -> (BotConfig, crate::utils::solana_client::RpcCalls) {
+        use crate::utils::solana_client::mint_account;
+
+        live_chain_config(HashMap::from([(mint, mint_account(None))]), balance_lamports, send_error).await
+    }
+
+    /// Like [`live_config`], with the RPC serving `accounts`
+    async fn live_chain_config(
+        accounts: HashMap<Pubkey, solana_sdk::account::Account>,
+        balance_lamports: u64,
+        send_error: &'static str,
+    ) -> (BotConfig, crate::utils::solana_client::RpcCalls) {
+        use crate::utils::solana_client::mock_chain;
+
+        let (rpc_url, calls) = mock_chain(Arc::new(std::sync::Mutex::new(accounts)), move |request, _| {
+            let context = serde_json::json!({ "slot": 1 });
+            let result = match request["method"].as_str().unwrap() {
+                "getRecentPrioritizationFees" => serde_json::json!([]),
+                "getBalance" => serde_json::json!({ "context": context, "value": balance_lamports }),
+                "getMinimumBalanceForRentExemption" => serde_json::json!(2_039_280),
+                "getLatestBlockhash" => serde_json::json!({
+                    "context": context,
+                    "value": { "blockhash": solana_sdk::hash::Hash::new_unique().to_string(), "lastValidBlockHeight": 1_000 },
+                }),
+                _ => return serde_json::json!({ "error": { "code": -32002, "message": send_error } }),
+            };
+            serde_json::json!({ "result": result })
+        }).await;
+        let config = BotConfig {
+            rpc_url,
+            simulation_mode: false,
+            max_send_retries: 2,
+            private_key: Some(bs58::encode(solana_sdk::signature::Keypair::new().to_bytes()).into_string()),
+            ..test_config()
+        };
+        (config, calls)
+    }
+
+    /// The mint and a fresh bonding curve account behind each position
+    fn held_accounts(positions: &[&Position]) -> HashMap<Pubkey, solana_sdk::account::Account> {
+        use crate::utils::solana_client::mint_account;
+
+        let mut accounts = HashMap::new();
+        for position in positions {
+            let mut curve = crate::types::test_analysis().bonding_curve;
+            curve.address = position.bonding_curve_address;
+            curve.token_address = position.token_address;
+            accounts.insert(position.token_address, mint_account(None));
+            accounts.insert(curve.address, solana_sdk::account::Account {
+                lamports: 1_000_000,
+                data: bonding_curve::encode(&curve),
+                owner: crate::config::constants::PUMP_FUN_PROGRAM_ID,
+                executable: false,
+                rent_epoch: 0,
+            });
+        }
+        accounts
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn transient_buy_failures_retry_but_honeypots_do_not() {
+        use crate::utils::solana_client::call_count;
+        const BLOCKHASH_NOT_FOUND: &str = "Transaction simulation failed: Blockhash not found";
+
+        // A landing failure is retried up to the configured count
+        let analysis = crate::types::test_analysis();
+        let (config, calls) = live_config(analysis.token.address, 0, BLOCKHASH_NOT_FOUND).await;
+        let trader = test_trader(config).await;
+        assert_eq!(trader.send_buy_with_retries(&analysis, 0.1, "test").await.unwrap(), None);
+        assert_eq!(call_count(&calls, "sendTransaction"), 3);
+
+        // A honeypot gets a single attempt
+        let mut honeypot = crate::types::test_analysis();
+        honeypot.safety.checks.is_honeypot = true;
+        let (config, calls) = live_config(honeypot.token.address, 0, BLOCKHASH_NOT_FOUND).await;
+        let trader = test_trader(config).await;
+        assert_eq!(trader.send_buy_with_retries(&honeypot, 0.1, "test").await.unwrap(), None);
+        assert_eq!(call_count(&calls, "sendTransaction"), 1);
+
+        // So does a failure that would repeat, like a program error
+        let (config, calls) = live_config(analysis.token.address, 0, "Transaction simulation failed: custom program error: 0x1").await;
+        let trader = test_trader(config).await;
+        assert_eq!(trader.send_buy_with_retries(&analysis, 0.1, "test").await.unwrap(), None);
+        assert_eq!(call_count(&calls, "sendTransaction"), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn balance_check_reserves_rent_for_the_new_token_account() {
+        use crate::utils::solana_client::call_count;
+
+        let analysis = crate::types::test_analysis();
+        // 0.1 SOL buy plus 0.01 SOL fee reserve, short of the 0.00203928 SOL token account rent
+        for (balance_lamports, sends) in [(111_000_000, 0), (113_000_000, 1)] {
+            let (config, calls) = live_config(analysis.token.address, balance_lamports, "Transaction simulation failed: custom program error: 0x1").await;
+            let config = BotConfig { buy_amount_sol: 0.1, fee_reserve_sol: 0.01, ..config };
+            let trader = test_trader(config).await;
+
+            trader.execute_buy(&analysis, "test").await.unwrap();
+            assert_eq!(call_count(&calls, "getMinimumBalanceForRentExemption"), 1);
+            assert_eq!(call_count(&calls, "sendTransaction"), sends, "balance {}", balance_lamports);
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn buys_whose_fees_swamp_the_trade_are_skipped() {
+        use crate::utils::solana_client::call_count;
+
+        let analysis = crate::types::test_analysis();
+        // Token account rent alone is a fifth of a 0.01 SOL buy, but a fraction of a percent of 1 SOL
+        for (buy_amount_sol, sends) in [(0.01, 0), (1.0, 1)] {
+            let (config, calls) = live_config(analysis.token.address, 2_000_000_000, "Transaction simulation failed: custom program error: 0x1").await;
+            let config = BotConfig { buy_amount_sol, max_fee_ratio_pct: Some(10.0), ..config };
+            let trader = test_trader(config).await;
+
+            trader.execute_buy(&analysis, "test").await.unwrap();
+            assert_eq!(call_count(&calls, "sendTransaction"), sends, "buy of {} SOL", buy_amount_sol);
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn positions_carry_their_entry_tag_and_status_filters_by_it() {
+        let config = BotConfig {
+            trading_cooldown_ms: 0,
+            position_tags: [("copy_trade".to_string(), "copy-traded".to_string())].into(),
+            ..test_config()
+        };
+        let trader = test_trader(config).await;
+        let copied = crate::types::test_analysis();
+        let sniped = crate::types::test_analysis();
+        trader.execute_buy(&copied, "copy_trade").await.unwrap();
+        trader.execute_buy(&sniped, "sniper").await.unwrap();
+
+        let status = trader.status_with_tag(Some("copy-traded")).await;
+        let positions = status["positions"].as_array().unwrap();
+        assert_eq!(positions.len(), 1);
+        assert_eq!(positions[0]["token_address"], copied.token.address.to_string());
+        assert_eq!(positions[0]["tag"], "copy-traded");
+        assert_eq!(status["tags"], serde_json::json!({ "copy-traded": 1, "sniper": 1 }));
+
+        assert_eq!(trader.status_with_tag(Some("manual")).await["positions"], serde_json::json!([]));
+        assert_eq!(trader.status().await["positions"].as_array().unwrap().len(), 2);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn loss_streaks_shrink_the_buy_size_until_a_win_restores_it() {
+        let config = BotConfig {
+            loss_streak_threshold: Some(2),
+            loss_streak_size_factor: 0.5,
+            min_buy_amount_sol: 0.02,
+            ..test_config()
+        };
+        let trader = test_trader(config).await;
+        let round_trip = |proceeds_sol: f64| {
+            let position = test_position("LOSS");
+            for (trade_type, total_value) in [(TradeType::Buy, position.cost_basis_sol), (TradeType::Sell, proceeds_sol)] {
+                trader.storage.record_trade(&TradeResult {
+                    signature: String::new(),
+                    token_address: position.token_address,
+                    trade_type,
+                    amount: position.amount,
+                    price: 0.0,
+                    total_value,
+                    fee: 0.0,
+                    timestamp: Utc::now(),
+                    success: true,
+                    error: None,
+                    source: "test".to_string(),
+                    config_version: String::new(),
+                }).unwrap();
+            }
+            position
+        };
+
+        let mut sizes = Vec::new();
+        for _ in 0..4 {
+            trader.record_outcome(&round_trip(0.5)).await;
+            sizes.push(trader.streak_sized(0.1).await);
+        }
+        assert_eq!(sizes, [0.1, 0.05, 0.025, 0.02]);
+
+        trader.record_outcome(&round_trip(1.5)).await;
+        assert_eq!(trader.streak_sized(0.1).await, 0.1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn learned_priority_fee_survives_a_restart() {
+        let config = BotConfig {
+            adaptive_priority_fee: true,
+            adaptive_fee_min_lamports: 10_000,
+            max_priority_fee_lamports: 200_000,
+            storage_backend: StorageBackend::Json,
+            storage_path: temp_storage_path("learned-fee"),
+            ..test_config()
+        };
+        let trader = test_trader(config.clone()).await;
+        trader.learn_priority_fee(100_000, false);
+        trader.learn_priority_fee(125_000, true);
+        assert_eq!(trader.fee_tuner.fee(), Some(118_750));
+        drop(trader);
+
+        let restarted = test_trader(config).await;
+        assert_eq!(restarted.fee_tuner.fee(), Some(118_750));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn wallet_status_reports_each_seeded_wallet() {
+        use solana_sdk::signature::{Keypair, Signer};
+
+        let (trading, main) = (Keypair::new(), Keypair::new());
+        let balances = HashMap::from([(trading.pubkey().to_string(), 50_000_000u64), (main.pubkey().to_string(), 3_000_000_000)]);
+        let (rpc_url, _) = crate::utils::solana_client::mock_rpc(move |request, _| match request["method"].as_str() {
+            Some("getBalance") => serde_json::json!({
+                "result": { "context": { "slot": 1 }, "value": balances[request["params"][0].as_str().unwrap()] },
+            }),
+            _ => serde_json::json!({ "error": { "code": -32601, "message": "Method not found" } }),
+        }).await;
+        let config = BotConfig {
+            rpc_url,
+            private_key: Some(bs58::encode(trading.to_bytes()).into_string()),
+            main_wallet_private_key: Some(bs58::encode(main.to_bytes()).into_string()),
+            buy_amount_sol: 0.1,
+            fee_reserve_sol: 0.01,
+            ..test_config()
+        };
+        let trader = test_trader(config).await;
+
+        let status = trader.wallet_status().await;
+        assert_eq!(status["wallets"], serde_json::json!([
+            { "role": "trading", "address": trading.pubkey().to_string(), "balance_sol": 0.05, "low": true },
+            { "role": "main", "address": main.pubkey().to_string(), "balance_sol": 3.0 },
+        ]));
+        // Only the trading wallet's SOL above the fee reserve can fund buys
+        assert!((status["deployable_sol"].as_f64().unwrap() - 0.04).abs() < 1e-9);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn scout_sell_leaves_a_sell_in_progress_alone() {
+        use crate::utils::solana_client::call_count;
+
+        let position = test_position("SCT");
+        let (config, calls) = live_chain_config(held_accounts(&[&position]), 0, "Transaction simulation failed").await;
+        let config = BotConfig { scout_sell_percentage: 10.0, ..config };
+        let trader = test_trader(config.clone()).await;
+
+        // A panic sell holds the lock
+        *trader.is_selling.write().await = true;
+        assert!(!trader.verify_scout_sell(&position, &config).await);
+        assert_eq!(call_count(&calls, "sendTransaction"), 0);
+        assert!(*trader.is_selling.read().await);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn planned_buy_follows_balance_tiers_loss_streak_and_token_quantity() {
+        let analysis = crate::types::test_analysis();
+        let (config, _calls) = live_config(analysis.token.address, 10_000_000_000, "unused").await;
+        let config = BotConfig {
+            buy_amount_sol: 0.1,
+            balance_tiers: vec![config::BalanceTier { min_balance_sol: 5.0, buy_amount_sol: 2.0 }],
+            loss_streak_threshold: Some(1),
+            loss_streak_size_factor: 0.5,
+            ..config
+        };
+        let trader = test_trader(config.clone()).await;
+
+        // A 10 SOL wallet reaches the 2 SOL tier, not BUY_AMOUNT_SOL
+        assert_eq!(trader.planned_buy_amount_sol(&analysis).await, Some(2.0));
+
+        // The impact checks see the larger size
+        let mut metrics = analysis.metrics.clone();
+        crate::utils::token_analyzer::TokenAnalyzer::apply_price_impact(&mut metrics, &analysis.bonding_curve, 2.0);
+        let tiered_exit = metrics.estimated_exit_impact_pct;
+        crate::utils::token_analyzer::TokenAnalyzer::apply_price_impact(&mut metrics, &analysis.bonding_curve, config.buy_amount_sol);
+        assert!(tiered_exit > metrics.estimated_exit_impact_pct);
+
+        // A losing streak halves it
+        *trader.loss_streak.write().await = 1;
+        assert_eq!(trader.planned_buy_amount_sol(&analysis).await, Some(1.0));
+
+        // A fixed token quantity is costed from the curve, whatever the streak
+        let by_tokens = test_trader(BotConfig { buy_mode: BuyMode::ByTokens, buy_amount_tokens: 1_000_000_000_000, ..config }).await;
+        *by_tokens.loss_streak.write().await = 1;
+        assert_eq!(by_tokens.planned_buy_amount_sol(&analysis).await, by_tokens.token_buy_cost_sol(&analysis));
+        assert!(by_tokens.planned_buy_amount_sol(&analysis).await.is_some());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn batched_sell_leaves_a_sell_in_progress_alone() {
+        use crate::utils::solana_client::call_count;
+
+        let (first, second) = (test_position("BA1"), test_position("BA2"));
+        let (config, calls) = live_chain_config(held_accounts(&[&first, &second]), 0, "Transaction simulation failed").await;
+        let trader = test_trader(config).await;
+        let chunk: Vec<_> = [first, second].into_iter().map(|position| {
+            let leg = SellLeg {
+                token_address: position.token_address,
+                bonding_curve_address: position.bonding_curve_address,
+                creator: crate::types::test_analysis().bonding_curve.creator,
+                amount: position.amount,
+                min_sol_output: 0,
+            };
+            (position, SellReason::TakeProfit, leg, 1_000_000)
+        }).collect();
+
+        // Another sell claimed the lock after the batch was quoted
+        *trader.is_selling.write().await = true;
+        trader.send_batch_sell(&chunk).await.unwrap();
+        assert_eq!(call_count(&calls, "sendTransaction"), 0);
+        assert!(*trader.is_selling.read().await);
     }
 }
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use solana_sdk::pubkey::Pubkey;
 use chrono::{DateTime, Utc};
-use crate::config::constants::TokenSafetyStatus;
+use crate::config::TokenSafetyStatus;
 
 /// Token information
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,6 +30,7 @@ pub struct BondingCurveInfo {
     pub real_token_reserves: u64,
     pub token_total_supply: u64,
     pub complete: bool,
+    pub creator: Pubkey,
 }
 
 /// Token metrics
@@ -41,6 +42,23 @@ pub struct TokenMetrics {
     pub volume_24h: f64,
     pub price: f64,
     pub price_change_24h: f64,
+    pub estimated_price_impact_pct: f64,
+    /// Percentage the price falls when the position from the estimated buy is sold back
+    #[serde(default)]
+    pub estimated_exit_impact_pct: f64,
+    /// The curve reserves were degenerate, so `price` and `market_cap` are not meaningful
+    #[serde(default)]
+    pub price_indeterminate: bool,
+    #[serde(default)]
+    pub liquidity_usd: Option<f64>,
+    /// 24h volume in USD from an enrichment provider; `volume_24h` stays in SOL
+    #[serde(default)]
+    pub volume_24h_usd: Option<f64>,
+    #[serde(default)]
+    pub last_trade_at: Option<DateTime<Utc>>,
+    /// Signers of recent buys on the curve, when they were fetched
+    #[serde(default)]
+    pub recent_buyers: Option<Vec<Pubkey>>,
 }
 
 /// Token analysis result
@@ -59,6 +77,7 @@ pub struct TokenSafety {
     pub status: TokenSafetyStatus,
     pub score: u32,
     pub checks: SafetyChecks,
+    pub details: SafetyCheckResult,
 }
 
 /// Safety checks results
@@ -70,6 +89,11 @@ pub struct SafetyChecks {
     pub has_social_links: bool,
     pub creator_verified: bool,
     pub suspicious_creator: bool,
+    pub token_2022: bool,
+    pub transfer_fee_bps: Option<u16>,
+    /// Many distinct wallets bought in the creation slot, a sign of a coordinated bundle
+    #[serde(default)]
+    pub bundle_launch: bool,
 }
 
 /// Token opportunities
@@ -77,16 +101,25 @@ pub struct SafetyChecks {
 pub struct TokenOpportunities {
     pub score: u32,
     pub reasons: Vec<String>,
+    pub creator_sol_contributed: f64,
 }
 
 /// Position information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Position {
     pub token_address: Pubkey,
+    pub bonding_curve_address: Pubkey,
+    /// Curve creator; the default key until the next price poll for positions saved
+    /// before it was tracked
+    #[serde(default)]
+    pub creator: Pubkey,
     pub token_symbol: String,
+    pub entry_signature: String,
     pub amount: u64,
+    pub cost_basis_sol: f64,
     pub entry_price: f64,
     pub current_price: f64,
+    pub highest_price: f64,
     pub pnl: f64,
     pub pnl_percentage: f64,
     pub opened_at: DateTime<Utc>,
@@ -94,17 +127,181 @@ pub struct Position {
     pub take_profit_price: Option<f64>,
     pub stop_loss_price: Option<f64>,
     pub trailing_stop_price: Option<f64>,
+    #[serde(default)]
+    pub last_sol_reserves: u64,
+    /// Gross curve volume over the last `volume_window_secs`
+    #[serde(default)]
+    pub recent_volume_sol: f64,
+    #[serde(default)]
+    pub peak_volume_sol: f64,
+    /// Reserve movements sampled inside the volume window
+    #[serde(default)]
+    pub volume_samples: Vec<VolumeSample>,
+    /// Exit rules already applied, keyed by `ExitRule::key`
+    #[serde(default)]
+    pub fired_exit_rules: Vec<String>,
+    #[serde(default)]
+    pub cost_recovered: bool,
+    /// Individual buys making up the position, when lot accounting is enabled
+    #[serde(default)]
+    pub lots: Vec<Lot>,
+    #[serde(default)]
+    pub realized_pnl_sol: f64,
+    #[serde(default)]
+    pub source: String,
+    /// Strategy label from `BotConfig::position_tag`, for grouping in status
+    #[serde(default)]
+    pub tag: String,
+    pub safety_issues: Vec<String>,
     pub status: PositionStatus,
 }
 
+/// Tokens from one buy, consumed by sells in the configured lot order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Lot {
+    pub signature: String,
+    pub amount: u64,
+    pub cost_sol: f64,
+    pub opened_at: DateTime<Utc>,
+}
+
+/// SOL moved through a bonding curve between two checks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolumeSample {
+    pub at: DateTime<Utc>,
+    pub sol: f64,
+}
+
+/// Outcome of reconciling persisted positions with on-chain holdings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReconciliationReport {
+    pub matched: Vec<Pubkey>,
+    pub adjusted: Vec<Pubkey>,
+    pub closed: Vec<Pubkey>,
+    pub adopted: Vec<Pubkey>,
+    pub untracked: Vec<Pubkey>,
+}
+
+/// Reason a sell was triggered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SellReason {
+    TakeProfit,
+    StopLoss,
+    TrailingStop,
+    MaxLoss,
+    SafetyDegraded,
+    VolumeDrop,
+    ExitRule,
+    RecoverCost,
+    Migration,
+    ScoutFailed,
+    CreatorDumping,
+    Panic,
+    Manual,
+}
+
+impl SellReason {
+    /// Exits that must go through even at a poor or tiny price
+    pub fn is_hard_exit(self) -> bool {
+        matches!(
+            self,
+            SellReason::StopLoss
+                | SellReason::MaxLoss
+                | SellReason::SafetyDegraded
+                | SellReason::Migration
+                | SellReason::ScoutFailed
+                | SellReason::CreatorDumping
+                | SellReason::Panic
+        )
+    }
+}
+
+/// How much of a position a sell disposes of
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SellAmount {
+    /// This percentage of the held tokens
+    Percentage(f64),
+    /// Enough tokens to realize this much SOL at the current curve quote, capped at the position
+    SolTarget(f64),
+}
+
+impl From<f64> for SellAmount {
+    fn from(percentage: f64) -> Self {
+        SellAmount::Percentage(percentage)
+    }
+}
+
+impl std::fmt::Display for SellAmount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SellAmount::Percentage(percentage) => write!(f, "{}%", percentage),
+            SellAmount::SolTarget(sol) => write!(f, "{} SOL target", sol),
+        }
+    }
+}
+
 /// Position status
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PositionStatus {
+    Pending,
     Open,
     Closed,
     Partial,
 }
 
+/// Hypothetical position for a token rejected by the filters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShadowEntry {
+    pub token_address: Pubkey,
+    pub bonding_curve_address: Pubkey,
+    pub token_symbol: String,
+    pub rejection_reason: String,
+    pub entry_price: f64,
+    pub current_price: f64,
+    pub peak_price: f64,
+    pub hypothetical_pnl_percentage: f64,
+    pub rejected_at: DateTime<Utc>,
+}
+
+/// A paper-traded config variant's virtual account, as ranked on the leaderboard
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaperStanding {
+    pub profile: String,
+    pub balance_sol: f64,
+    pub open_value_sol: f64,
+    pub equity_sol: f64,
+    pub pnl_sol: f64,
+    pub trades: usize,
+    pub open_positions: usize,
+}
+
+/// A buy or sell that was signed and sent but not yet confirmed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingTransaction {
+    pub signature: String,
+    pub intent: TradeType,
+    pub token_address: Pubkey,
+    pub bonding_curve_address: Pubkey,
+    pub amount_sol: f64,
+    pub token_amount: u64,
+    pub price: f64,
+    pub source: String,
+    pub sent_at: DateTime<Utc>,
+    /// Further positions sold by the same transaction, when sells were batched
+    #[serde(default)]
+    pub batch_legs: Vec<PendingLeg>,
+}
+
+/// One more position sold by a batched pending sell
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingLeg {
+    pub token_address: Pubkey,
+    pub amount_sol: f64,
+    pub token_amount: u64,
+    pub price: f64,
+    pub source: String,
+}
+
 /// Trade result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TradeResult {
@@ -118,6 +315,59 @@ pub struct TradeResult {
     pub timestamp: DateTime<Utc>,
     pub success: bool,
     pub error: Option<String>,
+    #[serde(default)]
+    pub source: String,
+    /// `BotConfig::version` in effect when the trade was decided
+    #[serde(default)]
+    pub config_version: String,
+}
+
+/// Outcome of the token filters, naming the first gate a token failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FilterDecision {
+    Pass,
+    PriceIndeterminate,
+    SafetyScoreTooLow,
+    OpportunityScoreTooLow,
+    MarketCapTooLow,
+    MarketCapTooHigh,
+    PriceBelowBand,
+    PriceAboveBand,
+    InsufficientLiquidity,
+    Token2022NotAllowed,
+    PriceImpactTooHigh,
+    ExitImpactTooHigh,
+    CurveStale,
+    TooFewBuyers,
+    BundleLaunch,
+}
+
+impl FilterDecision {
+    /// Whether the token passed every filter
+    pub fn passed(&self) -> bool {
+        *self == FilterDecision::Pass
+    }
+
+    /// Human-readable rejection reason, or `None` when the token passed
+    pub fn reason(&self) -> Option<&'static str> {
+        match self {
+            FilterDecision::Pass => None,
+            FilterDecision::PriceIndeterminate => Some("price indeterminate"),
+            FilterDecision::SafetyScoreTooLow => Some("safety score too low"),
+            FilterDecision::OpportunityScoreTooLow => Some("opportunity score too low"),
+            FilterDecision::MarketCapTooLow => Some("market cap too low"),
+            FilterDecision::MarketCapTooHigh => Some("market cap too high"),
+            FilterDecision::PriceBelowBand => Some("price below buy band"),
+            FilterDecision::PriceAboveBand => Some("price above buy band"),
+            FilterDecision::InsufficientLiquidity => Some("insufficient liquidity"),
+            FilterDecision::Token2022NotAllowed => Some("Token-2022 mint not allowed"),
+            FilterDecision::PriceImpactTooHigh => Some("price impact too high"),
+            FilterDecision::ExitImpactTooHigh => Some("exit price impact too high"),
+            FilterDecision::CurveStale => Some("no recent curve trades"),
+            FilterDecision::TooFewBuyers => Some("too few unique buyers"),
+            FilterDecision::BundleLaunch => Some("bundled launch"),
+        }
+    }
 }
 
 /// Trade type
@@ -141,7 +391,40 @@ pub struct NewTokenEvent {
     pub token_address: Pubkey,
     pub bonding_curve_address: Pubkey,
     pub creator: Pubkey,
+    pub creator_sol_contributed: f64,
+    pub timestamp: DateTime<Utc>,
+    pub source: String,
+    /// When the WebSocket message carrying the event arrived, before parsing
+    #[serde(default)]
+    pub received_at: Option<DateTime<Utc>>,
+}
+
+/// Bonding curve completion detected, ahead of migration to the AMM
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationEvent {
+    pub token_address: Pubkey,
+    pub bonding_curve_address: Pubkey,
+    pub timestamp: DateTime<Utc>,
+    pub source: String,
+}
+
+/// New state of a watched bonding curve, pushed by an account subscription
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CurveUpdateEvent {
+    pub curve: BondingCurveInfo,
+    pub slot: u64,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// A sell on a bonding curve, decoded from its `TradeEvent`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SellActivityEvent {
+    pub token_address: Pubkey,
+    pub seller: Pubkey,
+    pub sol_amount: u64,
+    pub token_amount: u64,
     pub timestamp: DateTime<Utc>,
+    pub source: String,
 }
 
 /// Buy instruction parameters
@@ -150,10 +433,28 @@ pub struct BuyInstruction {
     pub token_address: Pubkey,
     pub bonding_curve_address: Pubkey,
     pub associated_bonding_curve: Pubkey,
+    pub user_token_account: Pubkey,
+    pub creator: Pubkey,
+    pub token_program: Pubkey,  // SPL Token or Token-2022, per the mint's owner
     pub amount: u64,  // Amount of tokens to buy
     pub max_sol_cost: u64,  // Maximum SOL to spend in lamports
 }
 
+/// Pump.fun AMM swap instruction parameters, shared by buys and sells
+#[derive(Debug, Clone)]
+pub struct AmmSwapInstruction {
+    pub token_address: Pubkey,
+    pub pool: Pubkey,
+    pub user_base_token_account: Pubkey,
+    pub user_quote_token_account: Pubkey,
+    pub pool_base_token_account: Pubkey,
+    pub pool_quote_token_account: Pubkey,
+    pub base_token_program: Pubkey,
+    pub creator: Pubkey,
+    pub base_amount: u64,  // Tokens to receive on a buy, or to sell
+    pub quote_limit: u64,  // Maximum WSOL to spend on a buy, or minimum to receive on a sell
+}
+
 /// Sell instruction parameters
 #[derive(Debug, Clone)]
 pub struct SellInstruction {
@@ -161,6 +462,8 @@ pub struct SellInstruction {
     pub bonding_curve_address: Pubkey,
     pub associated_bonding_curve: Pubkey,
     pub user_token_account: Pubkey,
+    pub creator: Pubkey,
+    pub token_program: Pubkey,  // SPL Token or Token-2022, per the mint's owner
     pub amount: u64,  // Amount of tokens to sell
     pub min_sol_output: u64,  // Minimum SOL to receive
 }
@@ -192,3 +495,114 @@ pub struct HealthStatus {
     pub active_positions: usize,
     pub simulation_mode: bool,
 }
+
+/// An open position of 1M tokens bought just now at 1e-6 SOL each
+#[cfg(test)]
+pub(crate) fn test_position(symbol: &str) -> Position {
+    let now = Utc::now();
+    Position {
+        token_address: Pubkey::new_unique(),
+        bonding_curve_address: Pubkey::new_unique(),
+        creator: Pubkey::new_unique(),
+        token_symbol: symbol.to_string(),
+        entry_signature: solana_sdk::signature::Signature::new_unique().to_string(),
+        amount: 1_000_000,
+        cost_basis_sol: 1.0,
+        entry_price: 1e-6,
+        current_price: 1e-6,
+        highest_price: 1e-6,
+        pnl: 0.0,
+        pnl_percentage: 0.0,
+        opened_at: now,
+        last_updated: now,
+        take_profit_price: None,
+        stop_loss_price: None,
+        trailing_stop_price: None,
+        last_sol_reserves: 0,
+        recent_volume_sol: 0.0,
+        peak_volume_sol: 0.0,
+        volume_samples: Vec::new(),
+        fired_exit_rules: Vec::new(),
+        cost_recovered: false,
+        lots: Vec::new(),
+        realized_pnl_sol: 0.0,
+        source: "pump_fun".to_string(),
+        tag: String::new(),
+        safety_issues: Vec::new(),
+        status: PositionStatus::Open,
+    }
+}
+
+/// An analysis of a fresh token that passes the default filters
+#[cfg(test)]
+pub(crate) fn test_analysis() -> TokenAnalysis {
+    let mint = Pubkey::new_unique();
+    let creator = Pubkey::new_unique();
+    TokenAnalysis {
+        token: TokenInfo {
+            address: mint,
+            name: "Test Token".to_string(),
+            symbol: "TEST".to_string(),
+            description: None,
+            image: None,
+            metadata_uri: None,
+            twitter: None,
+            telegram: None,
+            website: None,
+            creator,
+            created_at: Utc::now(),
+        },
+        bonding_curve: BondingCurveInfo {
+            address: Pubkey::new_unique(),
+            token_address: mint,
+            virtual_sol_reserves: 30_000_000_000,
+            virtual_token_reserves: 1_073_000_000_000_000,
+            real_sol_reserves: 0,
+            real_token_reserves: 793_100_000_000_000,
+            token_total_supply: 1_000_000_000_000_000,
+            complete: false,
+            creator,
+        },
+        metrics: TokenMetrics {
+            market_cap: 5_000.0,
+            liquidity: 30.0,
+            holders: 1,
+            volume_24h: 0.0,
+            price: 2.8e-8,
+            price_change_24h: 0.0,
+            estimated_price_impact_pct: 1.0,
+            estimated_exit_impact_pct: 1.0,
+            price_indeterminate: false,
+            liquidity_usd: None,
+            volume_24h_usd: None,
+            last_trade_at: None,
+            recent_buyers: None,
+        },
+        safety: TokenSafety {
+            status: TokenSafetyStatus::Safe,
+            score: 90,
+            checks: SafetyChecks {
+                has_lock: false,
+                mint_revoked: true,
+                is_honeypot: false,
+                has_social_links: false,
+                creator_verified: false,
+                suspicious_creator: false,
+                token_2022: false,
+                transfer_fee_bps: None,
+                bundle_launch: false,
+            },
+            details: SafetyCheckResult {
+                passed: true,
+                score: 90,
+                issues: Vec::new(),
+                recommendations: Vec::new(),
+            },
+        },
+        opportunities: TokenOpportunities {
+            score: 50,
+            reasons: Vec::new(),
+            creator_sol_contributed: 0.0,
+        },
+    }
+}
@@ -2,4 +2,16 @@ pub mod solana_client;
 pub mod transaction_builder;
 pub mod token_analyzer;
 pub mod safety_checker;
-pub mod wallet_manager;
+pub mod event_log;
+pub mod bonding_curve;
+pub mod pump_fun_pdas;
+pub mod token_program;
+pub mod storage;
+pub mod coordination;
+pub mod notifier;
+pub mod enrichment;
+pub mod run_report;
+pub mod remote_signer;
+pub mod latency;
+pub mod fee_tuner;
+pub mod telemetry;
@@ -0,0 +1,270 @@
+use solana_sdk::pubkey::Pubkey;
+use crate::{
+    config::constants::{LAMPORTS_PER_SOL, PUMP_AMM_FEE_BPS, PUMP_FUN_FEE_BPS, PUMP_FUN_TOKEN_DECIMALS},
+    types::BondingCurveInfo,
+};
+
+/// Size of a bonding curve account up to and including the creator
+const BONDING_CURVE_ACCOUNT_LEN: usize = 8 + 8 * 5 + 1 + 32;
+
+/// Decode a Pump.fun bonding curve account
+///
+/// The account does not store its mint, so the caller supplies it.
+pub fn decode(address: &Pubkey, token_address: &Pubkey, data: &[u8]) -> Result<BondingCurveInfo, Box<dyn std::error::Error>> {
+    if data.len() < BONDING_CURVE_ACCOUNT_LEN {
+        return Err("Bonding curve account data too short".into());
+    }
+
+    // Fields follow the 8-byte Anchor discriminator
+    let read_u64 = |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+    Ok(BondingCurveInfo {
+        address: *address,
+        token_address: *token_address,
+        virtual_token_reserves: read_u64(8),
+        virtual_sol_reserves: read_u64(16),
+        real_token_reserves: read_u64(24),
+        real_sol_reserves: read_u64(32),
+        token_total_supply: read_u64(40),
+        complete: data[48] != 0,
+        creator: Pubkey::try_from(&data[49..81])?,
+    })
+}
+
+/// Encode a bonding curve account the way the program lays it out, for tests
+#[cfg(test)]
+pub(crate) fn encode(curve: &BondingCurveInfo) -> Vec<u8> {
+    // Anchor discriminator of the BondingCurve account, which decoding skips
+    let mut data = vec![23, 183, 248, 55, 96, 216, 172, 96];
+    for value in [
+        curve.virtual_token_reserves,
+        curve.virtual_sol_reserves,
+        curve.real_token_reserves,
+        curve.real_sol_reserves,
+        curve.token_total_supply,
+    ] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    data.push(curve.complete as u8);
+    data.extend_from_slice(curve.creator.as_ref());
+    data
+}
+
+/// Spot price in SOL per token base unit from the curve reserves
+pub fn spot_price(curve: &BondingCurveInfo) -> f64 {
+    let virtual_sol = curve.virtual_sol_reserves as f64 / LAMPORTS_PER_SOL as f64;
+    let virtual_tokens = curve.virtual_token_reserves as f64;
+    let real_sol = curve.real_sol_reserves as f64 / LAMPORTS_PER_SOL as f64;
+    let real_tokens = curve.real_token_reserves as f64;
+
+    (virtual_sol + real_sol) / (virtual_tokens - real_tokens).max(1.0)
+}
+
+/// Price in SOL per whole token from a price per base unit
+pub fn price_per_token(price_per_unit: f64) -> f64 {
+    price_per_unit * 10f64.powi(PUMP_FUN_TOKEN_DECIMALS as i32)
+}
+
+/// Whole tokens in an amount of base units
+pub fn whole_tokens(amount: u64) -> f64 {
+    amount as f64 / 10f64.powi(PUMP_FUN_TOKEN_DECIMALS as i32)
+}
+
+/// How far the curve is toward graduation, as a percentage of the `graduation_lamports` it must raise
+pub fn progress_pct(curve: &BondingCurveInfo, graduation_lamports: u64) -> f64 {
+    if curve.complete {
+        return 100.0;
+    }
+    (curve.real_sol_reserves as f64 / graduation_lamports.max(1) as f64 * 100.0).min(100.0)
+}
+
+/// Tokens received for spending `quote_in` lamports in a Pump.fun AMM pool, after fees
+pub fn amm_buy_quote(base_reserves: u64, quote_reserves: u64, quote_in: u64) -> u64 {
+    let quote_after_fee = quote_in as u128 * (10_000 - PUMP_AMM_FEE_BPS) as u128 / 10_000;
+    let base_reserves = base_reserves as u128;
+    let quote_reserves = quote_reserves as u128;
+
+    if quote_reserves + quote_after_fee == 0 {
+        return 0;
+    }
+
+    (base_reserves * quote_after_fee / (quote_reserves + quote_after_fee)) as u64
+}
+
+/// Lamports received for selling `base_in` tokens into a Pump.fun AMM pool, after fees
+pub fn amm_sell_quote(base_reserves: u64, quote_reserves: u64, base_in: u64) -> u64 {
+    let base_reserves = base_reserves as u128;
+    let quote_reserves = quote_reserves as u128;
+
+    if base_reserves + base_in as u128 == 0 {
+        return 0;
+    }
+
+    let quote_out = quote_reserves * base_in as u128 / (base_reserves + base_in as u128);
+    (quote_out * (10_000 - PUMP_AMM_FEE_BPS) as u128 / 10_000) as u64
+}
+
+/// Spot price in SOL per token base unit from AMM pool reserves
+pub fn amm_spot_price(base_reserves: u64, quote_reserves: u64) -> f64 {
+    quote_reserves as f64 / LAMPORTS_PER_SOL as f64 / (base_reserves as f64).max(1.0)
+}
+
+/// Tokens received for spending `sol_in` lamports on the curve, after the protocol fee
+pub fn buy_quote(curve: &BondingCurveInfo, sol_in: u64) -> u64 {
+    let sol_after_fee = sol_in as u128 * (10_000 - PUMP_FUN_FEE_BPS) as u128 / 10_000;
+    let virtual_sol = curve.virtual_sol_reserves as u128;
+    let virtual_tokens = curve.virtual_token_reserves as u128;
+
+    if virtual_sol + sol_after_fee == 0 {
+        return 0;
+    }
+
+    // Constant product: tokens_out = T * dx / (S + dx)
+    (virtual_tokens * sol_after_fee / (virtual_sol + sol_after_fee)) as u64
+}
+
+/// Lamports to spend for at least `tokens_out` from the curve, including the protocol fee
+///
+/// Returns `None` when the curve does not hold that many tokens.
+pub fn buy_cost_quote(curve: &BondingCurveInfo, tokens_out: u64) -> Option<u64> {
+    let virtual_sol = curve.virtual_sol_reserves as u128;
+    let virtual_tokens = curve.virtual_token_reserves as u128;
+    let tokens_out = tokens_out as u128;
+
+    if tokens_out >= virtual_tokens {
+        return None;
+    }
+
+    // Inverse of the constant product buy, rounded up: dx = S * dy / (T - dy)
+    let sol_after_fee = (virtual_sol * tokens_out).div_ceil(virtual_tokens - tokens_out);
+    let sol_in = (sol_after_fee * 10_000).div_ceil((10_000 - PUMP_FUN_FEE_BPS) as u128);
+    u64::try_from(sol_in).ok()
+}
+
+/// Percentage the spot price rises after spending `sol_in` lamports on the curve
+pub fn price_impact_pct(curve: &BondingCurveInfo, sol_in: u64) -> f64 {
+    let sol_after_fee = sol_in as f64 * (10_000 - PUMP_FUN_FEE_BPS) as f64 / 10_000.0;
+    let virtual_sol = curve.virtual_sol_reserves as f64;
+
+    if virtual_sol <= 0.0 {
+        return 0.0;
+    }
+
+    // Constant product: price scales with the square of the SOL reserve growth
+    let growth = (virtual_sol + sol_after_fee) / virtual_sol;
+    (growth * growth - 1.0) * 100.0
+}
+
+/// Percentage the spot price falls when the tokens bought for `sol_in` lamports are sold back
+///
+/// The sell runs against the curve as it stands after the buy, so this is the drop an
+/// exit of the whole position would cause if nobody else traded in between.
+pub fn exit_impact_pct(curve: &BondingCurveInfo, sol_in: u64) -> f64 {
+    let tokens = buy_quote(curve, sol_in) as f64;
+    let tokens_after_buy = curve.virtual_token_reserves as f64 - tokens;
+
+    if tokens_after_buy <= 0.0 {
+        return 100.0;
+    }
+
+    // Constant product: price scales with the square of the token reserve shrinkage
+    let shrink = tokens_after_buy / (tokens_after_buy + tokens);
+    (1.0 - shrink * shrink) * 100.0
+}
+
+/// Lamports received for selling `tokens_in` on the curve, after the protocol fee
+pub fn sell_quote(curve: &BondingCurveInfo, tokens_in: u64) -> u64 {
+    let virtual_sol = curve.virtual_sol_reserves as u128;
+    let virtual_tokens = curve.virtual_token_reserves as u128;
+    let tokens_in = tokens_in as u128;
+
+    if virtual_tokens + tokens_in == 0 {
+        return 0;
+    }
+
+    // Constant product: sol_out = S * dy / (T + dy)
+    let sol_out = virtual_sol * tokens_in / (virtual_tokens + tokens_in);
+    (sol_out * (10_000 - PUMP_FUN_FEE_BPS) as u128 / 10_000) as u64
+}
+
+/// Tokens to sell on the curve to receive at least `sol_out` lamports after the protocol fee
+///
+/// Returns `None` when the curve cannot pay out that much SOL.
+pub fn sell_tokens_for_sol(curve: &BondingCurveInfo, sol_out: u64) -> Option<u64> {
+    let virtual_sol = curve.virtual_sol_reserves as u128;
+    let virtual_tokens = curve.virtual_token_reserves as u128;
+    let sol_before_fee = (sol_out as u128 * 10_000).div_ceil((10_000 - PUMP_FUN_FEE_BPS) as u128);
+
+    if sol_before_fee >= virtual_sol {
+        return None;
+    }
+
+    // Inverse of the constant product sell, rounded up: dy = T * dx / (S - dx)
+    let tokens = (virtual_tokens * sol_before_fee).div_ceil(virtual_sol - sol_before_fee);
+    u64::try_from(tokens).ok()
+}
+
+/// Apply a slippage tolerance (in basis points) to a quoted output
+pub fn min_output_with_slippage(quote: u64, slippage_bps: u64) -> u64 {
+    let keep_bps = 10_000u64.saturating_sub(slippage_bps) as u128;
+    (quote as u128 * keep_bps / 10_000) as u64
+}
+
+/// Apply a slippage tolerance (in basis points) to a maximum input cost
+pub fn max_input_with_slippage(cost: u64, slippage_bps: u64) -> u64 {
+    let total_bps = 10_000u128 + slippage_bps as u128;
+    (cost as u128 * total_bps / 10_000).min(u64::MAX as u128) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn amm_sell_quote_pays_out_less_fees_than_the_spot_value() {
+        let base_reserves = 200_000_000_000_000;
+        let quote_reserves = 85 * LAMPORTS_PER_SOL;
+        let amount = 1_000_000_000;
+
+        let quote = amm_sell_quote(base_reserves, quote_reserves, amount);
+        let spot_value = (amm_spot_price(base_reserves, quote_reserves) * amount as f64 * LAMPORTS_PER_SOL as f64) as u64;
+        assert!(quote < spot_value);
+        assert!(quote as f64 > spot_value as f64 * 0.99);
+
+        // Selling back what a buy returns recovers less than was spent
+        let bought = amm_buy_quote(base_reserves, quote_reserves, LAMPORTS_PER_SOL);
+        assert!(amm_sell_quote(base_reserves - bought, quote_reserves + LAMPORTS_PER_SOL, bought) < LAMPORTS_PER_SOL);
+
+        assert_eq!(amm_sell_quote(0, 0, 0), 0);
+    }
+
+    #[test]
+    fn holding_value_converts_base_units_to_whole_tokens() {
+        assert_eq!(whole_tokens(1_500_000), 1.5);
+
+        // 2M whole tokens at 3e-8 SOL each
+        let price_per_unit = 3e-14;
+        let value = whole_tokens(2_000_000_000_000) * price_per_token(price_per_unit);
+        assert!((value - 0.06).abs() < 1e-12);
+    }
+
+    #[test]
+    fn sell_min_output_follows_the_curve_quote_not_the_spot_value() {
+        let curve = crate::types::test_analysis().bonding_curve;
+        let amount = 100_000_000_000_000;
+        let slippage_bps = 500;
+
+        // 30 SOL * 1e14 / (1.073e15 + 1e14) lamports, less the 1% fee
+        let quote = sell_quote(&curve, amount);
+        assert_eq!(quote, 2_531_969_309);
+        let quoted_min = min_output_with_slippage(quote, slippage_bps);
+        assert_eq!(quoted_min, 2_405_370_843);
+
+        // Valuing the sell at spot ignores its price impact and fee, demanding more than the curve pays
+        let naive_min = min_output_with_slippage(
+            (amount as f64 * spot_price(&curve) * LAMPORTS_PER_SOL as f64) as u64,
+            slippage_bps,
+        );
+        assert!(naive_min > quote);
+        assert!(quoted_min < quote);
+    }
+}
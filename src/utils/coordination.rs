@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use solana_sdk::pubkey::Pubkey;
+use crate::config::BotConfig;
+
+/// Prefix for claim keys, so a shared Redis can host other data
+const CLAIM_KEY_PREFIX: &str = "pumpfun-sniper:claim:";
+
+/// Claims on mints shared between bot instances, so only one of them buys each token
+///
+/// Implementations may block; async callers run them on the blocking pool.
+pub trait Coordinator: Send + Sync {
+    /// Claim a mint for `ttl`; false when another instance already holds the claim
+    fn try_claim(&self, mint: &Pubkey, owner: &str, ttl: Duration) -> Result<bool, Box<dyn std::error::Error>>;
+
+    /// Extend a claim this instance holds by `ttl`; false when it is no longer ours
+    fn renew(&self, mint: &Pubkey, owner: &str, ttl: Duration) -> Result<bool, Box<dyn std::error::Error>>;
+
+    /// Release a claim this instance holds, e.g. after a failed buy
+    fn release(&self, mint: &Pubkey, owner: &str) -> Result<(), Box<dyn std::error::Error>>;
+}
+
+/// Open the coordinator selected in the configuration, if any
+pub fn open(config: &BotConfig) -> Result<Option<Box<dyn Coordinator>>, Box<dyn std::error::Error>> {
+    match &config.redis_url {
+        Some(url) => Ok(Some(Box::new(RedisCoordinator::open(url)?))),
+        None => Ok(None),
+    }
+}
+
+fn claim_key(mint: &Pubkey) -> String {
+    format!("{}{}", CLAIM_KEY_PREFIX, mint)
+}
+
+/// In-process claims, equivalent to a single-instance fleet; stands in for Redis in tests
+#[derive(Default)]
+pub struct MemoryCoordinator {
+    claims: Mutex<HashMap<String, (String, Instant)>>,
+}
+
+impl Coordinator for MemoryCoordinator {
+    fn try_claim(&self, mint: &Pubkey, owner: &str, ttl: Duration) -> Result<bool, Box<dyn std::error::Error>> {
+        let mut claims = self.claims.lock().unwrap();
+        let key = claim_key(mint);
+        if let Some((_, expires)) = claims.get(&key) {
+            if *expires > Instant::now() {
+                return Ok(false);
+            }
+        }
+        claims.insert(key, (owner.to_string(), Instant::now() + ttl));
+        Ok(true)
+    }
+
+    fn renew(&self, mint: &Pubkey, owner: &str, ttl: Duration) -> Result<bool, Box<dyn std::error::Error>> {
+        let mut claims = self.claims.lock().unwrap();
+        match claims.get_mut(&claim_key(mint)) {
+            Some((holder, expires)) if holder == owner && *expires > Instant::now() => {
+                *expires = Instant::now() + ttl;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    fn release(&self, mint: &Pubkey, owner: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let mut claims = self.claims.lock().unwrap();
+        let key = claim_key(mint);
+        if claims.get(&key).is_some_and(|(holder, _)| holder == owner) {
+            claims.remove(&key);
+        }
+        Ok(())
+    }
+}
+
+/// Claims stored as expiring Redis keys shared by every instance
+pub struct RedisCoordinator {
+    client: redis::Client,
+    connection: Mutex<Option<redis::Connection>>,
+}
+
+impl RedisCoordinator {
+    /// Connect to the Redis server at `url`
+    pub fn open(url: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let client = redis::Client::open(url)?;
+        let connection = client.get_connection()?;
+        Ok(Self {
+            client,
+            connection: Mutex::new(Some(connection)),
+        })
+    }
+
+    /// Run a command on the cached connection, reconnecting once if it was dropped
+    fn query<T: redis::FromRedisValue>(&self, cmd: &redis::Cmd) -> Result<T, Box<dyn std::error::Error>> {
+        let mut connection = self.connection.lock().unwrap();
+        if let Some(conn) = connection.as_mut() {
+            match cmd.query(conn) {
+                Ok(value) => return Ok(value),
+                Err(e) if !e.is_connection_dropped() && !e.is_io_error() => return Err(e.into()),
+                Err(_) => *connection = None,
+            }
+        }
+
+        let mut conn = self.client.get_connection()?;
+        let value = cmd.query(&mut conn)?;
+        *connection = Some(conn);
+        Ok(value)
+    }
+}
+
+impl Coordinator for RedisCoordinator {
+    fn try_claim(&self, mint: &Pubkey, owner: &str, ttl: Duration) -> Result<bool, Box<dyn std::error::Error>> {
+        // SET NX only succeeds for the first instance; PX lets abandoned claims lapse
+        let reply: Option<String> = self.query(
+            redis::cmd("SET")
+                .arg(claim_key(mint))
+                .arg(owner)
+                .arg("NX")
+                .arg("PX")
+                .arg(ttl.as_millis() as u64),
+        )?;
+        Ok(reply.is_some())
+    }
+
+    fn renew(&self, mint: &Pubkey, owner: &str, ttl: Duration) -> Result<bool, Box<dyn std::error::Error>> {
+        // Extend only our own claim, atomically
+        const RENEW_SCRIPT: &str =
+            "if redis.call('GET', KEYS[1]) == ARGV[1] then return redis.call('PEXPIRE', KEYS[1], ARGV[2]) else return 0 end";
+        let renewed: i64 = self.query(
+            redis::cmd("EVAL")
+                .arg(RENEW_SCRIPT)
+                .arg(1)
+                .arg(claim_key(mint))
+                .arg(owner)
+                .arg(ttl.as_millis() as u64),
+        )?;
+        Ok(renewed == 1)
+    }
+
+    fn release(&self, mint: &Pubkey, owner: &str) -> Result<(), Box<dyn std::error::Error>> {
+        // Delete only our own claim, atomically
+        const RELEASE_SCRIPT: &str =
+            "if redis.call('GET', KEYS[1]) == ARGV[1] then return redis.call('DEL', KEYS[1]) else return 0 end";
+        let _: i64 = self.query(
+            redis::cmd("EVAL")
+                .arg(RELEASE_SCRIPT)
+                .arg(1)
+                .arg(claim_key(mint))
+                .arg(owner),
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use crate::config::{EnrichmentProvider, SharedConfig};
+
+/// How long a provider response is reused for the same mint
+const ENRICHMENT_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Market data for a mint from an external provider; fields it lacks are `None`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Enrichment {
+    pub liquidity_usd: Option<f64>,
+    pub volume_24h_usd: Option<f64>,
+    pub price_change_24h_pct: Option<f64>,
+    pub holders: Option<u32>,
+    pub risk_flags: Vec<String>,
+}
+
+impl Enrichment {
+    /// Parse a provider response body
+    pub fn parse(provider: EnrichmentProvider, body: &serde_json::Value) -> Self {
+        match provider {
+            EnrichmentProvider::DexScreener => {
+                // One entry per pair; the deepest pool is the most representative
+                let pair = body.as_array()
+                    .and_then(|pairs| {
+                        pairs.iter().max_by(|a, b| {
+                            let liquidity = |pair: &serde_json::Value| pair["liquidity"]["usd"].as_f64().unwrap_or(0.0);
+                            liquidity(a).total_cmp(&liquidity(b))
+                        })
+                    })
+                    .cloned()
+                    .unwrap_or_default();
+
+                Enrichment {
+                    liquidity_usd: pair["liquidity"]["usd"].as_f64(),
+                    volume_24h_usd: pair["volume"]["h24"].as_f64(),
+                    price_change_24h_pct: pair["priceChange"]["h24"].as_f64(),
+                    holders: None,
+                    risk_flags: Vec::new(),
+                }
+            }
+            EnrichmentProvider::Birdeye => {
+                let data = &body["data"];
+                let mut risk_flags = Vec::new();
+                if data["freezeable"].as_bool() == Some(true) {
+                    risk_flags.push("freeze authority set".to_string());
+                }
+                if data["mutableMetadata"].as_bool() == Some(true) {
+                    risk_flags.push("metadata is mutable".to_string());
+                }
+
+                Enrichment {
+                    liquidity_usd: data["liquidity"].as_f64(),
+                    volume_24h_usd: data["v24hUSD"].as_f64(),
+                    price_change_24h_pct: data["priceChange24hPercent"].as_f64(),
+                    holders: data["holder"].as_u64().map(|holders| holders as u32),
+                    risk_flags,
+                }
+            }
+        }
+    }
+}
+
+/// Fetches and caches enrichment data from the configured provider
+pub struct Enricher {
+    config: SharedConfig,
+    http: reqwest::Client,
+    cache: Mutex<HashMap<Pubkey, (Enrichment, Instant)>>,
+}
+
+impl Enricher {
+    /// Create a new enricher
+    pub fn new(config: SharedConfig) -> Result<Self, Box<dyn std::error::Error>> {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_millis(config.load().enrichment_timeout_ms))
+            .build()?;
+        Ok(Self {
+            config,
+            http,
+            cache: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Provider data for a mint, or `None` when enrichment is not configured
+    pub async fn fetch(&self, mint: &Pubkey) -> Result<Option<Enrichment>, Box<dyn std::error::Error>> {
+        let config = self.config.load_full();
+        let provider = match config.enrichment_provider {
+            Some(provider) => provider,
+            None => return Ok(None),
+        };
+        let url = config.enrichment_url.as_deref()
+            .unwrap_or(provider.default_url())
+            .replace("{mint}", &mint.to_string());
+
+        if let Some((enrichment, fetched_at)) = self.cache.lock().unwrap().get(mint) {
+            if fetched_at.elapsed() < ENRICHMENT_CACHE_TTL {
+                return Ok(Some(enrichment.clone()));
+            }
+        }
+
+        let mut request = self.http.get(&url).header("x-chain", "solana");
+        if let Some(api_key) = &config.enrichment_api_key {
+            request = request.header("X-API-KEY", api_key);
+        }
+        let body: serde_json::Value = request.send().await?.error_for_status()?.json().await?;
+        let enrichment = Enrichment::parse(provider, &body);
+
+        let mut cache = self.cache.lock().unwrap();
+        cache.retain(|_, (_, fetched_at)| fetched_at.elapsed() < ENRICHMENT_CACHE_TTL);
+        cache.insert(*mint, (enrichment.clone(), Instant::now()));
+        Ok(Some(enrichment))
+    }
+}
@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::sync::mpsc;
+use std::thread::JoinHandle;
+use chrono::{DateTime, Utc};
+use solana_sdk::pubkey::Pubkey;
+use crate::types::{NewTokenEvent, TokenAnalysis};
+
+/// Single entry in the event log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventRecord {
+    pub timestamp: DateTime<Utc>,
+    pub event: LoggedEvent,
+}
+
+/// Pipeline stages captured by the event log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LoggedEvent {
+    RawNotification {
+        text: String,
+    },
+    NewToken {
+        event: NewTokenEvent,
+    },
+    Analysis {
+        analysis: Box<TokenAnalysis>,
+    },
+    FilterDecision {
+        token_address: Pubkey,
+        passed: bool,
+        reason: Option<String>,
+    },
+    TradeOutcome {
+        token_address: Pubkey,
+        success: bool,
+        error: Option<String>,
+    },
+}
+
+/// Append-only JSONL event log for post-mortem debugging
+///
+/// Records are handed to a writer thread, so recording never waits on the disk.
+pub struct EventLog {
+    writer: Option<(mpsc::Sender<String>, JoinHandle<()>)>,
+}
+
+impl EventLog {
+    /// Open the event log, or create a no-op log when no path is configured
+    pub fn new(path: Option<&str>) -> Result<Self, Box<dyn std::error::Error>> {
+        let writer = match path {
+            Some(path) => {
+                let file = OpenOptions::new().create(true).append(true).open(path)?;
+                let (sender, receiver) = mpsc::channel();
+                let handle = std::thread::Builder::new()
+                    .name("event-log-writer".to_string())
+                    .spawn(move || Self::write_lines(file, receiver))?;
+                Some((sender, handle))
+            }
+            None => None,
+        };
+
+        Ok(Self { writer })
+    }
+
+    /// Write queued lines until the log is dropped, flushing whenever the queue drains
+    fn write_lines(file: File, receiver: mpsc::Receiver<String>) {
+        let mut file = BufWriter::new(file);
+        while let Ok(line) = receiver.recv() {
+            let mut result = writeln!(file, "{}", line);
+            while let (Ok(()), Ok(line)) = (&result, receiver.try_recv()) {
+                result = writeln!(file, "{}", line);
+            }
+            if let Err(e) = result.and_then(|_| file.flush()) {
+                tracing::warn!("Failed to write event log: {}", e);
+            }
+        }
+    }
+
+    /// Check whether events are being recorded
+    pub fn is_enabled(&self) -> bool {
+        self.writer.is_some()
+    }
+
+    /// Queue an event for the log
+    pub fn record(&self, event: LoggedEvent) {
+        let sender = match &self.writer {
+            Some((sender, _)) => sender,
+            None => return,
+        };
+
+        let record = EventRecord {
+            timestamp: Utc::now(),
+            event,
+        };
+
+        match serde_json::to_string(&record) {
+            Ok(line) => {
+                if sender.send(line).is_err() {
+                    tracing::warn!("Event log writer has stopped; dropping record");
+                }
+            }
+            Err(e) => tracing::warn!("Failed to serialize event log record: {}", e),
+        }
+    }
+
+    /// Read all records from a previously written event log
+    pub fn read(path: &str) -> Result<Vec<EventRecord>, Box<dyn std::error::Error>> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut records = Vec::new();
+
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            records.push(serde_json::from_str(&line)?);
+        }
+
+        Ok(records)
+    }
+}
+
+impl Drop for EventLog {
+    /// Let the writer finish the queued records before the log goes away
+    fn drop(&mut self) {
+        if let Some((sender, handle)) = self.writer.take() {
+            drop(sender);
+            let _ = handle.join();
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use std::sync::Mutex;
+
+/// Storage key the learned compute unit price is persisted under
+pub const LEARNED_FEE_KEY: &str = "learned_priority_fee";
+
+/// Fraction the fee is lowered after a send lands at it
+const LANDED_DECREASE: f64 = 0.05;
+
+/// Fraction the fee is raised after a send fails to land at it
+const DROPPED_INCREASE: f64 = 0.25;
+
+/// Learns the lowest compute unit price that still lands transactions
+///
+/// Every landed send nudges the fee down a little and every dropped send pushes it up
+/// further, so the estimate settles just above the price that reliably lands.
+pub struct FeeTuner {
+    learned: Mutex<Option<u64>>,
+}
+
+impl FeeTuner {
+    /// Start from a previously learned fee, if any
+    pub fn new(learned: Option<u64>) -> Self {
+        Self {
+            learned: Mutex::new(learned),
+        }
+    }
+
+    /// The learned fee, or `None` before any outcome was recorded
+    pub fn fee(&self) -> Option<u64> {
+        *self.learned.lock().unwrap()
+    }
+
+    /// Adjust the learned fee from whether a send at `fee_paid` landed, within `min..=max`
+    ///
+    /// Returns the new learned fee.
+    pub fn record(&self, fee_paid: u64, landed: bool, min: u64, max: u64) -> u64 {
+        let factor = if landed { 1.0 - LANDED_DECREASE } else { 1.0 + DROPPED_INCREASE };
+        let next = ((fee_paid as f64 * factor).round() as u64).clamp(min, max.max(min));
+        *self.learned.lock().unwrap() = Some(next);
+        next
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fee_falls_on_landings_and_rises_on_drops_within_bounds() {
+        let tuner = FeeTuner::new(None);
+        assert_eq!(tuner.fee(), None);
+
+        assert_eq!(tuner.record(100_000, true, 10_000, 200_000), 95_000);
+        assert_eq!(tuner.record(95_000, false, 10_000, 200_000), 118_750);
+        assert_eq!(tuner.fee(), Some(118_750));
+
+        // Repeated drops stop at the ceiling, repeated landings at the floor
+        for _ in 0..10 {
+            tuner.record(tuner.fee().unwrap(), false, 10_000, 200_000);
+        }
+        assert_eq!(tuner.fee(), Some(200_000));
+        for _ in 0..100 {
+            tuner.record(tuner.fee().unwrap(), true, 10_000, 200_000);
+        }
+        assert_eq!(tuner.fee(), Some(10_000));
+    }
+}
@@ -0,0 +1,188 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use solana_sdk::pubkey::Pubkey;
+use crate::config::SharedConfig;
+
+/// Recent per-stage durations of the detection-to-send pipeline, for percentiles in status
+pub struct LatencyStats {
+    config: SharedConfig,
+    samples: Mutex<HashMap<&'static str, VecDeque<Duration>>>,
+    token_stages: Mutex<VecDeque<(Pubkey, &'static str, Duration)>>,
+}
+
+impl LatencyStats {
+    /// Create empty stats keeping `latency_window` samples per stage
+    pub fn new(config: SharedConfig) -> Self {
+        Self {
+            config,
+            samples: Mutex::new(HashMap::new()),
+            token_stages: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Record a stage timed outside the detection path, such as a buy's build and send
+    ///
+    /// The duration is also held for the token's breakdown until it finishes. Only the
+    /// most recent `latency_window` of these are held, so unfinished tokens do not pile up.
+    pub fn record_for(&self, token_address: &Pubkey, stage: &'static str, duration: Duration) {
+        self.record(stage, duration);
+        let window = self.config.load().latency_window.max(1);
+        let mut token_stages = self.token_stages.lock().unwrap();
+        token_stages.push_back((*token_address, stage, duration));
+        while token_stages.len() > window {
+            token_stages.pop_front();
+        }
+    }
+
+    /// Remove and return the stages held for a token, in the order they were recorded
+    fn take_token_stages(&self, token_address: &Pubkey) -> Vec<(&'static str, Duration)> {
+        let mut token_stages = self.token_stages.lock().unwrap();
+        let mut taken = Vec::new();
+        token_stages.retain(|(address, stage, duration)| {
+            if address == token_address {
+                taken.push((*stage, *duration));
+                false
+            } else {
+                true
+            }
+        });
+        taken
+    }
+
+    /// Record one duration for a stage, evicting the oldest beyond the window
+    pub fn record(&self, stage: &'static str, duration: Duration) {
+        let window = self.config.load().latency_window.max(1);
+        let mut samples = self.samples.lock().unwrap();
+        let stage_samples = samples.entry(stage).or_default();
+        stage_samples.push_back(duration);
+        while stage_samples.len() > window {
+            stage_samples.pop_front();
+        }
+    }
+
+    /// Sample count and p50/p90/p99 in milliseconds per stage
+    pub fn status(&self) -> serde_json::Value {
+        let samples = self.samples.lock().unwrap();
+        let mut stages = serde_json::Map::new();
+        for (stage, stage_samples) in samples.iter() {
+            let mut sorted: Vec<Duration> = stage_samples.iter().copied().collect();
+            sorted.sort();
+            let percentile = |pct: usize| {
+                sorted.get((sorted.len() - 1) * pct / 100).map_or(0.0, |d| d.as_secs_f64() * 1000.0)
+            };
+            stages.insert(stage.to_string(), serde_json::json!({
+                "count": sorted.len(),
+                "p50_ms": percentile(50),
+                "p90_ms": percentile(90),
+                "p99_ms": percentile(99),
+            }));
+        }
+        stages.into()
+    }
+}
+
+/// Stage timings for one token, logged together once its decision is made
+pub struct LatencyBreakdown {
+    token_address: Pubkey,
+    stages: Vec<(&'static str, Duration)>,
+    last_mark: Instant,
+}
+
+impl LatencyBreakdown {
+    /// Start timing a token from now
+    pub fn start(token_address: Pubkey) -> Self {
+        Self {
+            token_address,
+            stages: Vec::new(),
+            last_mark: Instant::now(),
+        }
+    }
+
+    /// Add a stage measured elsewhere, such as before the event reached the pipeline
+    pub fn add(&mut self, stage: &'static str, duration: Duration) {
+        self.stages.push((stage, duration));
+    }
+
+    /// Close a stage at the time elapsed since the previous mark
+    pub fn mark(&mut self, stage: &'static str) {
+        let now = Instant::now();
+        self.stages.push((stage, now - self.last_mark));
+        self.last_mark = now;
+    }
+
+    /// Log the breakdown at debug and feed each stage into `stats`
+    ///
+    /// Stages the trader recorded for this token with `record_for` are appended, already
+    /// counted in the aggregates. Returns every stage in the breakdown.
+    pub fn finish(&mut self, stats: &LatencyStats) -> Vec<(&'static str, Duration)> {
+        let mut stages = std::mem::take(&mut self.stages);
+        for (stage, duration) in &stages {
+            stats.record(stage, *duration);
+        }
+        stages.extend(stats.take_token_stages(&self.token_address));
+
+        // Repeated stages, such as a retried send, are listed in order rather than merged
+        let breakdown: Vec<serde_json::Value> = stages
+            .iter()
+            .map(|(stage, duration)| serde_json::json!({ "stage": stage, "ms": duration.as_secs_f64() * 1000.0 }))
+            .collect();
+        tracing::debug!(
+            token = %self.token_address,
+            breakdown = %serde_json::Value::from(breakdown),
+            "Decision latency for {}",
+            self.token_address
+        );
+        stages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{self, BotConfig};
+
+    #[test]
+    fn breakdown_captures_every_stage_of_a_simulated_token() {
+        let stats = LatencyStats::new(config::shared(BotConfig::default()));
+        let token = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+
+        let mut breakdown = LatencyBreakdown::start(token);
+        breakdown.add("parse", Duration::from_micros(300));
+        breakdown.add("dispatch", Duration::from_micros(200));
+        for stage in ["analysis", "onchain_signals", "enrichment", "filter"] {
+            std::thread::sleep(Duration::from_millis(1));
+            breakdown.mark(stage);
+        }
+        stats.record_for(&token, "build", Duration::from_millis(2));
+        stats.record_for(&other, "build", Duration::from_millis(9));
+        stats.record_for(&token, "send", Duration::from_millis(3));
+
+        let stages = breakdown.finish(&stats);
+        let names: Vec<&str> = stages.iter().map(|(stage, _)| *stage).collect();
+        assert_eq!(
+            names,
+            ["parse", "dispatch", "analysis", "onchain_signals", "enrichment", "filter", "build", "send"]
+        );
+        assert!(stages.iter().all(|(_, duration)| !duration.is_zero()));
+
+        // Aggregates hold every stage, including the other token's build
+        let status = stats.status();
+        assert_eq!(status["build"]["count"], 2);
+        assert_eq!(status["filter"]["count"], 1);
+        assert_eq!(stats.take_token_stages(&other).len(), 1);
+    }
+
+    #[test]
+    fn held_token_stages_are_bounded_by_the_window() {
+        let config = BotConfig { latency_window: 2, ..Default::default() };
+        let stats = LatencyStats::new(config::shared(config));
+        let token = Pubkey::new_unique();
+
+        for _ in 0..5 {
+            stats.record_for(&token, "send", Duration::from_millis(1));
+        }
+        assert_eq!(stats.take_token_stages(&token).len(), 2);
+    }
+}
@@ -0,0 +1,84 @@
+use serde_json::json;
+use crate::{config::SharedConfig, types::TokenAnalysis};
+
+/// Sends alerts to the log and, when configured, to a Telegram chat
+pub struct Notifier {
+    config: SharedConfig,
+    http: reqwest::Client,
+}
+
+impl Notifier {
+    /// Create a new notifier
+    pub fn new(config: SharedConfig) -> Self {
+        Self {
+            config,
+            http: reqwest::Client::new(),
+        }
+    }
+
+    /// Send a message to every configured channel
+    pub async fn send(&self, message: &str) -> Result<(), Box<dyn std::error::Error>> {
+        tracing::info!("[NOTIFY] {}", message);
+
+        let config = self.config.load_full();
+        let (token, chat_id) = match (&config.telegram_bot_token, &config.telegram_chat_id) {
+            (Some(token), Some(chat_id)) => (token, chat_id),
+            _ => return Ok(()),
+        };
+
+        self.http
+            .post(format!("https://api.telegram.org/bot{}/sendMessage", token))
+            .json(&json!({
+                "chat_id": chat_id,
+                "text": message,
+                "disable_web_page_preview": true,
+            }))
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Describe a token that passed the filters, with its score, metrics and links
+    pub fn token_alert(analysis: &TokenAnalysis) -> String {
+        Self::describe(analysis, "passed filters")
+    }
+
+    /// Describe a token that was bought, including why it scored as an opportunity
+    pub fn buy_alert(analysis: &TokenAnalysis) -> String {
+        Self::describe(analysis, "bought")
+    }
+
+    fn describe(analysis: &TokenAnalysis, headline: &str) -> String {
+        let token = &analysis.token;
+        let metrics = &analysis.metrics;
+
+        let mut lines = vec![
+            format!("{} ({}) {}", token.name, token.symbol, headline),
+            format!("Mint: {}", token.address),
+            format!(
+                "Safety: {} ({:?}) | Opportunity: {}",
+                analysis.safety.score, analysis.safety.status, analysis.opportunities.score
+            ),
+            format!(
+                "Market cap: ${:.0} | Liquidity: {:.2} SOL | Price impact: {:.2}%",
+                metrics.market_cap, metrics.liquidity, metrics.estimated_price_impact_pct
+            ),
+        ];
+
+        if !analysis.opportunities.reasons.is_empty() {
+            lines.push(format!("Reasons: {}", analysis.opportunities.reasons.join(", ")));
+        }
+        if !analysis.safety.details.issues.is_empty() {
+            lines.push(format!("Issues: {}", analysis.safety.details.issues.join(", ")));
+        }
+
+        lines.push(format!("https://pump.fun/{}", token.address));
+        for link in [&token.website, &token.twitter, &token.telegram].into_iter().flatten() {
+            lines.push(link.clone());
+        }
+
+        lines.join("\n")
+    }
+}
@@ -0,0 +1,106 @@
+use solana_sdk::pubkey::Pubkey;
+use crate::config::constants::*;
+
+/// Global config account: `["global"]`
+pub fn global() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[GLOBAL_SEED.as_bytes()], &PUMP_FUN_PROGRAM_ID)
+}
+
+/// Anchor event authority: `["__event_authority"]`
+pub fn event_authority() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[EVENT_AUTHORITY_SEED.as_bytes()], &PUMP_FUN_PROGRAM_ID)
+}
+
+/// Bonding curve of a mint: `["bonding-curve", mint]`
+pub fn bonding_curve(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[BONDING_CURVE_SEED.as_bytes(), mint.as_ref()], &PUMP_FUN_PROGRAM_ID)
+}
+
+/// Creator fee vault: `["creator-vault", creator]`
+pub fn creator_vault(creator: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CREATOR_VAULT_SEED.as_bytes(), creator.as_ref()], &PUMP_FUN_PROGRAM_ID)
+}
+
+/// Authority that creates a graduated mint's AMM pool: `["pool-authority", mint]`
+pub fn pool_authority(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[POOL_AUTHORITY_SEED.as_bytes(), mint.as_ref()], &PUMP_FUN_PROGRAM_ID)
+}
+
+/// Canonical AMM pool of a graduated mint against WSOL: `["pool", 0u16, pool_authority, mint, wsol]`
+pub fn amm_pool(mint: &Pubkey) -> (Pubkey, u8) {
+    let (authority, _) = pool_authority(mint);
+    let quote_mint = spl_token::native_mint::id();
+    Pubkey::find_program_address(
+        &[
+            POOL_SEED.as_bytes(),
+            &0u16.to_le_bytes(),
+            authority.as_ref(),
+            mint.as_ref(),
+            quote_mint.as_ref(),
+        ],
+        &PUMP_AMM_PROGRAM_ID,
+    )
+}
+
+/// Metaplex metadata account of a mint: `["metadata", metadata_program, mint]`
+pub fn metadata(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[METADATA_SEED.as_bytes(), TOKEN_METADATA_PROGRAM_ID.as_ref(), mint.as_ref()],
+        &TOKEN_METADATA_PROGRAM_ID,
+    )
+}
+
+/// AMM global config: `["global_config"]`
+pub fn amm_global_config() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AMM_GLOBAL_CONFIG_SEED.as_bytes()], &PUMP_AMM_PROGRAM_ID)
+}
+
+/// AMM event authority: `["__event_authority"]`
+pub fn amm_event_authority() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[EVENT_AUTHORITY_SEED.as_bytes()], &PUMP_AMM_PROGRAM_ID)
+}
+
+/// AMM coin creator vault authority: `["creator_vault", creator]`
+pub fn amm_creator_vault_authority(creator: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AMM_CREATOR_VAULT_SEED.as_bytes(), creator.as_ref()], &PUMP_AMM_PROGRAM_ID)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn program_wide_pdas_match_mainnet() {
+        assert_eq!(global().0.to_string(), "4wTV1YmiEkRvAtNtsSGPtUrqRYQMe5SKy2uB4Jjaxnjf");
+        assert_eq!(event_authority().0.to_string(), "Ce6TQqeHC9p8KetsN6JsjHK7UTZk7nasjjnr7XxXp9F1");
+        assert_eq!(amm_global_config().0.to_string(), "ADyA8hdefvWN2dbGGWFotbzWxrAvLW83WG6QCVXvJKqw");
+        assert_eq!(amm_event_authority().0.to_string(), "GS4CU59F31iL7aR2Q8zVS8DRrcRnXX1yjQ66TqNVQnaR");
+    }
+
+    #[test]
+    fn mint_pdas_use_their_documented_seeds() {
+        let mint: Pubkey = "2qEHjDLDLbuBgRYvsxhc5D6uDWAivNFZGan56P1tpump".parse().unwrap();
+        let creator = Pubkey::new_unique();
+        let derive = |seeds: &[&[u8]], program: &Pubkey| Pubkey::find_program_address(seeds, program);
+
+        assert_eq!(bonding_curve(&mint), derive(&[b"bonding-curve", mint.as_ref()], &PUMP_FUN_PROGRAM_ID));
+        assert_eq!(creator_vault(&creator), derive(&[b"creator-vault", creator.as_ref()], &PUMP_FUN_PROGRAM_ID));
+        assert_eq!(pool_authority(&mint), derive(&[b"pool-authority", mint.as_ref()], &PUMP_FUN_PROGRAM_ID));
+        assert_eq!(
+            amm_creator_vault_authority(&creator),
+            derive(&[b"creator_vault", creator.as_ref()], &PUMP_AMM_PROGRAM_ID)
+        );
+        assert_eq!(
+            metadata(&mint),
+            derive(&[b"metadata", TOKEN_METADATA_PROGRAM_ID.as_ref(), mint.as_ref()], &TOKEN_METADATA_PROGRAM_ID)
+        );
+
+        // The canonical pool is index 0 under the mint's pool authority, quoted in WSOL
+        let (authority, _) = pool_authority(&mint);
+        let wsol = spl_token::native_mint::id();
+        assert_eq!(
+            amm_pool(&mint),
+            derive(&[b"pool", &[0, 0], authority.as_ref(), mint.as_ref(), wsol.as_ref()], &PUMP_AMM_PROGRAM_ID)
+        );
+    }
+}
@@ -0,0 +1,156 @@
+use base64::Engine;
+use serde_json::json;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::Signature,
+    signer::{Signer, SignerError},
+};
+use std::io::{BufRead, BufReader, Write};
+use std::time::Duration;
+
+/// How long a signing request may wait on the daemon
+const SIGNER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Signer that delegates to an out-of-process signing daemon over a Unix socket
+///
+/// Each request is one line of JSON: `{"method":"pubkey"}` answered with `{"pubkey":"<base58>"}`,
+/// and `{"method":"sign","message":"<base64>"}` answered with `{"signature":"<base58>"}`.
+/// Errors come back as `{"error":"..."}`. The key never enters this process.
+pub struct RemoteSigner {
+    socket_path: String,
+    pubkey: Pubkey,
+}
+
+impl RemoteSigner {
+    /// Connect to the daemon and fetch the public key it signs for
+    pub fn connect(socket_path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let response = Self::request(socket_path, &json!({ "method": "pubkey" }))?;
+        let pubkey = response["pubkey"]
+            .as_str()
+            .ok_or("Signer response is missing pubkey")?
+            .parse()?;
+
+        tracing::info!("Using external signer at {} for {}", socket_path, pubkey);
+        Ok(Self {
+            socket_path: socket_path.to_string(),
+            pubkey,
+        })
+    }
+
+    /// Send one request and read its one-line response
+    #[cfg(unix)]
+    fn request(socket_path: &str, request: &serde_json::Value) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+        let mut stream = std::os::unix::net::UnixStream::connect(socket_path)?;
+        stream.set_read_timeout(Some(SIGNER_TIMEOUT))?;
+        stream.set_write_timeout(Some(SIGNER_TIMEOUT))?;
+        writeln!(stream, "{}", request)?;
+
+        let mut line = String::new();
+        BufReader::new(stream).read_line(&mut line)?;
+        let response: serde_json::Value = serde_json::from_str(&line)?;
+        if let Some(error) = response["error"].as_str() {
+            return Err(format!("External signer refused: {}", error).into());
+        }
+        Ok(response)
+    }
+
+    #[cfg(not(unix))]
+    fn request(_socket_path: &str, _request: &serde_json::Value) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+        Err("External signers require Unix domain sockets".into())
+    }
+}
+
+impl Signer for RemoteSigner {
+    fn try_pubkey(&self) -> Result<Pubkey, SignerError> {
+        Ok(self.pubkey)
+    }
+
+    fn try_sign_message(&self, message: &[u8]) -> Result<Signature, SignerError> {
+        let request = json!({
+            "method": "sign",
+            "message": base64::engine::general_purpose::STANDARD.encode(message),
+        });
+        let response = Self::request(&self.socket_path, &request)
+            .map_err(|e| SignerError::Connection(e.to_string()))?;
+
+        let signature: Signature = response["signature"]
+            .as_str()
+            .ok_or_else(|| SignerError::Custom("Signer response is missing signature".to_string()))?
+            .parse()
+            .map_err(|e| SignerError::Custom(format!("Invalid signature from signer: {}", e)))?;
+
+        // A daemon holding the wrong key would otherwise produce transactions the cluster rejects
+        if !signature.verify(self.pubkey.as_ref(), message) {
+            return Err(SignerError::Custom("Signer returned a signature that does not verify".to_string()));
+        }
+        Ok(signature)
+    }
+
+    fn is_interactive(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use solana_sdk::{hash::Hash, signature::Keypair, system_instruction, transaction::Transaction};
+
+    /// Serve the signing protocol for `keypair` on a fresh socket, claiming to sign for `claimed`
+    fn mock_daemon(keypair: Keypair, claimed: Pubkey) -> String {
+        let path = std::env::temp_dir().join(format!("signer-{}-{}.sock", std::process::id(), Pubkey::new_unique()));
+        let _ = std::fs::remove_file(&path);
+        let listener = std::os::unix::net::UnixListener::bind(&path).unwrap();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut line = String::new();
+                BufReader::new(&stream).read_line(&mut line).unwrap();
+                let request: serde_json::Value = serde_json::from_str(&line).unwrap();
+                let response = match request["method"].as_str() {
+                    Some("pubkey") => json!({ "pubkey": claimed.to_string() }),
+                    Some("sign") => {
+                        let message = base64::engine::general_purpose::STANDARD
+                            .decode(request["message"].as_str().unwrap())
+                            .unwrap();
+                        json!({ "signature": keypair.sign_message(&message).to_string() })
+                    }
+                    _ => json!({ "error": "unknown method" }),
+                };
+                writeln!(stream, "{}", response).unwrap();
+            }
+        });
+        path.to_string_lossy().into_owned()
+    }
+
+    fn transfer(from: &Pubkey) -> Transaction {
+        let instruction = system_instruction::transfer(from, &Pubkey::new_unique(), 1_000);
+        Transaction::new_with_payer(&[instruction], Some(from))
+    }
+
+    #[test]
+    fn remote_signature_matches_the_in_memory_keypair() {
+        let keypair = Keypair::new();
+        let local = Keypair::from_bytes(&keypair.to_bytes()).unwrap();
+        let signer = RemoteSigner::connect(&mock_daemon(keypair, local.pubkey())).unwrap();
+        assert_eq!(signer.pubkey(), local.pubkey());
+
+        let blockhash = Hash::new_unique();
+        let mut remote_signed = transfer(&local.pubkey());
+        let mut local_signed = remote_signed.clone();
+        remote_signed.try_sign(&[&signer], blockhash).unwrap();
+        local_signed.try_sign(&[&local], blockhash).unwrap();
+
+        assert_eq!(remote_signed.signatures, local_signed.signatures);
+        assert!(remote_signed.verify().is_ok());
+    }
+
+    #[test]
+    fn signature_from_the_wrong_key_is_rejected() {
+        let claimed = Pubkey::new_unique();
+        let signer = RemoteSigner::connect(&mock_daemon(Keypair::new(), claimed)).unwrap();
+
+        let mut transaction = transfer(&claimed);
+        assert!(transaction.try_sign(&[&signer], Hash::new_unique()).is_err());
+    }
+}
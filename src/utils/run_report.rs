@@ -0,0 +1,90 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use crate::types::{Position, PositionStatus, TradeResult, TradeType};
+
+/// Machine-readable summary of one bot run, rewritten in place as the run progresses
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunReport {
+    pub started_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// `false` until the bot stops cleanly; a crashed run leaves its last checkpoint
+    pub complete: bool,
+    pub config_version: String,
+    pub buys: usize,
+    pub sells: usize,
+    pub failed_trades: usize,
+    pub sol_spent: f64,
+    pub sol_received: f64,
+    pub open_positions: usize,
+    pub unrealized_pnl_sol: f64,
+    pub filters: serde_json::Value,
+    pub errors: u64,
+}
+
+impl RunReport {
+    /// Aggregate the trades made since `started_at` and the positions still held
+    pub fn build(
+        started_at: DateTime<Utc>,
+        config_version: String,
+        trades: &[TradeResult],
+        positions: &[Position],
+        filters: serde_json::Value,
+        errors: u64,
+    ) -> Self {
+        let mut report = RunReport {
+            started_at,
+            updated_at: Utc::now(),
+            complete: false,
+            config_version,
+            buys: 0,
+            sells: 0,
+            failed_trades: 0,
+            sol_spent: 0.0,
+            sol_received: 0.0,
+            open_positions: 0,
+            unrealized_pnl_sol: 0.0,
+            filters,
+            errors,
+        };
+
+        for trade in trades.iter().filter(|trade| trade.timestamp >= started_at) {
+            if !trade.success {
+                report.failed_trades += 1;
+                continue;
+            }
+            match trade.trade_type {
+                TradeType::Buy => {
+                    report.buys += 1;
+                    report.sol_spent += trade.total_value;
+                }
+                TradeType::Sell => {
+                    report.sells += 1;
+                    report.sol_received += trade.total_value;
+                }
+            }
+        }
+
+        for position in positions.iter().filter(|position| position.status != PositionStatus::Closed) {
+            report.open_positions += 1;
+            report.unrealized_pnl_sol += position.pnl;
+        }
+
+        report
+    }
+
+    /// File the report for a run started at `started_at` is written to
+    pub fn path(dir: &str, started_at: DateTime<Utc>) -> PathBuf {
+        Path::new(dir).join(format!("{}.json", started_at.format("%Y%m%dT%H%M%SZ")))
+    }
+
+    /// Write the report, replacing the previous checkpoint atomically
+    pub fn write(&self, dir: &str) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        std::fs::create_dir_all(dir)?;
+        let path = Self::path(dir, self.started_at);
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_vec_pretty(self)?)?;
+        std::fs::rename(&tmp, &path)?;
+        Ok(path)
+    }
+}
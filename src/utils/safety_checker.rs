@@ -0,0 +1,103 @@
+use crate::types::{SafetyCheckResult, SafetyChecks};
+
+/// Minimum safety score for a token to pass
+pub const MIN_SAFETY_SCORE: u32 = 60;
+
+/// Translates raw safety checks into human-readable issues and recommendations
+pub struct SafetyChecker;
+
+impl SafetyChecker {
+    /// Build a structured result explaining a token's safety score
+    pub fn evaluate(checks: &SafetyChecks, score: u32) -> SafetyCheckResult {
+        let mut issues = Vec::new();
+        let mut recommendations = Vec::new();
+
+        if !checks.has_lock {
+            issues.push("Bonding curve complete, liquidity no longer locked".to_string());
+            recommendations.push("Verify liquidity on the destination AMM before trading".to_string());
+        }
+        if !checks.mint_revoked {
+            issues.push("Mint authority not revoked".to_string());
+            recommendations.push("Avoid until the creator revokes mint authority".to_string());
+        }
+        if checks.is_honeypot {
+            issues.push("Token appears to be a honeypot".to_string());
+            recommendations.push("Do not buy: sells are likely blocked".to_string());
+        }
+        if !checks.has_social_links {
+            issues.push("No social links".to_string());
+            recommendations.push("Check for community presence manually".to_string());
+        }
+        if !checks.creator_verified {
+            issues.push("Creator not verified".to_string());
+        }
+        if checks.suspicious_creator {
+            issues.push("Creator wallet flagged as suspicious".to_string());
+            recommendations.push("Review the creator's previous launches".to_string());
+        }
+        if let Some(bps) = checks.transfer_fee_bps {
+            issues.push(format!("Token-2022 transfer fee of {:.2}%", bps as f64 / 100.0));
+            recommendations.push("Account for the fee on both buy and sell".to_string());
+        }
+
+        SafetyCheckResult {
+            passed: score >= MIN_SAFETY_SCORE,
+            score,
+            issues,
+            recommendations,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Breaks one check, leaving the rest passing
+    type Failure = fn(&mut SafetyChecks);
+
+    fn passing_checks() -> SafetyChecks {
+        SafetyChecks {
+            has_lock: true,
+            mint_revoked: true,
+            is_honeypot: false,
+            has_social_links: true,
+            creator_verified: true,
+            suspicious_creator: false,
+            token_2022: false,
+            transfer_fee_bps: None,
+            bundle_launch: false,
+        }
+    }
+
+    #[test]
+    fn issues_list_exactly_the_failed_checks() {
+        let clean = SafetyChecker::evaluate(&passing_checks(), 100);
+        assert!(clean.passed);
+        assert!(clean.issues.is_empty() && clean.recommendations.is_empty());
+
+        let failures: [(Failure, &str); 7] = [
+            (|checks| checks.has_lock = false, "Bonding curve complete, liquidity no longer locked"),
+            (|checks| checks.mint_revoked = false, "Mint authority not revoked"),
+            (|checks| checks.is_honeypot = true, "Token appears to be a honeypot"),
+            (|checks| checks.has_social_links = false, "No social links"),
+            (|checks| checks.creator_verified = false, "Creator not verified"),
+            (|checks| checks.suspicious_creator = true, "Creator wallet flagged as suspicious"),
+            (|checks| checks.transfer_fee_bps = Some(250), "Token-2022 transfer fee of 2.50%"),
+        ];
+        for (fail, issue) in failures {
+            let mut checks = passing_checks();
+            fail(&mut checks);
+            assert_eq!(SafetyChecker::evaluate(&checks, 100).issues, [issue]);
+        }
+
+        // Every failure is listed, in check order, and the score alone decides passing
+        let mut checks = passing_checks();
+        checks.mint_revoked = false;
+        checks.is_honeypot = true;
+        let result = SafetyChecker::evaluate(&checks, MIN_SAFETY_SCORE - 1);
+        assert_eq!(result.issues, ["Mint authority not revoked", "Token appears to be a honeypot"]);
+        assert_eq!(result.recommendations.len(), 2);
+        assert!(!result.passed);
+    }
+}
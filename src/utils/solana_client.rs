@@ -1,20 +1,81 @@
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::{
     commitment_config::CommitmentConfig,
+    hash::Hash,
     pubkey::Pubkey,
     signature::{Keypair, Signer},
     transaction::Transaction,
     system_instruction,
     native_token::LAMPORTS_PER_SOL,
 };
+use std::collections::HashMap;
 use std::sync::Arc;
-use crate::config::{BotConfig, constants};
+use std::time::{Duration, Instant};
+use crate::{
+    config::{BotConfig, Cluster, constants},
+    utils::token_program::{self, MintInfo},
+};
+
+/// How long a block engine send waits for its transaction to confirm; a blockhash's validity
+const BLOCK_ENGINE_CONFIRM_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Where a sent transaction stands at a commitment level
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureState {
+    /// Landed and succeeded
+    Confirmed,
+    /// Not seen at this commitment yet, or dropped
+    Pending,
+    /// Landed but failed on-chain
+    Failed(String),
+}
+
+/// Whether transaction logs show a buy executed by the Pump.fun program itself
+///
+/// Tracks the invocation stack so a `Buy` logged by any other program does not count.
+pub fn is_pump_fun_buy(logs: &serde_json::Value) -> bool {
+    let pump_fun = constants::PUMP_FUN_PROGRAM_ID.to_string();
+    let mut stack: Vec<&str> = Vec::new();
+
+    for log in logs.as_array().into_iter().flatten().filter_map(|log| log.as_str()) {
+        if let Some(rest) = log.strip_prefix("Program ") {
+            let mut words = rest.split_whitespace();
+            match (words.next(), words.next()) {
+                (Some(program), Some("invoke")) => stack.push(program),
+                (Some(_), Some("success" | "failed:")) => {
+                    stack.pop();
+                }
+                _ => {}
+            }
+        }
+        if log == "Program log: Instruction: Buy" && stack.last() == Some(&pump_fun.as_str()) {
+            return true;
+        }
+    }
+    false
+}
+
+/// Signs for the trading wallet, either in memory or out of process
+pub type TradingSigner = Box<dyn Signer + Send + Sync>;
+
+/// One in-flight mint account fetch, shared by every caller asking for that mint
+type MintFetch = Arc<tokio::sync::OnceCell<Result<MintInfo, String>>>;
 
 /// Solana client wrapper for the bot
 pub struct SolanaClient {
     rpc_client: RpcClient,
-    keypair: Option<Keypair>,
+    nonblocking_rpc: solana_client::nonblocking::rpc_client::RpcClient,
+    signer: Option<TradingSigner>,
     main_keypair: Option<Keypair>,
+    debug_dump_failed_tx: bool,
+    read_only: bool,
+    mints: std::sync::RwLock<HashMap<Pubkey, MintInfo>>,
+    mint_fetches: std::sync::Mutex<HashMap<Pubkey, MintFetch>>,
+    blockhash: std::sync::RwLock<Option<(Hash, Instant)>>,
+    blockhash_max_age: Duration,
+    metadata_http: reqwest::Client,
+    block_engine_url: Option<String>,
+    block_engine_http: reqwest::Client,
 }
 
 impl SolanaClient {
@@ -29,9 +90,19 @@ impl SolanaClient {
             commitment_config,
         );
 
-        // Initialize keypairs
-        let keypair = if let Some(private_key) = &config.private_key {
-            Some(Self::keypair_from_base58(private_key)?)
+        Self::check_cluster(&rpc_client, config.cluster);
+
+        // Fan-out lookups go through the nonblocking client so they can run concurrently
+        let nonblocking_rpc = solana_client::nonblocking::rpc_client::RpcClient::new_with_commitment(
+            config.rpc_url.clone(),
+            commitment_config,
+        );
+
+        // An external signer keeps the trading key out of this process
+        let signer: Option<TradingSigner> = if let Some(socket_path) = &config.external_signer_socket {
+            Some(Box::new(crate::utils::remote_signer::RemoteSigner::connect(socket_path)?))
+        } else if let Some(private_key) = &config.private_key {
+            Some(Box::new(Self::keypair_from_base58(private_key)?))
         } else {
             None
         };
@@ -44,19 +115,46 @@ impl SolanaClient {
 
         Ok(Self {
             rpc_client,
-            keypair,
+            nonblocking_rpc,
+            signer,
             main_keypair,
+            debug_dump_failed_tx: config.debug_dump_failed_tx,
+            read_only: config.read_only,
+            mints: std::sync::RwLock::new(HashMap::new()),
+            mint_fetches: std::sync::Mutex::new(HashMap::new()),
+            blockhash: std::sync::RwLock::new(None),
+            blockhash_max_age: Duration::from_millis(config.blockhash_max_age_ms),
+            metadata_http: reqwest::Client::builder()
+                .timeout(Duration::from_millis(config.metadata_fetch_timeout_ms))
+                .build()?,
+            block_engine_url: config.jito_block_engine_url.clone(),
+            block_engine_http: reqwest::Client::builder()
+                .timeout(Duration::from_secs(5))
+                .build()?,
         })
     }
 
+    /// Whether the client refuses to send transactions
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Refuse the operation when read-only mode is enabled
+    fn ensure_writable(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if self.read_only {
+            return Err("Read-only mode: refusing to send transaction".into());
+        }
+        Ok(())
+    }
+
     /// Get the RPC client
     pub fn rpc_client(&self) -> &RpcClient {
         &self.rpc_client
     }
 
-    /// Get the trading keypair
-    pub fn keypair(&self) -> Option<&Keypair> {
-        self.keypair.as_ref()
+    /// Get the trading wallet signer
+    pub fn signer(&self) -> Option<&(dyn Signer + Send + Sync)> {
+        self.signer.as_deref()
     }
 
     /// Get the main wallet keypair
@@ -66,9 +164,9 @@ impl SolanaClient {
 
     /// Get the public key of the trading wallet
     pub fn public_key(&self) -> Result<Pubkey, Box<dyn std::error::Error>> {
-        self.keypair
+        self.signer
             .as_ref()
-            .map(|kp| kp.pubkey())
+            .map(|signer| signer.pubkey())
             .ok_or_else(|| "No trading wallet configured".into())
     }
 
@@ -84,30 +182,329 @@ impl SolanaClient {
         self.get_balance(&pubkey).await
     }
 
+    /// Get a mint's decoded account, cached after the first fetch
+    ///
+    /// Mint authority can be revoked later, so callers needing it fresh should
+    /// use [`Self::fetch_mint_info`].
+    pub async fn get_mint_info(&self, mint: &Pubkey) -> Result<MintInfo, Box<dyn std::error::Error>> {
+        if let Some(info) = self.mints.read().unwrap().get(mint) {
+            return Ok(info.clone());
+        }
+        self.fetch_mint_info(mint).await
+    }
+
+    /// Fetch and decode a mint account, refreshing the cache
+    ///
+    /// Concurrent fetches of the same mint share a single RPC request.
+    pub async fn fetch_mint_info(&self, mint: &Pubkey) -> Result<MintInfo, Box<dyn std::error::Error>> {
+        let fetch = Arc::clone(self.mint_fetches.lock().unwrap().entry(*mint).or_default());
+        let result = fetch.get_or_init(|| async {
+            let result = self.load_mint_info(mint).map_err(|e| e.to_string());
+            // Later callers start a fresh fetch; those already waiting share this one
+            self.mint_fetches.lock().unwrap().remove(mint);
+            result
+        }).await;
+        result.clone().map_err(Into::into)
+    }
+
+    /// Read a mint account from the RPC and cache its decoded fields
+    fn load_mint_info(&self, mint: &Pubkey) -> Result<MintInfo, Box<dyn std::error::Error>> {
+        let account = self.rpc_client.get_account(mint)?;
+        let info = token_program::decode_mint(&account.owner, &account.data)?;
+        self.mints.write().unwrap().insert(*mint, info.clone());
+        Ok(info)
+    }
+
+    /// Read an account's data, or `None` if the RPC node has no such account
+    pub async fn get_account_data(&self, address: &Pubkey) -> Result<Option<Vec<u8>>, Box<dyn std::error::Error>> {
+        let response = self.rpc_client.get_account_with_commitment(address, self.rpc_client.commitment())?;
+        Ok(response.value.map(|account| account.data))
+    }
+
+    /// Token program owning a mint, classic SPL Token or Token-2022
+    pub async fn token_program_for(&self, mint: &Pubkey) -> Result<Pubkey, Box<dyn std::error::Error>> {
+        Ok(self.get_mint_info(mint).await?.token_program)
+    }
+
+    /// The trading wallet's associated token account for a mint
+    pub async fn token_account_for(&self, mint: &Pubkey) -> Result<Pubkey, Box<dyn std::error::Error>> {
+        let owner = self.public_key()?;
+        let program = self.token_program_for(mint).await?;
+        Ok(token_program::associated_token_address(&owner, mint, &program))
+    }
+
+    /// State of the trading wallet's existing token account for a mint
+    pub async fn token_account_state(&self, mint: &Pubkey) -> Result<token_program::TokenAccountState, Box<dyn std::error::Error>> {
+        let wallet = self.public_key()?;
+        let token_account = self.token_account_for(mint).await?;
+        let response = self.rpc_client.get_account_with_commitment(&token_account, self.rpc_client.commitment())?;
+        Ok(match response.value {
+            Some(account) => token_program::inspect_token_account(&account.owner, &account.data, &wallet, mint),
+            None => token_program::TokenAccountState::Missing,
+        })
+    }
+
+    /// Get the trading wallet's token balance for a mint, in base units
+    pub async fn get_token_balance(&self, mint: &Pubkey) -> Result<u64, Box<dyn std::error::Error>> {
+        let token_account = self.token_account_for(mint).await?;
+
+        match self.rpc_client.get_token_account_balance(&token_account) {
+            Ok(balance) => Ok(balance.amount.parse()?),
+            Err(e) => {
+                // A missing token account simply holds nothing; any other failure is unknown
+                let account = self.rpc_client
+                    .get_account_with_commitment(&token_account, self.rpc_client.commitment())?
+                    .value;
+                match account {
+                    None => Ok(0),
+                    Some(_) => Err(e.into()),
+                }
+            }
+        }
+    }
+
+    /// Get every non-empty token balance held by the trading wallet, by mint, across both token programs
+    pub async fn get_token_holdings(&self) -> Result<std::collections::HashMap<Pubkey, u64>, Box<dyn std::error::Error>> {
+        use solana_account_decoder::UiAccountData;
+        use solana_client::rpc_request::TokenAccountsFilter;
+
+        let owner = self.public_key()?;
+        let mut accounts = Vec::new();
+        for program in [constants::TOKEN_PROGRAM_ID, constants::TOKEN_2022_PROGRAM_ID] {
+            accounts.extend(self.rpc_client.get_token_accounts_by_owner(
+                &owner,
+                TokenAccountsFilter::ProgramId(program),
+            )?);
+        }
+
+        let mut holdings = std::collections::HashMap::new();
+        for keyed in accounts {
+            let info = match &keyed.account.data {
+                UiAccountData::Json(parsed) => &parsed.parsed["info"],
+                _ => continue,
+            };
+            let mint = info["mint"].as_str().and_then(|mint| mint.parse::<Pubkey>().ok());
+            let amount = info["tokenAmount"]["amount"].as_str().and_then(|amount| amount.parse::<u64>().ok());
+            if let (Some(mint), Some(amount)) = (mint, amount) {
+                if amount > 0 {
+                    *holdings.entry(mint).or_insert(0) += amount;
+                }
+            }
+        }
+
+        Ok(holdings)
+    }
+
+    /// Rent the trading wallet must fund to create its token account for a mint
+    ///
+    /// Zero when the account already exists.
+    pub async fn token_account_rent(&self, mint: &Pubkey) -> Result<f64, Box<dyn std::error::Error>> {
+        use solana_sdk::program_pack::Pack;
+
+        let token_account = self.token_account_for(mint).await?;
+        if self.rpc_client.get_account(&token_account).is_ok() {
+            return Ok(0.0);
+        }
+
+        // Token-2022 accounts carry an immutable-owner extension, plus a withheld
+        // amount when the mint charges transfer fees
+        let info = self.get_mint_info(mint).await?;
+        let mut size = spl_token::state::Account::LEN;
+        if info.is_token_2022() {
+            size += 1 + 4;
+            if info.transfer_fee_bps.is_some() {
+                size += 4 + 8;
+            }
+        }
+
+        let rent = self.rpc_client.get_minimum_balance_for_rent_exemption(size)?;
+        Ok(rent as f64 / LAMPORTS_PER_SOL as f64)
+    }
+
     /// Get recent blockhash
     pub async fn get_recent_blockhash(&self) -> Result<String, Box<dyn std::error::Error>> {
-        let (blockhash, _) = self.rpc_client.get_recent_blockhash()?;
+        let blockhash = self.rpc_client.get_latest_blockhash()?;
         Ok(blockhash.to_string())
     }
 
+    /// Fetch the latest blockhash into the signing cache
+    pub fn refresh_blockhash(&self) -> Result<Hash, Box<dyn std::error::Error>> {
+        let blockhash = self.rpc_client.get_latest_blockhash()?;
+        *self.blockhash.write().unwrap() = Some((blockhash, Instant::now()));
+        Ok(blockhash)
+    }
+
+    /// Keep the signing cache fresh, refetching every `interval`
+    pub fn start_blockhash_refresh(self: &Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let client = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            loop {
+                interval.tick().await;
+                if let Err(e) = client.refresh_blockhash() {
+                    tracing::warn!("Blockhash refresh failed: {}", e);
+                }
+            }
+        })
+    }
+
+    /// Blockhash to sign with: the cached one while fresh, otherwise a newly fetched one
+    fn signing_blockhash(&self) -> Result<Hash, Box<dyn std::error::Error>> {
+        if let Some((blockhash, fetched_at)) = *self.blockhash.read().unwrap() {
+            if fetched_at.elapsed() < self.blockhash_max_age {
+                return Ok(blockhash);
+            }
+        }
+        self.refresh_blockhash()
+    }
+
     /// Send a transaction
     pub async fn send_transaction(
         &self,
         mut transaction: Transaction,
     ) -> Result<String, Box<dyn std::error::Error>> {
-        // Sign the transaction if we have a keypair
-        if let Some(keypair) = &self.keypair {
-            let recent_blockhash = self.rpc_client.get_recent_blockhash()?.0;
-            transaction.sign(&[keypair], recent_blockhash);
-
-            // Send the transaction
-            let signature = self.rpc_client.send_and_confirm_transaction(&transaction)?;
-            Ok(signature.to_string())
-        } else {
-            Err("No trading wallet configured for signing".into())
+        self.ensure_writable()?;
+        self.sign_transaction(&mut transaction)?;
+        self.send_signed_transaction(&transaction).await
+    }
+
+    /// Sign a transaction with the trading wallet and a recent blockhash, returning its signature
+    pub fn sign_transaction(&self, transaction: &mut Transaction) -> Result<String, Box<dyn std::error::Error>> {
+        let signer: &dyn Signer = self.signer.as_deref().ok_or("No trading wallet configured for signing")?;
+        let recent_blockhash = self.signing_blockhash()?;
+        transaction.try_sign(&[signer], recent_blockhash)?;
+        Ok(transaction.signatures[0].to_string())
+    }
+
+    /// Send an already signed transaction and wait for confirmation
+    ///
+    /// Transactions paying a Jito tip go to the block engine; over plain RPC the tip
+    /// would be paid without buying any priority.
+    pub async fn send_signed_transaction(&self, transaction: &Transaction) -> Result<String, Box<dyn std::error::Error>> {
+        self.ensure_writable()?;
+
+        let result = match &self.block_engine_url {
+            Some(url) if Self::pays_jito_tip(transaction) => self.send_via_block_engine(url, transaction).await,
+            _ => self.rpc_client.send_and_confirm_transaction(transaction)
+                .map(|signature| signature.to_string())
+                .map_err(|e| e.into()),
+        };
+        if result.is_err() && self.debug_dump_failed_tx {
+            self.dump_failed_transaction(transaction);
+        }
+        result
+    }
+
+    /// Whether a transaction transfers to the Jito tip account
+    fn pays_jito_tip(transaction: &Transaction) -> bool {
+        transaction.message.account_keys.contains(&constants::JITO_TIP_ACCOUNT)
+    }
+
+    /// Submit a signed transaction to a Jito block engine and wait for it to confirm
+    async fn send_via_block_engine(&self, url: &str, transaction: &Transaction) -> Result<String, Box<dyn std::error::Error>> {
+        use base64::Engine;
+
+        let encoded = base64::engine::general_purpose::STANDARD.encode(bincode::serialize(transaction)?);
+        let response: serde_json::Value = self.block_engine_http
+            .post(format!("{}/api/v1/transactions", url.trim_end_matches('/')))
+            .json(&serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "sendTransaction",
+                "params": [encoded, { "encoding": "base64" }],
+            }))
+            .send()
+            .await?
+            .json()
+            .await?;
+        if let Some(error) = response.get("error") {
+            return Err(format!("Block engine rejected transaction: {}", error).into());
+        }
+
+        let signature = transaction.signatures[0].to_string();
+        let deadline = Instant::now() + BLOCK_ENGINE_CONFIRM_TIMEOUT;
+        while Instant::now() < deadline {
+            let state = self.signature_state(&signature, self.rpc_client.commitment()).await?;
+            match state {
+                SignatureState::Confirmed => return Ok(signature),
+                SignatureState::Failed(e) => return Err(format!("Transaction {} failed: {}", signature, e).into()),
+                SignatureState::Pending => tokio::time::sleep(Duration::from_millis(500)).await,
+            }
+        }
+        Err(format!("Transaction {} was not confirmed before its blockhash expired", signature).into())
+    }
+
+    /// Look up whether signatures landed, searching history for older ones
+    ///
+    /// `Some(true)` landed, `Some(false)` failed on-chain, `None` unknown to the cluster.
+    pub async fn get_signature_outcomes(&self, signatures: &[String]) -> Result<Vec<Option<bool>>, Box<dyn std::error::Error>> {
+        let parsed = signatures
+            .iter()
+            .map(|signature| signature.parse::<solana_sdk::signature::Signature>())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let statuses = self.rpc_client.get_signature_statuses_with_history(&parsed)?.value;
+        Ok(statuses
+            .into_iter()
+            .map(|status| status.map(|status| status.err.is_none()))
+            .collect())
+    }
+
+    /// Log a failed transaction and its simulation logs for offline diagnosis
+    fn dump_failed_transaction(&self, transaction: &Transaction) {
+        use base64::Engine;
+
+        match bincode::serialize(transaction) {
+            Ok(bytes) => tracing::debug!(
+                "Failed transaction (base64): {}",
+                base64::engine::general_purpose::STANDARD.encode(bytes)
+            ),
+            Err(e) => tracing::debug!("Failed to serialize transaction for dump: {}", e),
+        }
+
+        match self.rpc_client.simulate_transaction(transaction) {
+            Ok(response) => {
+                tracing::debug!("Simulation error: {:?}", response.value.err);
+                for log in response.value.logs.unwrap_or_default() {
+                    tracing::debug!("Simulation log: {}", log);
+                }
+            }
+            Err(e) => tracing::debug!("Failed to simulate transaction for dump: {}", e),
         }
     }
 
+    /// Check whether a transaction has reached the given commitment
+    ///
+    /// Returns `Ok(false)` while still pending and an error if the transaction failed.
+    pub async fn is_signature_confirmed(
+        &self,
+        signature: &str,
+        commitment: CommitmentConfig,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        match self.signature_state(signature, commitment).await? {
+            SignatureState::Confirmed => Ok(true),
+            SignatureState::Failed(e) => Err(format!("Transaction {} failed: {}", signature, e).into()),
+            SignatureState::Pending => Ok(false),
+        }
+    }
+
+    /// Where a transaction stands at the given commitment, searching history for older ones
+    ///
+    /// An error means the RPC could not answer, not that the transaction failed.
+    pub async fn signature_state(
+        &self,
+        signature: &str,
+        commitment: CommitmentConfig,
+    ) -> Result<SignatureState, Box<dyn std::error::Error>> {
+        let signature: solana_sdk::signature::Signature = signature.parse()?;
+        let status = self.rpc_client.get_signature_status_with_commitment_and_history(&signature, commitment, true)?;
+        Ok(match status {
+            Some(Ok(())) => SignatureState::Confirmed,
+            Some(Err(e)) => SignatureState::Failed(e.to_string()),
+            None => SignatureState::Pending,
+        })
+    }
+
     /// Get latest block height
     pub async fn get_latest_block_height(&self) -> Result<u64, Box<dyn std::error::Error>> {
         let block_height = self.rpc_client.get_block_height()?;
@@ -127,7 +524,255 @@ impl SolanaClient {
         let total: u64 = fees.iter().map(|fee| fee.prioritization_fee).sum();
         let avg_fee = total / fees.len() as u64;
 
-        Ok(avg_fee.max(10000).min(100000)) // Clamp between min and max
+        Ok(avg_fee.clamp(10000, 100000)) // Clamp between min and max
+    }
+
+    /// Fee payers of the most recent Pump.fun buys touching a bonding curve
+    ///
+    /// Looks at up to `limit` recent transactions; a wallet appears once per buy.
+    pub async fn recent_curve_buyers(&self, curve: &Pubkey, limit: usize) -> Result<Vec<Pubkey>, Box<dyn std::error::Error>> {
+        use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+
+        let signatures = self.rpc_client.get_signatures_for_address_with_config(
+            curve,
+            GetConfirmedSignaturesForAddress2Config {
+                limit: Some(limit),
+                ..Default::default()
+            },
+        )?;
+
+        let landed: Vec<String> = signatures.into_iter()
+            .filter(|signature| signature.err.is_none())
+            .map(|signature| signature.signature)
+            .collect();
+        Ok(self.pump_fun_buyers(&landed).await)
+    }
+
+    /// Fee payers of the Pump.fun buys among `signatures`, fetched concurrently
+    ///
+    /// A transaction that fails to load is skipped rather than failing the lookup.
+    async fn pump_fun_buyers(&self, signatures: &[String]) -> Vec<Pubkey> {
+        use solana_client::rpc_request::RpcRequest;
+
+        let fetches = signatures.iter().map(|signature| {
+            self.nonblocking_rpc.send::<serde_json::Value>(
+                RpcRequest::GetTransaction,
+                serde_json::json!([
+                    signature,
+                    { "encoding": "json", "maxSupportedTransactionVersion": 0 }
+                ]),
+            )
+        });
+
+        futures::future::join_all(fetches).await
+            .into_iter()
+            .zip(signatures)
+            .filter_map(|(result, signature)| match result {
+                Ok(transaction) => Some(transaction),
+                Err(e) => {
+                    tracing::debug!("Failed to fetch transaction {}: {}", signature, e);
+                    None
+                }
+            })
+            .filter(|transaction| is_pump_fun_buy(&transaction["meta"]["logMessages"]))
+            .filter_map(|transaction| transaction["transaction"]["message"]["accountKeys"][0].as_str()?.parse().ok())
+            .collect()
+    }
+
+    /// Block time and log messages of successful transactions touching `program` since `since`
+    ///
+    /// Pages back through signatures until `since`, looking at no more than `limit` of them,
+    /// and returns the oldest transaction first.
+    pub async fn recent_program_logs(
+        &self,
+        program: &Pubkey,
+        since: chrono::DateTime<chrono::Utc>,
+        limit: usize,
+    ) -> Result<Vec<(chrono::DateTime<chrono::Utc>, serde_json::Value)>, Box<dyn std::error::Error>> {
+        use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+        use solana_client::rpc_request::RpcRequest;
+
+        // The RPC returns at most this many signatures per call
+        const SIGNATURE_PAGE_LIMIT: usize = 1000;
+
+        let mut signatures = Vec::new();
+        let mut before = None;
+        while signatures.len() < limit {
+            let page = self.rpc_client.get_signatures_for_address_with_config(
+                program,
+                GetConfirmedSignaturesForAddress2Config {
+                    before,
+                    limit: Some(SIGNATURE_PAGE_LIMIT.min(limit - signatures.len())),
+                    ..Default::default()
+                },
+            )?;
+            let oldest = match page.last() {
+                Some(oldest) => oldest,
+                None => break,
+            };
+            let reached_since = oldest.block_time.is_some_and(|block_time| block_time < since.timestamp());
+            before = Some(oldest.signature.parse()?);
+            signatures.extend(page);
+            if reached_since {
+                break;
+            }
+        }
+        if signatures.len() >= limit {
+            tracing::warn!("Catch-up window truncated at {} signatures", limit);
+        }
+
+        let mut logs = Vec::new();
+        for signature in signatures.iter().filter(|signature| signature.err.is_none()) {
+            // Signatures come newest first, so the first one before `since` ends the window
+            let block_time = match signature.block_time
+                .and_then(|block_time| chrono::TimeZone::timestamp_opt(&chrono::Utc, block_time, 0).single())
+            {
+                Some(block_time) if block_time >= since => block_time,
+                Some(_) => break,
+                None => continue,
+            };
+            let transaction: serde_json::Value = self.rpc_client.send(
+                RpcRequest::GetTransaction,
+                serde_json::json!([
+                    signature.signature,
+                    { "encoding": "json", "maxSupportedTransactionVersion": 0 }
+                ]),
+            )?;
+            logs.push((block_time, transaction["meta"]["logMessages"].clone()));
+        }
+        logs.reverse();
+        Ok(logs)
+    }
+
+    /// Fee payers of Pump.fun buys that landed in the same slot as a curve's creation
+    ///
+    /// The creation slot is the earliest among up to `limit` recent signatures, which
+    /// covers the whole history of a just-launched curve.
+    pub async fn creation_slot_buyers(&self, curve: &Pubkey, limit: usize) -> Result<Vec<Pubkey>, Box<dyn std::error::Error>> {
+        use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+
+        let signatures = self.rpc_client.get_signatures_for_address_with_config(
+            curve,
+            GetConfirmedSignaturesForAddress2Config {
+                limit: Some(limit),
+                ..Default::default()
+            },
+        )?;
+        let creation_slot = match signatures.iter().map(|signature| signature.slot).min() {
+            Some(slot) => slot,
+            None => return Ok(Vec::new()),
+        };
+
+        let creation_signatures: Vec<String> = signatures.into_iter()
+            .filter(|signature| signature.slot == creation_slot && signature.err.is_none())
+            .map(|signature| signature.signature)
+            .collect();
+        Ok(self.pump_fun_buyers(&creation_signatures).await)
+    }
+
+    /// Block time of the most recent transaction touching an account
+    pub async fn last_activity_time(&self, address: &Pubkey) -> Result<Option<chrono::DateTime<chrono::Utc>>, Box<dyn std::error::Error>> {
+        use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+
+        let signatures = self.rpc_client.get_signatures_for_address_with_config(
+            address,
+            GetConfirmedSignaturesForAddress2Config {
+                limit: Some(1),
+                ..Default::default()
+            },
+        )?;
+        Ok(signatures
+            .first()
+            .and_then(|signature| signature.block_time)
+            .and_then(|block_time| chrono::TimeZone::timestamp_opt(&chrono::Utc, block_time, 0).single()))
+    }
+
+    /// Fetch off-chain token metadata JSON, retrying once
+    ///
+    /// Returns `None` when both attempts fail or time out, so analysis can continue
+    /// with on-chain name and symbol only.
+    pub async fn fetch_offchain_metadata(&self, uri: &str) -> Option<serde_json::Value> {
+        // IPFS URIs are not fetchable directly; go through a public gateway
+        let url = match uri.strip_prefix("ipfs://") {
+            Some(cid) => format!("https://ipfs.io/ipfs/{}", cid),
+            None => uri.to_string(),
+        };
+
+        for attempt in 1..=2 {
+            let result = async {
+                self.metadata_http.get(&url).send().await?.error_for_status()?.json::<serde_json::Value>().await
+            }.await;
+            match result {
+                Ok(metadata) => return Some(metadata),
+                Err(e) => tracing::debug!("Metadata fetch attempt {} for {} failed: {}", attempt, url, e),
+            }
+        }
+
+        tracing::warn!("Off-chain metadata unavailable for {}, using on-chain fields", url);
+        None
+    }
+
+    /// Whether the RPC endpoint serves devnet, judged by its genesis hash
+    pub async fn is_devnet(&self) -> Result<bool, Box<dyn std::error::Error>> {
+        Ok(Self::detect_cluster(&self.rpc_client)? == Some(Cluster::Devnet))
+    }
+
+    /// Identify the cluster behind an RPC endpoint from its genesis hash
+    fn detect_cluster(rpc_client: &RpcClient) -> Result<Option<Cluster>, Box<dyn std::error::Error>> {
+        let genesis_hash = rpc_client.get_genesis_hash()?;
+        Ok(Cluster::from_genesis_hash(&genesis_hash.to_string()))
+    }
+
+    /// Warn when the RPC serves a different cluster than configured or lacks the Pump.fun program
+    fn check_cluster(rpc_client: &RpcClient, expected: Option<Cluster>) {
+        match Self::detect_cluster(rpc_client) {
+            Ok(detected) => {
+                tracing::info!("RPC cluster: {}", detected.map_or("unknown".to_string(), |c| format!("{:?}", c)));
+                if let (Some(expected), Some(detected)) = (expected, detected) {
+                    if expected != detected {
+                        tracing::error!(
+                            "CLUSTER is {:?} but the RPC endpoint serves {:?}; transactions will target the wrong network",
+                            expected,
+                            detected
+                        );
+                    }
+                }
+            }
+            Err(e) => tracing::warn!("Could not detect the RPC cluster: {}", e),
+        }
+
+        if let Ok(accounts) = rpc_client.get_multiple_accounts(&[constants::PUMP_FUN_PROGRAM_ID]) {
+            if accounts.first().is_none_or(|account| account.is_none()) {
+                tracing::error!(
+                    "Pump.fun program {} does not exist on this cluster; program ids do not match the network",
+                    constants::PUMP_FUN_PROGRAM_ID
+                );
+            }
+        }
+    }
+
+    /// Airdrop SOL to the trading wallet on devnet and wait for it to confirm
+    ///
+    /// Refuses on any other cluster.
+    pub async fn request_airdrop(&self, amount_sol: f64) -> Result<String, Box<dyn std::error::Error>> {
+        const AIRDROP_CONFIRM_TIMEOUT: Duration = Duration::from_secs(60);
+
+        if !self.is_devnet().await? {
+            return Err("Airdrops are only available on devnet".into());
+        }
+
+        let lamports = (amount_sol * LAMPORTS_PER_SOL as f64) as u64;
+        let signature = self.rpc_client.request_airdrop(&self.public_key()?, lamports)?.to_string();
+
+        let started = Instant::now();
+        while started.elapsed() < AIRDROP_CONFIRM_TIMEOUT {
+            if self.is_signature_confirmed(&signature, CommitmentConfig::confirmed()).await? {
+                return Ok(signature);
+            }
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+
+        Err(format!("Airdrop {} did not confirm within {}s", signature, AIRDROP_CONFIRM_TIMEOUT.as_secs()).into())
     }
 
     /// Health check
@@ -156,24 +801,381 @@ impl SolanaClient {
         to: &Pubkey,
         amount_lamports: u64,
     ) -> Result<String, Box<dyn std::error::Error>> {
-        let from_keypair = self.keypair.as_ref()
-            .ok_or("No trading wallet configured")?;
+        self.ensure_writable()?;
+
+        let from = self.public_key()?;
 
         let instruction = system_instruction::transfer(
-            &from_keypair.pubkey(),
+            &from,
             to,
             amount_lamports,
         );
 
         let mut transaction = Transaction::new_with_payer(
             &[instruction],
-            Some(&from_keypair.pubkey()),
+            Some(&from),
         );
 
-        let recent_blockhash = self.rpc_client.get_recent_blockhash()?.0;
-        transaction.sign(&[from_keypair], recent_blockhash);
+        self.sign_transaction(&mut transaction)?;
 
         let signature = self.rpc_client.send_and_confirm_transaction(&transaction)?;
         Ok(signature.to_string())
     }
 }
+
+/// Methods a [`mock_rpc`] endpoint was called with, in order
+#[cfg(test)]
+pub(crate) type RpcCalls = Arc<std::sync::Mutex<Vec<String>>>;
+
+/// A JSON-RPC endpoint answering each request with `respond(request, nth call of its method)`
+///
+/// `respond` returns the `result` or `error` member of the reply; `getVersion` is answered
+/// for the client's own cluster version probe.
+#[cfg(test)]
+pub(crate) async fn mock_rpc<F>(respond: F) -> (String, RpcCalls)
+where
+    F: Fn(&serde_json::Value, usize) -> serde_json::Value + Send + Sync + 'static,
+{
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let calls = RpcCalls::default();
+    let respond = Arc::new(respond);
+    let recorded = Arc::clone(&calls);
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let (recorded, respond) = (Arc::clone(&recorded), Arc::clone(&respond));
+            tokio::spawn(async move {
+                let mut buffer = Vec::new();
+                let mut chunk = [0u8; 4096];
+                loop {
+                    // Answer each complete request on the kept-alive connection
+                    if let Some(end) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+                        let headers = String::from_utf8_lossy(&buffer[..end]).to_lowercase();
+                        let length: usize = headers.lines()
+                            .find_map(|line| line.strip_prefix("content-length:"))
+                            .map_or(0, |value| value.trim().parse().unwrap());
+                        if buffer.len() >= end + 4 + length {
+                            let request: serde_json::Value = serde_json::from_slice(&buffer[end + 4..end + 4 + length]).unwrap();
+                            buffer.drain(..end + 4 + length);
+                            let method = request["method"].as_str().unwrap_or_default().to_string();
+                            let mut reply = if method == "getVersion" {
+                                serde_json::json!({ "result": { "solana-core": "1.18.26", "feature-set": 0 } })
+                            } else {
+                                let nth = {
+                                    let mut calls = recorded.lock().unwrap();
+                                    calls.push(method.clone());
+                                    calls.iter().filter(|call| **call == method).count()
+                                };
+                                respond(&request, nth)
+                            };
+                            reply["jsonrpc"] = "2.0".into();
+                            reply["id"] = request["id"].clone();
+                            let body = reply.to_string();
+                            let response = format!(
+                                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}",
+                                body.len(),
+                                body
+                            );
+                            if stream.write_all(response.as_bytes()).await.is_err() {
+                                return;
+                            }
+                            continue;
+                        }
+                    }
+                    match stream.read(&mut chunk).await {
+                        Ok(0) | Err(_) => return,
+                        Ok(n) => buffer.extend_from_slice(&chunk[..n]),
+                    }
+                }
+            });
+        }
+    });
+    (url, calls)
+}
+
+/// How many times a [`mock_rpc`] endpoint was called with `method`
+#[cfg(test)]
+pub(crate) fn call_count(calls: &RpcCalls, method: &str) -> usize {
+    calls.lock().unwrap().iter().filter(|call| *call == method).count()
+}
+
+/// Accounts a [`mock_chain`] endpoint serves, editable while it runs
+#[cfg(test)]
+pub(crate) type MockAccounts = Arc<std::sync::Mutex<HashMap<Pubkey, solana_sdk::account::Account>>>;
+
+/// A [`mock_rpc`] endpoint serving `getAccountInfo` from `accounts` and every other method through `respond`
+#[cfg(test)]
+pub(crate) async fn mock_chain<F>(accounts: MockAccounts, respond: F) -> (String, RpcCalls)
+where
+    F: Fn(&serde_json::Value, usize) -> serde_json::Value + Send + Sync + 'static,
+{
+    mock_rpc(move |request, nth| {
+        use base64::Engine;
+
+        if request["method"] != "getAccountInfo" {
+            return respond(request, nth);
+        }
+        let address: Pubkey = request["params"][0].as_str().unwrap().parse().unwrap();
+        let value = accounts.lock().unwrap().get(&address).map(|account| serde_json::json!({
+            "data": [base64::engine::general_purpose::STANDARD.encode(&account.data), "base64"],
+            "executable": account.executable,
+            "lamports": account.lamports,
+            "owner": account.owner.to_string(),
+            "rentEpoch": account.rent_epoch,
+            "space": account.data.len(),
+        }));
+        serde_json::json!({ "result": { "context": { "slot": 1 }, "value": value } })
+    }).await
+}
+
+/// An initialized classic SPL mint with six decimals and the given mint authority
+#[cfg(test)]
+pub(crate) fn mint_account(mint_authority: Option<Pubkey>) -> solana_sdk::account::Account {
+    use solana_sdk::program_pack::Pack;
+
+    let mut data = vec![0; spl_token::state::Mint::LEN];
+    spl_token::state::Mint {
+        mint_authority: mint_authority.into(),
+        decimals: 6,
+        is_initialized: true,
+        ..Default::default()
+    }.pack_into_slice(&mut data);
+    solana_sdk::account::Account {
+        lamports: 1_461_600,
+        data,
+        owner: constants::TOKEN_PROGRAM_ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_buys_logged_by_pump_fun_count() {
+        let pump_fun = constants::PUMP_FUN_PROGRAM_ID.to_string();
+        let other = Pubkey::new_unique().to_string();
+
+        let pump_fun_buy = serde_json::json!([
+            "Program ComputeBudget111111111111111111111111111111 invoke [1]",
+            "Program ComputeBudget111111111111111111111111111111 success",
+            format!("Program {} invoke [1]", pump_fun),
+            "Program log: Instruction: Buy",
+            format!("Program {} success", pump_fun),
+        ]);
+        assert!(is_pump_fun_buy(&pump_fun_buy));
+
+        // Another program's Buy, even one invoked inside a Pump.fun call, is not a curve buy
+        let other_buy = serde_json::json!([
+            format!("Program {} invoke [1]", pump_fun),
+            "Program log: Instruction: Sell",
+            format!("Program {} invoke [2]", other),
+            "Program log: Instruction: Buy",
+            format!("Program {} success", other),
+            format!("Program {} success", pump_fun),
+        ]);
+        assert!(!is_pump_fun_buy(&other_buy));
+        assert!(!is_pump_fun_buy(&serde_json::Value::Null));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn signs_with_the_cached_blockhash_until_it_ages_out() {
+        // Every fetch returns a new hash
+        let (rpc_url, calls) = mock_rpc(|_, nth| serde_json::json!({
+            "result": {
+                "context": { "slot": nth },
+                "value": { "blockhash": Hash::new_from_array([nth as u8; 32]).to_string(), "lastValidBlockHeight": 1_000 },
+            },
+        })).await;
+        let config = BotConfig { rpc_url, blockhash_max_age_ms: 300, ..Default::default() };
+        let client = Arc::new(SolanaClient::new(&config).await.unwrap());
+        let fetches = || call_count(&calls, "getLatestBlockhash");
+
+        // The refresh loop fetches on its interval
+        let refresh = client.start_blockhash_refresh(Duration::from_millis(50));
+        tokio::time::sleep(Duration::from_millis(230)).await;
+        refresh.abort();
+        let _ = refresh.await;
+        let refreshed = fetches();
+        assert!(refreshed >= 3, "only {} refreshes", refreshed);
+
+        // A fresh cached hash is signed with as is
+        let cached = client.blockhash.read().unwrap().unwrap().0;
+        assert_eq!(client.signing_blockhash().unwrap(), cached);
+        assert_eq!(fetches(), refreshed);
+
+        // Past the configured age the hash is fetched again before signing
+        tokio::time::sleep(Duration::from_millis(350)).await;
+        assert_ne!(client.signing_blockhash().unwrap(), cached);
+        assert_eq!(fetches(), refreshed + 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn failed_send_is_simulated_and_dumped_when_enabled() {
+        use base64::Engine;
+
+        let (rpc_url, calls) = mock_rpc(|request, _| match request["method"].as_str() {
+            Some("simulateTransaction") => serde_json::json!({
+                "result": {
+                    "context": { "slot": 1 },
+                    "value": {
+                        "err": { "InstructionError": [2, { "Custom": 6002 }] },
+                        "logs": ["Program log: Error: TooMuchSolRequired"],
+                    },
+                },
+            }),
+            _ => serde_json::json!({ "error": { "code": -32000, "message": "send rejected" } }),
+        }).await;
+        let payer = solana_sdk::signature::Keypair::new();
+        let transaction = Transaction::new_signed_with_payer(
+            &[solana_sdk::system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1)],
+            Some(&payer.pubkey()),
+            &[&payer],
+            Hash::new_unique(),
+        );
+        let encoded = base64::engine::general_purpose::STANDARD.encode(bincode::serialize(&transaction).unwrap());
+
+        for debug_dump_failed_tx in [false, true] {
+            let config = BotConfig { rpc_url: rpc_url.clone(), debug_dump_failed_tx, ..Default::default() };
+            let client = SolanaClient::new(&config).await.unwrap();
+            let (_guard, logs) = crate::utils::telemetry::capture_logs();
+
+            assert!(client.send_signed_transaction(&transaction).await.is_err());
+            let logs = logs.contents();
+            assert_eq!(call_count(&calls, "simulateTransaction"), debug_dump_failed_tx as usize);
+            assert_eq!(logs.contains(&format!("Failed transaction (base64): {}", encoded)), debug_dump_failed_tx);
+            assert_eq!(logs.contains("Custom(6002)"), debug_dump_failed_tx);
+            assert_eq!(logs.contains("Simulation log: Program log: Error: TooMuchSolRequired"), debug_dump_failed_tx);
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn read_only_mode_refuses_every_send() {
+        let (rpc_url, calls) = mock_rpc(|request, _| match request["method"].as_str() {
+            Some("getLatestBlockhash") => serde_json::json!({
+                "result": {
+                    "context": { "slot": 1 },
+                    "value": { "blockhash": Hash::new_unique().to_string(), "lastValidBlockHeight": 1_000 },
+                },
+            }),
+            _ => serde_json::json!({ "result": solana_sdk::signature::Signature::default().to_string() }),
+        }).await;
+        let config = BotConfig {
+            rpc_url,
+            read_only: true,
+            private_key: Some(bs58::encode(solana_sdk::signature::Keypair::new().to_bytes()).into_string()),
+            ..Default::default()
+        };
+        let client = SolanaClient::new(&config).await.unwrap();
+        let payer = client.public_key().unwrap();
+        let transfer = || Transaction::new_with_payer(
+            &[system_instruction::transfer(&payer, &Pubkey::new_unique(), 1)],
+            Some(&payer),
+        );
+
+        // Signing still works, so transactions can be built and inspected
+        let mut signed = transfer();
+        client.sign_transaction(&mut signed).unwrap();
+
+        let refusals = [
+            client.send_transaction(transfer()).await.unwrap_err(),
+            client.send_signed_transaction(&signed).await.unwrap_err(),
+            client.transfer_sol(&Pubkey::new_unique(), 1).await.unwrap_err(),
+        ];
+        for refusal in refusals {
+            assert!(refusal.to_string().starts_with("Read-only mode"), "{}", refusal);
+        }
+        assert_eq!(call_count(&calls, "sendTransaction"), 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_fetches_of_one_mint_share_a_single_request() {
+        let mint = Pubkey::new_unique();
+        let accounts: MockAccounts = Arc::new(std::sync::Mutex::new(HashMap::from([(mint, mint_account(None))])));
+        let (rpc_url, calls) = mock_chain(Arc::clone(&accounts), |_, _| serde_json::json!({ "result": null })).await;
+        let client = Arc::new(SolanaClient::new(&BotConfig { rpc_url, ..Default::default() }).await.unwrap());
+
+        // Hold the account store so the first fetch is still in flight when the rest arrive
+        let store = accounts.lock().unwrap();
+        let fetches: Vec<_> = (0..8)
+            .map(|_| {
+                let client = Arc::clone(&client);
+                tokio::spawn(async move { client.fetch_mint_info(&mint).await.map(|info| info.decimals).map_err(|e| e.to_string()) })
+            })
+            .collect();
+        std::thread::sleep(Duration::from_millis(200));
+        drop(store);
+
+        for fetch in fetches {
+            assert_eq!(fetch.await.unwrap(), Ok(6));
+        }
+        assert_eq!(call_count(&calls, "getAccountInfo"), 1);
+
+        // A later fetch refreshes rather than reusing the settled one
+        client.fetch_mint_info(&mint).await.unwrap();
+        assert_eq!(call_count(&calls, "getAccountInfo"), 2);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn airdrops_are_requested_on_devnet_and_refused_on_mainnet() {
+        let signature = solana_sdk::signature::Signature::from([7; 64]).to_string();
+        for (genesis_hash, airdrops) in [(constants::DEVNET_GENESIS_HASH, 1), (constants::MAINNET_GENESIS_HASH, 0)] {
+            let airdrop_signature = signature.clone();
+            let (rpc_url, calls) = mock_rpc(move |request, _| {
+                let result = match request["method"].as_str().unwrap() {
+                    "getGenesisHash" => serde_json::json!(genesis_hash),
+                    "requestAirdrop" => serde_json::json!(airdrop_signature),
+                    "getSignatureStatuses" => serde_json::json!({
+                        "context": { "slot": 1 },
+                        "value": [{ "slot": 1, "confirmations": null, "err": null, "status": { "Ok": null }, "confirmationStatus": "finalized" }],
+                    }),
+                    _ => return serde_json::json!({ "error": { "code": -32601, "message": "Method not found" } }),
+                };
+                serde_json::json!({ "result": result })
+            }).await;
+            let config = BotConfig {
+                rpc_url,
+                private_key: Some(bs58::encode(Keypair::new().to_bytes()).into_string()),
+                ..Default::default()
+            };
+            let client = SolanaClient::new(&config).await.unwrap();
+
+            let result = client.request_airdrop(1.5).await.map_err(|e| e.to_string());
+            assert_eq!(call_count(&calls, "requestAirdrop"), airdrops, "genesis {}", genesis_hash);
+            if airdrops == 1 {
+                assert_eq!(result, Ok(signature.clone()));
+            } else {
+                assert_eq!(result, Err("Airdrops are only available on devnet".to_string()));
+            }
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn genesis_hash_identifies_the_cluster_and_flags_a_mismatch() {
+        let cases = [
+            (constants::MAINNET_GENESIS_HASH, Some(Cluster::Mainnet)),
+            (constants::DEVNET_GENESIS_HASH, Some(Cluster::Devnet)),
+            (constants::TESTNET_GENESIS_HASH, Some(Cluster::Testnet)),
+            ("4sGjMW1sUnHzSxGspuhpqLDx6wiyjNtZAMdL4VZHirAn", None),
+        ];
+        for (genesis_hash, cluster) in cases {
+            let (rpc_url, _) = mock_rpc(move |request, _| match request["method"].as_str() {
+                Some("getGenesisHash") => serde_json::json!({ "result": genesis_hash }),
+                _ => serde_json::json!({ "error": { "code": -32601, "message": "Method not found" } }),
+            }).await;
+            let rpc_client = RpcClient::new(rpc_url.clone());
+            assert_eq!(SolanaClient::detect_cluster(&rpc_client).unwrap(), cluster, "genesis {}", genesis_hash);
+
+            // Configured for devnet, only a known other cluster is flagged
+            let (_guard, logs) = crate::utils::telemetry::capture_logs();
+            let config = BotConfig { rpc_url, cluster: Some(Cluster::Devnet), ..Default::default() };
+            SolanaClient::new(&config).await.unwrap();
+            let flagged = logs.contents().contains("CLUSTER is Devnet but the RPC endpoint serves");
+            assert_eq!(flagged, cluster.is_some_and(|cluster| cluster != Cluster::Devnet), "genesis {}", genesis_hash);
+        }
+    }
+}
@@ -0,0 +1,467 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::JoinHandle;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use crate::{
+    config::{BotConfig, StorageBackend},
+    types::{PendingTransaction, Position, PositionStatus, TradeResult},
+};
+
+/// Persistence for positions and trade history
+///
+/// Implementations are interchangeable so the trader stays agnostic of the backend.
+pub trait Storage: Send + Sync {
+    /// Load all persisted positions
+    fn load_positions(&self) -> Result<Vec<Position>, Box<dyn std::error::Error>>;
+
+    /// Insert or update a position
+    fn save_position(&self, position: &Position) -> Result<(), Box<dyn std::error::Error>>;
+
+    /// Append a trade to the history
+    fn record_trade(&self, trade: &TradeResult) -> Result<(), Box<dyn std::error::Error>>;
+
+    /// Load trade history, optionally for a single mint
+    fn load_trades(&self, token_address: Option<&Pubkey>) -> Result<Vec<TradeResult>, Box<dyn std::error::Error>>;
+
+    /// Record a sent transaction awaiting confirmation
+    fn save_pending_transaction(&self, pending: &PendingTransaction) -> Result<(), Box<dyn std::error::Error>>;
+
+    /// Forget a pending transaction once its outcome is known
+    fn remove_pending_transaction(&self, signature: &str) -> Result<(), Box<dyn std::error::Error>>;
+
+    /// Load transactions that were sent but never resolved
+    fn load_pending_transactions(&self) -> Result<Vec<PendingTransaction>, Box<dyn std::error::Error>>;
+
+    /// Persist a piece of learned bot state under `key`
+    fn save_state(&self, key: &str, value: &serde_json::Value) -> Result<(), Box<dyn std::error::Error>>;
+
+    /// Load state saved under `key`, if any
+    fn load_state(&self, key: &str) -> Result<Option<serde_json::Value>, Box<dyn std::error::Error>>;
+}
+
+/// Open the storage backend selected in the configuration
+pub fn open(config: &BotConfig) -> Result<Box<dyn Storage>, Box<dyn std::error::Error>> {
+    match config.storage_backend {
+        StorageBackend::Memory => Ok(Box::new(MemoryStorage::default())),
+        StorageBackend::Json => Ok(Box::new(JsonStorage::open(&config.storage_path)?)),
+        StorageBackend::Sqlite => Ok(Box::new(SqliteStorage::open(&config.storage_path)?)),
+    }
+}
+
+/// How long the JSON file keeps a closed position; its trades stay in the history
+const CLOSED_POSITION_RETENTION: chrono::Duration = chrono::Duration::hours(24);
+
+/// Contents of the JSON storage file
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StorageSnapshot {
+    positions: HashMap<String, Position>,
+    trades: Vec<TradeResult>,
+    #[serde(default)]
+    pending: HashMap<String, PendingTransaction>,
+    #[serde(default)]
+    state: HashMap<String, serde_json::Value>,
+}
+
+/// In-memory storage that is lost on restart
+#[derive(Default)]
+pub struct MemoryStorage {
+    snapshot: Mutex<StorageSnapshot>,
+}
+
+impl Storage for MemoryStorage {
+    fn load_positions(&self) -> Result<Vec<Position>, Box<dyn std::error::Error>> {
+        Ok(self.snapshot.lock().unwrap().positions.values().cloned().collect())
+    }
+
+    fn save_position(&self, position: &Position) -> Result<(), Box<dyn std::error::Error>> {
+        self.snapshot.lock().unwrap().positions.insert(position.token_address.to_string(), position.clone());
+        Ok(())
+    }
+
+    fn record_trade(&self, trade: &TradeResult) -> Result<(), Box<dyn std::error::Error>> {
+        self.snapshot.lock().unwrap().trades.push(trade.clone());
+        Ok(())
+    }
+
+    fn load_trades(&self, token_address: Option<&Pubkey>) -> Result<Vec<TradeResult>, Box<dyn std::error::Error>> {
+        Ok(self.snapshot.lock().unwrap().trades
+            .iter()
+            .filter(|t| token_address.is_none_or(|mint| t.token_address == *mint))
+            .cloned()
+            .collect())
+    }
+
+    fn save_pending_transaction(&self, pending: &PendingTransaction) -> Result<(), Box<dyn std::error::Error>> {
+        self.snapshot.lock().unwrap().pending.insert(pending.signature.clone(), pending.clone());
+        Ok(())
+    }
+
+    fn remove_pending_transaction(&self, signature: &str) -> Result<(), Box<dyn std::error::Error>> {
+        self.snapshot.lock().unwrap().pending.remove(signature);
+        Ok(())
+    }
+
+    fn load_pending_transactions(&self) -> Result<Vec<PendingTransaction>, Box<dyn std::error::Error>> {
+        Ok(self.snapshot.lock().unwrap().pending.values().cloned().collect())
+    }
+
+    fn save_state(&self, key: &str, value: &serde_json::Value) -> Result<(), Box<dyn std::error::Error>> {
+        self.snapshot.lock().unwrap().state.insert(key.to_string(), value.clone());
+        Ok(())
+    }
+
+    fn load_state(&self, key: &str) -> Result<Option<serde_json::Value>, Box<dyn std::error::Error>> {
+        Ok(self.snapshot.lock().unwrap().state.get(key).cloned())
+    }
+}
+
+/// JSON file storage, rewritten by a writer thread after every change
+///
+/// Changes update the in-memory snapshot and wake the writer, so callers never wait on
+/// the disk; bursts of changes are coalesced into one write.
+pub struct JsonStorage {
+    snapshot: Arc<Mutex<StorageSnapshot>>,
+    writer: Option<(mpsc::Sender<()>, JoinHandle<()>)>,
+}
+
+impl JsonStorage {
+    /// Open or create a JSON storage file
+    pub fn open(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let path = PathBuf::from(path);
+        let snapshot = if path.exists() {
+            serde_json::from_str(&fs::read_to_string(&path)?)?
+        } else {
+            StorageSnapshot::default()
+        };
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+
+        let snapshot = Arc::new(Mutex::new(snapshot));
+        let (sender, receiver) = mpsc::channel();
+        let handle = {
+            let snapshot = Arc::clone(&snapshot);
+            std::thread::Builder::new()
+                .name("json-storage-writer".to_string())
+                .spawn(move || Self::write_snapshots(path, snapshot, receiver))?
+        };
+
+        Ok(Self {
+            snapshot,
+            writer: Some((sender, handle)),
+        })
+    }
+
+    /// Write the latest snapshot each time a change is signalled, until storage is dropped
+    fn write_snapshots(path: PathBuf, snapshot: Arc<Mutex<StorageSnapshot>>, changes: mpsc::Receiver<()>) {
+        while changes.recv().is_ok() {
+            while changes.try_recv().is_ok() {}
+
+            // Serialize under the lock, write outside it
+            let contents = serde_json::to_string_pretty(&*snapshot.lock().unwrap());
+            let result = contents
+                .map_err(|e| e.to_string())
+                .and_then(|contents| Self::write_atomically(&path, &contents).map_err(|e| e.to_string()));
+            if let Err(e) = result {
+                tracing::warn!("Failed to write storage file {}: {}", path.display(), e);
+            }
+        }
+    }
+
+    /// Replace the file via a temporary one, so a crash never leaves it half-written
+    fn write_atomically(path: &Path, contents: &str) -> std::io::Result<()> {
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, contents)?;
+        fs::rename(&tmp, path)
+    }
+
+    /// Apply a change to the snapshot and schedule a write
+    fn update(&self, change: impl FnOnce(&mut StorageSnapshot)) -> Result<(), Box<dyn std::error::Error>> {
+        change(&mut self.snapshot.lock().unwrap());
+        if let Some((sender, _)) = &self.writer {
+            sender.send(()).map_err(|_| "storage writer has stopped")?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for JsonStorage {
+    /// Let the writer persist the final snapshot
+    fn drop(&mut self) {
+        if let Some((sender, handle)) = self.writer.take() {
+            drop(sender);
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Storage for JsonStorage {
+    fn load_positions(&self) -> Result<Vec<Position>, Box<dyn std::error::Error>> {
+        Ok(self.snapshot.lock().unwrap().positions.values().cloned().collect())
+    }
+
+    fn save_position(&self, position: &Position) -> Result<(), Box<dyn std::error::Error>> {
+        let cutoff = chrono::Utc::now() - CLOSED_POSITION_RETENTION;
+        self.update(|snapshot| {
+            snapshot.positions.insert(position.token_address.to_string(), position.clone());
+            snapshot.positions.retain(|_, pos| pos.status != PositionStatus::Closed || pos.last_updated > cutoff);
+        })
+    }
+
+    fn record_trade(&self, trade: &TradeResult) -> Result<(), Box<dyn std::error::Error>> {
+        self.update(|snapshot| snapshot.trades.push(trade.clone()))
+    }
+
+    fn load_trades(&self, token_address: Option<&Pubkey>) -> Result<Vec<TradeResult>, Box<dyn std::error::Error>> {
+        Ok(self.snapshot.lock().unwrap().trades
+            .iter()
+            .filter(|t| token_address.is_none_or(|mint| t.token_address == *mint))
+            .cloned()
+            .collect())
+    }
+
+    fn save_pending_transaction(&self, pending: &PendingTransaction) -> Result<(), Box<dyn std::error::Error>> {
+        self.update(|snapshot| {
+            snapshot.pending.insert(pending.signature.clone(), pending.clone());
+        })
+    }
+
+    fn remove_pending_transaction(&self, signature: &str) -> Result<(), Box<dyn std::error::Error>> {
+        if !self.snapshot.lock().unwrap().pending.contains_key(signature) {
+            return Ok(());
+        }
+        self.update(|snapshot| {
+            snapshot.pending.remove(signature);
+        })
+    }
+
+    fn load_pending_transactions(&self) -> Result<Vec<PendingTransaction>, Box<dyn std::error::Error>> {
+        Ok(self.snapshot.lock().unwrap().pending.values().cloned().collect())
+    }
+
+    fn save_state(&self, key: &str, value: &serde_json::Value) -> Result<(), Box<dyn std::error::Error>> {
+        self.update(|snapshot| {
+            snapshot.state.insert(key.to_string(), value.clone());
+        })
+    }
+
+    fn load_state(&self, key: &str) -> Result<Option<serde_json::Value>, Box<dyn std::error::Error>> {
+        Ok(self.snapshot.lock().unwrap().state.get(key).cloned())
+    }
+}
+
+/// SQLite storage for querying trade history with SQL
+pub struct SqliteStorage {
+    connection: Mutex<rusqlite::Connection>,
+}
+
+impl SqliteStorage {
+    /// Open or create a SQLite database
+    pub fn open(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        if let Some(parent) = Path::new(path).parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+
+        let connection = rusqlite::Connection::open(path)?;
+        connection.execute_batch(
+            "CREATE TABLE IF NOT EXISTS positions (
+                token_address TEXT PRIMARY KEY,
+                status TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                data TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS trades (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                signature TEXT NOT NULL,
+                token_address TEXT NOT NULL,
+                trade_type TEXT NOT NULL,
+                amount INTEGER NOT NULL,
+                price REAL NOT NULL,
+                total_value REAL NOT NULL,
+                fee REAL NOT NULL,
+                timestamp TEXT NOT NULL,
+                success INTEGER NOT NULL,
+                data TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS trades_token_address ON trades (token_address);
+            CREATE TABLE IF NOT EXISTS pending_transactions (
+                signature TEXT PRIMARY KEY,
+                intent TEXT NOT NULL,
+                token_address TEXT NOT NULL,
+                sent_at TEXT NOT NULL,
+                data TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS bot_state (
+                key TEXT PRIMARY KEY,
+                data TEXT NOT NULL
+            );",
+        )?;
+
+        Ok(Self {
+            connection: Mutex::new(connection),
+        })
+    }
+}
+
+impl Storage for SqliteStorage {
+    fn load_positions(&self) -> Result<Vec<Position>, Box<dyn std::error::Error>> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare("SELECT data FROM positions")?;
+        let rows = statement.query_map([], |row| row.get::<_, String>(0))?;
+
+        let mut positions = Vec::new();
+        for row in rows {
+            positions.push(serde_json::from_str(&row?)?);
+        }
+        Ok(positions)
+    }
+
+    fn save_position(&self, position: &Position) -> Result<(), Box<dyn std::error::Error>> {
+        self.connection.lock().unwrap().execute(
+            "INSERT INTO positions (token_address, status, updated_at, data) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(token_address) DO UPDATE SET status = ?2, updated_at = ?3, data = ?4",
+            rusqlite::params![
+                position.token_address.to_string(),
+                format!("{:?}", position.status),
+                position.last_updated.to_rfc3339(),
+                serde_json::to_string(position)?,
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn record_trade(&self, trade: &TradeResult) -> Result<(), Box<dyn std::error::Error>> {
+        self.connection.lock().unwrap().execute(
+            "INSERT INTO trades (signature, token_address, trade_type, amount, price, total_value, fee, timestamp, success, data)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            rusqlite::params![
+                trade.signature,
+                trade.token_address.to_string(),
+                format!("{:?}", trade.trade_type),
+                trade.amount as i64,
+                trade.price,
+                trade.total_value,
+                trade.fee,
+                trade.timestamp.to_rfc3339(),
+                trade.success,
+                serde_json::to_string(trade)?,
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn load_trades(&self, token_address: Option<&Pubkey>) -> Result<Vec<TradeResult>, Box<dyn std::error::Error>> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare(
+            "SELECT data FROM trades WHERE ?1 IS NULL OR token_address = ?1 ORDER BY id",
+        )?;
+        let rows = statement.query_map(
+            [token_address.map(|mint| mint.to_string())],
+            |row| row.get::<_, String>(0),
+        )?;
+
+        let mut trades = Vec::new();
+        for row in rows {
+            trades.push(serde_json::from_str(&row?)?);
+        }
+        Ok(trades)
+    }
+    fn save_pending_transaction(&self, pending: &PendingTransaction) -> Result<(), Box<dyn std::error::Error>> {
+        self.connection.lock().unwrap().execute(
+            "INSERT OR REPLACE INTO pending_transactions (signature, intent, token_address, sent_at, data)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![
+                pending.signature,
+                format!("{:?}", pending.intent),
+                pending.token_address.to_string(),
+                pending.sent_at.to_rfc3339(),
+                serde_json::to_string(pending)?,
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn remove_pending_transaction(&self, signature: &str) -> Result<(), Box<dyn std::error::Error>> {
+        self.connection.lock().unwrap().execute(
+            "DELETE FROM pending_transactions WHERE signature = ?1",
+            [signature],
+        )?;
+        Ok(())
+    }
+
+    fn load_pending_transactions(&self) -> Result<Vec<PendingTransaction>, Box<dyn std::error::Error>> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare("SELECT data FROM pending_transactions ORDER BY sent_at")?;
+        let rows = statement.query_map([], |row| row.get::<_, String>(0))?;
+
+        let mut pending = Vec::new();
+        for row in rows {
+            pending.push(serde_json::from_str(&row?)?);
+        }
+        Ok(pending)
+    }
+
+    fn save_state(&self, key: &str, value: &serde_json::Value) -> Result<(), Box<dyn std::error::Error>> {
+        self.connection.lock().unwrap().execute(
+            "INSERT OR REPLACE INTO bot_state (key, data) VALUES (?1, ?2)",
+            rusqlite::params![key, value.to_string()],
+        )?;
+        Ok(())
+    }
+
+    fn load_state(&self, key: &str) -> Result<Option<serde_json::Value>, Box<dyn std::error::Error>> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare("SELECT data FROM bot_state WHERE key = ?1")?;
+        let mut rows = statement.query_map([key], |row| row.get::<_, String>(0))?;
+
+        match rows.next() {
+            Some(row) => Ok(Some(serde_json::from_str(&row?)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(status: PositionStatus, age: chrono::Duration) -> Position {
+        Position {
+            token_address: Pubkey::new_unique(),
+            status,
+            last_updated: chrono::Utc::now() - age,
+            ..crate::types::test_position("STORED")
+        }
+    }
+
+    #[test]
+    fn json_storage_persists_on_drop_and_prunes_old_closed_positions() {
+        let path = std::env::temp_dir().join(format!("storage-{}.json", Pubkey::new_unique()));
+        let path = path.to_str().unwrap();
+        let open = position(PositionStatus::Open, chrono::Duration::days(3));
+        let recently_closed = position(PositionStatus::Closed, chrono::Duration::hours(1));
+        let long_closed = position(PositionStatus::Closed, chrono::Duration::days(2));
+
+        {
+            let storage = JsonStorage::open(path).unwrap();
+            for pos in [&long_closed, &open, &recently_closed] {
+                storage.save_position(pos).unwrap();
+            }
+            storage.save_state("key", &serde_json::json!(7)).unwrap();
+        }
+
+        let reopened = JsonStorage::open(path).unwrap();
+        let mut kept: Vec<Pubkey> = reopened.load_positions().unwrap().iter().map(|pos| pos.token_address).collect();
+        kept.sort();
+        let mut expected = vec![open.token_address, recently_closed.token_address];
+        expected.sort();
+        assert_eq!(kept, expected);
+        assert_eq!(reopened.load_state("key").unwrap(), Some(serde_json::json!(7)));
+        assert!(!Path::new(path).with_extension("tmp").exists());
+        drop(reopened);
+        let _ = fs::remove_file(path);
+    }
+}
@@ -0,0 +1,113 @@
+use tracing_subscriber::{layer::SubscriberExt, reload, util::SubscriberInitExt, Layer, Registry};
+use crate::config::BotConfig;
+
+/// Span export layer, empty until configuration is loaded
+type ExportLayer = Option<Box<dyn Layer<Registry> + Send + Sync>>;
+
+/// Global tracing setup: log output plus optional OTLP span export
+///
+/// The subscriber is installed before configuration loads so nothing logged while
+/// loading is lost; the exporter is attached afterwards through a reload handle.
+pub struct Telemetry {
+    export: reload::Handle<ExportLayer, Registry>,
+}
+
+impl Telemetry {
+    /// Install the global subscriber with log output and no span export
+    pub fn init() -> Self {
+        let (export, handle) = reload::Layer::new(None);
+        tracing_subscriber::registry()
+            .with(export)
+            .with(
+                tracing_subscriber::EnvFilter::try_from_default_env()
+                    .unwrap_or_else(|_| "solana_pumpfun_sniper=info".into()),
+            )
+            .with(tracing_subscriber::fmt::layer())
+            .init();
+
+        Self { export: handle }
+    }
+
+    /// Start exporting spans when `otlp_endpoint` is configured
+    pub fn configure(&self, config: &BotConfig) -> Result<(), Box<dyn std::error::Error>> {
+        self.export.reload(export_layer(config)?)?;
+        Ok(())
+    }
+
+    /// Flush spans still buffered for export
+    pub fn shutdown(&self) {
+        #[cfg(feature = "otel")]
+        opentelemetry::global::shutdown_tracer_provider();
+    }
+}
+
+/// Build the OTLP/gRPC export layer for the configured endpoint
+#[cfg(feature = "otel")]
+fn export_layer(config: &BotConfig) -> Result<ExportLayer, Box<dyn std::error::Error>> {
+    use opentelemetry_otlp::WithExportConfig;
+
+    let endpoint = match &config.otlp_endpoint {
+        Some(endpoint) => endpoint,
+        None => return Ok(None),
+    };
+
+    let resource = opentelemetry_sdk::Resource::new(vec![opentelemetry::KeyValue::new(
+        "service.name",
+        config.otel_service_name.clone(),
+    )]);
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(opentelemetry_otlp::new_exporter().tonic().with_endpoint(endpoint))
+        .with_trace_config(opentelemetry_sdk::trace::config().with_resource(resource))
+        .install_batch(opentelemetry_sdk::runtime::Tokio)?;
+
+    tracing::info!("Exporting spans over OTLP to {}", endpoint);
+    Ok(Some(Box::new(tracing_opentelemetry::layer().with_tracer(tracer))))
+}
+
+/// Without the `otel` feature there is no exporter to attach
+#[cfg(not(feature = "otel"))]
+fn export_layer(config: &BotConfig) -> Result<ExportLayer, Box<dyn std::error::Error>> {
+    if let Some(endpoint) = &config.otlp_endpoint {
+        tracing::warn!("OTLP_ENDPOINT {} ignored: built without the `otel` feature", endpoint);
+    }
+    Ok(None)
+}
+
+/// Log output captured by [`capture_logs`]
+#[cfg(test)]
+#[derive(Clone, Default)]
+pub(crate) struct CapturedLogs(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+#[cfg(test)]
+impl CapturedLogs {
+    /// Everything logged so far, one event per line
+    pub(crate) fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).into_owned()
+    }
+}
+
+#[cfg(test)]
+impl std::io::Write for CapturedLogs {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Capture this crate's events logged on this thread, down to debug, until the guard drops
+#[cfg(test)]
+pub(crate) fn capture_logs() -> (tracing::subscriber::DefaultGuard, CapturedLogs) {
+    let logs = CapturedLogs::default();
+    let writer = logs.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_env_filter("solana_pumpfun_sniper=debug")
+        .with_ansi(false)
+        .with_writer(move || writer.clone())
+        .finish();
+    (tracing::subscriber::set_default(subscriber), logs)
+}
@@ -1,12 +1,20 @@
-use solana_client::rpc_client::RpcClient;
 use solana_sdk::pubkey::Pubkey;
 use chrono::Utc;
 use crate::{
-    config::constants::*,
+    config::{BotConfig, TokenSafetyStatus, constants::*},
     types::*,
-    utils::solana_client::SolanaClient,
+    utils::{bonding_curve, enrichment::Enrichment, pump_fun_pdas, safety_checker::SafetyChecker, solana_client::SolanaClient},
 };
 
+/// Safety score deducted for each risk flag an enrichment provider reports
+const ENRICHMENT_RISK_FLAG_PENALTY: u32 = 10;
+
+/// Highest price in SOL per whole token still taken as real; launches start far below this
+const MAX_SANE_TOKEN_PRICE_SOL: f64 = 1.0;
+
+/// Safety score deducted for a launch bundled with many same-slot buyers
+const BUNDLE_LAUNCH_PENALTY: u32 = 30;
+
 /// Token analyzer for safety and opportunity assessment
 pub struct TokenAnalyzer;
 
@@ -17,11 +25,16 @@ impl TokenAnalyzer {
         bonding_curve_address: &Pubkey,
         client: &SolanaClient,
     ) -> Result<TokenAnalysis, Box<dyn std::error::Error>> {
+        // The curve account does not name its mint, so tie them through the PDA
+        Self::verify_curve_mint(token_address, bonding_curve_address)?;
+
         // Get token info
-        let token_info = Self::get_token_info(token_address, client).await?;
+        let mut token_info = Self::get_token_info(token_address, client).await?;
 
         // Get bonding curve info
-        let bonding_curve = Self::get_bonding_curve_info(bonding_curve_address, client).await?;
+        let mut bonding_curve = Self::get_bonding_curve_info(bonding_curve_address, client).await?;
+        bonding_curve.token_address = *token_address;
+        token_info.creator = bonding_curve.creator;
 
         // Calculate metrics
         let metrics = Self::calculate_metrics(&bonding_curve);
@@ -41,17 +54,29 @@ impl TokenAnalyzer {
         })
     }
 
+    /// Reject a bonding curve that is not the Pump.fun curve PDA of `token_address`
+    ///
+    /// Relaunches can reuse a name and symbol with a different mint; trading a curve
+    /// paired with the wrong mint would buy the wrong token.
+    pub fn verify_curve_mint(token_address: &Pubkey, bonding_curve_address: &Pubkey) -> Result<(), Box<dyn std::error::Error>> {
+        let (expected, _) = pump_fun_pdas::bonding_curve(token_address);
+        if expected != *bonding_curve_address {
+            return Err(format!(
+                "Bonding curve {} does not belong to mint {} (expected {})",
+                bonding_curve_address, token_address, expected
+            ).into());
+        }
+        Ok(())
+    }
+
     /// Get token information
     async fn get_token_info(
         token_address: &Pubkey,
         client: &SolanaClient,
     ) -> Result<TokenInfo, Box<dyn std::error::Error>> {
-        // Try to get token metadata (simplified)
-        // In a real implementation, you'd decode the metadata account
-
-        let token_info = TokenInfo {
+        let mut token_info = TokenInfo {
             address: *token_address,
-            name: format!("Token {}", token_address.to_string()[..8]),
+            name: format!("Token {}", &token_address.to_string()[..8]),
             symbol: token_address.to_string()[..4].to_uppercase(),
             description: None,
             image: None,
@@ -59,33 +84,95 @@ impl TokenAnalyzer {
             twitter: None,
             telegram: None,
             website: None,
-            creator: Pubkey::new_unique(), // Would be decoded from metadata
+            // Taken from the bonding curve by the caller
+            creator: Pubkey::default(),
             created_at: Utc::now(),
         };
 
+        // Name, symbol and URI come from the Metaplex metadata account when it exists
+        let (metadata_address, _) = pump_fun_pdas::metadata(token_address);
+        match client.rpc_client().get_account_with_commitment(&metadata_address, client.rpc_client().commitment()) {
+            Ok(response) => {
+                if let Some((name, symbol, uri)) = response.value.and_then(|account| Self::decode_metadata(&account.data)) {
+                    token_info.name = name;
+                    token_info.symbol = symbol;
+                    token_info.metadata_uri = Some(uri).filter(|uri| !uri.is_empty());
+                }
+            }
+            Err(e) => tracing::debug!("Failed to fetch metadata account of {}: {}", token_address, e),
+        }
+
+        // Off-chain fields are best-effort; a slow or dead URI must not fail the analysis
+        if let Some(uri) = token_info.metadata_uri.clone() {
+            if let Some(metadata) = client.fetch_offchain_metadata(&uri).await {
+                Self::apply_offchain_metadata(&mut token_info, &metadata);
+            }
+        }
+
         Ok(token_info)
     }
 
+    /// Flag a bundled launch when more than `max_buyers` distinct wallets besides the creator
+    /// bought in the creation slot
+    pub fn apply_creation_slot_buyers(safety: &mut TokenSafety, buyers: &[Pubkey], creator: &Pubkey, max_buyers: u32) {
+        let distinct: std::collections::HashSet<&Pubkey> = buyers.iter().filter(|buyer| *buyer != creator).collect();
+        if distinct.len() <= max_buyers as usize {
+            return;
+        }
+
+        safety.checks.bundle_launch = true;
+        safety.details.issues.push(format!("{} wallets bought in the creation slot", distinct.len()));
+        safety.score = safety.score.saturating_sub(BUNDLE_LAUNCH_PENALTY);
+        safety.details.score = safety.score;
+        safety.status = Self::status_for_score(safety.score);
+    }
+
+    /// Safety status band a score falls in
+    fn status_for_score(score: u32) -> TokenSafetyStatus {
+        if score >= 70 {
+            TokenSafetyStatus::Safe
+        } else if score >= 40 {
+            TokenSafetyStatus::Suspicious
+        } else {
+            TokenSafetyStatus::Dangerous
+        }
+    }
+
+    /// Decode name, symbol and URI from Metaplex metadata account data
+    ///
+    /// Layout: `key` (1), `update_authority` (32), `mint` (32), then three Borsh strings
+    /// (u32 length prefix) that Metaplex pads with trailing NULs.
+    fn decode_metadata(data: &[u8]) -> Option<(String, String, String)> {
+        let mut offset = 1 + 32 + 32;
+        let mut next_string = || {
+            let len = u32::from_le_bytes(data.get(offset..offset + 4)?.try_into().ok()?) as usize;
+            let bytes = data.get(offset + 4..offset + 4 + len)?;
+            offset += 4 + len;
+            Some(String::from_utf8_lossy(bytes).trim_end_matches('\0').to_string())
+        };
+        Some((next_string()?, next_string()?, next_string()?))
+    }
+
+    /// Fill description, image and social links from off-chain metadata JSON
+    fn apply_offchain_metadata(token_info: &mut TokenInfo, metadata: &serde_json::Value) {
+        let field = |key: &str| metadata[key].as_str().filter(|s| !s.is_empty()).map(str::to_string);
+        token_info.description = field("description");
+        token_info.image = field("image");
+        token_info.twitter = field("twitter");
+        token_info.telegram = field("telegram");
+        token_info.website = field("website");
+    }
+
     /// Get bonding curve information
     async fn get_bonding_curve_info(
         bonding_curve_address: &Pubkey,
         client: &SolanaClient,
     ) -> Result<BondingCurveInfo, Box<dyn std::error::Error>> {
-        // Get bonding curve account info (simplified)
-        // In a real implementation, you'd decode the bonding curve data
-
-        let bonding_curve = BondingCurveInfo {
-            address: *bonding_curve_address,
-            token_address: Pubkey::new_unique(), // Would be decoded
-            virtual_sol_reserves: LAMPORTS_PER_SOL, // 1 SOL
-            virtual_token_reserves: 1_000_000_000, // Placeholder
-            real_sol_reserves: 0,
-            real_token_reserves: 0,
-            token_total_supply: 1_000_000_000, // Placeholder
-            complete: false,
-        };
+        let data = client.get_account_data(bonding_curve_address).await?
+            .ok_or_else(|| format!("{}: {}", CURVE_NOT_FOUND, bonding_curve_address))?;
 
-        Ok(bonding_curve)
+        // The account does not name its mint; callers that know it fill it in
+        bonding_curve::decode(bonding_curve_address, &Pubkey::default(), &data)
     }
 
     /// Calculate token metrics
@@ -94,9 +181,15 @@ impl TokenAnalyzer {
         let virtual_sol = bonding_curve.virtual_sol_reserves as f64 / LAMPORTS_PER_SOL as f64;
         let virtual_tokens = bonding_curve.virtual_token_reserves as f64;
         let real_sol = bonding_curve.real_sol_reserves as f64 / LAMPORTS_PER_SOL as f64;
-        let real_tokens = bonding_curve.real_token_reserves as f64;
 
-        let price = (virtual_sol + real_sol) / (virtual_tokens - real_tokens).max(1.0);
+        // Empty or inverted reserves make the spot price formula meaningless
+        let price = bonding_curve::spot_price(bonding_curve);
+        let price_indeterminate = bonding_curve.virtual_sol_reserves == 0
+            || virtual_tokens <= bonding_curve.real_token_reserves as f64
+            || !price.is_finite()
+            || price <= 0.0
+            || bonding_curve::price_per_token(price) > MAX_SANE_TOKEN_PRICE_SOL;
+        let price = if price_indeterminate { 0.0 } else { price };
 
         // Calculate market cap
         let market_cap = price * bonding_curve.token_total_supply as f64;
@@ -111,6 +204,13 @@ impl TokenAnalyzer {
             volume_24h: 0.0, // Would need historical data
             price,
             price_change_24h: 0.0, // Would need historical data
+            estimated_price_impact_pct: 0.0,
+            estimated_exit_impact_pct: 0.0,
+            price_indeterminate,
+            liquidity_usd: None,
+            volume_24h_usd: None,
+            last_trade_at: None,
+            recent_buyers: None,
         }
     }
 
@@ -121,13 +221,19 @@ impl TokenAnalyzer {
         token_info: &TokenInfo,
         client: &SolanaClient,
     ) -> Result<TokenSafety, Box<dyn std::error::Error>> {
+        let mint = client.fetch_mint_info(token_address).await?;
+        let transfer_fee_bps = mint.transfer_fee_bps.filter(|bps| *bps > 0);
+
         let checks = SafetyChecks {
             has_lock: !bonding_curve.complete, // Active bonding curve = locked
-            mint_revoked: false, // Would check mint authority
+            mint_revoked: mint.mint_authority.is_none(),
             is_honeypot: false, // Would analyze token program
             has_social_links: token_info.twitter.is_some() || token_info.telegram.is_some() || token_info.website.is_some(),
             creator_verified: false, // Would check verification service
             suspicious_creator: false, // Would check blacklist
+            token_2022: mint.is_token_2022(),
+            transfer_fee_bps,
+            bundle_launch: false, // Set from creation-slot buyers when fetched
         };
 
         let mut score = 100;
@@ -139,21 +245,19 @@ impl TokenAnalyzer {
         if !checks.has_social_links { score -= 10; }
         if !checks.creator_verified { score -= 10; }
         if checks.suspicious_creator { score -= 30; }
+        if checks.transfer_fee_bps.is_some() { score -= 30; }
 
-        score = score.max(0).min(100);
+        score = score.clamp(0, 100);
 
-        let status = if score >= 70 {
-            TokenSafetyStatus::Safe
-        } else if score >= 40 {
-            TokenSafetyStatus::Suspicious
-        } else {
-            TokenSafetyStatus::Dangerous
-        };
+        let status = Self::status_for_score(score as u32);
+
+        let details = SafetyChecker::evaluate(&checks, score as u32);
 
         Ok(TokenSafety {
             status,
             score: score as u32,
             checks,
+            details,
         })
     }
 
@@ -200,8 +304,70 @@ impl TokenAnalyzer {
         TokenOpportunities {
             score: score.min(100),
             reasons,
+            creator_sol_contributed: 0.0,
         }
     }
+
+    /// Estimate the price impact of buying `amount_sol` against the analyzed curve, and of selling it back
+    pub fn apply_price_impact(metrics: &mut TokenMetrics, curve: &BondingCurveInfo, amount_sol: f64) {
+        let lamports = (amount_sol * LAMPORTS_PER_SOL as f64) as u64;
+        metrics.estimated_price_impact_pct = bonding_curve::price_impact_pct(curve, lamports);
+        metrics.estimated_exit_impact_pct = bonding_curve::exit_impact_pct(curve, lamports);
+    }
+
+    /// Adjust the opportunity score for the SOL the creator put into their own launch
+    ///
+    /// A moderate dev buy is a weak positive signal; an outsized one means insiders hold a large
+    /// share of the supply and is penalized instead.
+    pub fn apply_creator_contribution(
+        opportunities: &mut TokenOpportunities,
+        creator_sol: f64,
+        config: &BotConfig,
+    ) {
+        opportunities.creator_sol_contributed = creator_sol;
+
+        if creator_sol > config.creator_buy_penalty_max_sol {
+            opportunities.score = opportunities.score.saturating_sub(config.creator_buy_penalty);
+            opportunities.reasons.push(format!("Heavy creator buy: {:.2} SOL", creator_sol));
+        } else if creator_sol >= config.creator_buy_bonus_min_sol && config.creator_buy_bonus > 0 {
+            opportunities.score = (opportunities.score + config.creator_buy_bonus).min(100);
+            opportunities.reasons.push(format!("Creator bought in with {:.2} SOL", creator_sol));
+        }
+    }
+
+    /// Fold external provider data into the metrics and safety assessment
+    ///
+    /// Provider risk flags become safety issues and lower the safety score.
+    pub fn apply_enrichment(analysis: &mut TokenAnalysis, enrichment: &Enrichment) {
+        let metrics = &mut analysis.metrics;
+        metrics.liquidity_usd = enrichment.liquidity_usd.or(metrics.liquidity_usd);
+        metrics.volume_24h_usd = enrichment.volume_24h_usd.or(metrics.volume_24h_usd);
+        if let Some(change) = enrichment.price_change_24h_pct {
+            metrics.price_change_24h = change;
+        }
+        if let Some(holders) = enrichment.holders {
+            metrics.holders = holders;
+        }
+
+        let safety = &mut analysis.safety;
+        for flag in &enrichment.risk_flags {
+            safety.details.issues.push(format!("Provider risk flag: {}", flag));
+            safety.score = safety.score.saturating_sub(ENRICHMENT_RISK_FLAG_PENALTY);
+        }
+        safety.details.score = safety.score;
+        safety.status = Self::status_for_score(safety.score);
+    }
+}
+
+/// Error prefix for a bonding curve account the RPC node does not have
+const CURVE_NOT_FOUND: &str = "Bonding curve account not found";
+
+/// Check whether an analysis failed only because the curve account is not visible yet
+///
+/// Right after a create the account can lag on the RPC node by a slot or two, so this
+/// error is worth retrying shortly; decode and RPC failures are not.
+pub fn is_curve_not_found_error(error: &str) -> bool {
+    error.starts_with(CURVE_NOT_FOUND)
 }
 
 /// Convenience function for analyzing tokens
@@ -212,3 +378,133 @@ pub async fn analyze_token(
 ) -> Result<TokenAnalysis, Box<dyn std::error::Error>> {
     TokenAnalyzer::analyze_token(token_address, bonding_curve_address, client).await
 }
+
+/// Convenience function for fetching the current bonding curve state
+pub async fn fetch_bonding_curve(
+    bonding_curve_address: &Pubkey,
+    client: &SolanaClient,
+) -> Result<BondingCurveInfo, Box<dyn std::error::Error>> {
+    TokenAnalyzer::get_bonding_curve_info(bonding_curve_address, client).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Metaplex metadata account data with each string padded like the program pads it
+    fn metadata_account(name: &str, symbol: &str, uri: &str) -> Vec<u8> {
+        let mut data = vec![4u8];
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        for (value, padded_len) in [(name, 32), (symbol, 10), (uri, 200)] {
+            let mut bytes = value.as_bytes().to_vec();
+            bytes.resize(padded_len, 0);
+            data.extend_from_slice(&(padded_len as u32).to_le_bytes());
+            data.extend_from_slice(&bytes);
+        }
+        data
+    }
+
+    #[test]
+    fn decodes_name_symbol_and_uri_from_metadata_account() {
+        let data = metadata_account("Test Token", "TEST", "https://ipfs.io/ipfs/cid");
+        let (name, symbol, uri) = TokenAnalyzer::decode_metadata(&data).unwrap();
+        assert_eq!(name, "Test Token");
+        assert_eq!(symbol, "TEST");
+        assert_eq!(uri, "https://ipfs.io/ipfs/cid");
+
+        assert!(TokenAnalyzer::decode_metadata(&data[..100]).is_none());
+    }
+
+    #[test]
+    fn bundled_launch_is_flagged_and_downgraded() {
+        let mut safety = test_analysis().safety;
+        let creator = Pubkey::new_unique();
+        let snipers: Vec<Pubkey> = (0..6).map(|_| Pubkey::new_unique()).collect();
+
+        // The creator and repeat buys by one wallet do not count toward the bundle
+        let mut buyers = vec![creator, creator, snipers[0], snipers[0]];
+        TokenAnalyzer::apply_creation_slot_buyers(&mut safety, &buyers, &creator, 3);
+        assert!(!safety.checks.bundle_launch);
+        assert_eq!(safety.status, TokenSafetyStatus::Safe);
+
+        buyers.extend(&snipers);
+        TokenAnalyzer::apply_creation_slot_buyers(&mut safety, &buyers, &creator, 3);
+        assert!(safety.checks.bundle_launch);
+        assert_eq!(safety.score, 60);
+        assert_eq!(safety.status, TokenSafetyStatus::Suspicious);
+    }
+
+    #[test]
+    fn enrichment_keeps_units_apart_and_downgrades_flagged_tokens() {
+        let mut analysis = test_analysis();
+        analysis.metrics.volume_24h = 12.5;
+        let enrichment = Enrichment {
+            volume_24h_usd: Some(40_000.0),
+            risk_flags: vec!["freeze authority set".to_string(), "metadata is mutable".to_string(), "lp unlocked".to_string()],
+            ..Default::default()
+        };
+
+        TokenAnalyzer::apply_enrichment(&mut analysis, &enrichment);
+        assert_eq!(analysis.metrics.volume_24h, 12.5);
+        assert_eq!(analysis.metrics.volume_24h_usd, Some(40_000.0));
+        assert_eq!(analysis.safety.score, 60);
+        assert_eq!(analysis.safety.status, TokenSafetyStatus::Suspicious);
+    }
+
+    #[test]
+    fn degenerate_reserves_leave_the_price_indeterminate() {
+        let healthy = test_analysis().bonding_curve;
+        assert!(!TokenAnalyzer::calculate_metrics(&healthy).price_indeterminate);
+
+        let degenerate = [
+            BondingCurveInfo { virtual_sol_reserves: 0, ..healthy.clone() },
+            BondingCurveInfo { virtual_token_reserves: 0, ..healthy.clone() },
+            BondingCurveInfo { virtual_token_reserves: healthy.real_token_reserves, ..healthy.clone() },
+            BondingCurveInfo { virtual_sol_reserves: u64::MAX, virtual_token_reserves: 793_100_000_000_001, ..healthy.clone() },
+        ];
+        for curve in &degenerate {
+            let metrics = TokenAnalyzer::calculate_metrics(curve);
+            assert!(metrics.price_indeterminate, "{:?} was priced", curve);
+            assert_eq!(metrics.price, 0.0);
+            assert_eq!(metrics.market_cap, 0.0);
+        }
+    }
+
+    #[test]
+    fn price_impact_follows_the_constant_product_for_the_buy_size() {
+        let curve = test_analysis().bonding_curve;
+        let mut metrics = test_analysis().metrics;
+
+        // 3 SOL less the 1% fee grows the 30 SOL reserve by 9.9%, so the price rises 1.099² - 1
+        TokenAnalyzer::apply_price_impact(&mut metrics, &curve, 3.0);
+        assert!((metrics.estimated_price_impact_pct - 20.7801).abs() < 1e-9);
+
+        // The same rise the curve's own quote implies
+        let tokens = bonding_curve::buy_quote(&curve, 3 * LAMPORTS_PER_SOL) as f64;
+        let price_before = curve.virtual_sol_reserves as f64 / curve.virtual_token_reserves as f64;
+        let price_after = (curve.virtual_sol_reserves as f64 + 2.97e9) / (curve.virtual_token_reserves as f64 - tokens);
+        assert!(((price_after / price_before - 1.0) * 100.0 - metrics.estimated_price_impact_pct).abs() < 1e-6);
+
+        // Smaller buys move the price less
+        TokenAnalyzer::apply_price_impact(&mut metrics, &curve, 0.3);
+        assert!(metrics.estimated_price_impact_pct < 2.0);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn curve_belonging_to_another_mint_is_rejected() {
+        let mint = Pubkey::new_unique();
+        let (own_curve, _) = pump_fun_pdas::bonding_curve(&mint);
+        let (relaunch_curve, _) = pump_fun_pdas::bonding_curve(&Pubkey::new_unique());
+        assert!(TokenAnalyzer::verify_curve_mint(&mint, &own_curve).is_ok());
+
+        // Rejected before anything is fetched for the wrong curve
+        let config = crate::config::BotConfig { rpc_url: "http://127.0.0.1:1".to_string(), ..Default::default() };
+        let client = SolanaClient::new(&config).await.unwrap();
+        let error = TokenAnalyzer::analyze_token(&mint, &relaunch_curve, &client).await.unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!("Bonding curve {} does not belong to mint {} (expected {})", relaunch_curve, mint, own_curve)
+        );
+    }
+}
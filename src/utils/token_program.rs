@@ -0,0 +1,231 @@
+use solana_sdk::{program_pack::Pack, pubkey::Pubkey};
+use crate::config::constants::*;
+
+/// Token-2022 account type tag marking a mint, stored after the padded base account
+const ACCOUNT_TYPE_MINT: u8 = 1;
+
+/// Token-2022 extension type of the transfer fee config
+const EXTENSION_TRANSFER_FEE_CONFIG: u16 = 1;
+
+/// Offset of the newer transfer fee's basis points within the transfer fee config
+const NEWER_TRANSFER_FEE_BPS_OFFSET: usize = 32 + 32 + 8 + 18 + 16;
+
+/// Fields of a mint the bot cares about, for either token program
+#[derive(Debug, Clone)]
+pub struct MintInfo {
+    pub token_program: Pubkey,
+    pub mint_authority: Option<Pubkey>,
+    pub decimals: u8,
+    pub transfer_fee_bps: Option<u16>,
+}
+
+impl MintInfo {
+    /// Whether the mint is owned by the Token-2022 program
+    pub fn is_token_2022(&self) -> bool {
+        self.token_program == TOKEN_2022_PROGRAM_ID
+    }
+}
+
+/// A wallet's existing token account for a mint, as it affects a buy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenAccountState {
+    /// No account yet; the buy creates it
+    Missing,
+    /// An initialized account the buy can credit
+    Usable,
+    /// Frozen by the mint's freeze authority; buys into it fail and it cannot be closed
+    Frozen,
+    /// Not a token account the wallet can use, with the reason
+    Nonstandard(String),
+}
+
+/// Inspect the account at a wallet's associated token address for `mint`
+pub fn inspect_token_account(program: &Pubkey, data: &[u8], wallet: &Pubkey, mint: &Pubkey) -> TokenAccountState {
+    if *program != TOKEN_PROGRAM_ID && *program != TOKEN_2022_PROGRAM_ID {
+        return TokenAccountState::Nonstandard(format!("owned by program {}", program));
+    }
+    if data.len() < spl_token::state::Account::LEN {
+        return TokenAccountState::Nonstandard("account data too short".to_string());
+    }
+
+    // Token-2022 accounts share the base layout, followed by extensions
+    let account = match spl_token::state::Account::unpack(&data[..spl_token::state::Account::LEN]) {
+        Ok(account) => account,
+        Err(e) => return TokenAccountState::Nonstandard(format!("not an initialized token account: {}", e)),
+    };
+    if account.mint != *mint {
+        return TokenAccountState::Nonstandard(format!("holds mint {}", account.mint));
+    }
+    if account.owner != *wallet {
+        return TokenAccountState::Nonstandard(format!("owned by wallet {}", account.owner));
+    }
+    if account.is_frozen() {
+        return TokenAccountState::Frozen;
+    }
+    TokenAccountState::Usable
+}
+
+/// Check that an account owner is one of the supported token programs
+pub fn validate(owner: &Pubkey) -> Result<Pubkey, Box<dyn std::error::Error>> {
+    if *owner == TOKEN_PROGRAM_ID || *owner == TOKEN_2022_PROGRAM_ID {
+        Ok(*owner)
+    } else {
+        Err(format!("Account owned by {} is not a token mint", owner).into())
+    }
+}
+
+/// Associated token account of `owner` for a mint under the given token program
+pub fn associated_token_address(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    spl_associated_token_account::get_associated_token_address_with_program_id(owner, mint, token_program)
+}
+
+/// Decode a mint account owned by either token program
+///
+/// The base layout is shared; Token-2022 appends TLV extensions after the
+/// account type tag, of which only the transfer fee is read.
+pub fn decode_mint(owner: &Pubkey, data: &[u8]) -> Result<MintInfo, Box<dyn std::error::Error>> {
+    let token_program = validate(owner)?;
+    if data.len() < spl_token::state::Mint::LEN {
+        return Err("Mint account data too short".into());
+    }
+    let mint = spl_token::state::Mint::unpack(&data[..spl_token::state::Mint::LEN])?;
+
+    let transfer_fee_bps = if token_program == TOKEN_2022_PROGRAM_ID {
+        transfer_fee_bps(data)
+    } else {
+        None
+    };
+
+    Ok(MintInfo {
+        token_program,
+        mint_authority: mint.mint_authority.into(),
+        decimals: mint.decimals,
+        transfer_fee_bps,
+    })
+}
+
+/// Walk the Token-2022 extensions for a transfer fee config
+fn transfer_fee_bps(data: &[u8]) -> Option<u16> {
+    // Extensions follow the base account, padded to token account size, and a type tag
+    let tag_offset = spl_token::state::Account::LEN;
+    if data.get(tag_offset) != Some(&ACCOUNT_TYPE_MINT) {
+        return None;
+    }
+
+    let mut offset = tag_offset + 1;
+    while offset + 4 <= data.len() {
+        let extension = u16::from_le_bytes([data[offset], data[offset + 1]]);
+        let length = u16::from_le_bytes([data[offset + 2], data[offset + 3]]) as usize;
+        let value = data.get(offset + 4..offset + 4 + length)?;
+
+        if extension == EXTENSION_TRANSFER_FEE_CONFIG {
+            let bps = value.get(NEWER_TRANSFER_FEE_BPS_OFFSET..NEWER_TRANSFER_FEE_BPS_OFFSET + 2)?;
+            return Some(u16::from_le_bytes([bps[0], bps[1]]));
+        }
+        offset += 4 + length;
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::program_option::COption;
+
+    /// A packed mint with an optional Token-2022 transfer fee extension
+    fn mint_data(transfer_fee_bps: Option<u16>) -> Vec<u8> {
+        let mint = spl_token::state::Mint {
+            mint_authority: COption::None,
+            supply: 1_000_000,
+            decimals: 6,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        };
+        let mut data = vec![0u8; spl_token::state::Mint::LEN];
+        spl_token::state::Mint::pack(mint, &mut data).unwrap();
+
+        if let Some(bps) = transfer_fee_bps {
+            data.resize(spl_token::state::Account::LEN, 0);
+            data.push(ACCOUNT_TYPE_MINT);
+            let mut value = vec![0u8; NEWER_TRANSFER_FEE_BPS_OFFSET + 2];
+            value[NEWER_TRANSFER_FEE_BPS_OFFSET..].copy_from_slice(&bps.to_le_bytes());
+            data.extend_from_slice(&EXTENSION_TRANSFER_FEE_CONFIG.to_le_bytes());
+            data.extend_from_slice(&(value.len() as u16).to_le_bytes());
+            data.extend_from_slice(&value);
+        }
+        data
+    }
+
+    #[test]
+    fn token_2022_program_id_is_the_spl_program() {
+        assert_eq!(TOKEN_2022_PROGRAM_ID.to_string(), "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+    }
+
+    #[test]
+    fn detects_token_2022_mint_and_transfer_fee() {
+        let info = decode_mint(&TOKEN_2022_PROGRAM_ID, &mint_data(Some(250))).unwrap();
+        assert!(info.is_token_2022());
+        assert_eq!(info.decimals, 6);
+        assert_eq!(info.transfer_fee_bps, Some(250));
+
+        let legacy = decode_mint(&TOKEN_PROGRAM_ID, &mint_data(None)).unwrap();
+        assert!(!legacy.is_token_2022());
+        assert_eq!(legacy.transfer_fee_bps, None);
+    }
+
+    #[test]
+    fn rejects_mint_owned_by_other_program() {
+        assert!(decode_mint(&Pubkey::new_unique(), &mint_data(None)).is_err());
+    }
+
+    #[test]
+    fn derives_ata_with_the_mints_token_program() {
+        let owner = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let info = decode_mint(&TOKEN_2022_PROGRAM_ID, &mint_data(None)).unwrap();
+
+        let (expected, _) = Pubkey::find_program_address(
+            &[owner.as_ref(), TOKEN_2022_PROGRAM_ID.as_ref(), mint.as_ref()],
+            &ASSOCIATED_TOKEN_PROGRAM_ID,
+        );
+        let ata = associated_token_address(&owner, &mint, &info.token_program);
+        assert_eq!(ata, expected);
+        assert_ne!(ata, associated_token_address(&owner, &mint, &TOKEN_PROGRAM_ID));
+    }
+
+    /// A packed token account for `mint` held by `wallet`
+    fn token_account_data(wallet: &Pubkey, mint: &Pubkey, state: spl_token::state::AccountState) -> Vec<u8> {
+        let account = spl_token::state::Account {
+            mint: *mint,
+            owner: *wallet,
+            amount: 0,
+            delegate: COption::None,
+            state,
+            is_native: COption::None,
+            delegated_amount: 0,
+            close_authority: COption::None,
+        };
+        let mut data = vec![0u8; spl_token::state::Account::LEN];
+        spl_token::state::Account::pack(account, &mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn frozen_token_account_is_reported_as_frozen() {
+        let wallet = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+
+        let frozen = token_account_data(&wallet, &mint, spl_token::state::AccountState::Frozen);
+        assert_eq!(inspect_token_account(&TOKEN_PROGRAM_ID, &frozen, &wallet, &mint), TokenAccountState::Frozen);
+        assert_eq!(inspect_token_account(&TOKEN_2022_PROGRAM_ID, &frozen, &wallet, &mint), TokenAccountState::Frozen);
+
+        let usable = token_account_data(&wallet, &mint, spl_token::state::AccountState::Initialized);
+        assert_eq!(inspect_token_account(&TOKEN_PROGRAM_ID, &usable, &wallet, &mint), TokenAccountState::Usable);
+        assert!(matches!(
+            inspect_token_account(&TOKEN_PROGRAM_ID, &usable, &Pubkey::new_unique(), &mint),
+            TokenAccountState::Nonstandard(_)
+        ));
+    }
+
+}
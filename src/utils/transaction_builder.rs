@@ -4,25 +4,234 @@ use solana_sdk::{
     system_program,
     compute_budget,
 };
+use std::collections::HashMap;
 use crate::{
-    config::BotConfig,
-    types::{BuyInstruction, SellInstruction},
-    utils::solana_client::SolanaClient,
+    config::{BotConfig, BuyMode, SharedConfig},
+    types::{AmmSwapInstruction, BondingCurveInfo, BuyInstruction, SellInstruction},
+    utils::{bonding_curve, pump_fun_pdas, solana_client::SolanaClient, token_analyzer, token_program},
 };
 
 /// Transaction builder for Pump.fun operations
 pub struct TransactionBuilder {
     client: std::sync::Arc<SolanaClient>,
-    config: std::sync::Arc<BotConfig>,
+    config: SharedConfig,
+    fee_recipients: std::sync::RwLock<Vec<Pubkey>>,
+    amm_fee_recipients: std::sync::RwLock<Vec<Pubkey>>,
+    next_fee_recipient: std::sync::atomic::AtomicUsize,
+    prepared_buys: std::sync::RwLock<HashMap<Pubkey, PreparedBuy>>,
+}
+
+/// Where a buy is executed, depending on the token's lifecycle stage
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuyRoute {
+    BondingCurve,
+    PumpAmm,
+}
+
+/// One position's exit within a batched sell transaction
+#[derive(Debug, Clone)]
+pub struct SellLeg {
+    pub token_address: Pubkey,
+    pub bonding_curve_address: Pubkey,
+    pub creator: Pubkey,
+    pub amount: u64,
+    pub min_sol_output: u64,
+}
+
+/// How long a prepared buy is used before its priority fee counts as stale
+pub const PREPARED_BUY_MAX_AGE: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// A buy built ahead of detection, waiting only for a tip, blockhash and signature
+struct PreparedBuy {
+    instructions: Vec<Instruction>,
+    lamports: u64,
+    prepared_at: std::time::Instant,
+}
+
+/// Buy size in lamports, so amounts that differ only by float rounding match
+fn prepared_lamports(amount_sol: f64) -> u64 {
+    (amount_sol * crate::config::constants::LAMPORTS_PER_SOL as f64).round() as u64
 }
 
 impl TransactionBuilder {
     /// Create a new transaction builder
     pub fn new(
         client: std::sync::Arc<SolanaClient>,
-        config: std::sync::Arc<BotConfig>,
+        config: SharedConfig,
     ) -> Self {
-        Self { client, config }
+        let configured: Vec<Pubkey> = config.load().fee_recipients
+            .iter()
+            .filter_map(|s| s.parse().ok())
+            .collect();
+
+        Self {
+            client,
+            config,
+            fee_recipients: std::sync::RwLock::new(configured),
+            amm_fee_recipients: std::sync::RwLock::new(Vec::new()),
+            next_fee_recipient: std::sync::atomic::AtomicUsize::new(0),
+            prepared_buys: std::sync::RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Refresh the accepted fee recipients from the Pump.fun global account
+    pub async fn refresh_fee_recipients(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let (global, _) = pump_fun_pdas::global();
+        let data = self.client.rpc_client().get_account_data(&global)?;
+        let fetched = Self::decode_global_fee_recipients(&data)?;
+
+        // Warn about configured recipients the program no longer accepts
+        let config = self.config.load();
+        for configured in &config.fee_recipients {
+            if !fetched.iter().any(|r| r.to_string() == *configured) {
+                tracing::warn!("Configured fee recipient {} is not in the current Pump.fun set", configured);
+            }
+        }
+
+        tracing::debug!("Refreshed {} Pump.fun fee recipients", fetched.len());
+        *self.fee_recipients.write().unwrap() = fetched;
+
+        // The AMM keeps its own protocol fee recipients in its global config
+        let (amm_global, _) = pump_fun_pdas::amm_global_config();
+        match self.client.rpc_client().get_account_data(&amm_global) {
+            Ok(data) => *self.amm_fee_recipients.write().unwrap() = Self::decode_amm_fee_recipients(&data)?,
+            Err(e) => tracing::warn!("Failed to refresh Pump.fun AMM fee recipients: {}", e),
+        }
+        Ok(())
+    }
+
+    /// Decode the protocol fee recipients from Pump.fun AMM global config data
+    ///
+    /// Layout: 8-byte discriminator, `admin` (32), `lp_fee_basis_points` (8),
+    /// `protocol_fee_basis_points` (8), `disable_flags` (1), then 8 recipients (32 each).
+    fn decode_amm_fee_recipients(data: &[u8]) -> Result<Vec<Pubkey>, Box<dyn std::error::Error>> {
+        const RECIPIENTS_OFFSET: usize = 8 + 32 + 8 + 8 + 1;
+        const RECIPIENTS: usize = 8;
+
+        let bytes = data
+            .get(RECIPIENTS_OFFSET..RECIPIENTS_OFFSET + 32 * RECIPIENTS)
+            .ok_or("Pump.fun AMM global config data too short")?;
+
+        Ok(bytes
+            .chunks(32)
+            .filter_map(|chunk| Pubkey::try_from(chunk).ok())
+            .filter(|recipient| *recipient != Pubkey::default())
+            .collect())
+    }
+
+    /// Decode the fee recipient from Pump.fun global account data
+    ///
+    /// Layout: 8-byte discriminator, `initialized` (1), `authority` (32), `fee_recipient` (32).
+    fn decode_global_fee_recipients(data: &[u8]) -> Result<Vec<Pubkey>, Box<dyn std::error::Error>> {
+        const FEE_RECIPIENT_OFFSET: usize = 8 + 1 + 32;
+
+        let bytes = data
+            .get(FEE_RECIPIENT_OFFSET..FEE_RECIPIENT_OFFSET + 32)
+            .ok_or("Pump.fun global account data too short")?;
+
+        Ok(vec![Pubkey::try_from(bytes)?])
+    }
+
+    /// Pick a fee recipient from the accepted set, rotating between entries
+    pub fn fee_recipient(&self) -> Pubkey {
+        let recipients = self.fee_recipients.read().unwrap();
+        if recipients.is_empty() {
+            return crate::config::constants::PUMP_FUN_FEE_RECIPIENT;
+        }
+
+        let index = self.next_fee_recipient.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        recipients[index % recipients.len()]
+    }
+
+    /// Pick an AMM protocol fee recipient from the fetched set, rotating between entries
+    pub fn amm_protocol_fee_recipient(&self) -> Pubkey {
+        let recipients = self.amm_fee_recipients.read().unwrap();
+        if recipients.is_empty() {
+            return crate::config::constants::PUMP_AMM_PROTOCOL_FEE_RECIPIENT;
+        }
+
+        let index = self.next_fee_recipient.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        recipients[index % recipients.len()]
+    }
+
+    /// Whether the Pump.fun AMM pool of a graduated mint has been created
+    pub async fn amm_pool_exists(&self, token_address: &Pubkey) -> Result<bool, Box<dyn std::error::Error>> {
+        let (pool, _) = pump_fun_pdas::amm_pool(token_address);
+        let rpc_client = self.client.rpc_client();
+        Ok(rpc_client.get_account_with_commitment(&pool, rpc_client.commitment())?.value.is_some())
+    }
+
+    /// Base token and WSOL reserves of a graduated mint's AMM pool
+    pub async fn amm_pool_reserves(&self, token_address: &Pubkey) -> Result<(u64, u64), Box<dyn std::error::Error>> {
+        let swap = self.amm_swap_instruction(token_address, &Pubkey::default(), 0, 0).await?;
+        let rpc_client = self.client.rpc_client();
+        let base_reserves = rpc_client.get_token_account_balance(&swap.pool_base_token_account)?.amount.parse()?;
+        let quote_reserves = rpc_client.get_token_account_balance(&swap.pool_quote_token_account)?.amount.parse()?;
+        Ok((base_reserves, quote_reserves))
+    }
+
+    /// Pre-build a buy for an expected mint so detection only needs a blockhash and signature
+    pub async fn prepare_buy(
+        &self,
+        token_address: &Pubkey,
+        creator: &Pubkey,
+        amount_sol: f64,
+        slippage_bps: u64,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (bonding_curve_address, _) = pump_fun_pdas::bonding_curve(token_address);
+
+        let priority_fee = self.client.get_priority_fee_estimate().await?;
+        let instructions = self.build_buy_instructions(
+            token_address,
+            &bonding_curve_address,
+            creator,
+            amount_sol,
+            slippage_bps,
+            priority_fee,
+        ).await?;
+
+        self.prepared_buys.write().unwrap().insert(*token_address, PreparedBuy {
+            instructions,
+            lamports: prepared_lamports(amount_sol),
+            prepared_at: std::time::Instant::now(),
+        });
+        tracing::debug!("Prepared buy for {} ({} SOL)", token_address, amount_sol);
+        Ok(())
+    }
+
+    /// Take the prepared buy instructions for a mint if they were built for the same amount
+    /// within [`PREPARED_BUY_MAX_AGE`]
+    pub fn take_prepared_buy(
+        &self,
+        token_address: &Pubkey,
+        amount_sol: f64,
+    ) -> Option<Vec<Instruction>> {
+        let mut prepared = self.prepared_buys.write().unwrap();
+        match prepared.get(token_address) {
+            Some(buy) if buy.prepared_at.elapsed() >= PREPARED_BUY_MAX_AGE => {
+                prepared.remove(token_address);
+                None
+            }
+            Some(buy) if buy.lamports == prepared_lamports(amount_sol) => {
+                prepared.remove(token_address).map(|buy| buy.instructions)
+            }
+            _ => None,
+        }
+    }
+
+    /// Drop every prepared buy, so none outlives the configuration it was built with
+    pub fn clear_prepared_buys(&self) {
+        self.prepared_buys.write().unwrap().clear();
+    }
+
+    /// Pick the buy route from curve progress: the AMM once it reaches `amm_route_progress_pct`
+    pub fn select_buy_route(curve: &BondingCurveInfo, config: &BotConfig) -> BuyRoute {
+        let progress = bonding_curve::progress_pct(curve, config.graduation_lamports());
+        if curve.complete || progress >= config.amm_route_progress_pct {
+            BuyRoute::PumpAmm
+        } else {
+            BuyRoute::BondingCurve
+        }
     }
 
     /// Build a buy transaction
@@ -30,30 +239,96 @@ impl TransactionBuilder {
         &self,
         token_address: &Pubkey,
         bonding_curve_address: &Pubkey,
+        creator: &Pubkey,
         amount_sol: f64,
-        slippage_percentage: f64,
+        slippage_bps: u64,
     ) -> Result<solana_sdk::transaction::Transaction, Box<dyn std::error::Error>> {
+        // Get priority fee
+        let priority_fee = self.client.get_priority_fee_estimate().await?;
+
+        let instructions = self.build_buy_instructions(
+            token_address,
+            bonding_curve_address,
+            creator,
+            amount_sol,
+            slippage_bps,
+            priority_fee,
+        ).await?;
+        self.buy_transaction(instructions, 0)
+    }
+
+    /// Wrap buy instructions into a transaction, appending a Jito tip when non-zero
+    pub fn buy_transaction(
+        &self,
+        mut instructions: Vec<Instruction>,
+        tip_lamports: u64,
+    ) -> Result<solana_sdk::transaction::Transaction, Box<dyn std::error::Error>> {
+        let user = self.client.public_key()?;
+        if tip_lamports > 0 {
+            instructions.push(Self::tip_instruction(&user, tip_lamports));
+        }
+        Ok(solana_sdk::transaction::Transaction::new_with_payer(&instructions, Some(&user)))
+    }
+
+    /// Token amount and SOL ceiling for buying `buy_amount_tokens` at the curve's current quote
+    async fn token_amount_buy_args(
+        &self,
+        bonding_curve_address: &Pubkey,
+        slippage_bps: u64,
+    ) -> Result<(u64, u64), Box<dyn std::error::Error>> {
+        let tokens = self.config.load().buy_amount_tokens;
+        let curve = token_analyzer::fetch_bonding_curve(bonding_curve_address, &self.client).await?;
+        let cost = bonding_curve::buy_cost_quote(&curve, tokens)
+            .ok_or_else(|| format!("Bonding curve holds fewer than {} tokens", tokens))?;
+        Ok((tokens, bonding_curve::max_input_with_slippage(cost, slippage_bps)))
+    }
+
+    /// Build buy instructions with an explicit compute unit price
+    ///
+    /// With `BuyMode::ByTokens` the buy is for `buy_amount_tokens` and `amount_sol` is ignored.
+    pub async fn build_buy_instructions(
+        &self,
+        token_address: &Pubkey,
+        bonding_curve_address: &Pubkey,
+        creator: &Pubkey,
+        amount_sol: f64,
+        slippage_bps: u64,
+        priority_fee: u64,
+    ) -> Result<Vec<Instruction>, Box<dyn std::error::Error>> {
         // Calculate amounts
         let amount_lamports = (amount_sol * crate::config::constants::LAMPORTS_PER_SOL as f64) as u64;
-        let max_sol_cost = ((amount_lamports as f64) * (1.0 + slippage_percentage / 100.0)) as u64;
+        let (amount, max_sol_cost) = match self.config.load().buy_mode {
+            BuyMode::BySol => (amount_lamports, bonding_curve::max_input_with_slippage(amount_lamports, slippage_bps)),
+            BuyMode::ByTokens => self.token_amount_buy_args(bonding_curve_address, slippage_bps).await?,
+        };
+
+        // Classic and Token-2022 mints derive different token accounts
+        let token_program = self.client.token_program_for(token_address).await?;
 
         // Get associated bonding curve
         let associated_bonding_curve = self.find_associated_token_address(
             bonding_curve_address,
             token_address,
+            &token_program,
+        )?;
+
+        let user_token_account = self.find_associated_token_address(
+            &self.client.public_key()?,
+            token_address,
+            &token_program,
         )?;
 
         let buy_instruction = BuyInstruction {
             token_address: *token_address,
             bonding_curve_address: *bonding_curve_address,
             associated_bonding_curve,
-            amount: amount_lamports,
+            user_token_account,
+            creator: *creator,
+            token_program,
+            amount,
             max_sol_cost,
         };
 
-        // Get priority fee
-        let priority_fee = self.client.get_priority_fee_estimate().await?;
-
         // Build instructions
         let mut instructions = Vec::new();
 
@@ -63,19 +338,199 @@ impl TransactionBuilder {
         );
 
         instructions.push(
-            compute_budget::ComputeBudgetInstruction::set_compute_unit_limit(200_000),
+            compute_budget::ComputeBudgetInstruction::set_compute_unit_limit(
+                crate::config::constants::SWAP_COMPUTE_UNIT_LIMIT,
+            ),
+        );
+
+        // The curve debits native SOL, so no WSOL account is involved
+        let user = self.client.public_key()?;
+
+        // Create the user's token account if needed; the payer covers its rent
+        instructions.push(
+            spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                &user,
+                &user,
+                token_address,
+                &token_program,
+            ),
         );
 
         // Add buy instruction
         instructions.push(self.create_buy_instruction(&buy_instruction)?);
 
-        // Create transaction
-        let mut transaction = solana_sdk::transaction::Transaction::new_with_payer(
-            &instructions,
-            Some(&self.client.public_key()?),
+        Ok(instructions)
+    }
+
+    /// Build buy instructions for the Pump.fun AMM pool of a graduated token
+    ///
+    /// The pool trades against WSOL, so SOL is wrapped for the swap and unwrapped after.
+    pub async fn build_amm_buy_instructions(
+        &self,
+        token_address: &Pubkey,
+        creator: &Pubkey,
+        amount_sol: f64,
+        slippage_bps: u64,
+        priority_fee: u64,
+    ) -> Result<Vec<Instruction>, Box<dyn std::error::Error>> {
+        use crate::config::constants::*;
+
+        let user = self.client.public_key()?;
+
+        // Quote against the pool's current reserves
+        let (base_reserves, quote_reserves) = self.amm_pool_reserves(token_address).await?;
+        let amount_lamports = (amount_sol * LAMPORTS_PER_SOL as f64) as u64;
+        let base_amount_out = bonding_curve::min_output_with_slippage(
+            bonding_curve::amm_buy_quote(base_reserves, quote_reserves, amount_lamports),
+            slippage_bps,
         );
+        let max_quote_amount_in = bonding_curve::max_input_with_slippage(amount_lamports, slippage_bps);
+        let buy_instruction = self.amm_swap_instruction(token_address, creator, base_amount_out, max_quote_amount_in).await?;
 
-        Ok(transaction)
+        let mut instructions = vec![
+            compute_budget::ComputeBudgetInstruction::set_compute_unit_price(priority_fee),
+            compute_budget::ComputeBudgetInstruction::set_compute_unit_limit(SWAP_COMPUTE_UNIT_LIMIT),
+        ];
+        instructions.extend(Self::wrap_sol_instructions(&user, max_quote_amount_in)?);
+        instructions.push(
+            spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                &user,
+                &user,
+                token_address,
+                &buy_instruction.base_token_program,
+            ),
+        );
+        instructions.push(self.create_amm_instruction(AMM_BUY_DISCRIMINATOR, &buy_instruction)?);
+        instructions.push(Self::unwrap_sol_instruction(&user)?);
+
+        Ok(instructions)
+    }
+
+    /// Build a sell into the Pump.fun AMM pool of a graduated token
+    ///
+    /// Proceeds arrive as WSOL, so the WSOL account is opened for the swap and closed after.
+    pub async fn build_amm_sell_transaction(
+        &self,
+        token_address: &Pubkey,
+        creator: &Pubkey,
+        amount: u64,
+        min_sol_output: u64,
+    ) -> Result<solana_sdk::transaction::Transaction, Box<dyn std::error::Error>> {
+        use crate::config::constants::*;
+
+        let user = self.client.public_key()?;
+        let sell_instruction = self.amm_swap_instruction(token_address, creator, amount, min_sol_output).await?;
+        let priority_fee = self.client.get_priority_fee_estimate().await?;
+
+        let instructions = vec![
+            compute_budget::ComputeBudgetInstruction::set_compute_unit_price(priority_fee),
+            compute_budget::ComputeBudgetInstruction::set_compute_unit_limit(SWAP_COMPUTE_UNIT_LIMIT),
+            spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                &user,
+                &user,
+                &spl_token::native_mint::id(),
+                &TOKEN_PROGRAM_ID,
+            ),
+            self.create_amm_instruction(AMM_SELL_DISCRIMINATOR, &sell_instruction)?,
+            Self::unwrap_sol_instruction(&user)?,
+        ];
+
+        Ok(solana_sdk::transaction::Transaction::new_with_payer(&instructions, Some(&user)))
+    }
+
+    /// Resolve the pool and token accounts of an AMM swap for the trading wallet
+    async fn amm_swap_instruction(
+        &self,
+        token_address: &Pubkey,
+        creator: &Pubkey,
+        base_amount: u64,
+        quote_limit: u64,
+    ) -> Result<AmmSwapInstruction, Box<dyn std::error::Error>> {
+        use crate::config::constants::TOKEN_PROGRAM_ID;
+
+        let user = self.client.public_key()?;
+        let quote_mint = spl_token::native_mint::id();
+        let base_token_program = self.client.token_program_for(token_address).await?;
+        let (pool, _) = pump_fun_pdas::amm_pool(token_address);
+
+        Ok(AmmSwapInstruction {
+            token_address: *token_address,
+            pool,
+            user_base_token_account: token_program::associated_token_address(&user, token_address, &base_token_program),
+            user_quote_token_account: token_program::associated_token_address(&user, &quote_mint, &TOKEN_PROGRAM_ID),
+            pool_base_token_account: token_program::associated_token_address(&pool, token_address, &base_token_program),
+            pool_quote_token_account: token_program::associated_token_address(&pool, &quote_mint, &TOKEN_PROGRAM_ID),
+            base_token_program,
+            creator: *creator,
+            base_amount,
+            quote_limit,
+        })
+    }
+
+    /// Pump.fun AMM buy or sell instruction; both take a base amount then a quote limit
+    fn create_amm_instruction(
+        &self,
+        discriminator: [u8; 8],
+        params: &AmmSwapInstruction,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let mut data = discriminator.to_vec();
+        data.extend_from_slice(&params.base_amount.to_le_bytes());
+        data.extend_from_slice(&params.quote_limit.to_le_bytes());
+
+        Ok(Instruction {
+            program_id: crate::config::constants::PUMP_AMM_PROGRAM_ID,
+            accounts: self.amm_swap_accounts(params)?,
+            data,
+        })
+    }
+
+    /// Transfer paying a Jito tip, so block engines prioritize the transaction
+    fn tip_instruction(payer: &Pubkey, tip_lamports: u64) -> Instruction {
+        solana_sdk::system_instruction::transfer(payer, &crate::config::constants::JITO_TIP_ACCOUNT, tip_lamports)
+    }
+
+    /// Pump.fun AMM buy and sell instruction accounts, in IDL order for the configured layout
+    pub fn amm_swap_accounts(&self, params: &AmmSwapInstruction) -> Result<Vec<AccountMeta>, Box<dyn std::error::Error>> {
+        use crate::config::{constants::*, AccountLayout};
+
+        let quote_mint = spl_token::native_mint::id();
+        let (global_config, _) = pump_fun_pdas::amm_global_config();
+        let (event_authority, _) = pump_fun_pdas::amm_event_authority();
+        let protocol_fee_recipient = self.amm_protocol_fee_recipient();
+        let protocol_fee_recipient_token_account = token_program::associated_token_address(
+            &protocol_fee_recipient,
+            &quote_mint,
+            &TOKEN_PROGRAM_ID,
+        );
+
+        let mut accounts = vec![
+            AccountMeta::new(params.pool, false), // Pool
+            AccountMeta::new(self.client.public_key()?, true), // User
+            AccountMeta::new_readonly(global_config, false), // Global config
+            AccountMeta::new_readonly(params.token_address, false), // Base mint
+            AccountMeta::new_readonly(quote_mint, false), // Quote mint
+            AccountMeta::new(params.user_base_token_account, false), // User base token account
+            AccountMeta::new(params.user_quote_token_account, false), // User quote token account
+            AccountMeta::new(params.pool_base_token_account, false), // Pool base token account
+            AccountMeta::new(params.pool_quote_token_account, false), // Pool quote token account
+            AccountMeta::new_readonly(protocol_fee_recipient, false), // Protocol fee recipient
+            AccountMeta::new(protocol_fee_recipient_token_account, false), // Protocol fee recipient token account
+            AccountMeta::new_readonly(params.base_token_program, false), // Base token program
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false), // Quote token program
+            AccountMeta::new_readonly(system_program::id(), false), // System program
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false), // Associated token program
+            AccountMeta::new_readonly(event_authority, false), // Event authority
+            AccountMeta::new_readonly(PUMP_AMM_PROGRAM_ID, false), // Program
+        ];
+
+        if self.config.load().account_layout == AccountLayout::CreatorFee {
+            let (vault_authority, _) = pump_fun_pdas::amm_creator_vault_authority(&params.creator);
+            let vault = token_program::associated_token_address(&vault_authority, &quote_mint, &TOKEN_PROGRAM_ID);
+            accounts.push(AccountMeta::new(vault, false)); // Coin creator vault
+            accounts.push(AccountMeta::new_readonly(vault_authority, false)); // Coin creator vault authority
+        }
+
+        Ok(accounts)
     }
 
     /// Build a sell transaction
@@ -83,54 +538,159 @@ impl TransactionBuilder {
         &self,
         token_address: &Pubkey,
         bonding_curve_address: &Pubkey,
+        creator: &Pubkey,
         amount: u64,
         min_sol_output: u64,
     ) -> Result<solana_sdk::transaction::Transaction, Box<dyn std::error::Error>> {
-        // Get associated accounts
-        let associated_bonding_curve = self.find_associated_token_address(
-            bonding_curve_address,
-            token_address,
-        )?;
-
-        let user_token_account = self.find_associated_token_address(
-            &self.client.public_key()?,
-            token_address,
-        )?;
-
-        let sell_instruction = SellInstruction {
+        let sell_instruction = self.sell_instruction_for(&SellLeg {
             token_address: *token_address,
             bonding_curve_address: *bonding_curve_address,
-            associated_bonding_curve,
-            user_token_account,
+            creator: *creator,
             amount,
             min_sol_output,
-        };
+        }).await?;
 
         // Get priority fee
         let priority_fee = self.client.get_priority_fee_estimate().await?;
 
-        // Build instructions
-        let mut instructions = Vec::new();
-
-        // Add compute budget instructions
-        instructions.push(
+        // Build instructions: compute budget, then the sell
+        let instructions = vec![
             compute_budget::ComputeBudgetInstruction::set_compute_unit_price(priority_fee),
-        );
+            compute_budget::ComputeBudgetInstruction::set_compute_unit_limit(
+                crate::config::constants::SWAP_COMPUTE_UNIT_LIMIT,
+            ),
+            self.create_sell_instruction(&sell_instruction)?,
+        ];
 
-        instructions.push(
-            compute_budget::ComputeBudgetInstruction::set_compute_unit_limit(200_000),
+        // Create transaction
+        let transaction = solana_sdk::transaction::Transaction::new_with_payer(
+            &instructions,
+            Some(&self.client.public_key()?),
         );
 
-        // Add sell instruction
-        instructions.push(self.create_sell_instruction(&sell_instruction)?);
+        Ok(transaction)
+    }
 
-        // Create transaction
-        let mut transaction = solana_sdk::transaction::Transaction::new_with_payer(
+    /// Build one transaction that sells several positions on their bonding curves
+    ///
+    /// Returns `None` when the legs would not fit in a single transaction, either by
+    /// compute budget or by serialized size, so the caller can sell them one at a time.
+    pub async fn build_batch_sell_transaction(
+        &self,
+        legs: &[SellLeg],
+    ) -> Result<Option<solana_sdk::transaction::Transaction>, Box<dyn std::error::Error>> {
+        use crate::config::constants::{MAX_COMPUTE_UNIT_LIMIT, SWAP_COMPUTE_UNIT_LIMIT};
+
+        let compute_units = SWAP_COMPUTE_UNIT_LIMIT as u64 * legs.len() as u64;
+        if legs.is_empty() || compute_units > MAX_COMPUTE_UNIT_LIMIT as u64 {
+            return Ok(None);
+        }
+
+        let priority_fee = self.client.get_priority_fee_estimate().await?;
+        let mut sells = Vec::with_capacity(legs.len());
+        for leg in legs {
+            sells.push(self.sell_instruction_for(leg).await?);
+        }
+
+        self.pack_batch_sell(&sells, priority_fee)
+    }
+
+    /// Pack resolved sells into one transaction, or `None` if they exceed compute or size limits
+    fn pack_batch_sell(
+        &self,
+        sells: &[SellInstruction],
+        priority_fee: u64,
+    ) -> Result<Option<solana_sdk::transaction::Transaction>, Box<dyn std::error::Error>> {
+        use crate::config::constants::{MAX_COMPUTE_UNIT_LIMIT, SWAP_COMPUTE_UNIT_LIMIT};
+
+        let compute_units = SWAP_COMPUTE_UNIT_LIMIT as u64 * sells.len() as u64;
+        if sells.is_empty() || compute_units > MAX_COMPUTE_UNIT_LIMIT as u64 {
+            return Ok(None);
+        }
+
+        let mut instructions = vec![
+            compute_budget::ComputeBudgetInstruction::set_compute_unit_price(priority_fee),
+            compute_budget::ComputeBudgetInstruction::set_compute_unit_limit(compute_units as u32),
+        ];
+        for sell in sells {
+            instructions.push(self.create_sell_instruction(sell)?);
+        }
+
+        let transaction = solana_sdk::transaction::Transaction::new_with_payer(
             &instructions,
             Some(&self.client.public_key()?),
         );
 
-        Ok(transaction)
+        // The unsigned transaction already carries placeholder signatures, so its size is final
+        if bincode::serialized_size(&transaction)? > solana_sdk::packet::PACKET_DATA_SIZE as u64 {
+            return Ok(None);
+        }
+
+        Ok(Some(transaction))
+    }
+
+    /// Resolve the accounts for one sell on a bonding curve
+    async fn sell_instruction_for(&self, leg: &SellLeg) -> Result<SellInstruction, Box<dyn std::error::Error>> {
+        let token_program = self.client.token_program_for(&leg.token_address).await?;
+        let associated_bonding_curve = self.find_associated_token_address(
+            &leg.bonding_curve_address,
+            &leg.token_address,
+            &token_program,
+        )?;
+
+        let user_token_account = self.find_associated_token_address(
+            &self.client.public_key()?,
+            &leg.token_address,
+            &token_program,
+        )?;
+
+        Ok(SellInstruction {
+            token_address: leg.token_address,
+            bonding_curve_address: leg.bonding_curve_address,
+            associated_bonding_curve,
+            user_token_account,
+            creator: leg.creator,
+            token_program,
+            amount: leg.amount,
+            min_sol_output: leg.min_sol_output,
+        })
+    }
+
+    /// Priority fee for a given send attempt, escalated and capped
+    pub fn escalated_priority_fee(base_fee: u64, attempt: u32, multiplier: f64, max_fee: u64) -> u64 {
+        let escalated = (base_fee as f64) * multiplier.max(1.0).powi(attempt as i32);
+        (escalated as u64).min(max_fee)
+    }
+
+    /// Instructions to create and fund the user's WSOL account
+    pub fn wrap_sol_instructions(user: &Pubkey, lamports: u64) -> Result<Vec<Instruction>, Box<dyn std::error::Error>> {
+        let native_mint = spl_token::native_mint::id();
+        let wsol_account = spl_associated_token_account::get_associated_token_address(user, &native_mint);
+
+        Ok(vec![
+            spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                user,
+                user,
+                &native_mint,
+                &spl_token::id(),
+            ),
+            solana_sdk::system_instruction::transfer(user, &wsol_account, lamports),
+            spl_token::instruction::sync_native(&spl_token::id(), &wsol_account)?,
+        ])
+    }
+
+    /// Instruction to close the user's WSOL account back to native SOL
+    pub fn unwrap_sol_instruction(user: &Pubkey) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let native_mint = spl_token::native_mint::id();
+        let wsol_account = spl_associated_token_account::get_associated_token_address(user, &native_mint);
+
+        Ok(spl_token::instruction::close_account(
+            &spl_token::id(),
+            &wsol_account,
+            user,
+            user,
+            &[],
+        )?)
     }
 
     /// Create buy instruction for Pump.fun
@@ -140,20 +700,10 @@ impl TransactionBuilder {
     ) -> Result<Instruction, Box<dyn std::error::Error>> {
         use crate::config::constants::*;
 
-        // Pump.fun buy instruction accounts (approximate)
-        let accounts = vec![
-            AccountMeta::new(self.client.public_key()?, true), // User
-            AccountMeta::new_readonly(PUMP_FUN_FEE_RECIPIENT, false), // Fee recipient
-            AccountMeta::new(params.token_address, false), // Mint
-            AccountMeta::new(params.bonding_curve_address, false), // Bonding curve
-            AccountMeta::new(params.associated_bonding_curve, false), // Associated bonding curve
-            AccountMeta::new_readonly(system_program::id(), false), // System program
-            AccountMeta::new_readonly(spl_token::id(), false), // Token program
-            AccountMeta::new_readonly(spl_associated_token_account::id(), false), // Associated token program
-        ];
+        let accounts = self.buy_accounts(params)?;
 
-        // Instruction data for buy (simplified)
-        let mut data = vec![0x00]; // Buy instruction discriminator
+        // Anchor discriminator, then the token amount and the SOL cost cap
+        let mut data = BUY_DISCRIMINATOR.to_vec();
         data.extend_from_slice(&params.amount.to_le_bytes());
         data.extend_from_slice(&params.max_sol_cost.to_le_bytes());
 
@@ -164,6 +714,41 @@ impl TransactionBuilder {
         })
     }
 
+    /// Pump.fun buy instruction accounts, in IDL order for the configured layout
+    pub fn buy_accounts(&self, params: &BuyInstruction) -> Result<Vec<AccountMeta>, Box<dyn std::error::Error>> {
+        use crate::config::{constants::*, AccountLayout};
+
+        let (global, _) = pump_fun_pdas::global();
+        let (event_authority, _) = pump_fun_pdas::event_authority();
+
+        let mut accounts = vec![
+            AccountMeta::new_readonly(global, false), // Global config
+            AccountMeta::new(self.fee_recipient(), false), // Fee recipient
+            AccountMeta::new_readonly(params.token_address, false), // Mint
+            AccountMeta::new(params.bonding_curve_address, false), // Bonding curve
+            AccountMeta::new(params.associated_bonding_curve, false), // Associated bonding curve
+            AccountMeta::new(params.user_token_account, false), // User token account
+            AccountMeta::new(self.client.public_key()?, true), // User
+            AccountMeta::new_readonly(system_program::id(), false), // System program
+            AccountMeta::new_readonly(params.token_program, false), // Token program
+        ];
+
+        match self.config.load().account_layout {
+            AccountLayout::Legacy => {
+                accounts.push(AccountMeta::new_readonly(solana_sdk::sysvar::rent::id(), false)); // Rent
+            }
+            AccountLayout::CreatorFee => {
+                let (creator_vault, _) = pump_fun_pdas::creator_vault(&params.creator);
+                accounts.push(AccountMeta::new(creator_vault, false)); // Creator vault
+            }
+        }
+
+        accounts.push(AccountMeta::new_readonly(event_authority, false)); // Event authority
+        accounts.push(AccountMeta::new_readonly(PUMP_FUN_PROGRAM_ID, false)); // Program
+
+        Ok(accounts)
+    }
+
     /// Create sell instruction for Pump.fun
     fn create_sell_instruction(
         &self,
@@ -171,21 +756,10 @@ impl TransactionBuilder {
     ) -> Result<Instruction, Box<dyn std::error::Error>> {
         use crate::config::constants::*;
 
-        // Pump.fun sell instruction accounts
-        let accounts = vec![
-            AccountMeta::new(self.client.public_key()?, true), // User
-            AccountMeta::new_readonly(PUMP_FUN_FEE_RECIPIENT, false), // Fee recipient
-            AccountMeta::new(params.token_address, false), // Mint
-            AccountMeta::new(params.bonding_curve_address, false), // Bonding curve
-            AccountMeta::new(params.associated_bonding_curve, false), // Associated bonding curve
-            AccountMeta::new(params.user_token_account, false), // User token account
-            AccountMeta::new_readonly(system_program::id(), false), // System program
-            AccountMeta::new_readonly(spl_token::id(), false), // Token program
-            AccountMeta::new_readonly(spl_associated_token_account::id(), false), // Associated token program
-        ];
+        let accounts = self.sell_accounts(params)?;
 
-        // Instruction data for sell
-        let mut data = vec![0x01]; // Sell instruction discriminator
+        // Anchor discriminator, then the token amount and the minimum SOL output
+        let mut data = SELL_DISCRIMINATOR.to_vec();
         data.extend_from_slice(&params.amount.to_le_bytes());
         data.extend_from_slice(&params.min_sol_output.to_le_bytes());
 
@@ -196,14 +770,265 @@ impl TransactionBuilder {
         })
     }
 
-    /// Find associated token address
+    /// Pump.fun sell instruction accounts, in IDL order for the configured layout
+    pub fn sell_accounts(&self, params: &SellInstruction) -> Result<Vec<AccountMeta>, Box<dyn std::error::Error>> {
+        use crate::config::{constants::*, AccountLayout};
+
+        let (global, _) = pump_fun_pdas::global();
+        let (event_authority, _) = pump_fun_pdas::event_authority();
+
+        let mut accounts = vec![
+            AccountMeta::new_readonly(global, false), // Global config
+            AccountMeta::new(self.fee_recipient(), false), // Fee recipient
+            AccountMeta::new_readonly(params.token_address, false), // Mint
+            AccountMeta::new(params.bonding_curve_address, false), // Bonding curve
+            AccountMeta::new(params.associated_bonding_curve, false), // Associated bonding curve
+            AccountMeta::new(params.user_token_account, false), // User token account
+            AccountMeta::new(self.client.public_key()?, true), // User
+            AccountMeta::new_readonly(system_program::id(), false), // System program
+        ];
+
+        match self.config.load().account_layout {
+            AccountLayout::Legacy => {
+                accounts.push(AccountMeta::new_readonly(spl_associated_token_account::id(), false)); // Associated token program
+            }
+            AccountLayout::CreatorFee => {
+                let (creator_vault, _) = pump_fun_pdas::creator_vault(&params.creator);
+                accounts.push(AccountMeta::new(creator_vault, false)); // Creator vault
+            }
+        }
+
+        accounts.push(AccountMeta::new_readonly(params.token_program, false)); // Token program
+        accounts.push(AccountMeta::new_readonly(event_authority, false)); // Event authority
+        accounts.push(AccountMeta::new_readonly(PUMP_FUN_PROGRAM_ID, false)); // Program
+
+        Ok(accounts)
+    }
+
+    /// Find associated token address under the mint's token program
     fn find_associated_token_address(
         &self,
         owner: &Pubkey,
         mint: &Pubkey,
+        token_program: &Pubkey,
     ) -> Result<Pubkey, Box<dyn std::error::Error>> {
-        // For now, return a placeholder - would need proper derivation
-        // In a real implementation, you'd use spl_associated_token_account::get_associated_token_address
-        Ok(Pubkey::new_unique()) // Placeholder
+        Ok(token_program::associated_token_address(owner, mint, token_program))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config;
+    use solana_sdk::signature::Keypair;
+
+    /// Builder for a throwaway wallet against an endpoint that refuses connections
+    async fn test_builder() -> TransactionBuilder {
+        let config = BotConfig {
+            rpc_url: "http://127.0.0.1:1".to_string(),
+            private_key: Some(bs58::encode(Keypair::new().to_bytes()).into_string()),
+            ..Default::default()
+        };
+        let client = std::sync::Arc::new(SolanaClient::new(&config).await.unwrap());
+        TransactionBuilder::new(client, config::shared(config))
+    }
+
+    fn sell_for_new_token() -> SellInstruction {
+        SellInstruction {
+            token_address: Pubkey::new_unique(),
+            bonding_curve_address: Pubkey::new_unique(),
+            associated_bonding_curve: Pubkey::new_unique(),
+            user_token_account: Pubkey::new_unique(),
+            creator: Pubkey::new_unique(),
+            token_program: crate::config::constants::TOKEN_PROGRAM_ID,
+            amount: 1_000_000,
+            min_sol_output: 1,
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn curve_instructions_carry_anchor_discriminators_and_the_creators_vault() {
+        use crate::config::constants::{BUY_DISCRIMINATOR, SELL_DISCRIMINATOR};
+
+        let builder = test_builder().await;
+        let sell = sell_for_new_token();
+        let buy = BuyInstruction {
+            token_address: sell.token_address,
+            bonding_curve_address: sell.bonding_curve_address,
+            associated_bonding_curve: sell.associated_bonding_curve,
+            user_token_account: sell.user_token_account,
+            creator: sell.creator,
+            token_program: sell.token_program,
+            amount: 1_000_000,
+            max_sol_cost: 2_000_000,
+        };
+        let (creator_vault, _) = pump_fun_pdas::creator_vault(&sell.creator);
+
+        let buy_ix = builder.create_buy_instruction(&buy).unwrap();
+        assert_eq!(buy_ix.data[..8], BUY_DISCRIMINATOR);
+        assert_eq!(buy_ix.data[8..16], 1_000_000u64.to_le_bytes());
+        assert_eq!(buy_ix.data[16..], 2_000_000u64.to_le_bytes());
+        assert_eq!(buy_ix.accounts[9].pubkey, creator_vault);
+
+        let sell_ix = builder.create_sell_instruction(&sell).unwrap();
+        assert_eq!(sell_ix.data[..8], SELL_DISCRIMINATOR);
+        assert_eq!(sell_ix.data.len(), 24);
+        assert_eq!(sell_ix.accounts[8].pubkey, creator_vault);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn batches_sells_into_one_transaction_up_to_the_size_limit() {
+        let builder = test_builder().await;
+        let sells: Vec<SellInstruction> = (0..8).map(|_| sell_for_new_token()).collect();
+
+        let pair = builder.pack_batch_sell(&sells[..2], 10_000).unwrap().unwrap();
+        // Two compute budget instructions, then one sell per position
+        assert_eq!(pair.message.instructions.len(), 4);
+
+        // The largest batch that packs still fits a packet; one more leg does not
+        let fits = (2..=sells.len())
+            .take_while(|n| builder.pack_batch_sell(&sells[..*n], 10_000).unwrap().is_some())
+            .last()
+            .unwrap();
+        let largest = builder.pack_batch_sell(&sells[..fits], 10_000).unwrap().unwrap();
+        assert!(bincode::serialized_size(&largest).unwrap() <= solana_sdk::packet::PACKET_DATA_SIZE as u64);
+        assert_eq!(largest.message.instructions.len(), fits + 2);
+        assert!(builder.pack_batch_sell(&sells[..fits + 1], 10_000).unwrap().is_none());
+
+        assert!(builder.pack_batch_sell(&[], 10_000).unwrap().is_none());
+    }
+
+    #[test]
+    fn decodes_amm_fee_recipients_skipping_unset_slots() {
+        let recipients = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let mut data = vec![0u8; 8 + 32 + 8 + 8 + 1];
+        for slot in 0..8 {
+            let recipient = recipients.get(slot).copied().unwrap_or_default();
+            data.extend_from_slice(recipient.as_ref());
+        }
+
+        assert_eq!(TransactionBuilder::decode_amm_fee_recipients(&data).unwrap(), recipients);
+        assert!(TransactionBuilder::decode_amm_fee_recipients(&data[..100]).is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn prepared_buy_matches_by_lamports_until_it_expires() {
+        let builder = test_builder().await;
+        let prepare = |mint: Pubkey, amount_sol: f64, age: std::time::Duration| {
+            builder.prepared_buys.write().unwrap().insert(mint, PreparedBuy {
+                instructions: Vec::new(),
+                lamports: prepared_lamports(amount_sol),
+                prepared_at: std::time::Instant::now() - age,
+            });
+        };
+        let fresh = std::time::Duration::ZERO;
+
+        // The same size computed differently still matches; another size does not
+        let mint = Pubkey::new_unique();
+        prepare(mint, 0.1 + 0.2, fresh);
+        assert!(builder.take_prepared_buy(&mint, 0.25).is_none());
+        assert!(builder.take_prepared_buy(&mint, 0.3).is_some());
+        assert!(builder.take_prepared_buy(&mint, 0.3).is_none());
+
+        prepare(mint, 0.3, PREPARED_BUY_MAX_AGE);
+        assert!(builder.take_prepared_buy(&mint, 0.3).is_none());
+
+        prepare(mint, 0.3, fresh);
+        builder.clear_prepared_buys();
+        assert!(builder.take_prepared_buy(&mint, 0.3).is_none());
+    }
+
+    #[test]
+    fn wraps_sol_into_the_wsol_account_and_closes_it_back_to_the_user() {
+        let user = Pubkey::new_unique();
+        let wsol_account = spl_associated_token_account::get_associated_token_address(&user, &spl_token::native_mint::id());
+
+        let wrap = TransactionBuilder::wrap_sol_instructions(&user, 5_000).unwrap();
+        let programs: Vec<Pubkey> = wrap.iter().map(|ix| ix.program_id).collect();
+        assert_eq!(programs, [spl_associated_token_account::id(), solana_sdk::system_program::id(), spl_token::id()]);
+        assert_eq!(wrap[1].accounts[1].pubkey, wsol_account);
+        assert_eq!(wrap[2].accounts[0].pubkey, wsol_account);
+
+        // Closing sends the remaining lamports and rent back to the user
+        let unwrap = TransactionBuilder::unwrap_sol_instruction(&user).unwrap();
+        assert_eq!(unwrap.program_id, spl_token::id());
+        assert_eq!(unwrap.accounts[0].pubkey, wsol_account);
+        assert_eq!(unwrap.accounts[1].pubkey, user);
+    }
+
+    #[test]
+    fn each_retry_escalates_the_priority_fee_up_to_the_cap() {
+        let fees: Vec<u64> = (0..6)
+            .map(|attempt| TransactionBuilder::escalated_priority_fee(10_000, attempt, 1.5, 40_000))
+            .collect();
+        assert_eq!(fees, [10_000, 15_000, 22_500, 33_750, 40_000, 40_000]);
+
+        // A multiplier below one never lowers the fee on retry
+        assert_eq!(TransactionBuilder::escalated_priority_fee(10_000, 3, 0.5, 40_000), 10_000);
+    }
+
+    #[test]
+    fn curve_progress_is_measured_against_the_configured_graduation_target() {
+        let mut curve = crate::types::test_analysis().bonding_curve;
+        curve.real_sol_reserves = 42_500_000_000;
+
+        let default_target = BotConfig { amm_route_progress_pct: 80.0, ..Default::default() };
+        assert_eq!(bonding_curve::progress_pct(&curve, default_target.graduation_lamports()), 50.0);
+        assert_eq!(TransactionBuilder::select_buy_route(&curve, &default_target), BuyRoute::BondingCurve);
+
+        let lower_target = BotConfig { graduation_sol_target: 50.0, ..default_target };
+        assert_eq!(bonding_curve::progress_pct(&curve, lower_target.graduation_lamports()), 85.0);
+        assert_eq!(TransactionBuilder::select_buy_route(&curve, &lower_target), BuyRoute::PumpAmm);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn buy_modes_set_the_instruction_amount_and_cost_ceiling() {
+        use crate::config::constants::{BUY_DISCRIMINATOR, PUMP_FUN_PROGRAM_ID};
+        use crate::utils::solana_client::{mint_account, mock_chain};
+
+        let mut curve = crate::types::test_analysis().bonding_curve;
+        curve.address = pump_fun_pdas::bonding_curve(&curve.token_address).0;
+        let curve_account = solana_sdk::account::Account {
+            lamports: 1_000_000,
+            data: bonding_curve::encode(&curve),
+            owner: PUMP_FUN_PROGRAM_ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        let accounts = std::sync::Arc::new(std::sync::Mutex::new(std::collections::HashMap::from([
+            (curve.token_address, mint_account(None)),
+            (curve.address, curve_account),
+        ])));
+        let (rpc_url, _) = mock_chain(accounts, |_, _| serde_json::json!({ "result": null })).await;
+
+        let buy_args = |buy_mode| {
+            let config = BotConfig {
+                rpc_url: rpc_url.clone(),
+                private_key: Some(bs58::encode(Keypair::new().to_bytes()).into_string()),
+                buy_mode,
+                buy_amount_tokens: 10_000_000_000,
+                ..Default::default()
+            };
+            async move {
+                let client = std::sync::Arc::new(SolanaClient::new(&config).await.unwrap());
+                let builder = TransactionBuilder::new(client, config::shared(config));
+                let instructions = builder
+                    .build_buy_instructions(&curve.token_address, &curve.address, &curve.creator, 0.5, 1_000, 1)
+                    .await
+                    .unwrap();
+                let buy = instructions.iter().find(|ix| ix.data.starts_with(&BUY_DISCRIMINATOR)).unwrap().clone();
+                let arg = |at: usize| u64::from_le_bytes(buy.data[at..at + 8].try_into().unwrap());
+                (arg(8), arg(16))
+            }
+        };
+
+        // By SOL: the SOL amount is the instruction amount, with slippage on top as the ceiling
+        assert_eq!(buy_args(BuyMode::BySol).await, (500_000_000, 550_000_000));
+
+        // By tokens: the token count, with the quoted cost plus slippage as the ceiling
+        let cost = bonding_curve::buy_cost_quote(&curve, 10_000_000_000).unwrap();
+        let spot_cost = (10_000_000_000 * curve.virtual_sol_reserves as u128 / curve.virtual_token_reserves as u128) as u64;
+        assert!(cost > spot_cost && cost < spot_cost * 102 / 100);
+        assert_eq!(buy_args(BuyMode::ByTokens).await, (10_000_000_000, cost * 11 / 10));
     }
 }